@@ -0,0 +1,134 @@
+//! Order signing and ws parsing hot paths. Run with `cargo bench --bench hot_path`.
+
+use alloy::signers::local::PrivateKeySigner;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use hyperliquid_rust_sdk::{
+    Actions, BaseUrl, BulkOrder, ExchangeClient, Limit, Message, Meta, NetworkConfig, Order,
+    OrderRequest, SpotMeta, Tif,
+};
+
+const NONCE: u64 = 1_700_000_000_000;
+
+fn client() -> ExchangeClient {
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: PrivateKeySigner =
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap();
+    let meta: Meta = serde_json::from_value(serde_json::json!({
+        "universe": [{"name": "ETH", "szDecimals": 4, "maxLeverage": 50}]
+    }))
+    .unwrap();
+    let spot_meta: SpotMeta =
+        serde_json::from_value(serde_json::json!({"universe": [], "tokens": []})).unwrap();
+    ExchangeClient::from_meta(
+        None,
+        wallet,
+        BaseUrl::Mainnet,
+        meta,
+        &spot_meta,
+        None,
+        NetworkConfig::default(),
+    )
+}
+
+fn order_action(orders: usize, with_cloid: bool) -> Actions {
+    let orders = (0..orders)
+        .map(|i| OrderRequest {
+            asset: 0,
+            is_buy: i % 2 == 0,
+            limit_px: format!("{}.5", 1800 + i),
+            sz: "0.0123".to_string(),
+            reduce_only: false,
+            order_type: Order::Limit(Limit { tif: Tif::Gtc }),
+            cloid: with_cloid.then(|| format!("0x{i:032x}")),
+        })
+        .collect();
+    Actions::Order(BulkOrder {
+        orders,
+        grouping: "na".to_string(),
+        builder: None,
+    })
+}
+
+fn order_path(c: &mut Criterion) {
+    let client = client();
+    let mut group = c.benchmark_group("order_path");
+    for (orders, with_cloid) in [(1, false), (1, true), (10, false)] {
+        let id = format!("{orders}{}", if with_cloid { "_cloid" } else { "" });
+        group.bench_with_input(BenchmarkId::new("msgpack", &id), &orders, |b, &orders| {
+            let action = order_action(orders, with_cloid);
+            b.iter(|| rmp_serde::to_vec_named(black_box(&action)).unwrap())
+        });
+        // builds the action, then msgpack, hash, sign and the JSON body
+        group.bench_with_input(
+            BenchmarkId::new("signed_payload", &id),
+            &orders,
+            |b, &orders| {
+                b.iter(|| {
+                    client
+                        .preview(
+                            order_action(black_box(orders), with_cloid),
+                            Some(NONCE),
+                            None,
+                        )
+                        .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn l2_book_message(levels: usize) -> String {
+    let side = |start: usize, step: i64| -> Vec<serde_json::Value> {
+        (0..levels)
+            .map(|i| {
+                let px = start as i64 + step * i as i64;
+                serde_json::json!({"px": format!("{px}.0"), "sz": "1.2345", "n": i + 1})
+            })
+            .collect()
+    };
+    serde_json::json!({
+        "channel": "l2Book",
+        "data": {"coin": "ETH", "time": NONCE, "levels": [side(1799, -1), side(1801, 1)]}
+    })
+    .to_string()
+}
+
+fn trades_message(trades: usize) -> String {
+    let trades: Vec<_> = (0..trades)
+        .map(|i| {
+            serde_json::json!({
+                "coin": "ETH", "side": if i % 2 == 0 { "B" } else { "A" }, "px": "1800.5",
+                "sz": "0.1", "time": NONCE, "hash": format!("0x{i:064x}"), "tid": i
+            })
+        })
+        .collect();
+    serde_json::json!({"channel": "trades", "data": trades}).to_string()
+}
+
+fn all_mids_message(coins: usize) -> String {
+    let mids: serde_json::Map<_, _> = (0..coins)
+        .map(|i| (format!("COIN{i}"), serde_json::json!(format!("{i}.125"))))
+        .collect();
+    serde_json::json!({"channel": "allMids", "data": {"mids": mids}}).to_string()
+}
+
+fn ws_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ws_parse");
+    let messages = [
+        ("l2_book_20", l2_book_message(20)),
+        ("trades_10", trades_message(10)),
+        ("all_mids_200", all_mids_message(200)),
+    ];
+    for (name, message) in &messages {
+        group.bench_with_input(BenchmarkId::from_parameter(name), message, |b, message| {
+            b.iter(|| serde_json::from_str::<Message>(black_box(message)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, order_path, ws_parse);
+criterion_main!(benches);
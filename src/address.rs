@@ -0,0 +1,2 @@
+/// Address type used across the public API
+pub use alloy::primitives::Address;
@@ -1,15 +1,16 @@
 use log::info;
 
-use ethers::signers::{LocalWallet, Signer};
-use hyperliquid_rust_sdk::{BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient};
+use alloy::signers::local::PrivateKeySigner;
+use hyperliquid_rust_sdk::{BaseUrl, DecimalOrder, ExchangeClient, Tif};
 
 #[tokio::main]
 async fn main() {
     env_logger::init();
     // Key was randomly generated for testing and shouldn't be used with any real funds
-    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
-        .parse()
-        .unwrap();
+    let wallet: PrivateKeySigner =
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap();
 
     let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
         .await
@@ -23,7 +24,7 @@ async fn main() {
     let (private_key, response) = exchange_client.approve_agent(None).await.unwrap();
     info!("Agent creation response: {response:?}");
 
-    let wallet: LocalWallet = private_key.parse().unwrap();
+    let wallet: PrivateKeySigner = private_key.parse().unwrap();
 
     info!("Agent address: {:?}", wallet.address());
 
@@ -31,17 +32,13 @@ async fn main() {
         .await
         .unwrap();
 
-    let order = ClientOrderRequest {
-        asset: "ETH".to_string(),
-        is_buy: true,
-        reduce_only: false,
-        limit_px: 1795.0,
-        sz: 0.01,
-        cloid: None,
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: "Gtc".to_string(),
-        }),
-    };
+    let order = DecimalOrder::new(
+        "ETH",
+        true,
+        "1795".parse().unwrap(),
+        "0.01".parse().unwrap(),
+        Tif::Gtc,
+    );
 
     let response = exchange_client.order(order, None).await.unwrap();
 
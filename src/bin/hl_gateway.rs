@@ -0,0 +1,388 @@
+//! Local order gateway: several processes share one authenticated ExchangeClient and one
+//! websocket through a Unix socket speaking JSON lines.
+//!
+//! Configured with `HL_PRIVATE_KEY`, `HL_NETWORK` (`mainnet` or `testnet`, default testnet),
+//! `HL_GATEWAY_SOCKET` (default `$XDG_RUNTIME_DIR/hl-gateway.sock`) and
+//! `HL_GATEWAY_MIN_INTERVAL_MS`, the minimum time between exchange actions across all
+//! connections (default 100). The socket is only accessible to the user running the gateway.
+//!
+//! Requests carry an `id` echoed in the response:
+//! - `{"id": 1, "method": "execute", "intents": [{"kind": "order", "coin": "ETH", ...}]}`
+//! - `{"id": 2, "method": "subscribe", "subscription": {"type": "l2Book", "coin": "ETH"}}`
+//! - `{"id": 3, "method": "unsubscribe", "subscriptionId": 0}`
+//!
+//! `execute` answers with the exchange's response to each batch it sent, e.g.
+//! `{"cancels": null, "cancelsByCloid": null, "orders": {"status": "ok", "response": {...}}}`.
+//!
+//! Subscribed messages arrive as `{"subscriptionId": 0, "message": {...}}`, with a `null`
+//! message when the websocket disconnects.
+use alloy::signers::local::PrivateKeySigner;
+use hyperliquid_rust_sdk::{BaseUrl, ExchangeClient, InfoClient, Intent, Subscription};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    env, fs,
+    os::unix::fs::{FileTypeExt, PermissionsExt},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    spawn,
+    sync::{
+        mpsc::{unbounded_channel, UnboundedSender},
+        Mutex,
+    },
+    time::{sleep_until, Instant},
+};
+
+#[derive(Deserialize)]
+#[serde(tag = "method", rename_all = "camelCase")]
+enum Method {
+    Execute {
+        intents: Vec<Intent>,
+    },
+    Subscribe {
+        subscription: Subscription,
+    },
+    #[serde(rename_all = "camelCase")]
+    Unsubscribe {
+        subscription_id: u32,
+    },
+}
+
+#[derive(Deserialize)]
+struct Request {
+    id: Value,
+    #[serde(flatten)]
+    method: Method,
+}
+
+#[derive(Serialize)]
+struct Response {
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+struct Gateway {
+    exchange_client: ExchangeClient,
+    info_client: Mutex<InfoClient>,
+    min_interval: Duration,
+    /// Earliest time the next exchange action may be sent
+    next_action_at: Mutex<Instant>,
+}
+
+impl Gateway {
+    async fn handle(
+        &self,
+        method: Method,
+        outgoing: &UnboundedSender<String>,
+    ) -> Result<Value, String> {
+        match method {
+            Method::Execute { intents } => {
+                {
+                    let mut next_action_at = self.next_action_at.lock().await;
+                    sleep_until(*next_action_at).await;
+                    *next_action_at = Instant::now() + self.min_interval;
+                }
+                let results = self
+                    .exchange_client
+                    .execute_intents(&intents, None)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                serde_json::to_value(results).map_err(|e| e.to_string())
+            }
+            Method::Subscribe { subscription } => {
+                let (sender, mut receiver) = unbounded_channel();
+                let subscription_id = self
+                    .info_client
+                    .lock()
+                    .await
+                    .subscribe_with_raw(subscription, sender)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let outgoing = outgoing.clone();
+                spawn(async move {
+                    while let Some((_, raw)) = receiver.recv().await {
+                        let message = serde_json::from_str::<Value>(&raw).unwrap_or(Value::Null);
+                        let line = json!({"subscriptionId": subscription_id, "message": message});
+                        if outgoing.send(line.to_string()).is_err() {
+                            break;
+                        }
+                    }
+                });
+                Ok(json!(subscription_id))
+            }
+            Method::Unsubscribe { subscription_id } => {
+                self.info_client
+                    .lock()
+                    .await
+                    .unsubscribe(subscription_id)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(Value::Null)
+            }
+        }
+    }
+
+    async fn serve(self: Arc<Self>, stream: UnixStream) {
+        let (reader, mut writer) = stream.into_split();
+        let (outgoing, mut outgoing_receiver) = unbounded_channel::<String>();
+        spawn(async move {
+            while let Some(line) = outgoing_receiver.recv().await {
+                if writer
+                    .write_all(format!("{line}\n").as_bytes())
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        let mut subscription_ids = Vec::new();
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let response = match serde_json::from_str::<Request>(&line) {
+                Ok(Request { id, method }) => {
+                    let is_subscribe = matches!(method, Method::Subscribe { .. });
+                    if let Method::Unsubscribe { subscription_id } = method {
+                        subscription_ids.retain(|id| *id != subscription_id);
+                    }
+                    let result = self.handle(method, &outgoing).await;
+                    if let (true, Ok(subscription_id)) = (is_subscribe, &result) {
+                        subscription_ids.extend(subscription_id.as_u64().map(|id| id as u32));
+                    }
+                    match result {
+                        Ok(result) => Response {
+                            id,
+                            result: Some(result),
+                            error: None,
+                        },
+                        Err(error) => Response {
+                            id,
+                            result: None,
+                            error: Some(error),
+                        },
+                    }
+                }
+                Err(err) => Response {
+                    id: Value::Null,
+                    result: None,
+                    error: Some(format!("invalid request: {err}")),
+                },
+            };
+            match serde_json::to_string(&response) {
+                Ok(response) => {
+                    let _ = outgoing.send(response);
+                }
+                Err(err) => error!("Could not serialize response: {err}"),
+            }
+        }
+
+        // subscriptions end with the connection that made them
+        let mut info_client = self.info_client.lock().await;
+        for subscription_id in subscription_ids {
+            if let Err(err) = info_client.unsubscribe(subscription_id).await {
+                warn!("Could not unsubscribe {subscription_id}: {err}");
+            }
+        }
+    }
+}
+
+/// Removes a socket left behind by a previous run, which would make bind fail, refusing to
+/// remove anything else
+fn remove_stale_socket(path: &Path) {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => fs::remove_file(path).unwrap(),
+        Ok(_) => panic!("{} exists and is not a socket", path.display()),
+        Err(_) => {}
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    let wallet: PrivateKeySigner = env::var("HL_PRIVATE_KEY")
+        .expect("HL_PRIVATE_KEY must be set")
+        .parse()
+        .expect("invalid HL_PRIVATE_KEY");
+    let base_url = match env::var("HL_NETWORK").as_deref() {
+        Ok("mainnet") => BaseUrl::Mainnet,
+        _ => BaseUrl::Testnet,
+    };
+    let socket_path = env::var("HL_GATEWAY_SOCKET").unwrap_or_else(|_| {
+        let runtime_dir = env::var("XDG_RUNTIME_DIR")
+            .expect("HL_GATEWAY_SOCKET must be set when XDG_RUNTIME_DIR is not");
+        format!("{runtime_dir}/hl-gateway.sock")
+    });
+    let min_interval = env::var("HL_GATEWAY_MIN_INTERVAL_MS")
+        .ok()
+        .and_then(|ms| ms.parse().ok())
+        .unwrap_or(100);
+
+    let gateway = Arc::new(Gateway {
+        exchange_client: ExchangeClient::new(None, wallet, Some(base_url), None, None)
+            .await
+            .unwrap(),
+        info_client: Mutex::new(
+            InfoClient::with_reconnect(None, Some(base_url))
+                .await
+                .unwrap(),
+        ),
+        min_interval: Duration::from_millis(min_interval),
+        next_action_at: Mutex::new(Instant::now()),
+    });
+
+    remove_stale_socket(Path::new(&socket_path));
+    let listener = UnixListener::bind(&socket_path).unwrap();
+    // anyone who can connect can sign with the gateway's key
+    fs::set_permissions(&socket_path, fs::Permissions::from_mode(0o600)).unwrap();
+    info!("hl-gateway listening on {socket_path}");
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                spawn(Arc::clone(&gateway).serve(stream));
+            }
+            Err(err) => error!("Could not accept connection: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyperliquid_rust_sdk::{Meta, NetworkConfig, SpotMeta};
+    use tokio::{
+        io::Lines,
+        net::unix::{OwnedReadHalf, OwnedWriteHalf},
+    };
+
+    /// A gateway that only knows ETH and can't reach any server
+    async fn gateway(min_interval: Duration) -> Arc<Gateway> {
+        let meta: Meta = serde_json::from_value(json!({
+            "universe": [{"name": "ETH", "szDecimals": 4, "maxLeverage": 50}]
+        }))
+        .unwrap();
+        let spot_meta: SpotMeta =
+            serde_json::from_value(json!({"universe": [], "tokens": []})).unwrap();
+        Arc::new(Gateway {
+            exchange_client: ExchangeClient::from_meta(
+                None,
+                PrivateKeySigner::random(),
+                BaseUrl::Localhost,
+                meta,
+                &spot_meta,
+                None,
+                NetworkConfig::default(),
+            ),
+            info_client: Mutex::new(
+                InfoClient::new(None, Some(BaseUrl::Localhost))
+                    .await
+                    .unwrap(),
+            ),
+            min_interval,
+            next_action_at: Mutex::new(Instant::now()),
+        })
+    }
+
+    struct Connection {
+        writer: OwnedWriteHalf,
+        lines: Lines<BufReader<OwnedReadHalf>>,
+    }
+
+    impl Connection {
+        fn open(gateway: Arc<Gateway>) -> Connection {
+            let (stream, gateway_stream) = UnixStream::pair().unwrap();
+            spawn(gateway.serve(gateway_stream));
+            let (reader, writer) = stream.into_split();
+            Connection {
+                writer,
+                lines: BufReader::new(reader).lines(),
+            }
+        }
+
+        async fn request(&mut self, request: &str) -> Value {
+            self.writer
+                .write_all(format!("{request}\n").as_bytes())
+                .await
+                .unwrap();
+            let response = self.lines.next_line().await.unwrap().unwrap();
+            serde_json::from_str(&response).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_request_gets_an_error() {
+        let mut connection = Connection::open(gateway(Duration::ZERO).await);
+        let response = connection
+            .request(r#"{"id": 1, "method": "explode"}"#)
+            .await;
+        assert_eq!(response["id"], Value::Null);
+        assert!(response.get("result").is_none());
+        assert!(response["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("invalid request"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_answers_with_id_and_results() {
+        let mut connection = Connection::open(gateway(Duration::ZERO).await);
+        let response = connection
+            .request(r#"{"id": "a", "method": "execute", "intents": []}"#)
+            .await;
+        assert_eq!(
+            response,
+            json!({"id": "a", "result": {"cancels": null, "cancelsByCloid": null, "orders": null}})
+        );
+
+        let response = connection
+            .request(
+                r#"{"id": 2, "method": "execute", "intents": [{"kind": "cancel", "coin": "NOPE", "oid": 1}]}"#,
+            )
+            .await;
+        assert_eq!(response["id"], json!(2));
+        assert!(response.get("result").is_none());
+        assert!(response["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_actions_are_spaced_across_connections() {
+        let gateway = gateway(Duration::from_millis(100)).await;
+        let mut first = Connection::open(gateway.clone());
+        let mut second = Connection::open(gateway);
+        let execute = r#"{"id": 1, "method": "execute", "intents": []}"#;
+        let started = Instant::now();
+        first.request(execute).await;
+        second.request(execute).await;
+        first.request(execute).await;
+        assert!(started.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_remove_stale_socket() {
+        let dir = env::temp_dir().join(format!("hl-gateway-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let socket = dir.join("gateway.sock");
+        // nothing to remove
+        remove_stale_socket(&socket);
+
+        drop(std::os::unix::net::UnixListener::bind(&socket).unwrap());
+        remove_stale_socket(&socket);
+        assert!(!socket.exists());
+
+        let file = dir.join("not-a-socket");
+        fs::write(&file, "keep").unwrap();
+        let removed = std::panic::catch_unwind(|| remove_stale_socket(&file));
+        assert!(removed.is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "keep");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
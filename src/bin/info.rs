@@ -1,5 +1,5 @@
-use ethers::types::H160;
-use hyperliquid_rust_sdk::{BaseUrl, InfoClient};
+use hyperliquid_rust_sdk::Address;
+use hyperliquid_rust_sdk::{BaseUrl, Coin, InfoClient};
 use log::info;
 
 const ADDRESS: &str = "0xc64cc00b46101bd40aa1c3121195e85c0b0918d8";
@@ -11,24 +11,29 @@ async fn main() {
     open_orders_example(&info_client).await;
     user_state_example(&info_client).await;
     user_states_example(&info_client).await;
+    margin_summary_example(&info_client).await;
     recent_trades(&info_client).await;
     meta_example(&info_client).await;
     all_mids_example(&info_client).await;
     user_fills_example(&info_client).await;
     funding_history_example(&info_client).await;
+    next_funding_time_example(&info_client).await;
     l2_snapshot_example(&info_client).await;
     candles_snapshot_example(&info_client).await;
     user_token_balances_example(&info_client).await;
+    spot_dust_example(&info_client).await;
     user_fees_example(&info_client).await;
     user_funding_example(&info_client).await;
     spot_meta_example(&info_client).await;
     spot_meta_and_asset_contexts_example(&info_client).await;
+    spot_market_snapshot_example(&info_client).await;
+    perp_market_snapshot_example(&info_client).await;
     query_order_by_oid_example(&info_client).await;
     query_referral_state_example(&info_client).await;
     historical_orders_example(&info_client).await;
 }
 
-fn address() -> H160 {
+fn address() -> Address {
     ADDRESS.to_string().parse().unwrap()
 }
 
@@ -59,6 +64,24 @@ async fn user_states_example(info_client: &InfoClient) {
     );
 }
 
+async fn margin_summary_example(info_client: &InfoClient) {
+    let user = address();
+
+    info!(
+        "Margin summary for {user}: {:?}",
+        info_client.margin_summary(user).await.unwrap()
+    );
+}
+
+async fn next_funding_time_example(info_client: &InfoClient) {
+    let coin = Coin::try_from("ETH").unwrap();
+
+    info!(
+        "Next funding time for {coin}: {}",
+        info_client.next_funding_time(&coin).await.unwrap()
+    );
+}
+
 async fn user_token_balances_example(info_client: &InfoClient) {
     let user = address();
 
@@ -68,6 +91,15 @@ async fn user_token_balances_example(info_client: &InfoClient) {
     );
 }
 
+async fn spot_dust_example(info_client: &InfoClient) {
+    let user = address();
+
+    info!(
+        "Spot dust for {user}: {:?}",
+        info_client.spot_dust(user).await.unwrap()
+    );
+}
+
 async fn user_fees_example(info_client: &InfoClient) {
     let user = address();
 
@@ -104,13 +136,13 @@ async fn user_fills_example(info_client: &InfoClient) {
 }
 
 async fn funding_history_example(info_client: &InfoClient) {
-    let coin = "ETH";
+    let coin = Coin::try_from("ETH").unwrap();
 
     let start_timestamp = 1690540602225;
     let end_timestamp = 1690569402225;
     info!(
         "Funding data history for {coin} between timestamps {start_timestamp} and {end_timestamp}: {:?}",
-        info_client.funding_history(coin.to_string(), start_timestamp, Some(end_timestamp)).await.unwrap()
+        info_client.funding_history(coin.clone(), start_timestamp, Some(end_timestamp)).await.unwrap()
     );
 }
 
@@ -159,6 +191,16 @@ async fn spot_meta_and_asset_contexts_example(info_client: &InfoClient) {
     );
 }
 
+async fn spot_market_snapshot_example(info_client: &InfoClient) {
+    let snapshot = info_client.spot_market_snapshot().await.unwrap();
+    info!("PURR/USDC spot market: {:?}", snapshot.get("PURR/USDC"));
+}
+
+async fn perp_market_snapshot_example(info_client: &InfoClient) {
+    let snapshot = info_client.perp_market_snapshot().await.unwrap();
+    info!("ETH perp market: {:?}", snapshot.get("ETH"));
+}
+
 async fn query_order_by_oid_example(info_client: &InfoClient) {
     let user = address();
     let oid = 26342632321;
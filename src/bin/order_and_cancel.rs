@@ -1,9 +1,9 @@
-use ethers::signers::LocalWallet;
+use alloy::signers::local::PrivateKeySigner;
 use log::info;
 
 use hyperliquid_rust_sdk::{
-    BaseUrl, ClientCancelRequest, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient,
-    ExchangeDataStatus, ExchangeResponseStatus,
+    BaseUrl, ClientCancelRequest, DecimalOrder, ExchangeClient, ExchangeDataStatus,
+    ExchangeResponseStatus, Tif,
 };
 use std::{thread::sleep, time::Duration};
 
@@ -11,25 +11,22 @@ use std::{thread::sleep, time::Duration};
 async fn main() {
     env_logger::init();
     // Key was randomly generated for testing and shouldn't be used with any real funds
-    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
-        .parse()
-        .unwrap();
+    let wallet: PrivateKeySigner =
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap();
 
     let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
         .await
         .unwrap();
 
-    let order = ClientOrderRequest {
-        asset: "ETH".to_string(),
-        is_buy: true,
-        reduce_only: false,
-        limit_px: 1800.0,
-        sz: 0.01,
-        cloid: None,
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: "Gtc".to_string(),
-        }),
-    };
+    let order = DecimalOrder::new(
+        "ETH",
+        true,
+        "1800".parse().unwrap(),
+        "0.01".parse().unwrap(),
+        Tif::Gtc,
+    );
 
     let response = exchange_client.order(order, None).await.unwrap();
     info!("Order placed: {response:?}");
@@ -1,9 +1,7 @@
-use ethers::signers::LocalWallet;
+use alloy::signers::local::PrivateKeySigner;
 use log::info;
 
-use hyperliquid_rust_sdk::{
-    BaseUrl, ClientCancelRequestCloid, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient,
-};
+use hyperliquid_rust_sdk::{BaseUrl, ClientCancelRequestCloid, DecimalOrder, ExchangeClient, Tif};
 use std::{thread::sleep, time::Duration};
 use uuid::Uuid;
 
@@ -11,9 +9,10 @@ use uuid::Uuid;
 async fn main() {
     env_logger::init();
     // Key was randomly generated for testing and shouldn't be used with any real funds
-    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
-        .parse()
-        .unwrap();
+    let wallet: PrivateKeySigner =
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap();
 
     let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
         .await
@@ -21,16 +20,15 @@ async fn main() {
 
     // Order and Cancel with cloid
     let cloid = Uuid::new_v4();
-    let order = ClientOrderRequest {
-        asset: "ETH".to_string(),
-        is_buy: true,
-        reduce_only: false,
-        limit_px: 1800.0,
-        sz: 0.01,
+    let order = DecimalOrder {
         cloid: Some(cloid),
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: "Gtc".to_string(),
-        }),
+        ..DecimalOrder::new(
+            "ETH",
+            true,
+            "1800".parse().unwrap(),
+            "0.01".parse().unwrap(),
+            Tif::Gtc,
+        )
     };
 
     let response = exchange_client.order(order, None).await.unwrap();
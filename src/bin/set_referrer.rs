@@ -1,4 +1,4 @@
-use ethers::signers::LocalWallet;
+use alloy::signers::local::PrivateKeySigner;
 
 use hyperliquid_rust_sdk::{BaseUrl, ExchangeClient};
 use log::info;
@@ -7,9 +7,10 @@ use log::info;
 async fn main() {
     env_logger::init();
     // Key was randomly generated for testing and shouldn't be used with any real funds
-    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
-        .parse()
-        .unwrap();
+    let wallet: PrivateKeySigner =
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap();
 
     let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
         .await
@@ -0,0 +1,30 @@
+use alloy::signers::local::PrivateKeySigner;
+use hyperliquid_rust_sdk::{BaseUrl, InfoClient, TestnetFaucet};
+use log::info;
+use rust_decimal::Decimal;
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: PrivateKeySigner =
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap();
+
+    let faucet_url = std::env::var("HL_FAUCET_URL").expect("HL_FAUCET_URL must be set");
+    let faucet = TestnetFaucet::new(None, faucet_url);
+    let info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
+
+    let account_value = faucet
+        .claim_and_wait(
+            &info_client,
+            wallet.address(),
+            Decimal::from(100),
+            Duration::from_secs(60),
+        )
+        .await
+        .unwrap();
+    info!("Testnet account value: {account_value}");
+}
@@ -0,0 +1,30 @@
+use log::info;
+
+use hyperliquid_rust_sdk::{BaseUrl, InfoClient, InfoRequest, Message, Subscription};
+use std::collections::HashMap;
+use tokio::sync::mpsc::unbounded_channel;
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+
+    let mut info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
+
+    let (sender, mut receiver) = unbounded_channel();
+    info_client
+        .subscribe(Subscription::AllMids, sender)
+        .await
+        .unwrap();
+
+    // Posts go over the same socket as the AllMids subscription
+    let post_client = info_client.ws_post_client().await.unwrap();
+    let mids: HashMap<String, String> = post_client.info(&InfoRequest::AllMids).await.unwrap();
+    info!("ETH mid from ws post: {:?}", mids.get("ETH"));
+
+    if let Some(Message::AllMids(all_mids)) = receiver.recv().await {
+        info!(
+            "ETH mid from subscription: {:?}",
+            all_mids.data.mids.get("ETH")
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    helpers::parse_decimal, prelude::*, Address, BaseUrl, Error, InfoClient, LedgerUpdate,
+    LedgerUpdateData, Message, Subscription,
+};
+use alloy::{
+    network::EthereumWallet,
+    primitives::{address, TxHash, U256},
+    providers::ProviderBuilder,
+    signers::local::PrivateKeySigner,
+    sol,
+    sol_types::SolCall,
+};
+use rust_decimal::Decimal;
+use std::time::Duration;
+use tokio::{sync::mpsc::unbounded_channel, time::timeout};
+
+sol! {
+    #[sol(rpc)]
+    interface IERC20 {
+        function transfer(address to, uint256 amount) external returns (bool);
+    }
+}
+
+/// Deposits below this amount are lost by the bridge
+pub const MIN_DEPOSIT_USDC: u64 = 5;
+const USDC_DECIMALS: u32 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BridgeConfig {
+    pub bridge_address: Address,
+    pub usdc_address: Address,
+}
+
+impl BridgeConfig {
+    pub fn mainnet() -> BridgeConfig {
+        BridgeConfig {
+            bridge_address: address!("2Df1c51E09aECF9cacB7bc98cB1742757f163dF7"),
+            usdc_address: address!("af88d065e77c8cC2239327C5EDb3A432268e5831"),
+        }
+    }
+
+    pub fn testnet() -> BridgeConfig {
+        BridgeConfig {
+            bridge_address: address!("08cfc1B6b2dCF36A1480b99353A354AA8AC56f89"),
+            usdc_address: address!("1baAbB04529D43a73232B713C0FE471f7c7334d5"),
+        }
+    }
+
+    pub fn for_base_url(base_url: BaseUrl) -> Result<BridgeConfig> {
+        match base_url {
+            BaseUrl::Mainnet => Ok(Self::mainnet()),
+            BaseUrl::Testnet => Ok(Self::testnet()),
+            BaseUrl::Localhost => Err(Error::ChainNotAllowed),
+        }
+    }
+}
+
+/// Converts a USDC amount to the token's on-chain units, rejecting amounts below the bridge minimum
+pub fn usdc_to_units(usdc: Decimal) -> Result<U256> {
+    if usdc < Decimal::from(MIN_DEPOSIT_USDC) {
+        return Err(Error::Bridge(format!(
+            "deposit of {usdc} USDC is below the minimum of {MIN_DEPOSIT_USDC}"
+        )));
+    }
+    let units = (usdc * Decimal::from(10u64.pow(USDC_DECIMALS))).trunc();
+    let units = u128::try_from(units).map_err(|e| Error::Bridge(e.to_string()))?;
+    Ok(U256::from(units))
+}
+
+/// Calldata for the USDC transfer that credits `usdc` to the sender's Hyperliquid account
+pub fn deposit_calldata(config: &BridgeConfig, usdc: Decimal) -> Result<Vec<u8>> {
+    let amount = usdc_to_units(usdc)?;
+    Ok(IERC20::transferCall {
+        to: config.bridge_address,
+        amount,
+    }
+    .abi_encode())
+}
+
+/// Sends the USDC transfer to the bridge on Arbitrum and waits for the transaction receipt
+pub async fn deposit(
+    rpc_url: &str,
+    signer: PrivateKeySigner,
+    config: &BridgeConfig,
+    usdc: Decimal,
+) -> Result<TxHash> {
+    let amount = usdc_to_units(usdc)?;
+    let rpc_url = rpc_url
+        .parse()
+        .map_err(|e: url::ParseError| Error::Bridge(e.to_string()))?;
+    let provider = ProviderBuilder::new()
+        .wallet(EthereumWallet::from(signer))
+        .connect_http(rpc_url);
+
+    let receipt = IERC20::new(config.usdc_address, provider)
+        .transfer(config.bridge_address, amount)
+        .send()
+        .await
+        .map_err(|e| Error::Bridge(e.to_string()))?
+        .get_receipt()
+        .await
+        .map_err(|e| Error::Bridge(e.to_string()))?;
+
+    if !receipt.status() {
+        return Err(Error::Bridge(format!(
+            "deposit transaction {} reverted",
+            receipt.transaction_hash
+        )));
+    }
+    Ok(receipt.transaction_hash)
+}
+
+/// First deposit of at least `usdc` credited at or after `since` (ms) among `updates`
+fn matching_deposit(
+    updates: Vec<LedgerUpdateData>,
+    usdc: Decimal,
+    since: u64,
+) -> Result<Option<LedgerUpdateData>> {
+    for update in updates {
+        if update.time < since {
+            continue;
+        }
+        if let LedgerUpdate::Deposit(deposit) = &update.delta {
+            if parse_decimal(&deposit.usdc)? >= usdc {
+                return Ok(Some(update));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Waits for a deposit of at least `usdc` to show up in the user's ledger updates. `since` is a
+/// time (ms) from before the deposit was sent, so a deposit credited before the subscription
+/// started is still found in its snapshot, while older deposits are ignored.
+pub async fn await_deposit(
+    info_client: &mut InfoClient,
+    user: Address,
+    usdc: Decimal,
+    since: u64,
+    wait: Duration,
+) -> Result<LedgerUpdateData> {
+    let (sender, mut receiver) = unbounded_channel();
+    let subscription_id = info_client
+        .subscribe(Subscription::UserNonFundingLedgerUpdates { user }, sender)
+        .await?;
+
+    let deposit = timeout(wait, async {
+        while let Some(message) = receiver.recv().await {
+            let Message::UserNonFundingLedgerUpdates(updates) = message else {
+                continue;
+            };
+            let updates = updates.data.non_funding_ledger_updates;
+            if let Some(deposit) = matching_deposit(updates, usdc, since)? {
+                return Ok(deposit);
+            }
+        }
+        Err(Error::Bridge(
+            "ledger update subscription closed before the deposit arrived".to_string(),
+        ))
+    })
+    .await;
+
+    info_client.unsubscribe(subscription_id).await?;
+    deposit.map_err(|_| Error::Bridge(format!("deposit not credited within {wait:?}")))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deposit_calldata() -> Result<()> {
+        assert!(usdc_to_units(Decimal::from(4)).is_err());
+        assert_eq!(
+            usdc_to_units(Decimal::new(125, 1))?,
+            U256::from(12_500_000u64)
+        );
+
+        let calldata = deposit_calldata(&BridgeConfig::mainnet(), Decimal::from(10))?;
+        // transfer(address,uint256) selector
+        assert_eq!(calldata[..4], [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(calldata.len(), 4 + 32 + 32);
+        Ok(())
+    }
+
+    #[test]
+    fn test_matching_deposit() -> Result<()> {
+        let updates: Vec<LedgerUpdateData> = serde_json::from_value(serde_json::json!([
+            {"time": 900, "hash": "0x1", "delta": {"type": "deposit", "usdc": "100.0"}},
+            {"time": 1000, "hash": "0x2", "delta": {"type": "deposit", "usdc": "5.0"}},
+            {"time": 1100, "hash": "0x3", "delta": {"type": "deposit", "usdc": "10.0"}}
+        ]))
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        // the older, larger deposit is from before the one awaited
+        let deposit = matching_deposit(updates.clone(), Decimal::from(10), 1000)?;
+        assert_eq!(deposit.map(|deposit| deposit.hash), Some("0x3".to_string()));
+        assert!(matching_deposit(updates, Decimal::from(20), 1000)?.is_none());
+        Ok(())
+    }
+}
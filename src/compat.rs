@@ -0,0 +1,50 @@
+//! Conversions from ethers types, for callers moving to the alloy based API.
+//! Enabled with the `ethers-compat` feature and removed in the next major release.
+
+use crate::{prelude::*, Address, Error};
+use alloy::{primitives::B256, signers::local::PrivateKeySigner};
+use ethers::{
+    signers::LocalWallet,
+    types::{H160, H256},
+};
+
+pub fn address_from_ethers(address: H160) -> Address {
+    Address::from(address.0)
+}
+
+pub fn address_to_ethers(address: Address) -> H160 {
+    H160(address.0 .0)
+}
+
+pub fn b256_from_ethers(hash: H256) -> B256 {
+    B256::from(hash.0)
+}
+
+/// Builds a signer holding the same private key as `wallet`
+pub fn signer_from_ethers(wallet: &LocalWallet) -> Result<PrivateKeySigner> {
+    PrivateKeySigner::from_slice(&wallet.signer().to_bytes())
+        .map_err(|e| Error::Wallet(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signer_from_ethers() -> Result<()> {
+        let wallet: LocalWallet =
+            "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+                .parse()
+                .map_err(|e: ethers::signers::WalletError| Error::Wallet(e.to_string()))?;
+        let signer = signer_from_ethers(&wallet)?;
+        assert_eq!(
+            signer.address(),
+            address_from_ethers(ethers::signers::Signer::address(&wallet))
+        );
+        assert_eq!(
+            address_to_ethers(signer.address()),
+            ethers::signers::Signer::address(&wallet)
+        );
+        Ok(())
+    }
+}
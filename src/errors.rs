@@ -37,6 +37,8 @@ pub enum Error {
     WsManagerNotFound,
     #[error("WS send error: {0:?}")]
     WsSend(String),
+    #[error("Websocket post error: {0}")]
+    WsPost(String),
     #[error("Reader data not found")]
     ReaderDataNotFound,
     #[error("Reader error: {0:?}")]
@@ -61,4 +63,68 @@ pub enum Error {
     SignatureFailure(String),
     #[error("Vault address not found")]
     VaultAddressNotFound,
+    #[error("{0:?} is not a sub-account of this account")]
+    SubAccountNotFound(crate::Address),
+    #[error("Invalid symbol: {0}")]
+    InvalidSymbol(String),
+    #[error("Request timed out after {0:?}")]
+    Timeout(std::time::Duration),
+    #[error("Request cancelled")]
+    Cancelled,
+    #[error("Destination {0} is not in the address book")]
+    DestinationNotAllowed(String),
+    #[error("Margin mode not applied: {0}")]
+    MarginMode(String),
+    #[error("Agent rotation error: {0}")]
+    AgentRotation(String),
+    #[error("Invalid config: {0}")]
+    Config(String),
+    #[error("Invalid intent: {0}")]
+    Intent(String),
+    #[cfg(feature = "exchange")]
+    #[error("Intents partially sent, a later batch failed: {error}, sent: {results:?}")]
+    IntentsPartiallySent {
+        /// Responses of the batches sent before the failure
+        results: Box<crate::IntentResults>,
+        error: Box<Error>,
+    },
+    #[error("Signature is from {recovered}, expected {expected}")]
+    SignerMismatch {
+        expected: crate::Address,
+        recovered: crate::Address,
+    },
+    #[error("Multi-sig signatures rejected: {reason}, missing signers {missing:?}, unknown signers {unknown:?}")]
+    MultiSig {
+        reason: String,
+        missing: Vec<crate::Address>,
+        unknown: Vec<crate::Address>,
+    },
+    #[error("Bridge error: {0:?}")]
+    Bridge(String),
+    #[error("Webhook notification error: {0:?}")]
+    Notify(String),
+    #[error("Audit error: {0:?}")]
+    Audit(String),
+    #[error("Trade tape error: {0}")]
+    TradeTape(String),
+    #[error("Invalid spot deploy: {0}")]
+    SpotDeploy(String),
+    #[error("Snapshot export error: {0}")]
+    SnapshotExport(String),
+    #[error("Strategy state persistence error: {0:?}")]
+    Persistence(String),
+    #[error("Action vetoed by hook: {0}")]
+    HookVeto(String),
+    #[error("Order would cross own resting order {oid} on {asset}")]
+    SelfCross { asset: String, oid: u64 },
+    #[error("Order on {asset} exceeds exchange limits: {reason}")]
+    OrderLimit { asset: String, reason: String },
+    #[error("Order on {asset} refused by liquidity guard: {reason}")]
+    LiquidityGuard { asset: String, reason: String },
+    #[error("Invalid {token} amount: {reason}")]
+    TokenAmount { token: String, reason: String },
+    #[error("Exchange unavailable, retry after {retry_after:?}")]
+    ExchangeUnavailable {
+        retry_after: Option<std::time::Duration>,
+    },
 }
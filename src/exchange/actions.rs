@@ -1,14 +1,11 @@
-use crate::exchange::{cancel::CancelRequest, modify::ModifyRequest, order::OrderRequest};
-pub(crate) use ethers::{
-    abi::{encode, ParamType, Tokenizable},
-    types::{
-        transaction::{
-            eip712,
-            eip712::{encode_eip712_type, EIP712Domain, Eip712, Eip712Error},
-        },
-        H160, U256,
-    },
-    utils::keccak256,
+use crate::{
+    exchange::{cancel::CancelRequest, modify::ModifyRequest, order::OrderRequest},
+    signature::Eip712,
+    Address,
+};
+use alloy::{
+    primitives::{keccak256, B256, U256},
+    sol_types::{Eip712Domain, SolValue},
 };
 use serde::{Deserialize, Serialize};
 
@@ -16,20 +13,20 @@ use super::{cancel::CancelRequestCloid, BuilderInfo};
 
 pub(crate) const HYPERLIQUID_EIP_PREFIX: &str = "HyperliquidTransaction:";
 
-fn eip_712_domain(chain_id: U256) -> EIP712Domain {
-    EIP712Domain {
-        name: Some("HyperliquidSignTransaction".to_string()),
-        version: Some("1".to_string()),
+fn eip_712_domain(chain_id: U256) -> Eip712Domain {
+    Eip712Domain {
+        name: Some("HyperliquidSignTransaction".into()),
+        version: Some("1".into()),
         chain_id: Some(chain_id),
-        verifying_contract: Some(
-            "0x0000000000000000000000000000000000000000"
-                .parse()
-                .unwrap(),
-        ),
+        verifying_contract: Some(Address::ZERO),
         salt: None,
     }
 }
 
+fn type_hash(primary_type: &str, fields: &str) -> B256 {
+    keccak256(format!("{HYPERLIQUID_EIP_PREFIX}{primary_type}({fields})"))
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UsdSend {
@@ -41,25 +38,11 @@ pub struct UsdSend {
 }
 
 impl Eip712 for UsdSend {
-    type Error = Eip712Error;
-
-    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
-        Ok(eip_712_domain(self.signature_chain_id))
+    fn domain(&self) -> Eip712Domain {
+        eip_712_domain(self.signature_chain_id)
     }
 
-    fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}UsdSend"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("destination".to_string(), ParamType::String),
-                ("amount".to_string(), ParamType::String),
-                ("time".to_string(), ParamType::Uint(64)),
-            ],
-        ))
-    }
-
-    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+    fn struct_hash(&self) -> B256 {
         let Self {
             signature_chain_id: _,
             hyperliquid_chain,
@@ -67,14 +50,20 @@ impl Eip712 for UsdSend {
             amount,
             time,
         } = self;
-        let items = vec![
-            ethers::abi::Token::Uint(Self::type_hash()?.into()),
-            encode_eip712_type(hyperliquid_chain.clone().into_token()),
-            encode_eip712_type(destination.clone().into_token()),
-            encode_eip712_type(amount.clone().into_token()),
-            encode_eip712_type(time.into_token()),
-        ];
-        Ok(keccak256(encode(&items)))
+        let type_hash = type_hash(
+            "UsdSend",
+            "string hyperliquidChain,string destination,string amount,uint64 time",
+        );
+        keccak256(
+            (
+                type_hash,
+                keccak256(hyperliquid_chain),
+                keccak256(destination),
+                keccak256(amount),
+                *time,
+            )
+                .abi_encode(),
+        )
     }
 }
 
@@ -126,31 +115,17 @@ pub struct BulkCancelCloid {
 pub struct ApproveAgent {
     pub signature_chain_id: U256,
     pub hyperliquid_chain: String,
-    pub agent_address: H160,
+    pub agent_address: Address,
     pub agent_name: Option<String>,
     pub nonce: u64,
 }
 
 impl Eip712 for ApproveAgent {
-    type Error = Eip712Error;
-
-    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
-        Ok(eip_712_domain(self.signature_chain_id))
+    fn domain(&self) -> Eip712Domain {
+        eip_712_domain(self.signature_chain_id)
     }
 
-    fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}ApproveAgent"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("agentAddress".to_string(), ParamType::Address),
-                ("agentName".to_string(), ParamType::String),
-                ("nonce".to_string(), ParamType::Uint(64)),
-            ],
-        ))
-    }
-
-    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+    fn struct_hash(&self) -> B256 {
         let Self {
             signature_chain_id: _,
             hyperliquid_chain,
@@ -158,14 +133,20 @@ impl Eip712 for ApproveAgent {
             agent_name,
             nonce,
         } = self;
-        let items = vec![
-            ethers::abi::Token::Uint(Self::type_hash()?.into()),
-            encode_eip712_type(hyperliquid_chain.clone().into_token()),
-            encode_eip712_type(agent_address.into_token()),
-            encode_eip712_type(agent_name.clone().unwrap_or_default().into_token()),
-            encode_eip712_type(nonce.into_token()),
-        ];
-        Ok(keccak256(encode(&items)))
+        let type_hash = type_hash(
+            "ApproveAgent",
+            "string hyperliquidChain,address agentAddress,string agentName,uint64 nonce",
+        );
+        keccak256(
+            (
+                type_hash,
+                keccak256(hyperliquid_chain),
+                *agent_address,
+                keccak256(agent_name.as_deref().unwrap_or_default()),
+                *nonce,
+            )
+                .abi_encode(),
+        )
     }
 }
 
@@ -180,25 +161,11 @@ pub struct Withdraw3 {
 }
 
 impl Eip712 for Withdraw3 {
-    type Error = Eip712Error;
-
-    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
-        Ok(eip_712_domain(self.signature_chain_id))
+    fn domain(&self) -> Eip712Domain {
+        eip_712_domain(self.signature_chain_id)
     }
 
-    fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}Withdraw"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("destination".to_string(), ParamType::String),
-                ("amount".to_string(), ParamType::String),
-                ("time".to_string(), ParamType::Uint(64)),
-            ],
-        ))
-    }
-
-    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+    fn struct_hash(&self) -> B256 {
         let Self {
             signature_chain_id: _,
             hyperliquid_chain,
@@ -206,14 +173,20 @@ impl Eip712 for Withdraw3 {
             time,
             destination,
         } = self;
-        let items = vec![
-            ethers::abi::Token::Uint(Self::type_hash()?.into()),
-            encode_eip712_type(hyperliquid_chain.clone().into_token()),
-            encode_eip712_type(destination.clone().into_token()),
-            encode_eip712_type(amount.clone().into_token()),
-            encode_eip712_type(time.into_token()),
-        ];
-        Ok(keccak256(encode(&items)))
+        let type_hash = type_hash(
+            "Withdraw",
+            "string hyperliquidChain,string destination,string amount,uint64 time",
+        );
+        keccak256(
+            (
+                type_hash,
+                keccak256(hyperliquid_chain),
+                keccak256(destination),
+                keccak256(amount),
+                *time,
+            )
+                .abi_encode(),
+        )
     }
 }
 
@@ -229,26 +202,11 @@ pub struct SpotSend {
 }
 
 impl Eip712 for SpotSend {
-    type Error = Eip712Error;
-
-    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
-        Ok(eip_712_domain(self.signature_chain_id))
+    fn domain(&self) -> Eip712Domain {
+        eip_712_domain(self.signature_chain_id)
     }
 
-    fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}SpotSend"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("destination".to_string(), ParamType::String),
-                ("token".to_string(), ParamType::String),
-                ("amount".to_string(), ParamType::String),
-                ("time".to_string(), ParamType::Uint(64)),
-            ],
-        ))
-    }
-
-    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+    fn struct_hash(&self) -> B256 {
         let Self {
             signature_chain_id: _,
             hyperliquid_chain,
@@ -257,15 +215,21 @@ impl Eip712 for SpotSend {
             amount,
             time,
         } = self;
-        let items = vec![
-            ethers::abi::Token::Uint(Self::type_hash()?.into()),
-            encode_eip712_type(hyperliquid_chain.clone().into_token()),
-            encode_eip712_type(destination.clone().into_token()),
-            encode_eip712_type(token.clone().into_token()),
-            encode_eip712_type(amount.clone().into_token()),
-            encode_eip712_type(time.into_token()),
-        ];
-        Ok(keccak256(encode(&items)))
+        let type_hash = type_hash(
+            "SpotSend",
+            "string hyperliquidChain,string destination,string token,string amount,uint64 time",
+        );
+        keccak256(
+            (
+                type_hash,
+                keccak256(hyperliquid_chain),
+                keccak256(destination),
+                keccak256(token),
+                keccak256(amount),
+                *time,
+            )
+                .abi_encode(),
+        )
     }
 }
 
@@ -285,11 +249,29 @@ pub struct ClassTransfer {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct VaultTransfer {
-    pub vault_address: H160,
+    pub vault_address: Address,
     pub is_deposit: bool,
     pub usd: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountUsdTransfer {
+    pub sub_account_user: Address,
+    pub is_deposit: bool,
+    /// usd without decimals
+    pub usd: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountSpotTransfer {
+    pub sub_account_user: Address,
+    pub is_deposit: bool,
+    pub token: String,
+    pub amount: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SetReferrer {
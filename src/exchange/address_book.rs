@@ -0,0 +1,68 @@
+use crate::{prelude::*, Address, Error};
+use std::collections::HashMap;
+
+/// Named destinations that transfers and withdrawals from an
+/// [`ExchangeClient`](crate::ExchangeClient) are restricted to once set with `set_address_book`
+#[derive(Debug, Clone, Default)]
+pub struct AddressBook {
+    entries: HashMap<String, Address>,
+}
+
+impl AddressBook {
+    pub fn new() -> AddressBook {
+        AddressBook::default()
+    }
+
+    pub fn with_entry(mut self, name: &str, address: Address) -> Self {
+        self.insert(name, address);
+        self
+    }
+
+    pub fn insert(&mut self, name: &str, address: Address) {
+        self.entries.insert(name.to_string(), address);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Address> {
+        self.entries.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Address> {
+        self.entries.get(name).copied()
+    }
+
+    pub fn contains(&self, address: Address) -> bool {
+        self.entries.values().any(|entry| *entry == address)
+    }
+
+    /// Errors unless `destination` parses to an address in the book
+    pub(crate) fn check(&self, destination: &str) -> Result<()> {
+        match destination.parse::<Address>() {
+            Ok(address) if self.contains(address) => Ok(()),
+            _ => Err(Error::DestinationNotAllowed(destination.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_book_check() {
+        let treasury: Address = "0x0D1d9635D0640821d15e323ac8AdADfA9c111414"
+            .parse()
+            .unwrap();
+        let book = AddressBook::new().with_entry("treasury", treasury);
+
+        assert_eq!(book.get("treasury"), Some(treasury));
+        // checksum casing doesn't matter
+        assert!(book
+            .check("0x0d1d9635d0640821d15e323ac8adadfa9c111414")
+            .is_ok());
+        assert!(matches!(
+            book.check("0x0000000000000000000000000000000000000001"),
+            Err(Error::DestinationNotAllowed(_))
+        ));
+        assert!(book.check("treasury").is_err());
+    }
+}
@@ -0,0 +1,223 @@
+use crate::{
+    helpers::generate_random_key, info::poller::Poller, prelude::*, Address, Error, ExchangeClient,
+    ExchangeResponseStatus,
+};
+use alloy::{primitives::B256, signers::local::PrivateKeySigner};
+use log::{error, info};
+use std::{fmt::Debug, sync::Arc, time::Duration};
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedReceiver},
+    watch,
+};
+
+/// Receives every new agent key before it is approved, e.g. to write it to a secret store, so
+/// a restarted process can keep signing with the current agent. An error aborts the rotation.
+pub trait AgentKeySink: Debug + Send + Sync {
+    fn persist(&self, agent_name: &str, agent: &PrivateKeySigner) -> Result<()>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentRotated {
+    pub agent_name: String,
+    pub agent: Address,
+    /// Agent replaced by an unusable one, `None` on the first rotation
+    pub revoked: Option<Address>,
+}
+
+/// Rotates the agent signing for an account on a schedule. Each rotation approves a new agent
+/// with the master wallet, switches the published [`ExchangeClient`] to it, then revokes the
+/// previous agent by approving a throwaway address under its name.
+/// Agents alternate between two names, so the old agent keeps working until the switch.
+#[derive(Debug, Clone)]
+pub struct AgentRotator {
+    client: ExchangeClient,
+    master: PrivateKeySigner,
+    names: [String; 2],
+    interval: Duration,
+    key_sink: Option<Arc<dyn AgentKeySink>>,
+    /// Index in `names` and address of the agent in use
+    current: Option<(usize, Address)>,
+}
+
+impl AgentRotator {
+    /// `client` is the template of the published clients, e.g. with a vault address or hooks set.
+    /// Agents are named `"{name_prefix} a"` and `"{name_prefix} b"`.
+    pub fn new(
+        client: ExchangeClient,
+        master: PrivateKeySigner,
+        name_prefix: &str,
+        interval: Duration,
+    ) -> AgentRotator {
+        AgentRotator {
+            client,
+            master,
+            names: [format!("{name_prefix} a"), format!("{name_prefix} b")],
+            interval,
+            key_sink: None,
+            current: None,
+        }
+    }
+
+    pub fn set_key_sink(&mut self, key_sink: impl AgentKeySink + 'static) {
+        self.key_sink = Some(Arc::new(key_sink));
+    }
+
+    /// Name the next agent is approved under
+    fn next_slot(&self) -> usize {
+        self.current.map_or(0, |(slot, _)| 1 - slot)
+    }
+
+    /// Generates and persists the next agent's key
+    fn next_agent(&self, slot: usize) -> Result<PrivateKeySigner> {
+        let agent = PrivateKeySigner::from_bytes(&B256::from(generate_random_key()?))
+            .map_err(|e| Error::PrivateKeyParse(e.to_string()))?;
+        if let Some(key_sink) = &self.key_sink {
+            key_sink.persist(&self.names[slot], &agent)?;
+        }
+        Ok(agent)
+    }
+
+    async fn approve(&self, agent: Address, slot: usize) -> Result<()> {
+        let response = self
+            .client
+            .approve_agent_address(agent, Some(self.names[slot].clone()), Some(&self.master))
+            .await?;
+        match response {
+            ExchangeResponseStatus::Ok(_) => Ok(()),
+            ExchangeResponseStatus::Err(err) => Err(Error::AgentRotation(format!(
+                "approving {agent} as {} failed: {err}",
+                self.names[slot]
+            ))),
+        }
+    }
+
+    /// Runs one rotation, publishing the client signing with the new agent on `clients`
+    pub async fn rotate(
+        &mut self,
+        clients: &watch::Sender<ExchangeClient>,
+    ) -> Result<AgentRotated> {
+        let slot = self.next_slot();
+        let agent = self.next_agent(slot)?;
+        self.approve(agent.address(), slot).await?;
+
+        let mut client = self.client.clone();
+        client.wallet = agent.clone();
+        clients.send_replace(client);
+        let previous = self.current.replace((slot, agent.address()));
+
+        let mut revoked = None;
+        if let Some((previous_slot, previous_agent)) = previous {
+            // nobody holds the key of the replacement, so the old name can't sign anymore
+            let throwaway = PrivateKeySigner::from_bytes(&B256::from(generate_random_key()?))
+                .map_err(|e| Error::PrivateKeyParse(e.to_string()))?;
+            self.approve(throwaway.address(), previous_slot).await?;
+            revoked = Some(previous_agent);
+        }
+        Ok(AgentRotated {
+            agent_name: self.names[slot].clone(),
+            agent: agent.address(),
+            revoked,
+        })
+    }
+
+    /// Rotates right away and then every `interval` in the background. Returns the current
+    /// client, to be read before each action, and the stream of completed rotations.
+    /// A failed first rotation is returned as an error; later failures are logged and retried
+    /// at the next interval. The task stops once both receivers are dropped.
+    pub async fn start(
+        mut self,
+    ) -> Result<(
+        watch::Receiver<ExchangeClient>,
+        UnboundedReceiver<AgentRotated>,
+    )> {
+        let poller = Poller::new("AgentRotation", self.interval)?;
+        let (clients, client_receiver) = watch::channel(self.client.clone());
+        let (sender, receiver) = unbounded_channel();
+        let rotated = self.rotate(&clients).await?;
+        let _ = sender.send(rotated);
+
+        poller.spawn_after_interval(
+            (self, clients, sender),
+            |(mut rotation, clients, sender)| async move {
+                if sender.is_closed() && clients.is_closed() {
+                    return None;
+                }
+                match rotation.rotate(&clients).await {
+                    Ok(rotated) => {
+                        info!("Rotated agent to {}", rotated.agent);
+                        let _ = sender.send(rotated);
+                    }
+                    Err(err) => error!("Agent rotation failed: {err}"),
+                }
+                Some((rotation, clients, sender))
+            },
+        );
+        Ok((client_receiver, receiver))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::exchange_client::tests::{serve_resting_orders, test_client};
+    use std::sync::Mutex;
+    use tokio::spawn;
+
+    #[derive(Debug, Default)]
+    struct MemorySink(Mutex<Vec<(String, Address)>>);
+
+    impl AgentKeySink for Arc<MemorySink> {
+        fn persist(&self, agent_name: &str, agent: &PrivateKeySigner) -> Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .push((agent_name.to_string(), agent.address()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_agent_rotation() -> Result<()> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| Error::GenericRequest(e.to_string()))?;
+        let base_url = format!(
+            "http://{}",
+            listener
+                .local_addr()
+                .map_err(|e| Error::GenericRequest(e.to_string()))?
+        );
+        spawn(serve_resting_orders(listener));
+
+        let client = test_client(base_url)?;
+        let master = client.wallet.clone();
+        let mut rotator =
+            AgentRotator::new(client, master.clone(), "bot", Duration::from_secs(3600));
+        let sink = Arc::new(MemorySink::default());
+        rotator.set_key_sink(Arc::clone(&sink));
+
+        let (clients, mut rotations) = rotator.clone().start().await?;
+        let first = rotations.recv().await.unwrap();
+        assert_eq!(first.agent_name, "bot a");
+        assert_eq!(first.revoked, None);
+        assert_eq!(clients.borrow().wallet.address(), first.agent);
+
+        let (clients, _) = watch::channel(clients.borrow().clone());
+        rotator.current = Some((0, first.agent));
+        let second = rotator.rotate(&clients).await?;
+        assert_eq!(second.agent_name, "bot b");
+        assert_eq!(second.revoked, Some(first.agent));
+        assert_eq!(clients.borrow().wallet.address(), second.agent);
+        assert_ne!(second.agent, master.address());
+
+        let persisted = sink.0.lock().unwrap().clone();
+        assert_eq!(
+            persisted,
+            vec![
+                ("bot a".to_string(), first.agent),
+                ("bot b".to_string(), second.agent)
+            ]
+        );
+        Ok(())
+    }
+}
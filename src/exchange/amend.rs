@@ -0,0 +1,127 @@
+use crate::{
+    exchange::order_ids::parse_cloid, prelude::*, BasicOrderInfo, ClientLimit, ClientOrder,
+    ClientOrderRequest, Error, ExchangeDataStatus, ExchangeResponseStatus,
+};
+
+/// How [`ExchangeClient::amend_order`](crate::ExchangeClient::amend_order) changed an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmendMethod {
+    /// Same price with a smaller size, sent as a modify since it may keep the order's queue priority
+    Modify,
+    /// Any other change loses queue priority, so the order is cancelled and placed again
+    CancelReplace,
+}
+
+impl AmendMethod {
+    pub fn choose(px: f64, sz: f64, new_px: f64, new_sz: f64) -> AmendMethod {
+        if new_px == px && new_sz < sz {
+            AmendMethod::Modify
+        } else {
+            AmendMethod::CancelReplace
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AmendResult {
+    pub method: AmendMethod,
+    /// Response to the modify, or to the cancel for [`AmendMethod::CancelReplace`]
+    pub response: ExchangeResponseStatus,
+    /// Response to the replacement order, `None` for a modify or when the cancel failed
+    pub replace_response: Option<ExchangeResponseStatus>,
+}
+
+/// True if every status in the response is a success, e.g. for a cancel
+pub(crate) fn is_success(response: &ExchangeResponseStatus) -> bool {
+    match response {
+        ExchangeResponseStatus::Ok(response) => response.data.as_ref().is_some_and(|data| {
+            data.statuses
+                .iter()
+                .all(|status| matches!(status, ExchangeDataStatus::Success))
+        }),
+        ExchangeResponseStatus::Err(_) => false,
+    }
+}
+
+/// Request for a resting limit order with a new price and size, keeping its side, tif,
+/// reduce only flag and cloid
+pub(crate) fn amended_request(
+    order: &BasicOrderInfo,
+    new_px: f64,
+    new_sz: f64,
+) -> Result<ClientOrderRequest> {
+    if order.is_trigger {
+        return Err(Error::GenericRequest(format!(
+            "Order {} is a trigger order, only resting limit orders can be amended",
+            order.oid
+        )));
+    }
+    let Some(tif) = order.tif else {
+        return Err(Error::GenericParse(format!(
+            "Order {} has no limit order tif",
+            order.oid
+        )));
+    };
+    let cloid = order.cloid.as_deref().map(parse_cloid).transpose()?;
+
+    Ok(ClientOrderRequest {
+        asset: order.coin.clone(),
+        is_buy: order.side == "B",
+        reduce_only: order.reduce_only,
+        limit_px: new_px,
+        sz: new_sz,
+        cloid,
+        stp: None,
+        order_type: ClientOrder::Limit(ClientLimit { tif }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tif;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_amend_method_and_request() {
+        assert_eq!(
+            AmendMethod::choose(100.0, 2.0, 100.0, 1.0),
+            AmendMethod::Modify
+        );
+        assert_eq!(
+            AmendMethod::choose(100.0, 2.0, 100.0, 3.0),
+            AmendMethod::CancelReplace
+        );
+        assert_eq!(
+            AmendMethod::choose(100.0, 2.0, 101.0, 1.0),
+            AmendMethod::CancelReplace
+        );
+
+        let order: BasicOrderInfo = serde_json::from_value(serde_json::json!({
+            "coin": "ETH", "side": "A", "limitPx": "2000", "sz": "2", "oid": 7, "timestamp": 0,
+            "triggerCondition": "N/A", "isTrigger": false, "triggerPx": "0", "isPositionTpsl": false,
+            "reduceOnly": true, "orderType": "Limit", "origSz": "2", "tif": "Alo",
+            "cloid": "0x00000000000000000000000000000001"
+        }))
+        .unwrap();
+        let request = amended_request(&order, 2010.0, 1.5).unwrap();
+        assert!(!request.is_buy);
+        assert!(request.reduce_only);
+        assert_eq!(request.limit_px, 2010.0);
+        assert_eq!(request.cloid, Some(Uuid::from_u128(1)));
+        assert!(matches!(
+            request.order_type,
+            ClientOrder::Limit(ClientLimit { tif: Tif::Alo })
+        ));
+
+        let market: BasicOrderInfo = serde_json::from_value(serde_json::json!({
+            "coin": "ETH", "side": "B", "limitPx": "2000", "sz": "2", "oid": 8, "timestamp": 0,
+            "triggerCondition": "N/A", "isTrigger": false, "triggerPx": "0", "isPositionTpsl": false,
+            "reduceOnly": false, "orderType": "Limit", "origSz": "2", "tif": "FrontendMarket",
+            "cloid": null
+        }))
+        .unwrap();
+        assert_eq!(market.tif, None);
+        assert!(amended_request(&market, 2010.0, 1.5).is_err());
+    }
+}
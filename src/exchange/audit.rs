@@ -0,0 +1,164 @@
+use crate::Address;
+use crate::{prelude::*, Error};
+use alloy::primitives::B256;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Debug,
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::Mutex,
+};
+
+/// Everything sent to and received from `/exchange` for one signed action
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    pub action: serde_json::Value,
+    /// L1 action hash (connection id), or the EIP-712 hash for user-signed actions
+    pub action_hash: B256,
+    pub nonce: u64,
+    pub vault_address: Option<Address>,
+    pub signature: String,
+    /// Raw response body, `None` if the request failed
+    pub response: Option<String>,
+    pub error: Option<String>,
+    /// Local times in milliseconds
+    pub sent_at: u64,
+    pub received_at: u64,
+}
+
+/// Store for audit records. Failures to record are logged and don't fail the action.
+pub trait AuditSink: Debug + Send + Sync {
+    fn record(&self, record: &AuditRecord) -> Result<()>;
+}
+
+/// Appends audit records to a file as JSON lines
+#[derive(Debug)]
+pub struct FileAuditSink {
+    file: Mutex<File>,
+}
+
+impl FileAuditSink {
+    pub fn new(path: impl AsRef<Path>) -> Result<FileAuditSink> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| Error::Audit(e.to_string()))?;
+        Ok(FileAuditSink {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl AuditSink for FileAuditSink {
+    fn record(&self, record: &AuditRecord) -> Result<()> {
+        let mut line =
+            serde_json::to_string(record).map_err(|e| Error::JsonParse(e.to_string()))?;
+        line.push('\n');
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.flush())
+            .map_err(|e| Error::Audit(e.to_string()))
+    }
+}
+
+/// Stores audit records in an `audit_records` sqlite table
+#[cfg(feature = "audit-sqlite")]
+#[derive(Debug)]
+pub struct SqliteAuditSink {
+    connection: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "audit-sqlite")]
+impl SqliteAuditSink {
+    pub fn new(path: impl AsRef<Path>) -> Result<SqliteAuditSink> {
+        let connection =
+            rusqlite::Connection::open(path).map_err(|e| Error::Audit(e.to_string()))?;
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS audit_records (
+                    id INTEGER PRIMARY KEY,
+                    nonce INTEGER NOT NULL,
+                    action TEXT NOT NULL,
+                    action_hash TEXT NOT NULL,
+                    vault_address TEXT,
+                    signature TEXT NOT NULL,
+                    response TEXT,
+                    error TEXT,
+                    sent_at INTEGER NOT NULL,
+                    received_at INTEGER NOT NULL
+                )",
+                (),
+            )
+            .map_err(|e| Error::Audit(e.to_string()))?;
+        Ok(SqliteAuditSink {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+#[cfg(feature = "audit-sqlite")]
+impl AuditSink for SqliteAuditSink {
+    fn record(&self, record: &AuditRecord) -> Result<()> {
+        let connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        connection
+            .execute(
+                "INSERT INTO audit_records
+                    (nonce, action, action_hash, vault_address, signature, response, error, sent_at, received_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![
+                    record.nonce as i64,
+                    record.action.to_string(),
+                    format!("{:?}", record.action_hash),
+                    record.vault_address.map(|address| format!("{address:?}")),
+                    record.signature,
+                    record.response,
+                    record.error,
+                    record.sent_at as i64,
+                    record.received_at as i64,
+                ],
+            )
+            .map_err(|e| Error::Audit(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+
+    #[test]
+    fn test_file_audit_sink() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("audit-{}.jsonl", uuid::Uuid::new_v4()));
+        let sink = FileAuditSink::new(&path)?;
+        let record = AuditRecord {
+            action: serde_json::json!({"type": "setReferrer", "code": "CODE"}),
+            action_hash: B256::ZERO,
+            nonce: 1,
+            vault_address: None,
+            signature: "0x00".to_string(),
+            response: Some(r#"{"status": "ok"}"#.to_string()),
+            error: None,
+            sent_at: 1,
+            received_at: 2,
+        };
+        sink.record(&record)?;
+        sink.record(&record)?;
+
+        let file = File::open(&path).map_err(|e| Error::Audit(e.to_string()))?;
+        let lines: Vec<String> = BufReader::new(file)
+            .lines()
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|e| Error::Audit(e.to_string()))?;
+        std::fs::remove_file(&path).map_err(|e| Error::Audit(e.to_string()))?;
+
+        assert_eq!(lines.len(), 2);
+        let parsed: AuditRecord =
+            serde_json::from_str(&lines[0]).map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(parsed, record);
+        Ok(())
+    }
+}
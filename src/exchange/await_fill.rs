@@ -0,0 +1,295 @@
+use crate::{
+    helpers::uuid_to_hex_string, prelude::*, Address, Error, ExchangeClient, InfoClient, Message,
+    Subscription, TradeInfo, UserFillsResponse,
+};
+use log::warn;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    spawn,
+    sync::{mpsc::unbounded_channel, watch},
+    task::JoinHandle,
+    time::sleep,
+};
+use uuid::Uuid;
+
+/// How often [`ExchangeClient::await_fill`] polls fills when it can't use the ws
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Fills a [`FillWatcher`] keeps for calls looking for orders that filled before them
+const RECENT_FILLS: usize = 2000;
+
+fn to_trade_info(fill: UserFillsResponse) -> TradeInfo {
+    TradeInfo {
+        coin: fill.coin,
+        side: fill.side,
+        px: fill.px,
+        sz: fill.sz,
+        time: fill.time,
+        hash: fill.hash,
+        start_position: fill.start_position,
+        dir: fill.dir,
+        closed_pnl: fill.closed_pnl,
+        oid: fill.oid,
+        cloid: fill.cloid,
+        crossed: fill.crossed,
+        fee: fill.fee,
+        fee_token: fill.fee_token,
+        tid: fill.tid,
+    }
+}
+
+fn fills_of<'a>(fills: impl IntoIterator<Item = &'a TradeInfo>, cloid: &str) -> Vec<TradeInfo> {
+    fills
+        .into_iter()
+        .filter(|fill| {
+            fill.cloid
+                .as_deref()
+                .is_some_and(|fill_cloid| fill_cloid.eq_ignore_ascii_case(cloid))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Recent fills of the trading account from one `userFills` subscription, shared by every
+/// [`ExchangeClient::await_fill`] call of a client and its clones. The subscription ends
+/// when the last clone is dropped.
+#[derive(Debug)]
+pub(crate) struct FillWatcher {
+    fills: Arc<Mutex<VecDeque<TradeInfo>>>,
+    /// Whether the subscription is live, sent again whenever fills arrive
+    live: watch::Receiver<bool>,
+    task: JoinHandle<()>,
+}
+
+impl Drop for FillWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Records the fills of a `userFills` message, or that the subscription disconnected. Fills
+/// already recorded, like those replayed in the snapshot after a reconnect, are skipped.
+fn on_fills_message(
+    message: Message,
+    fills: &Mutex<VecDeque<TradeInfo>>,
+    live: &watch::Sender<bool>,
+) {
+    match message {
+        Message::UserFills(user_fills) => {
+            {
+                let mut fills = fills.lock().unwrap_or_else(|e| e.into_inner());
+                for fill in user_fills.data.fills {
+                    if fills.iter().any(|recorded| recorded.tid == fill.tid) {
+                        continue;
+                    }
+                    if fills.len() == RECENT_FILLS {
+                        fills.pop_front();
+                    }
+                    fills.push_back(fill);
+                }
+            }
+            live.send_replace(true);
+        }
+        Message::NoData => {
+            warn!("Fills subscription disconnected, polling fills until it is back");
+            live.send_replace(false);
+        }
+        _ => {}
+    }
+}
+
+impl FillWatcher {
+    async fn start(mut info_client: InfoClient, user: Address) -> Result<FillWatcher> {
+        let (sender, mut receiver) = unbounded_channel();
+        info_client
+            .subscribe(Subscription::UserFills { user }, sender)
+            .await?;
+        let fills = Arc::new(Mutex::new(VecDeque::new()));
+        // live once the snapshot arrives
+        let (live_sender, live) = watch::channel(false);
+        let task_fills = fills.clone();
+        let task = spawn(async move {
+            // owns the client so the subscription lives as long as the watcher
+            let _info_client = info_client;
+            while let Some(message) = receiver.recv().await {
+                on_fills_message(message, &task_fills, &live_sender);
+            }
+            warn!("Fills subscription ended, polling fills from now on");
+            live_sender.send_replace(false);
+        });
+        Ok(FillWatcher { fills, live, task })
+    }
+
+    fn fills_of(&self, cloid: &str) -> Vec<TradeInfo> {
+        fills_of(
+            self.fills.lock().unwrap_or_else(|e| e.into_inner()).iter(),
+            cloid,
+        )
+    }
+
+    /// Waits until fills of `cloid` are seen, polling them while the subscription is down
+    async fn wait(&self, info_client: &InfoClient, user: Address, cloid: &str) -> Vec<TradeInfo> {
+        let mut live = self.live.clone();
+        loop {
+            let is_live = *live.borrow_and_update();
+            let fills = self.fills_of(cloid);
+            if !fills.is_empty() {
+                return fills;
+            }
+            if is_live {
+                if live.changed().await.is_err() {
+                    return polled_fills(info_client, user, cloid).await;
+                }
+                continue;
+            }
+            let fills = polled_fills_once(info_client, user, cloid).await;
+            if !fills.is_empty() {
+                return fills;
+            }
+            tokio::select! {
+                _ = live.changed() => {}
+                _ = sleep(POLL_INTERVAL) => {}
+            }
+        }
+    }
+}
+
+/// Fills of `cloid` among the account's fills, empty if there are none or the poll failed
+async fn polled_fills_once(info_client: &InfoClient, user: Address, cloid: &str) -> Vec<TradeInfo> {
+    match info_client.user_fills(user).await {
+        Ok(fills) => {
+            let fills: Vec<_> = fills.into_iter().map(to_trade_info).collect();
+            fills_of(&fills, cloid)
+        }
+        Err(err) => {
+            warn!("Could not poll fills of {cloid}: {err}");
+            Vec::new()
+        }
+    }
+}
+
+async fn polled_fills(info_client: &InfoClient, user: Address, cloid: &str) -> Vec<TradeInfo> {
+    loop {
+        let fills = polled_fills_once(info_client, user, cloid).await;
+        if !fills.is_empty() {
+            return fills;
+        }
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+impl ExchangeClient {
+    /// Waits until the order with `cloid` fills, fully or partially, and returns its fills.
+    /// Fills come from one `userFills` subscription of the account the client trades for,
+    /// opened by the first call and shared by later calls and clones. Its snapshot and the
+    /// most recent fills it kept also cover orders that filled before the call. While the
+    /// subscription can't be used or is disconnected, the account's fills are polled instead.
+    /// Fails with [`Error::Timeout`] after `timeout`.
+    pub async fn await_fill(&self, cloid: Uuid, timeout: Duration) -> Result<Vec<TradeInfo>> {
+        let cloid = uuid_to_hex_string(cloid);
+        tokio::time::timeout(timeout, async {
+            let user = self.account_of(self.wallet.address()).await?;
+            let info_client = self.info_client();
+            let watcher = self
+                .fill_watcher
+                .get_or_try_init(|| FillWatcher::start(self.info_client().reconnecting(), user))
+                .await;
+            match watcher {
+                Ok(watcher) => Ok(watcher.wait(&info_client, user, &cloid).await),
+                Err(err) => {
+                    warn!("Polling fills of {cloid}, could not subscribe to fills: {err}");
+                    Ok(polled_fills(&info_client, user, &cloid).await)
+                }
+            }
+        })
+        .await
+        .map_err(|_| Error::Timeout(timeout))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws::trade_info;
+
+    fn fill(tid: u64, cloid: Option<&str>) -> TradeInfo {
+        TradeInfo {
+            tid,
+            cloid: cloid.map(str::to_string),
+            ..trade_info("ETH", "B", "1800.0", "0.1")
+        }
+    }
+
+    #[test]
+    fn test_fills_of_cloid() {
+        let cloid = uuid_to_hex_string(Uuid::from_u128(0xabc));
+        let fills = fills_of(
+            &[
+                fill(1, Some(&cloid.to_uppercase().replace("0X", "0x"))),
+                fill(2, None),
+                fill(3, Some("0x0000000000000000000000000000000f")),
+                fill(4, Some(&cloid)),
+            ],
+            &cloid,
+        );
+        assert_eq!(
+            fills.iter().map(|fill| fill.tid).collect::<Vec<_>>(),
+            vec![1, 4]
+        );
+    }
+
+    #[test]
+    fn test_fill_messages_are_recorded_once() {
+        let user_fills = |tids: &[u64]| {
+            Message::UserFills(crate::UserFills {
+                data: crate::UserFillsData {
+                    is_snapshot: None,
+                    user: Address::ZERO,
+                    fills: tids.iter().map(|tid| fill(*tid, Some("0x1"))).collect(),
+                },
+            })
+        };
+        let fills = Mutex::new(VecDeque::new());
+        let (live_sender, live) = watch::channel(false);
+
+        on_fills_message(user_fills(&[1, 2]), &fills, &live_sender);
+        assert!(*live.borrow());
+        on_fills_message(Message::NoData, &fills, &live_sender);
+        assert!(!*live.borrow());
+        // the snapshot replayed after a reconnect repeats fills already seen
+        on_fills_message(user_fills(&[1, 2, 3]), &fills, &live_sender);
+        assert!(*live.borrow());
+        let tids: Vec<_> = fills.lock().unwrap().iter().map(|fill| fill.tid).collect();
+        assert_eq!(tids, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_agent_fills_are_polled_under_master() -> Result<()> {
+        use crate::exchange::exchange_client::tests::{json_server, test_client};
+        use serde_json::json;
+
+        let cloid = Uuid::from_u128(0xabc);
+        let hex_cloid = uuid_to_hex_string(cloid);
+        let master = Address::repeat_byte(7);
+        // answers the websocket upgrade like any request, so subscribing fails and fills are polled
+        let base_url = json_server(move |_, body| match body["type"].as_str() {
+            Some("userRole") => Some(json!({"role": "agent", "data": {"user": master}})),
+            Some("userFills") if body["user"] == json!(master) => Some(json!([{
+                "closedPnl": "0.0", "coin": "ETH", "crossed": false, "dir": "Open Long",
+                "hash": "0x", "oid": 7, "cloid": hex_cloid, "px": "1800.0", "side": "B",
+                "startPosition": "0.0", "sz": "0.1", "time": 1700000000000u64, "fee": "0.01",
+                "tid": 1
+            }])),
+            _ => Some(json!([])),
+        })
+        .await;
+
+        let client = test_client(base_url)?;
+        let fills = client.await_fill(cloid, Duration::from_secs(5)).await?;
+        assert_eq!(fills.len(), 1);
+        Ok(())
+    }
+}
@@ -6,48 +6,136 @@ use crate::{
             UpdateIsolatedMargin, UpdateLeverage, UsdSend,
         },
         cancel::{CancelRequest, CancelRequestCloid},
+        intents::IntentBatches,
         modify::{ClientModifyRequest, ModifyRequest},
-        ClientCancelRequest, ClientOrderRequest,
+        ClientCancelRequest, ClientOrderRequest, DecimalOrder,
     },
-    helpers::{generate_random_key, next_nonce, uuid_to_hex_string},
+    helpers::{generate_random_key, next_nonce, now_timestamp_ms, uuid_to_hex_string},
     info::info_client::InfoClient,
-    meta::Meta,
+    meta::{Meta, PerpDexMeta, SpotMeta, TokenInfo},
     prelude::*,
-    req::HttpClient,
-    signature::sign_l1_action,
-    BaseUrl, BulkCancelCloid, Error, ExchangeResponseStatus,
+    req::{HttpClient, RequestOptions},
+    signature::{sign_l1_action, Eip712},
+    Address, AssetInfo, AssetRegistry, BaseUrl, BulkCancelCloid, Coin, Error,
+    ExchangeResponseStatus, ExchangeStatusMonitor, HyperliquidChain, Intent, IntentResults,
+    Leverage, NetworkConfig, Px, RateLimitConfig, RateLimiter, Sz,
 };
-use crate::{ClassTransfer, SpotSend, SpotUser, VaultTransfer, Withdraw3};
-use ethers::{
-    abi::AbiEncode,
-    signers::{LocalWallet, Signer},
-    types::{Signature, H160, H256},
+use crate::{
+    ClassTransfer, SpotSend, SpotUser, SubAccountSpotTransfer, SubAccountUsdTransfer, Tif,
+    UserRole, VaultTransfer, Withdraw3,
+};
+use alloy::{
+    primitives::{keccak256, Signature, B256, U256},
+    signers::{local::PrivateKeySigner, SignerSync},
 };
-use log::debug;
+use log::{debug, error};
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use rust_decimal::Decimal;
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
+use uuid::Uuid;
 
+use super::address_book::AddressBook;
+use super::amend::{amended_request, is_success};
+use super::audit::{AuditRecord, AuditSink};
 use super::cancel::ClientCancelRequestCloid;
+use super::hooks::{ExchangeHooks, HookContext};
 use super::order::{MarketCloseParams, MarketOrderParams};
-use super::{BuilderInfo, ClientLimit, ClientOrder};
+use super::order_ids::{record_placed_orders, OrderIdStore};
+use super::order_results::{
+    checked_status, unknown_fate, zip_statuses, OrderPlacement, OrderRejection, OrderResult,
+};
+use super::recovery::is_unknown_fate;
+use super::self_cross::{with_prevented_statuses, PendingOrder};
+use super::{
+    AmendMethod, AmendResult, BuilderInfo, ClientLimit, ClientOrder, MarginMode, OrderDefaults,
+    OrderLimits, SelfCrossGuard, SelfCrossPolicy, SpotDeploy,
+};
 
-#[derive(Debug)]
+/// Cheap to clone: clones share the metadata, the self-cross guard, hooks and stores, so one
+/// client can be cloned into several tasks to place and cancel orders concurrently.
+#[derive(Debug, Clone)]
 pub struct ExchangeClient {
     pub http_client: HttpClient,
-    pub wallet: LocalWallet,
-    pub meta: Meta,
-    pub vault_address: Option<H160>,
-    pub coin_to_asset: HashMap<String, u32>,
+    pub wallet: PrivateKeySigner,
+    pub meta: Arc<Meta>,
+    pub vault_address: Option<Address>,
+    pub coin_to_asset: Arc<HashMap<String, u32>>,
+    pub self_cross_guard: Option<Arc<SelfCrossGuard>>,
+    /// Checked against every order and modify before signing
+    pub order_limits: Option<Arc<OrderLimits>>,
+    /// Index of each builder-deployed perp dex loaded by `load_perp_dexs`
+    pub perp_dex_indices: Arc<HashMap<String, usize>>,
+    hooks: ExchangeHooks,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    address_book: Option<Arc<AddressBook>>,
+    order_id_store: Option<Arc<dyn OrderIdStore>>,
+    asset_registry: Option<AssetRegistry>,
+    /// Looks up coins missing from `meta` when no `asset_registry` is set, created on first use
+    /// and shared by clones
+    fallback_registry: Arc<OnceLock<AssetRegistry>>,
+    order_defaults: OrderDefaults,
+    status_monitor: Option<ExchangeStatusMonitor>,
+    request_options: RequestOptions,
+    network: NetworkConfig,
+    /// Fills subscription used by `await_fill`, opened on first use and shared by clones
+    #[cfg(feature = "ws")]
+    pub(crate) fill_watcher: Arc<tokio::sync::OnceCell<super::await_fill::FillWatcher>>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ExchangePayload {
     action: serde_json::Value,
+    #[serde(serialize_with = "serialize_signature")]
     signature: Signature,
     nonce: u64,
-    vault_address: Option<H160>,
+    vault_address: Option<Address>,
+}
+
+impl ExchangePayload {
+    fn body(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| Error::JsonParse(e.to_string()))
+    }
+}
+
+/// Failure of a signed action, telling failures of the request itself apart from the ones
+/// raised before it was sent or while reading its response
+#[derive(Debug)]
+pub(crate) enum PostError {
+    Local(Error),
+    /// The request was sent but got no usable response, so the action may have landed
+    Request(Error),
+}
+
+impl From<Error> for PostError {
+    fn from(err: Error) -> PostError {
+        PostError::Local(err)
+    }
+}
+
+impl From<PostError> for Error {
+    fn from(err: PostError) -> Error {
+        match err {
+            PostError::Local(err) => err,
+            PostError::Request(err) => Error::JsonParse(err.to_string()),
+        }
+    }
+}
+
+/// The exchange takes `v` as 27 or 28 rather than alloy's y parity
+fn serialize_signature<S: Serializer>(
+    signature: &Signature,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    let mut state = serializer.serialize_struct("Signature", 3)?;
+    state.serialize_field("r", &signature.r())?;
+    state.serialize_field("s", &signature.s())?;
+    state.serialize_field("v", &(27 + signature.v() as u64))?;
+    state.end()
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -65,114 +153,601 @@ pub enum Actions {
     Withdraw3(Withdraw3),
     SpotUser(SpotUser),
     VaultTransfer(VaultTransfer),
+    SubAccountTransfer(SubAccountUsdTransfer),
+    SubAccountSpotTransfer(SubAccountSpotTransfer),
     SpotSend(SpotSend),
     SetReferrer(SetReferrer),
     ApproveBuilderFee(ApproveBuilderFee),
+    SpotDeploy(SpotDeploy),
+    /// Does nothing but use up its nonce
+    Noop,
+    /// Action JSON sent through [`ExchangeClient::raw_action`], including its `type`
+    #[serde(untagged)]
+    Raw(serde_json::Value),
+}
+
+/// Everything [`ExchangeClient::preview`] would send for an action, for comparing with other SDKs
+/// or reviewing before trading live
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedPayloadPreview {
+    /// Exact `/exchange` request body
+    pub body: String,
+    /// Bytes hashed into the L1 action hash: the msgpack action, the nonce and the vault address
+    /// marker. `None` for user-signed actions, which are signed as EIP-712 typed data.
+    pub msgpack_hex: Option<String>,
+    /// L1 action hash (connection id), or the EIP-712 hash for user-signed actions
+    pub action_hash: B256,
+    pub signature: String,
+    pub nonce: u64,
+    pub vault_address: Option<Address>,
 }
 
 impl Actions {
-    fn hash(&self, timestamp: u64, vault_address: Option<H160>) -> Result<H256> {
-        let mut bytes =
-            rmp_serde::to_vec_named(self).map_err(|e| Error::RmpParse(e.to_string()))?;
+    pub(crate) fn is_user_signed(&self) -> bool {
+        matches!(
+            self,
+            Actions::UsdSend(_)
+                | Actions::ApproveAgent(_)
+                | Actions::Withdraw3(_)
+                | Actions::SpotSend(_)
+        )
+    }
+
+    fn hash(&self, timestamp: u64, vault_address: Option<Address>) -> Result<B256> {
+        Ok(keccak256(self.hash_input(timestamp, vault_address)?))
+    }
+
+    fn hash_input(&self, timestamp: u64, vault_address: Option<Address>) -> Result<Vec<u8>> {
+        // Human readable so addresses and chain ids are packed as hex strings, as the exchange does
+        let mut bytes = Vec::new();
+        self.serialize(
+            &mut rmp_serde::Serializer::new(&mut bytes)
+                .with_struct_map()
+                .with_human_readable(),
+        )
+        .map_err(|e| Error::RmpParse(e.to_string()))?;
         bytes.extend(timestamp.to_be_bytes());
         if let Some(vault_address) = vault_address {
             bytes.push(1);
-            bytes.extend(vault_address.to_fixed_bytes());
+            bytes.extend(vault_address.as_slice());
         } else {
             bytes.push(0);
         }
-        Ok(H256(ethers::utils::keccak256(bytes)))
+        Ok(bytes)
+    }
+
+    /// Hash covered by the action's signature: the EIP-712 hash for user-signed actions,
+    /// the L1 action hash otherwise
+    pub(crate) fn signed_hash(
+        &self,
+        timestamp: u64,
+        vault_address: Option<Address>,
+    ) -> Result<B256> {
+        match self {
+            Actions::UsdSend(usd_send) => Ok(usd_send.eip712_signing_hash()),
+            Actions::ApproveAgent(approve_agent) => Ok(approve_agent.eip712_signing_hash()),
+            Actions::Withdraw3(withdraw) => Ok(withdraw.eip712_signing_hash()),
+            Actions::SpotSend(spot_send) => Ok(spot_send.eip712_signing_hash()),
+            _ => self.hash(timestamp, vault_address),
+        }
     }
 }
 
 impl ExchangeClient {
     pub async fn new(
         client: Option<Client>,
-        wallet: LocalWallet,
+        wallet: PrivateKeySigner,
+        base_url: Option<BaseUrl>,
+        meta: Option<Meta>,
+        vault_address: Option<Address>,
+    ) -> Result<ExchangeClient> {
+        Self::with_network_config(
+            client,
+            wallet,
+            base_url,
+            meta,
+            vault_address,
+            NetworkConfig::default(),
+        )
+        .await
+    }
+
+    /// Like [`ExchangeClient::new`], for forks and test environments whose asset numbering,
+    /// price precision or signature chain id differ from mainnet
+    pub async fn with_network_config(
+        client: Option<Client>,
+        wallet: PrivateKeySigner,
+        base_url: Option<BaseUrl>,
+        meta: Option<Meta>,
+        vault_address: Option<Address>,
+        network: NetworkConfig,
+    ) -> Result<ExchangeClient> {
+        Self::connect(
+            client,
+            wallet,
+            base_url,
+            meta,
+            vault_address,
+            network,
+            RequestOptions::default(),
+        )
+        .await
+    }
+
+    /// Like [`ExchangeClient::new`], with every request, including the metadata fetched here,
+    /// spaced out and retried by a rate limiter built from `config`
+    pub async fn with_rate_limit(
+        client: Option<Client>,
+        wallet: PrivateKeySigner,
+        base_url: Option<BaseUrl>,
+        meta: Option<Meta>,
+        vault_address: Option<Address>,
+        config: RateLimitConfig,
+    ) -> Result<ExchangeClient> {
+        Self::connect(
+            client,
+            wallet,
+            base_url,
+            meta,
+            vault_address,
+            NetworkConfig::default(),
+            RequestOptions::default().with_rate_limit(RateLimiter::new(config)),
+        )
+        .await
+    }
+
+    async fn connect(
+        client: Option<Client>,
+        wallet: PrivateKeySigner,
         base_url: Option<BaseUrl>,
         meta: Option<Meta>,
-        vault_address: Option<H160>,
+        vault_address: Option<Address>,
+        network: NetworkConfig,
+        request_options: RequestOptions,
     ) -> Result<ExchangeClient> {
-        let client = client.unwrap_or_default();
         let base_url = base_url.unwrap_or(BaseUrl::Mainnet);
 
-        let info = InfoClient::new(None, Some(base_url)).await?;
+        let info = InfoClient::new(None, Some(base_url))
+            .await?
+            .with_request_options(request_options.clone());
         let meta = if let Some(meta) = meta {
             meta
         } else {
             info.meta().await?
         };
+        let spot_meta = info.spot_meta().await?;
+
+        let mut exchange_client = Self::from_meta(
+            client,
+            wallet,
+            base_url,
+            meta,
+            &spot_meta,
+            vault_address,
+            network,
+        );
+        exchange_client.request_options = request_options;
+        Ok(exchange_client)
+    }
 
+    /// Builds a client from metadata fetched elsewhere without making any request, e.g. to
+    /// share one fetch between several clients
+    pub fn from_meta(
+        client: Option<Client>,
+        wallet: PrivateKeySigner,
+        base_url: BaseUrl,
+        meta: Meta,
+        spot_meta: &SpotMeta,
+        vault_address: Option<Address>,
+        network: NetworkConfig,
+    ) -> ExchangeClient {
         let mut coin_to_asset = HashMap::new();
         for (asset_ind, asset) in meta.universe.iter().enumerate() {
             coin_to_asset.insert(asset.name.clone(), asset_ind as u32);
         }
+        coin_to_asset = spot_meta.add_pair_and_name_to_index_map_for(coin_to_asset, &network);
 
-        coin_to_asset = info
-            .spot_meta()
-            .await?
-            .add_pair_and_name_to_index_map(coin_to_asset);
-
-        Ok(ExchangeClient {
+        ExchangeClient {
             wallet,
-            meta,
+            meta: Arc::new(meta),
             vault_address,
             http_client: HttpClient {
-                client,
+                client: client.unwrap_or_default(),
                 base_url: base_url.get_url(),
             },
-            coin_to_asset,
-        })
+            coin_to_asset: Arc::new(coin_to_asset),
+            self_cross_guard: None,
+            order_limits: None,
+            perp_dex_indices: Arc::default(),
+            hooks: ExchangeHooks::default(),
+            audit_sink: None,
+            address_book: None,
+            order_id_store: None,
+            asset_registry: None,
+            fallback_registry: Arc::default(),
+            order_defaults: OrderDefaults::default(),
+            status_monitor: None,
+            request_options: RequestOptions::default(),
+            network,
+            #[cfg(feature = "ws")]
+            fill_watcher: Arc::default(),
+        }
+    }
+
+    /// Fetches every builder-deployed perp dex so orders can use names like "xyz:XYZ100"
+    pub async fn load_perp_dexs(&mut self) -> Result<()> {
+        let info_client = self.info_client();
+        let perp_dexs = info_client.perp_dexs().await?;
+
+        for (dex_index, perp_dex) in perp_dexs.into_iter().enumerate() {
+            let Some(perp_dex) = perp_dex else {
+                continue;
+            };
+            let perp_dex_meta = PerpDexMeta {
+                meta: info_client.meta_for_dex(&perp_dex.name).await?,
+                dex: perp_dex.name,
+                dex_index,
+            };
+            let coin_to_asset = Arc::make_mut(&mut self.coin_to_asset);
+            *coin_to_asset = perp_dex_meta
+                .add_perp_to_asset_map_for(std::mem::take(coin_to_asset), &self.network);
+            Arc::make_mut(&mut self.perp_dex_indices).insert(perp_dex_meta.dex, dex_index);
+        }
+        Ok(())
+    }
+
+    /// Clone whose requests, including the info requests some methods make, use `options`,
+    /// e.g. `exchange_client.with_request_options(options).order(order, None)` for one call
+    pub fn with_request_options(&self, options: RequestOptions) -> ExchangeClient {
+        ExchangeClient {
+            request_options: options,
+            ..self.clone()
+        }
+    }
+
+    pub fn network_config(&self) -> &NetworkConfig {
+        &self.network
+    }
+
+    /// Chain actions are signed for, from the network config or else the base url
+    pub fn hyperliquid_chain(&self) -> HyperliquidChain {
+        self.network
+            .hyperliquid_chain
+            .unwrap_or(if self.http_client.is_mainnet() {
+                HyperliquidChain::Mainnet
+            } else {
+                HyperliquidChain::Testnet
+            })
+    }
+
+    pub(crate) fn info_client(&self) -> InfoClient {
+        let mut info_client = InfoClient::from_http_client(self.http_client.clone())
+            .with_request_options(self.request_options.clone());
+        if let Some(asset_registry) = &self.asset_registry {
+            info_client.set_asset_registry(asset_registry.clone());
+        }
+        info_client
+    }
+
+    pub fn set_order_limits(&mut self, order_limits: OrderLimits) {
+        self.order_limits = Some(Arc::new(order_limits));
+    }
+
+    /// Fetches `maxMarketOrderNtls` and checks orders against it and the minimum order value
+    pub async fn load_order_limits(&mut self) -> Result<()> {
+        let max_market_order_ntls = self.info_client().max_market_order_ntls().await?;
+        self.set_order_limits(OrderLimits::new(&self.meta, &max_market_order_ntls)?);
+        Ok(())
+    }
+
+    pub fn enable_self_cross_guard(&mut self, policy: SelfCrossPolicy) {
+        self.self_cross_guard = Some(Arc::new(SelfCrossGuard::new(policy)));
+    }
+
+    /// Records every signed action sent by this client, along with its response, to `audit_sink`
+    pub fn set_audit_sink(&mut self, audit_sink: impl AuditSink + 'static) {
+        self.audit_sink = Some(Arc::new(audit_sink));
+    }
+
+    /// Restricts `usdc_transfer`, `spot_transfer` and `withdraw_from_bridge` to destinations in
+    /// `address_book`, rejecting others before anything is signed
+    pub fn set_address_book(&mut self, address_book: AddressBook) {
+        self.address_book = Some(Arc::new(address_book));
+    }
+
+    /// Links the cloid of every order placed or modified by this client to its oid in `store`
+    pub fn set_order_id_store(&mut self, store: impl OrderIdStore + 'static) {
+        self.order_id_store = Some(Arc::new(store));
+    }
+
+    /// Store set with `set_order_id_store`, e.g. to tag cloids with a strategy before placing
+    pub fn order_id_store(&self) -> Option<&dyn OrderIdStore> {
+        self.order_id_store.as_deref()
+    }
+
+    /// Resolves asset ids through `asset_registry` instead of `coin_to_asset`, so assets listed
+    /// after the client was built can be traded without rebuilding it. Lookups the client makes
+    /// with its [`InfoClient`], e.g. of book snapshots, resolve coins through it too.
+    pub fn set_asset_registry(&mut self, asset_registry: AssetRegistry) {
+        self.asset_registry = Some(asset_registry);
+    }
+
+    pub fn asset_registry(&self) -> Option<&AssetRegistry> {
+        self.asset_registry.as_ref()
+    }
+
+    /// Fails orders and modifies with [`Error::ExchangeUnavailable`] without sending them while
+    /// `status_monitor` sees the exchange down
+    pub fn set_status_monitor(&mut self, status_monitor: ExchangeStatusMonitor) {
+        self.status_monitor = Some(status_monitor);
+    }
+
+    /// Tif, slippage, reduce only and builder used where a call doesn't set them
+    pub fn set_order_defaults(&mut self, order_defaults: OrderDefaults) {
+        self.order_defaults = order_defaults;
+    }
+
+    pub fn order_defaults(&self) -> &OrderDefaults {
+        &self.order_defaults
+    }
+
+    /// Limit order with the default tif and reduce only
+    pub fn limit_order(&self, asset: &str, is_buy: bool, px: Px, sz: Sz) -> DecimalOrder {
+        DecimalOrder {
+            reduce_only: self.order_defaults.reduce_only,
+            ..DecimalOrder::new(asset, is_buy, px, sz, self.order_defaults.tif)
+        }
+    }
+
+    /// Limit order request with the default tif and reduce only
+    pub fn limit_order_request(
+        &self,
+        asset: &str,
+        is_buy: bool,
+        limit_px: f64,
+        sz: f64,
+    ) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: asset.to_string(),
+            is_buy,
+            reduce_only: self.order_defaults.reduce_only,
+            limit_px,
+            sz,
+            cloid: None,
+            stp: None,
+            order_type: ClientOrder::Limit(ClientLimit {
+                tif: self.order_defaults.tif,
+            }),
+        }
+    }
+
+    async fn asset_id(&self, coin: &str) -> Result<u32> {
+        match &self.asset_registry {
+            Some(asset_registry) => asset_registry.asset_id(coin).await,
+            None => self
+                .coin_to_asset
+                .get(coin)
+                .copied()
+                .ok_or(Error::AssetNotFound),
+        }
+    }
+
+    /// Asset ids to convert requests for `coins` with
+    async fn asset_ids(&self, coins: Vec<&str>) -> Result<Arc<HashMap<String, u32>>> {
+        let Some(asset_registry) = &self.asset_registry else {
+            return Ok(self.coin_to_asset.clone());
+        };
+        for coin in coins {
+            asset_registry.resolve(coin).await?;
+        }
+        Ok(asset_registry.coin_to_asset())
+    }
+
+    fn record_order_ids(
+        &self,
+        orders: Vec<(Option<Uuid>, String)>,
+        response: &ExchangeResponseStatus,
+    ) {
+        if let Some(store) = &self.order_id_store {
+            if let Err(err) = record_placed_orders(store.as_ref(), orders, response) {
+                error!("Could not record order ids: {err}");
+            }
+        }
+    }
+
+    fn check_destination(&self, destination: &str) -> Result<()> {
+        match &self.address_book {
+            Some(address_book) => address_book.check(destination),
+            None => Ok(()),
+        }
+    }
+
+    pub fn add_pre_send_hook(
+        &mut self,
+        hook: impl Fn(&Actions, &HookContext) -> Result<()> + Send + Sync + 'static,
+    ) {
+        self.hooks.add_pre_send(Arc::new(hook));
+    }
+
+    pub fn add_post_receive_hook(
+        &mut self,
+        hook: impl Fn(&Actions, &HookContext, &ExchangeResponseStatus) + Send + Sync + 'static,
+    ) {
+        self.hooks.add_post_receive(Arc::new(hook));
     }
 
     async fn post(
         &self,
-        action: serde_json::Value,
+        action: Actions,
+        signature: Signature,
+        nonce: u64,
+    ) -> Result<ExchangeResponseStatus> {
+        self.post_for_vault(action, signature, nonce, self.vault_address)
+            .await
+    }
+
+    async fn post_for_vault(
+        &self,
+        action: Actions,
         signature: Signature,
         nonce: u64,
+        vault_address: Option<Address>,
     ) -> Result<ExchangeResponseStatus> {
+        self.send_action(action, signature, nonce, vault_address)
+            .await
+            .map_err(Error::from)
+    }
+
+    async fn send_action(
+        &self,
+        action: Actions,
+        signature: Signature,
+        nonce: u64,
+        vault_address: Option<Address>,
+    ) -> std::result::Result<ExchangeResponseStatus, PostError> {
+        let context = HookContext {
+            nonce,
+            vault_address,
+            is_mainnet: self.hyperliquid_chain().is_mainnet(),
+        };
+        self.hooks.pre_send(&action, &context)?;
+
         let exchange_payload = ExchangePayload {
-            action,
+            action: serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
             signature,
             nonce,
-            vault_address: self.vault_address,
+            vault_address,
         };
-        let res = serde_json::to_string(&exchange_payload)
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let res = exchange_payload.body()?;
         debug!("Sending request {res:?}");
+        // hashed before sending, so an action the exchange accepted is always recorded
+        let action_hash = match &self.audit_sink {
+            Some(_) => Some(action.signed_hash(nonce, vault_address)?),
+            None => None,
+        };
 
-        let output = &self
+        let sent_at = now_timestamp_ms();
+        let output = self
             .http_client
-            .post("/exchange", res)
+            .post_with_options("/exchange", res, &self.request_options)
+            .await;
+        if let (Some(audit_sink), Some(action_hash)) = (&self.audit_sink, action_hash) {
+            let record = AuditRecord {
+                action_hash,
+                action: exchange_payload.action,
+                nonce,
+                vault_address,
+                signature: hex::encode(signature.as_bytes()),
+                response: output.as_ref().ok().cloned(),
+                error: output.as_ref().err().map(ToString::to_string),
+                sent_at,
+                received_at: now_timestamp_ms(),
+            };
+            if let Err(err) = audit_sink.record(&record) {
+                error!("Could not record audit record for nonce {nonce}: {err}");
+            }
+        }
+        let output = output.as_ref().map_err(|e| PostError::Request(e.clone()))?;
+        let response: ExchangeResponseStatus =
+            serde_json::from_str(output).map_err(|e| Error::JsonParse(e.to_string()))?;
+        self.hooks.post_receive(&action, &context, &response);
+        Ok(response)
+    }
+
+    /// Signs and sends an L1 action this SDK has no typed method for yet, e.g.
+    /// `json!({"type": "newAction", "asset": 0, "value": "1"})`. `nonce` defaults to the next
+    /// nonce and `vault_address` to the client's.
+    ///
+    /// The action is hashed as msgpack with its keys in the order of the JSON object, and the
+    /// exchange recomputes the hash from its own field order, so keys must be given in the order
+    /// the exchange defines them (`type` first) or the signature recovers to a different address.
+    /// Numbers must have the same type the exchange expects, e.g. prices and sizes as strings.
+    /// User-signed (EIP-712) actions like transfers can't be sent this way.
+    pub async fn raw_action(
+        &self,
+        action: serde_json::Value,
+        nonce: Option<u64>,
+        vault_address: Option<Address>,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        if !action.get("type").is_some_and(|t| t.is_string()) {
+            return Err(Error::GenericRequest(
+                "Raw action must be an object with a string \"type\"".to_string(),
+            ));
+        }
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let nonce = nonce.unwrap_or_else(next_nonce);
+        let vault_address = vault_address.or(self.vault_address);
+
+        let action = Actions::Raw(action);
+        let connection_id = action.hash(nonce, vault_address)?;
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
+        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+        self.post_for_vault(action, signature, nonce, vault_address)
             .await
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-        serde_json::from_str(output).map_err(|e| Error::JsonParse(e.to_string()))
+    }
+
+    /// Signs `action` like a send would and returns the request without sending it.
+    /// `nonce` defaults to the next nonce. Hooks are not run.
+    pub fn preview(
+        &self,
+        action: Actions,
+        nonce: Option<u64>,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<SignedPayloadPreview> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let nonce = nonce.unwrap_or_else(next_nonce);
+        let (action_hash, msgpack_hex, signature) = if action.is_user_signed() {
+            let action_hash = action.signed_hash(nonce, self.vault_address)?;
+            let signature = wallet
+                .sign_hash_sync(&action_hash)
+                .map_err(|e| Error::SignatureFailure(e.to_string()))?;
+            (action_hash, None, signature)
+        } else {
+            let hash_input = action.hash_input(nonce, self.vault_address)?;
+            let action_hash = keccak256(&hash_input);
+            let signature =
+                sign_l1_action(wallet, action_hash, self.hyperliquid_chain().is_mainnet())?;
+            (action_hash, Some(hex::encode(hash_input)), signature)
+        };
+
+        let body = ExchangePayload {
+            action: serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            signature,
+            nonce,
+            vault_address: self.vault_address,
+        }
+        .body()?;
+        Ok(SignedPayloadPreview {
+            body,
+            msgpack_hex,
+            action_hash,
+            signature: hex::encode(signature.as_bytes()),
+            nonce,
+            vault_address: self.vault_address,
+        })
     }
 
     pub async fn usdc_transfer(
         &self,
         amount: &str,
         destination: &str,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
+        self.check_destination(destination)?;
         let wallet = wallet.unwrap_or(&self.wallet);
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+        let hyperliquid_chain = self.hyperliquid_chain().to_string();
 
         let timestamp = next_nonce();
         let usd_send = UsdSend {
-            signature_chain_id: 421614.into(),
+            signature_chain_id: U256::from(self.network.user_signature_chain_id),
             hyperliquid_chain,
             destination: destination.to_string(),
             amount: amount.to_string(),
             time: timestamp,
         };
         let signature = sign_typed_data(&usd_send, wallet)?;
-        let action = serde_json::to_value(Actions::UsdSend(usd_send))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let action = Actions::UsdSend(usd_send);
 
         self.post(action, signature, timestamp).await
     }
@@ -181,7 +756,7 @@ impl ExchangeClient {
         &self,
         usdc: f64,
         to_perp: bool,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
         // payload expects usdc without decimals
         let usdc = (usdc * 1e6).round() as u64;
@@ -193,8 +768,7 @@ impl ExchangeClient {
             class_transfer: ClassTransfer { usdc, to_perp },
         });
         let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
         let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
 
         self.post(action, signature, timestamp).await
@@ -204,8 +778,8 @@ impl ExchangeClient {
         &self,
         is_deposit: bool,
         usd: String,
-        vault_address: Option<H160>,
-        wallet: Option<&LocalWallet>,
+        vault_address: Option<Address>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
         let vault_address = self
             .vault_address
@@ -221,18 +795,120 @@ impl ExchangeClient {
             usd,
         });
         let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
+        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+
+        self.post(action, signature, timestamp).await
+    }
+
+    /// Moves perp usd from the master account to `sub_account`
+    pub async fn fund_sub_account(
+        &self,
+        sub_account: Address,
+        usd: f64,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.sub_account_transfer(sub_account, true, usd, wallet)
+            .await
+    }
+
+    /// Moves perp usd from `sub_account` back to the master account
+    pub async fn defund_sub_account(
+        &self,
+        sub_account: Address,
+        usd: f64,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.sub_account_transfer(sub_account, false, usd, wallet)
+            .await
+    }
+
+    async fn sub_account_transfer(
+        &self,
+        sub_account: Address,
+        is_deposit: bool,
+        usd: f64,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        self.ensure_sub_account(wallet.address(), sub_account)
+            .await?;
+        // payload expects usd without decimals
+        let usd = (usd * 1e6).round() as u64;
+
+        let timestamp = next_nonce();
+
+        let action = Actions::SubAccountTransfer(SubAccountUsdTransfer {
+            sub_account_user: sub_account,
+            is_deposit,
+            usd,
+        });
+        let connection_id = action.hash(timestamp, self.vault_address)?;
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
+        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+
+        self.post(action, signature, timestamp).await
+    }
+
+    /// Moves a spot token between the master account and `sub_account`
+    pub async fn sub_account_spot_transfer(
+        &self,
+        sub_account: Address,
+        is_deposit: bool,
+        token: &str,
+        amount: &str,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        self.ensure_sub_account(wallet.address(), sub_account)
+            .await?;
+
+        let timestamp = next_nonce();
+
+        let action = Actions::SubAccountSpotTransfer(SubAccountSpotTransfer {
+            sub_account_user: sub_account,
+            is_deposit,
+            token: token.to_string(),
+            amount: amount.to_string(),
+        });
+        let connection_id = action.hash(timestamp, self.vault_address)?;
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
         let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
 
         self.post(action, signature, timestamp).await
     }
 
+    /// Account `signer` acts for, which holds its orders, fills and positions: the vault address
+    /// if one is set, the approving user if `signer` is an agent, else `signer` itself
+    pub(crate) async fn account_of(&self, signer: Address) -> Result<Address> {
+        if let Some(vault_address) = self.vault_address {
+            return Ok(vault_address);
+        }
+        match self.info_client().user_role(signer).await? {
+            UserRole::Agent { user } => Ok(user),
+            _ => Ok(signer),
+        }
+    }
+
+    /// Checks that `sub_account` belongs to the account `signer` acts for
+    async fn ensure_sub_account(&self, signer: Address, sub_account: Address) -> Result<()> {
+        let master = self.account_of(signer).await?;
+        let sub_accounts = self.info_client().sub_accounts(master).await?;
+        if sub_accounts
+            .iter()
+            .any(|account| account.sub_account_user == sub_account)
+        {
+            Ok(())
+        } else {
+            Err(Error::SubAccountNotFound(sub_account))
+        }
+    }
+
     pub async fn market_open(
         &self,
         params: MarketOrderParams<'_>,
     ) -> Result<ExchangeResponseStatus> {
-        let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
+        let slippage = params.slippage.unwrap_or(self.order_defaults.slippage);
         let (px, sz_decimals) = self
             .calculate_slippage_price(params.asset, params.is_buy, slippage, params.px)
             .await?;
@@ -244,12 +920,11 @@ impl ExchangeClient {
             limit_px: px,
             sz: round_to_decimals(params.sz, sz_decimals),
             cloid: params.cloid,
-            order_type: ClientOrder::Limit(ClientLimit {
-                tif: "Ioc".to_string(),
-            }),
+            stp: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Ioc }),
         };
 
-        self.order(order, params.wallet).await
+        self.place_order(order, params.wallet).await
     }
 
     pub async fn market_open_with_builder(
@@ -257,7 +932,7 @@ impl ExchangeClient {
         params: MarketOrderParams<'_>,
         builder: BuilderInfo,
     ) -> Result<ExchangeResponseStatus> {
-        let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
+        let slippage = params.slippage.unwrap_or(self.order_defaults.slippage);
         let (px, sz_decimals) = self
             .calculate_slippage_price(params.asset, params.is_buy, slippage, params.px)
             .await?;
@@ -269,9 +944,8 @@ impl ExchangeClient {
             limit_px: px,
             sz: round_to_decimals(params.sz, sz_decimals),
             cloid: params.cloid,
-            order_type: ClientOrder::Limit(ClientLimit {
-                tif: "Ioc".to_string(),
-            }),
+            stp: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Ioc }),
         };
 
         self.order_with_builder(order, params.wallet, builder).await
@@ -281,15 +955,10 @@ impl ExchangeClient {
         &self,
         params: MarketCloseParams<'_>,
     ) -> Result<ExchangeResponseStatus> {
-        let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
+        let slippage = params.slippage.unwrap_or(self.order_defaults.slippage);
         let wallet = params.wallet.unwrap_or(&self.wallet);
 
-        let base_url = match self.http_client.base_url.as_str() {
-            "https://api.hyperliquid.xyz" => BaseUrl::Mainnet,
-            "https://api.hyperliquid-testnet.xyz" => BaseUrl::Testnet,
-            _ => return Err(Error::GenericRequest("Invalid base URL".to_string())),
-        };
-        let info_client = InfoClient::new(None, Some(base_url)).await?;
+        let info_client = self.info_client();
         let user_state = info_client.user_state(wallet.address()).await?;
 
         let position = user_state
@@ -317,12 +986,11 @@ impl ExchangeClient {
             limit_px: px,
             sz,
             cloid: params.cloid,
-            order_type: ClientOrder::Limit(ClientLimit {
-                tif: "Ioc".to_string(),
-            }),
+            stp: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Ioc }),
         };
 
-        self.order(order, Some(wallet)).await
+        self.place_order(order, Some(wallet)).await
     }
 
     async fn calculate_slippage_price(
@@ -332,34 +1000,16 @@ impl ExchangeClient {
         slippage: f64,
         px: Option<f64>,
     ) -> Result<(f64, u32)> {
-        let base_url = match self.http_client.base_url.as_str() {
-            "https://api.hyperliquid.xyz" => BaseUrl::Mainnet,
-            "https://api.hyperliquid-testnet.xyz" => BaseUrl::Testnet,
-            _ => return Err(Error::GenericRequest("Invalid base URL".to_string())),
-        };
-        let info_client = InfoClient::new(None, Some(base_url)).await?;
-        let meta = info_client.meta().await?;
-
-        let asset_meta = meta
-            .universe
-            .iter()
-            .find(|a| a.name == asset)
-            .ok_or(Error::AssetNotFound)?;
-
-        let sz_decimals = asset_meta.sz_decimals;
-        let max_decimals: u32 = if self.coin_to_asset[asset] < 10000 {
-            6
-        } else {
-            8
-        };
-        let price_decimals = max_decimals.saturating_sub(sz_decimals);
+        let asset_info = self.asset_info(asset).await?;
+        let sz_decimals = asset_info.sz_decimals;
+        let price_decimals = self.network.max_px_decimals(asset_info.asset, sz_decimals);
 
         let px = if let Some(px) = px {
             px
         } else {
-            let all_mids = info_client.all_mids().await?;
+            let all_mids = self.info_client().all_mids().await?;
             all_mids
-                .get(asset)
+                .get(&asset_info.name)
                 .ok_or(Error::AssetNotFound)?
                 .parse::<f64>()
                 .map_err(|_| Error::FloatStringParse)?
@@ -374,24 +1024,127 @@ impl ExchangeClient {
         let px = px * slippage_factor;
 
         // Round to the correct number of decimal places and significant figures
-        let px = round_to_significant_and_decimal(px, 5, price_decimals);
+        let px = round_to_significant_and_decimal(px, self.network.max_px_sig_figs, price_decimals);
 
         debug!("px after slippage: {px:?}");
         Ok((px, sz_decimals))
     }
 
+    /// Places `order` after checking its price and size against the asset's tick and lot size
     pub async fn order(
+        &self,
+        order: DecimalOrder,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.check_tick_and_lot(&order).await?;
+        self.place_order(order.to_client_order()?, wallet).await
+    }
+
+    #[deprecated(
+        note = "f64 prices and sizes aren't checked against the tick and lot size, use `order` with a `DecimalOrder`"
+    )]
+    pub async fn order_f64(
         &self,
         order: ClientOrderRequest,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
+        self.place_order(order, wallet).await
+    }
+
+    pub(crate) async fn place_order(
+        &self,
+        order: ClientOrderRequest,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        if let Some(guard) = &self.self_cross_guard {
+            if guard.policy == SelfCrossPolicy::ModifyResting && order.stp.is_none() {
+                if let Some(oid) = guard.crossing_order(&order, None) {
+                    return self
+                        .bulk_modify(vec![ClientModifyRequest { oid, order }], wallet)
+                        .await;
+                }
+            }
+        }
         self.bulk_order(vec![order], wallet).await
     }
 
+    /// Like [`ExchangeClient::order`], with the response reduced to where the order ended up or
+    /// why it was rejected
+    pub async fn order_checked(
+        &self,
+        order: DecimalOrder,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> std::result::Result<OrderPlacement, OrderRejection> {
+        let response = self
+            .order(order, wallet)
+            .await
+            .map_err(OrderRejection::Request)?;
+        checked_status(response)
+    }
+
+    #[cfg(feature = "ws")]
+    pub(crate) async fn place_order_checked(
+        &self,
+        order: ClientOrderRequest,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> std::result::Result<OrderPlacement, OrderRejection> {
+        let response = self
+            .place_order(order, wallet)
+            .await
+            .map_err(OrderRejection::Request)?;
+        checked_status(response)
+    }
+
+    /// `coin` from the asset registry if one is set, else from the perp metadata or fetched
+    /// with the spot and builder perp metadata
+    pub(crate) async fn asset_info(&self, coin: &str) -> Result<AssetInfo> {
+        if let Some(asset_registry) = &self.asset_registry {
+            return asset_registry.resolve(coin).await;
+        }
+        if let Some(asset) = self.meta.universe.iter().find(|asset| asset.name == coin) {
+            return Ok(AssetInfo {
+                name: asset.name.clone(),
+                asset: self.asset_id(coin).await?,
+                sz_decimals: asset.sz_decimals,
+                is_spot: false,
+            });
+        }
+        self.fallback_registry
+            .get_or_init(|| AssetRegistry::new(&self.info_client(), self.network))
+            .resolve(coin)
+            .await
+    }
+
+    pub(crate) async fn sz_decimals(&self, coin: &str) -> Result<u32> {
+        Ok(self.asset_info(coin).await?.sz_decimals)
+    }
+
+    async fn check_tick_and_lot(&self, order: &DecimalOrder) -> Result<()> {
+        let sz_decimals = self.sz_decimals(&order.asset).await?;
+        let max_decimals = self
+            .network
+            .max_px_decimals(self.asset_id(&order.asset).await?, sz_decimals);
+        let max_sig_figs = self.network.max_px_sig_figs;
+        let reason = if !order.px.is_on_tick(max_decimals, max_sig_figs) {
+            format!(
+                "price {} has more than {max_decimals} decimals or {max_sig_figs} significant figures",
+                order.px
+            )
+        } else if !order.sz.is_on_lot(sz_decimals) {
+            format!("size {} has more than {sz_decimals} decimals", order.sz)
+        } else {
+            return Ok(());
+        };
+        Err(Error::OrderLimit {
+            asset: order.asset.clone(),
+            reason,
+        })
+    }
+
     pub async fn order_with_builder(
         &self,
         order: ClientOrderRequest,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
         builder: BuilderInfo,
     ) -> Result<ExchangeResponseStatus> {
         self.bulk_order_with_builder(vec![order], wallet, builder)
@@ -401,64 +1154,229 @@ impl ExchangeClient {
     pub async fn bulk_order(
         &self,
         orders: Vec<ClientOrderRequest>,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
+        self.bulk_order_with_grouping(orders, wallet, "na", None)
+            .await
+    }
 
-        let mut transformed_orders = Vec::new();
+    pub async fn bulk_order_with_builder(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+        wallet: Option<&PrivateKeySigner>,
+        mut builder: BuilderInfo,
+    ) -> Result<ExchangeResponseStatus> {
+        builder.builder = builder.builder.to_lowercase();
+        self.bulk_order_with_grouping(orders, wallet, "na", Some(builder))
+            .await
+    }
 
-        for order in orders {
-            transformed_orders.push(order.convert(&self.coin_to_asset)?);
-        }
+    /// Like [`ExchangeClient::bulk_order`], with each status paired with the request it answers.
+    /// If the exchange rejects the whole action, every order gets its reason as an error status.
+    pub async fn bulk_order_results(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<Vec<OrderResult>> {
+        let response = self.bulk_order(orders.clone(), wallet).await?;
+        Ok(zip_statuses(orders, Ok(response)))
+    }
 
-        let action = Actions::Order(BulkOrder {
-            orders: transformed_orders,
-            grouping: "na".to_string(),
-            builder: None,
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+    /// Sends `orders` as bulk orders of at most `chunk_size` orders each, one after the other,
+    /// returning the status of every order next to its request in the original order.
+    /// A chunk that fails to send or is rejected gives each of its orders an error status, and
+    /// one that times out leaves them of unknown fate. Later chunks are still sent.
+    pub async fn chunked_order_results(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+        chunk_size: usize,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<Vec<OrderResult>> {
+        if chunk_size == 0 {
+            return Err(Error::GenericRequest(
+                "chunk_size must be non-zero".to_string(),
+            ));
+        }
 
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp).await
+        let mut results = Vec::with_capacity(orders.len());
+        let mut orders = orders.into_iter().peekable();
+        while orders.peek().is_some() {
+            let chunk: Vec<_> = orders.by_ref().take(chunk_size).collect();
+            let response = self
+                .bulk_order_with_nonce(chunk.clone(), wallet, "na", None, next_nonce())
+                .await;
+            results.extend(match response {
+                Err(PostError::Request(err)) if is_unknown_fate(&err) => unknown_fate(chunk, &err),
+                response => zip_statuses(chunk, response.map_err(Error::from)),
+            });
+        }
+        Ok(results)
     }
 
-    pub async fn bulk_order_with_builder(
+    async fn bulk_order_with_grouping(
         &self,
         orders: Vec<ClientOrderRequest>,
-        wallet: Option<&LocalWallet>,
-        mut builder: BuilderInfo,
+        wallet: Option<&PrivateKeySigner>,
+        grouping: &str,
+        builder: Option<BuilderInfo>,
     ) -> Result<ExchangeResponseStatus> {
+        self.bulk_order_with_nonce(orders, wallet, grouping, builder, next_nonce())
+            .await
+            .map_err(Error::from)
+    }
+
+    /// Self-trade prevention is enforced by the [`SelfCrossGuard`], so orders asking for it
+    /// fail without one rather than going out unprotected
+    fn check_stp_guard<'a>(
+        &self,
+        mut orders: impl Iterator<Item = &'a ClientOrderRequest>,
+    ) -> Result<()> {
+        if self.self_cross_guard.is_some() {
+            return Ok(());
+        }
+        match orders.find(|order| order.stp.is_some()) {
+            Some(order) => Err(Error::Config(format!(
+                "Order on {} sets stp, which needs a SelfCrossGuard, see enable_self_cross_guard",
+                order.asset
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) async fn bulk_order_with_nonce(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+        wallet: Option<&PrivateKeySigner>,
+        grouping: &str,
+        builder: Option<BuilderInfo>,
+        timestamp: u64,
+    ) -> std::result::Result<ExchangeResponseStatus, PostError> {
+        if let Some(status_monitor) = &self.status_monitor {
+            status_monitor.check()?;
+        }
         let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
+        let builder = builder.or_else(|| {
+            let mut builder = self.order_defaults.builder.clone()?;
+            builder.builder = builder.builder.to_lowercase();
+            Some(builder)
+        });
 
-        builder.builder = builder.builder.to_lowercase();
+        if let Some(order_limits) = &self.order_limits {
+            orders
+                .iter()
+                .try_for_each(|order| order_limits.check(order))?;
+        }
 
+        self.check_stp_guard(orders.iter())?;
+        let mut pending_orders = Vec::new();
+        let mut prevented = Vec::new();
+        let orders = match &self.self_cross_guard {
+            Some(guard) => {
+                let (orders, self_trades) = guard.prevent_self_trades(orders)?;
+                pending_orders = orders.iter().map(PendingOrder::from_request).collect();
+                prevented = self_trades;
+                orders
+            }
+            None => orders,
+        };
+        if orders.is_empty() && !prevented.is_empty() {
+            return Ok(with_prevented_statuses(None, &prevented));
+        }
+
+        let order_ids: Vec<_> = orders
+            .iter()
+            .map(|order| (order.cloid, order.asset.clone()))
+            .collect();
+        let coin_to_asset = self
+            .asset_ids(orders.iter().map(|order| order.asset.as_str()).collect())
+            .await?;
         let mut transformed_orders = Vec::new();
 
         for order in orders {
-            transformed_orders.push(order.convert(&self.coin_to_asset)?);
+            transformed_orders.push(order.convert(&coin_to_asset)?);
         }
 
         let action = Actions::Order(BulkOrder {
             orders: transformed_orders,
-            grouping: "na".to_string(),
-            builder: Some(builder),
+            grouping: grouping.to_string(),
+            builder,
         });
         let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
 
-        let is_mainnet = self.http_client.is_mainnet();
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
         let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp).await
+        let response = self
+            .send_action(action, signature, timestamp, self.vault_address)
+            .await?;
+        if let Some(guard) = &self.self_cross_guard {
+            guard.record_orders(pending_orders, &response);
+        }
+        self.record_order_ids(order_ids, &response);
+        if prevented.is_empty() {
+            Ok(response)
+        } else {
+            Ok(with_prevented_statuses(Some(response), &prevented))
+        }
+    }
+
+    /// Places market take profit and/or stop loss triggers for the whole open position in `coin`.
+    /// The triggers are grouped as `positionTpsl`, so they resize with the position.
+    pub async fn set_position_tpsl(
+        &self,
+        coin: &Coin,
+        tp: Option<f64>,
+        sl: Option<f64>,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        if tp.is_none() && sl.is_none() {
+            return Err(Error::GenericRequest(
+                "Either a take profit or a stop loss price is required".to_string(),
+            ));
+        }
+        let user = self
+            .vault_address
+            .unwrap_or_else(|| wallet.unwrap_or(&self.wallet).address());
+        let info_client = self.info_client();
+        let user_state = info_client.user_state(user).await?;
+        let position = user_state
+            .asset_positions
+            .iter()
+            .find(|p| p.position.coin == coin.as_str())
+            .ok_or(Error::AssetNotFound)?;
+        let szi = position
+            .position
+            .szi
+            .parse::<f64>()
+            .map_err(|_| Error::FloatStringParse)?;
+
+        let is_buy = szi < 0.0;
+        let mut orders = Vec::new();
+        if let Some(tp) = tp {
+            orders.push(ClientOrderRequest::take_profit(
+                coin,
+                is_buy,
+                tp,
+                szi.abs(),
+                true,
+            ));
+        }
+        if let Some(sl) = sl {
+            orders.push(ClientOrderRequest::stop_loss(
+                coin,
+                is_buy,
+                sl,
+                szi.abs(),
+                true,
+            ));
+        }
+        self.bulk_order_with_grouping(orders, wallet, "positionTpsl", None)
+            .await
     }
 
     pub async fn cancel(
         &self,
         cancel: ClientCancelRequest,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
         self.bulk_cancel(vec![cancel], wallet).await
     }
@@ -466,17 +1384,15 @@ impl ExchangeClient {
     pub async fn bulk_cancel(
         &self,
         cancels: Vec<ClientCancelRequest>,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
         let timestamp = next_nonce();
 
+        let oids: Vec<u64> = cancels.iter().map(|cancel| cancel.oid).collect();
         let mut transformed_cancels = Vec::new();
         for cancel in cancels.into_iter() {
-            let &asset = self
-                .coin_to_asset
-                .get(&cancel.asset)
-                .ok_or(Error::AssetNotFound)?;
+            let asset = self.asset_id(&cancel.asset).await?;
             transformed_cancels.push(CancelRequest {
                 asset,
                 oid: cancel.oid,
@@ -488,17 +1404,36 @@ impl ExchangeClient {
         });
         let connection_id = action.hash(timestamp, self.vault_address)?;
 
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
         let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
 
-        self.post(action, signature, timestamp).await
+        let response = self.post(action, signature, timestamp).await?;
+        if let Some(guard) = &self.self_cross_guard {
+            guard.record_cancels(oids, &response);
+        }
+        Ok(response)
     }
 
+    /// Modifies order `oid` into `order`, checked like [`ExchangeClient::order`]
     pub async fn modify(
+        &self,
+        oid: u64,
+        order: DecimalOrder,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.check_tick_and_lot(&order).await?;
+        let order = order.to_client_order()?;
+        self.bulk_modify(vec![ClientModifyRequest { oid, order }], wallet)
+            .await
+    }
+
+    #[deprecated(
+        note = "f64 prices and sizes aren't checked against the tick and lot size, use `modify` with a `DecimalOrder`"
+    )]
+    pub async fn modify_f64(
         &self,
         modify: ClientModifyRequest,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
         self.bulk_modify(vec![modify], wallet).await
     }
@@ -506,16 +1441,46 @@ impl ExchangeClient {
     pub async fn bulk_modify(
         &self,
         modifies: Vec<ClientModifyRequest>,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
+        if let Some(status_monitor) = &self.status_monitor {
+            status_monitor.check()?;
+        }
         let wallet = wallet.unwrap_or(&self.wallet);
         let timestamp = next_nonce();
 
+        if let Some(order_limits) = &self.order_limits {
+            modifies
+                .iter()
+                .try_for_each(|modify| order_limits.check(&modify.order))?;
+        }
+
+        self.check_stp_guard(modifies.iter().map(|modify| &modify.order))?;
+        let mut pending_modifies = Vec::new();
+        if let Some(guard) = &self.self_cross_guard {
+            for modify in &modifies {
+                guard.check(&modify.order, Some(modify.oid))?;
+                pending_modifies.push((modify.oid, PendingOrder::from_request(&modify.order)));
+            }
+        }
+
+        let order_ids: Vec<_> = modifies
+            .iter()
+            .map(|modify| (modify.order.cloid, modify.order.asset.clone()))
+            .collect();
+        let coin_to_asset = self
+            .asset_ids(
+                modifies
+                    .iter()
+                    .map(|modify| modify.order.asset.as_str())
+                    .collect(),
+            )
+            .await?;
         let mut transformed_modifies = Vec::new();
         for modify in modifies.into_iter() {
             transformed_modifies.push(ModifyRequest {
                 oid: modify.oid,
-                order: modify.order.convert(&self.coin_to_asset)?,
+                order: modify.order.convert(&coin_to_asset)?,
             });
         }
 
@@ -524,17 +1489,135 @@ impl ExchangeClient {
         });
         let connection_id = action.hash(timestamp, self.vault_address)?;
 
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
         let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
 
-        self.post(action, signature, timestamp).await
+        let response = self.post(action, signature, timestamp).await?;
+        if let Some(guard) = &self.self_cross_guard {
+            guard.record_modifies(pending_modifies, &response);
+        }
+        self.record_order_ids(order_ids, &response);
+        Ok(response)
+    }
+
+    /// Changes the price and size of a resting limit order. A size decrease at the same price is
+    /// sent as a modify, anything else as a cancel followed by a new order, see [`AmendMethod`].
+    /// The replacement is only placed once the cancel succeeded, so the order can't be doubled.
+    pub async fn amend_order(
+        &self,
+        oid: u64,
+        new_px: f64,
+        new_sz: f64,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<AmendResult> {
+        let user = self
+            .vault_address
+            .unwrap_or_else(|| wallet.unwrap_or(&self.wallet).address());
+        let info_client = self.info_client();
+        let status = info_client.query_order_by_oid(user, oid).await?;
+        let order = match status.order {
+            Some(order) if order.status == "open" => order.order,
+            Some(order) => {
+                return Err(Error::GenericRequest(format!(
+                    "Order {oid} is {}, not open",
+                    order.status
+                )))
+            }
+            None => return Err(Error::GenericRequest(format!("Order {oid} not found"))),
+        };
+
+        let px = order
+            .limit_px
+            .parse::<f64>()
+            .map_err(|_| Error::FloatStringParse)?;
+        let sz = order
+            .sz
+            .parse::<f64>()
+            .map_err(|_| Error::FloatStringParse)?;
+        let request = amended_request(&order, new_px, new_sz)?;
+        let method = AmendMethod::choose(px, sz, new_px, new_sz);
+
+        match method {
+            AmendMethod::Modify => Ok(AmendResult {
+                method,
+                response: self
+                    .bulk_modify(
+                        vec![ClientModifyRequest {
+                            oid,
+                            order: request,
+                        }],
+                        wallet,
+                    )
+                    .await?,
+                replace_response: None,
+            }),
+            AmendMethod::CancelReplace => {
+                let response = self
+                    .cancel(
+                        ClientCancelRequest {
+                            asset: order.coin,
+                            oid,
+                        },
+                        wallet,
+                    )
+                    .await?;
+                let replace_response = if is_success(&response) {
+                    Some(self.place_order(request, wallet).await?)
+                } else {
+                    None
+                };
+                Ok(AmendResult {
+                    method,
+                    response,
+                    replace_response,
+                })
+            }
+        }
+    }
+
+    /// Validates `intents`, then sends their cancels by oid, cancels by cloid and orders as one
+    /// batched action each, in that order. Nothing is sent if any intent is invalid. If a batch
+    /// fails after earlier ones were sent, the error is [`Error::IntentsPartiallySent`] with
+    /// their responses.
+    pub async fn execute_intents(
+        &self,
+        intents: &[Intent],
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<IntentResults> {
+        let coin_to_asset = self
+            .asset_ids(
+                intents
+                    .iter()
+                    .map(|intent| match intent {
+                        Intent::Order(order) => order.coin.as_str(),
+                        Intent::Cancel(cancel) => cancel.coin.as_str(),
+                    })
+                    .collect(),
+            )
+            .await?;
+        let batches = IntentBatches::new(intents, &coin_to_asset)?;
+        let mut results = IntentResults::default();
+        if !batches.cancels.is_empty() {
+            let response = self.bulk_cancel(batches.cancels, wallet).await;
+            results.cancels = Some(results.next(response)?);
+        }
+        if !batches.cancels_by_cloid.is_empty() {
+            let response = self
+                .bulk_cancel_by_cloid(batches.cancels_by_cloid, wallet)
+                .await;
+            results.cancels_by_cloid = Some(results.next(response)?);
+        }
+        if !batches.orders.is_empty() {
+            let response = self.bulk_order(batches.orders, wallet).await;
+            results.orders = Some(results.next(response)?);
+        }
+        Ok(results)
     }
 
     pub async fn cancel_by_cloid(
         &self,
         cancel: ClientCancelRequestCloid,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
         self.bulk_cancel_by_cloid(vec![cancel], wallet).await
     }
@@ -542,17 +1625,15 @@ impl ExchangeClient {
     pub async fn bulk_cancel_by_cloid(
         &self,
         cancels: Vec<ClientCancelRequestCloid>,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
         let timestamp = next_nonce();
 
+        let cloids: Vec<Uuid> = cancels.iter().map(|cancel| cancel.cloid).collect();
         let mut transformed_cancels: Vec<CancelRequestCloid> = Vec::new();
         for cancel in cancels.into_iter() {
-            let &asset = self
-                .coin_to_asset
-                .get(&cancel.asset)
-                .ok_or(Error::AssetNotFound)?;
+            let asset = self.asset_id(&cancel.asset).await?;
             transformed_cancels.push(CancelRequestCloid {
                 asset,
                 cloid: uuid_to_hex_string(cancel.cloid),
@@ -564,58 +1645,82 @@ impl ExchangeClient {
         });
 
         let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
         let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
 
-        self.post(action, signature, timestamp).await
+        let response = self.post(action, signature, timestamp).await?;
+        if let Some(guard) = &self.self_cross_guard {
+            guard.record_cancels_by_cloid(cloids, &response);
+        }
+        Ok(response)
     }
 
     pub async fn update_leverage(
         &self,
         leverage: u32,
-        coin: &str,
+        coin: &Coin,
         is_cross: bool,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
 
         let timestamp = next_nonce();
 
-        let &asset_index = self.coin_to_asset.get(coin).ok_or(Error::AssetNotFound)?;
+        let asset_index = self.asset_id(coin.as_str()).await?;
         let action = Actions::UpdateLeverage(UpdateLeverage {
             asset: asset_index,
             is_cross,
             leverage,
         });
         let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
         let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
 
         self.post(action, signature, timestamp).await
     }
 
+    /// Switches `coin` to cross or isolated margin at the given leverage, then checks through
+    /// `activeAssetData` that the mode and leverage were applied and returns the reported leverage.
+    /// `activeAssetData` reports the leverage whether or not there is an open position.
+    pub async fn set_margin_mode(
+        &self,
+        coin: &Coin,
+        mode: MarginMode,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<Leverage> {
+        let response = self
+            .update_leverage(mode.leverage(), coin, mode.is_cross(), wallet)
+            .await?;
+        if let ExchangeResponseStatus::Err(err) = response {
+            return Err(Error::MarginMode(err));
+        }
+
+        let user = self
+            .vault_address
+            .unwrap_or_else(|| wallet.unwrap_or(&self.wallet).address());
+        let active_asset_data = self.info_client().active_asset_data(user, coin).await?;
+        mode.verify(active_asset_data.leverage)
+    }
+
     pub async fn update_isolated_margin(
         &self,
         amount: f64,
-        coin: &str,
-        wallet: Option<&LocalWallet>,
+        coin: &Coin,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
 
         let amount = (amount * 1_000_000.0).round() as i64;
         let timestamp = next_nonce();
 
-        let &asset_index = self.coin_to_asset.get(coin).ok_or(Error::AssetNotFound)?;
+        let asset_index = self.asset_id(coin.as_str()).await?;
         let action = Actions::UpdateIsolatedMargin(UpdateIsolatedMargin {
             asset: asset_index,
             is_buy: true,
             ntli: amount,
         });
         let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
         let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
 
         self.post(action, signature, timestamp).await
@@ -623,60 +1728,62 @@ impl ExchangeClient {
 
     pub async fn approve_agent(
         &self,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<(String, ExchangeResponseStatus)> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let key = H256::from(generate_random_key()?).encode_hex()[2..].to_string();
+        let key = B256::from(generate_random_key()?);
 
-        let address = key
-            .parse::<LocalWallet>()
+        let address = PrivateKeySigner::from_bytes(&key)
             .map_err(|e| Error::PrivateKeyParse(e.to_string()))?
             .address();
 
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+        let response = self.approve_agent_address(address, None, wallet).await?;
+        Ok((hex::encode(key), response))
+    }
+
+    /// Approves `agent_address` to sign for this account. Approving another address under the
+    /// same `agent_name` replaces the agent approved under it; there is only one unnamed agent.
+    pub async fn approve_agent_address(
+        &self,
+        agent_address: Address,
+        agent_name: Option<String>,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let hyperliquid_chain = self.hyperliquid_chain().to_string();
 
         let nonce = next_nonce();
         let approve_agent = ApproveAgent {
-            signature_chain_id: 421614.into(),
+            signature_chain_id: U256::from(self.network.user_signature_chain_id),
             hyperliquid_chain,
-            agent_address: address,
-            agent_name: None,
+            agent_address,
+            agent_name,
             nonce,
         };
         let signature = sign_typed_data(&approve_agent, wallet)?;
-        let action = serde_json::to_value(Actions::ApproveAgent(approve_agent))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-        Ok((key, self.post(action, signature, nonce).await?))
+        let action = Actions::ApproveAgent(approve_agent);
+        self.post(action, signature, nonce).await
     }
 
     pub async fn withdraw_from_bridge(
         &self,
         amount: &str,
         destination: &str,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
+        self.check_destination(destination)?;
         let wallet = wallet.unwrap_or(&self.wallet);
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+        let hyperliquid_chain = self.hyperliquid_chain().to_string();
 
         let timestamp = next_nonce();
         let withdraw = Withdraw3 {
-            signature_chain_id: 421614.into(),
+            signature_chain_id: U256::from(self.network.user_signature_chain_id),
             hyperliquid_chain,
             destination: destination.to_string(),
             amount: amount.to_string(),
             time: timestamp,
         };
         let signature = sign_typed_data(&withdraw, wallet)?;
-        let action = serde_json::to_value(Actions::Withdraw3(withdraw))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let action = Actions::Withdraw3(withdraw);
 
         self.post(action, signature, timestamp).await
     }
@@ -686,18 +1793,65 @@ impl ExchangeClient {
         amount: &str,
         destination: &str,
         token: &str,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.check_destination(destination)?;
+        self.send_spot(amount, destination, token, wallet).await
+    }
+
+    /// Sends `amount` of `token`, rounded toward zero to the token's wei decimals so it is never
+    /// rejected for its precision. Fails with [`Error::TokenAmount`] if the amount is negative or
+    /// rounds to zero.
+    pub async fn spot_transfer_token(
+        &self,
+        amount: Decimal,
+        destination: &str,
+        token: &TokenInfo,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.check_destination(destination)?;
+        self.send_spot(
+            &token.format_amount(amount)?,
+            destination,
+            &token.spot_send_token(),
+            wallet,
+        )
+        .await
+    }
+
+    /// Moves `amount` of a linked spot token to the same address on HyperEVM by sending it to the
+    /// token's system address
+    pub async fn spot_transfer_to_evm(
+        &self,
+        amount: &str,
+        token: &TokenInfo,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        if token.evm_contract.is_none() && token.name != "HYPE" {
+            return Err(Error::AssetNotFound);
+        }
+        self.send_spot(
+            amount,
+            &token.system_address().to_string(),
+            &token.spot_send_token(),
+            wallet,
+        )
+        .await
+    }
+
+    async fn send_spot(
+        &self,
+        amount: &str,
+        destination: &str,
+        token: &str,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+        let hyperliquid_chain = self.hyperliquid_chain().to_string();
 
         let timestamp = next_nonce();
         let spot_send = SpotSend {
-            signature_chain_id: 421614.into(),
+            signature_chain_id: U256::from(self.network.user_signature_chain_id),
             hyperliquid_chain,
             destination: destination.to_string(),
             amount: amount.to_string(),
@@ -705,8 +1859,7 @@ impl ExchangeClient {
             token: token.to_string(),
         };
         let signature = sign_typed_data(&spot_send, wallet)?;
-        let action = serde_json::to_value(Actions::SpotSend(spot_send))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let action = Actions::SpotSend(spot_send);
 
         self.post(action, signature, timestamp).await
     }
@@ -714,7 +1867,7 @@ impl ExchangeClient {
     pub async fn set_referrer(
         &self,
         code: String,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
         let timestamp = next_nonce();
@@ -722,30 +1875,59 @@ impl ExchangeClient {
         let action = Actions::SetReferrer(SetReferrer { code });
 
         let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
 
-        let is_mainnet = self.http_client.is_mainnet();
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
+        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+        self.post(action, signature, timestamp).await
+    }
+
+    /// Sends a step of a spot token deployment, e.g. the actions built by [`crate::SpotGenesisBuilder`]
+    pub async fn spot_deploy(
+        &self,
+        spot_deploy: SpotDeploy,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let timestamp = next_nonce();
+
+        let action = Actions::SpotDeploy(spot_deploy);
+        let connection_id = action.hash(timestamp, self.vault_address)?;
+
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
         let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
         self.post(action, signature, timestamp).await
     }
 
+    /// Uses up `nonce` without doing anything else, so no action signed with it can land
+    /// afterwards. Fails if the nonce was already used.
+    pub async fn noop(
+        &self,
+        nonce: u64,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+
+        let action = Actions::Noop;
+        let connection_id = action.hash(nonce, self.vault_address)?;
+
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
+        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+        self.post(action, signature, nonce).await
+    }
+
     pub async fn approve_builder_fee(
         &self,
         builder: String,
         max_fee_rate: String,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&PrivateKeySigner>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
         let timestamp = next_nonce();
 
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+        let hyperliquid_chain = self.hyperliquid_chain().to_string();
 
         let action = Actions::ApproveBuilderFee(ApproveBuilderFee {
-            signature_chain_id: 421614.into(),
+            signature_chain_id: U256::from(self.network.user_signature_chain_id),
             hyperliquid_chain,
             builder,
             max_fee_rate,
@@ -753,9 +1935,8 @@ impl ExchangeClient {
         });
 
         let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
 
-        let is_mainnet = self.http_client.is_mainnet();
+        let is_mainnet = self.hyperliquid_chain().is_mainnet();
         let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
         self.post(action, signature, timestamp).await
     }
@@ -775,7 +1956,7 @@ fn round_to_significant_and_decimal(value: f64, sig_figs: u32, max_decimals: u32
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use std::str::FromStr;
 
     use super::*;
@@ -784,10 +1965,10 @@ mod tests {
         Order,
     };
 
-    fn get_wallet() -> Result<LocalWallet> {
+    fn get_wallet() -> Result<PrivateKeySigner> {
         let priv_key = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e";
         priv_key
-            .parse::<LocalWallet>()
+            .parse::<PrivateKeySigner>()
             .map_err(|e| Error::Wallet(e.to_string()))
     }
 
@@ -801,9 +1982,7 @@ mod tests {
                 limit_px: "2000.0".to_string(),
                 sz: "3.5".to_string(),
                 reduce_only: false,
-                order_type: Order::Limit(Limit {
-                    tif: "Ioc".to_string(),
-                }),
+                order_type: Order::Limit(Limit { tif: Tif::Ioc }),
                 cloid: None,
             }],
             grouping: "na".to_string(),
@@ -812,11 +1991,71 @@ mod tests {
         let connection_id = action.hash(1583838, None)?;
 
         let signature = sign_l1_action(&wallet, connection_id, true)?;
-        assert_eq!(signature.to_string(), "77957e58e70f43b6b68581f2dc42011fc384538a2e5b7bf42d5b936f19fbb67360721a8598727230f67080efee48c812a6a4442013fd3b0eed509171bef9f23f1c");
+        assert_eq!(hex::encode(signature.as_bytes()), "77957e58e70f43b6b68581f2dc42011fc384538a2e5b7bf42d5b936f19fbb67360721a8598727230f67080efee48c812a6a4442013fd3b0eed509171bef9f23f1c");
 
         let signature = sign_l1_action(&wallet, connection_id, false)?;
-        assert_eq!(signature.to_string(), "cd0925372ff1ed499e54883e9a6205ecfadec748f80ec463fe2f84f1209648776377961965cb7b12414186b1ea291e95fd512722427efcbcfb3b0b2bcd4d79d01c");
+        assert_eq!(hex::encode(signature.as_bytes()), "cd0925372ff1ed499e54883e9a6205ecfadec748f80ec463fe2f84f1209648776377961965cb7b12414186b1ea291e95fd512722427efcbcfb3b0b2bcd4d79d01c");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_raw_action_hashing_follows_key_order() -> Result<()> {
+        let action = Actions::Order(BulkOrder {
+            orders: vec![OrderRequest {
+                asset: 1,
+                is_buy: true,
+                limit_px: "2000.0".to_string(),
+                sz: "3.5".to_string(),
+                reduce_only: false,
+                order_type: Order::Limit(Limit { tif: Tif::Ioc }),
+                cloid: None,
+            }],
+            grouping: "na".to_string(),
+            builder: None,
+        });
+        let order = serde_json::json!({"a": 1, "b": true, "p": "2000.0", "s": "3.5", "r": false, "t": {"limit": {"tif": "Ioc"}}});
+
+        let raw =
+            Actions::Raw(serde_json::json!({"type": "order", "orders": [order], "grouping": "na"}));
+        assert_eq!(raw.hash(1583838, None)?, action.hash(1583838, None)?);
+
+        // same action with keys in another order signs a different hash
+        let reordered =
+            Actions::Raw(serde_json::json!({"type": "order", "grouping": "na", "orders": [order]}));
+        assert_ne!(reordered.hash(1583838, None)?, action.hash(1583838, None)?);
+        Ok(())
+    }
 
+    #[test]
+    fn test_preview_matches_signed_action() -> Result<()> {
+        let exchange_client = test_client(crate::MAINNET_API_URL.to_string())?;
+        let action = Actions::Order(BulkOrder {
+            orders: vec![OrderRequest {
+                asset: 1,
+                is_buy: true,
+                limit_px: "2000.0".to_string(),
+                sz: "3.5".to_string(),
+                reduce_only: false,
+                order_type: Order::Limit(Limit { tif: Tif::Ioc }),
+                cloid: None,
+            }],
+            grouping: "na".to_string(),
+            builder: None,
+        });
+        let preview = exchange_client.preview(action.clone(), Some(1583838), None)?;
+
+        assert_eq!(preview.action_hash, action.hash(1583838, None)?);
+        assert_eq!(preview.signature, "77957e58e70f43b6b68581f2dc42011fc384538a2e5b7bf42d5b936f19fbb67360721a8598727230f67080efee48c812a6a4442013fd3b0eed509171bef9f23f1c");
+        // msgpack map of 3 entries starting with "type", ending with the nonce and no vault
+        let msgpack_hex = preview.msgpack_hex.unwrap();
+        assert!(msgpack_hex.starts_with("83a474797065"));
+        assert!(msgpack_hex.ends_with("0000000000182ade00"));
+        let body: serde_json::Value =
+            serde_json::from_str(&preview.body).map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(body["nonce"], 1583838);
+        assert_eq!(body["action"]["type"], "order");
+        assert_eq!(body["signature"]["v"], 28);
         Ok(())
     }
 
@@ -832,9 +2071,7 @@ mod tests {
                 limit_px: "2000.0".to_string(),
                 sz: "3.5".to_string(),
                 reduce_only: false,
-                order_type: Order::Limit(Limit {
-                    tif: "Ioc".to_string(),
-                }),
+                order_type: Order::Limit(Limit { tif: Tif::Ioc }),
                 cloid: Some(uuid_to_hex_string(cloid.unwrap())),
             }],
             grouping: "na".to_string(),
@@ -843,10 +2080,10 @@ mod tests {
         let connection_id = action.hash(1583838, None)?;
 
         let signature = sign_l1_action(&wallet, connection_id, true)?;
-        assert_eq!(signature.to_string(), "d3e894092eb27098077145714630a77bbe3836120ee29df7d935d8510b03a08f456de5ec1be82aa65fc6ecda9ef928b0445e212517a98858cfaa251c4cd7552b1c");
+        assert_eq!(hex::encode(signature.as_bytes()), "d3e894092eb27098077145714630a77bbe3836120ee29df7d935d8510b03a08f456de5ec1be82aa65fc6ecda9ef928b0445e212517a98858cfaa251c4cd7552b1c");
 
         let signature = sign_l1_action(&wallet, connection_id, false)?;
-        assert_eq!(signature.to_string(), "3768349dbb22a7fd770fc9fc50c7b5124a7da342ea579b309f58002ceae49b4357badc7909770919c45d850aabb08474ff2b7b3204ae5b66d9f7375582981f111c");
+        assert_eq!(hex::encode(signature.as_bytes()), "3768349dbb22a7fd770fc9fc50c7b5124a7da342ea579b309f58002ceae49b4357badc7909770919c45d850aabb08474ff2b7b3204ae5b66d9f7375582981f111c");
 
         Ok(())
     }
@@ -888,14 +2125,88 @@ mod tests {
             let connection_id = action.hash(1583838, None)?;
 
             let signature = sign_l1_action(&wallet, connection_id, true)?;
-            assert_eq!(signature.to_string(), mainnet_signature);
+            assert_eq!(hex::encode(signature.as_bytes()), mainnet_signature);
 
             let signature = sign_l1_action(&wallet, connection_id, false)?;
-            assert_eq!(signature.to_string(), testnet_signature);
+            assert_eq!(hex::encode(signature.as_bytes()), testnet_signature);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_tpsl_constructors() -> Result<()> {
+        let coin_to_asset = HashMap::from([("ETH".to_string(), 1)]);
+        let eth = Coin::try_from("ETH")?;
+        for (order, tpsl) in [
+            (
+                ClientOrderRequest::take_profit(&eth, false, 2500.0, 3.5, true),
+                "tp",
+            ),
+            (
+                ClientOrderRequest::stop_loss(&eth, false, 1500.0, 3.5, false),
+                "sl",
+            ),
+        ] {
+            let order = order.convert(&coin_to_asset)?;
+            assert!(order.reduce_only);
+            assert!(!order.is_buy);
+            assert_eq!(order.sz, "3.5");
+            match order.order_type {
+                Order::Trigger(trigger) => {
+                    assert_eq!(trigger.tpsl, tpsl);
+                    assert_eq!(trigger.trigger_px, order.limit_px);
+                }
+                Order::Limit(_) => panic!("expected a trigger order"),
+            }
         }
         Ok(())
     }
 
+    #[test]
+    fn test_sub_account_transfer_serialization() -> Result<()> {
+        let action = Actions::SubAccountTransfer(SubAccountUsdTransfer {
+            sub_account_user: "0x1d9470d4b963f552e6f671a81619d395877bf409"
+                .parse()
+                .map_err(|_| Error::GenericParse("bad address".to_string()))?,
+            is_deposit: true,
+            usd: 1_000_000,
+        });
+        assert_eq!(
+            serde_json::to_string(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            r#"{"type":"subAccountTransfer","subAccountUser":"0x1d9470d4b963f552e6f671a81619d395877bf409","isDeposit":true,"usd":1000000}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_exchange_payload_signature_serialization() -> Result<()> {
+        let wallet = get_wallet()?;
+        let action = Actions::Cancel(BulkCancel {
+            cancels: vec![CancelRequest {
+                asset: 1,
+                oid: 82382,
+            }],
+        });
+        let signature = sign_l1_action(&wallet, action.hash(1583838, None)?, true)?;
+        let payload = ExchangePayload {
+            action: serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            signature,
+            nonce: 1583838,
+            vault_address: None,
+        };
+        let payload =
+            serde_json::to_value(&payload).map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(
+            payload["signature"],
+            serde_json::json!({
+                "r": "0x2f76cc5b16e0810152fa0e14e7b219f49c361e3325f771544c6f54e157bf9fa",
+                "s": "0x17ed0afc11a98596be85d5cd9f86600aad515337318f7ab346e5ccc1b03425d5",
+                "v": 27
+            })
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_cancel_action_hashing() -> Result<()> {
         let wallet = get_wallet()?;
@@ -908,11 +2219,479 @@ mod tests {
         let connection_id = action.hash(1583838, None)?;
 
         let signature = sign_l1_action(&wallet, connection_id, true)?;
-        assert_eq!(signature.to_string(), "02f76cc5b16e0810152fa0e14e7b219f49c361e3325f771544c6f54e157bf9fa17ed0afc11a98596be85d5cd9f86600aad515337318f7ab346e5ccc1b03425d51b");
+        assert_eq!(hex::encode(signature.as_bytes()), "02f76cc5b16e0810152fa0e14e7b219f49c361e3325f771544c6f54e157bf9fa17ed0afc11a98596be85d5cd9f86600aad515337318f7ab346e5ccc1b03425d51b");
 
         let signature = sign_l1_action(&wallet, connection_id, false)?;
-        assert_eq!(signature.to_string(), "6ffebadfd48067663390962539fbde76cfa36f53be65abe2ab72c9db6d0db44457720db9d7c4860f142a484f070c84eb4b9694c3a617c83f0d698a27e55fd5e01c");
+        assert_eq!(hex::encode(signature.as_bytes()), "6ffebadfd48067663390962539fbde76cfa36f53be65abe2ab72c9db6d0db44457720db9d7c4860f142a484f070c84eb4b9694c3a617c83f0d698a27e55fd5e01c");
+
+        Ok(())
+    }
+
+    pub(crate) fn test_client(base_url: String) -> Result<ExchangeClient> {
+        let meta: Meta = serde_json::from_value(serde_json::json!({
+            "universe": [{"name": "ETH", "szDecimals": 4, "maxLeverage": 50}]
+        }))
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        Ok(ExchangeClient {
+            http_client: HttpClient {
+                client: Client::new(),
+                base_url,
+            },
+            wallet: get_wallet()?,
+            meta: Arc::new(meta),
+            vault_address: None,
+            coin_to_asset: Arc::new(HashMap::from([("ETH".to_string(), 0)])),
+            self_cross_guard: None,
+            order_limits: None,
+            perp_dex_indices: Arc::default(),
+            hooks: ExchangeHooks::default(),
+            audit_sink: None,
+            address_book: None,
+            order_id_store: None,
+            asset_registry: None,
+            fallback_registry: Arc::default(),
+            order_defaults: OrderDefaults::default(),
+            status_monitor: None,
+            request_options: RequestOptions::default(),
+            network: NetworkConfig::default(),
+            #[cfg(feature = "ws")]
+            fill_watcher: Arc::default(),
+        })
+    }
+
+    /// Answers each request with the json `respond` gives for its path and body, or with a 500
+    /// if it gives `None`
+    pub(crate) async fn serve_json(
+        listener: tokio::net::TcpListener,
+        mut respond: impl FnMut(&str, &serde_json::Value) -> Option<serde_json::Value>,
+    ) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            let (path, body) = loop {
+                let read = stream.read(&mut buf).await.unwrap_or(0);
+                request.extend_from_slice(&buf[..read]);
+                let text = String::from_utf8_lossy(&request);
+                let Some(header_end) = text.find("\r\n\r\n") else {
+                    if read == 0 {
+                        break (String::new(), String::new());
+                    }
+                    continue;
+                };
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|line| {
+                        line.to_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|len| len.trim().parse::<usize>().unwrap_or(0))
+                    })
+                    .unwrap_or(0);
+                if request.len() >= header_end + 4 + content_length || read == 0 {
+                    let path = text.split_whitespace().nth(1).unwrap_or_default();
+                    break (path.to_string(), text[header_end + 4..].to_string());
+                }
+            };
+            let body = serde_json::from_str(&body).unwrap_or_default();
+            let response = match respond(&path, &body) {
+                Some(json) => {
+                    let json = json.to_string();
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{json}",
+                        json.len()
+                    )
+                }
+                None => "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+            };
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    }
+
+    /// Runs [`serve_json`] on a local port, returning its base url
+    pub(crate) async fn json_server(
+        respond: impl FnMut(&str, &serde_json::Value) -> Option<serde_json::Value> + Send + 'static,
+    ) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve_json(listener, respond));
+        base_url
+    }
+
+    /// Answers every request with a resting order status carrying an increasing oid
+    pub(crate) async fn serve_resting_orders(listener: tokio::net::TcpListener) {
+        let mut oid = 0;
+        serve_json(listener, |_, _| {
+            oid += 1;
+            Some(serde_json::json!({
+                "status": "ok",
+                "response": {"type": "order", "data": {"statuses": [{"resting": {"oid": oid}}]}}
+            }))
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_clones_place_orders_concurrently() -> Result<()> {
+        fn assert_send_sync<T: Clone + Send + Sync>() {}
+        assert_send_sync::<ExchangeClient>();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| Error::GenericRequest(e.to_string()))?;
+        let base_url = format!(
+            "http://{}",
+            listener
+                .local_addr()
+                .map_err(|e| Error::GenericRequest(e.to_string()))?
+        );
+        tokio::spawn(serve_resting_orders(listener));
+
+        let mut exchange_client = test_client(base_url)?;
+        exchange_client.enable_self_cross_guard(SelfCrossPolicy::Reject);
+
+        let tasks: Vec<_> = (0..8)
+            .map(|i| {
+                let exchange_client = exchange_client.clone();
+                tokio::spawn(async move {
+                    let order = ClientOrderRequest {
+                        asset: "ETH".to_string(),
+                        is_buy: true,
+                        reduce_only: false,
+                        limit_px: 1000.0 + i as f64,
+                        sz: 0.1,
+                        cloid: None,
+                        stp: None,
+                        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+                    };
+                    exchange_client.place_order(order, None).await
+                })
+            })
+            .collect();
+        for task in tasks {
+            let response = task
+                .await
+                .map_err(|e| Error::GenericRequest(e.to_string()))??;
+            assert!(matches!(response, ExchangeResponseStatus::Ok(_)));
+        }
+
+        // every clone recorded its resting order in the shared guard
+        let guard = exchange_client.self_cross_guard.as_ref().unwrap();
+        let mut oids: Vec<u64> = guard
+            .resting_orders("ETH")
+            .iter()
+            .map(|order| order.oid)
+            .collect();
+        oids.sort_unstable();
+        assert_eq!(oids, (1..=8).collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fallback_registry_is_reused() -> Result<()> {
+        let exchange_client = test_client("http://127.0.0.1:1".to_string())?;
+        let clone = exchange_client.clone();
+        assert_eq!(exchange_client.sz_decimals("ETH").await?, 4);
+        assert!(exchange_client.fallback_registry.get().is_none());
+
+        // nothing listens there, so the lookup fails, but the registry is kept for later calls
+        assert!(exchange_client.sz_decimals("PURR/USDC").await.is_err());
+        let registry = exchange_client.fallback_registry.get().unwrap();
+        assert!(std::ptr::eq(
+            registry,
+            clone.fallback_registry.get().unwrap()
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_order_checks_tick_and_lot() -> Result<()> {
+        // nothing listens there, so an order that got past the check would fail to send
+        let exchange_client = test_client("http://127.0.0.1:1".to_string())?;
+        let order = |px: &str, sz: &str| {
+            DecimalOrder::new(
+                "ETH",
+                true,
+                px.parse().unwrap(),
+                sz.parse().unwrap(),
+                Tif::Gtc,
+            )
+        };
+
+        // ETH has 4 sz decimals, leaving 2 price decimals, and prices have 5 significant figures
+        for order in [
+            order("180.123", "0.1"),
+            order("1800.12", "0.1"),
+            order("1800.1", "0.12345"),
+        ] {
+            assert!(matches!(
+                exchange_client.order(order.clone(), None).await,
+                Err(Error::OrderLimit { .. })
+            ));
+            assert!(matches!(
+                exchange_client.modify(1, order, None).await,
+                Err(Error::OrderLimit { .. })
+            ));
+        }
+        assert!(matches!(
+            exchange_client.order(order("1800.1", "0.1234"), None).await,
+            Err(Error::JsonParse(_))
+        ));
+        Ok(())
+    }
+
+    /// Answers the metadata requests of an [`AssetRegistry`] with ETH and the HYPE/USDC spot pair
+    pub(crate) fn registry_metadata(body: &serde_json::Value) -> Option<serde_json::Value> {
+        use serde_json::json;
+
+        match body["type"].as_str()? {
+            "meta" => {
+                Some(json!({"universe": [{"name": "ETH", "szDecimals": 4, "maxLeverage": 25}]}))
+            }
+            "spotMeta" => Some(json!({
+                "universe": [{"name": "@107", "tokens": [150, 0], "index": 107, "isCanonical": false}],
+                "tokens": [
+                    {"name": "USDC", "szDecimals": 8, "weiDecimals": 8, "index": 0, "tokenId": "0x6d1e7cde53ba9467b783cb7c530ce054", "isCanonical": true},
+                    {"name": "HYPE", "szDecimals": 2, "weiDecimals": 8, "index": 150, "tokenId": "0x0d01dc56dcaaca66ad901c959b4011ec", "isCanonical": false}
+                ]
+            })),
+            "perpDexs" => Some(json!([null])),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slippage_price_of_spot_pair() -> Result<()> {
+        let base_url = json_server(|_, body| {
+            registry_metadata(body).or_else(|| match body["type"].as_str() {
+                Some("allMids") => Some(serde_json::json!({"ETH": "2000.0", "@107": "20.0"})),
+                _ => None,
+            })
+        })
+        .await;
+        let exchange_client = test_client(base_url)?;
+
+        // not in the perp metadata the client was built with
+        let (px, sz_decimals) = exchange_client
+            .calculate_slippage_price("HYPE/USDC", true, 0.05, None)
+            .await?;
+        assert_eq!(sz_decimals, 2);
+        assert!((px - 21.0).abs() < crate::EPSILON);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_info_client_shares_asset_registry() -> Result<()> {
+        let base_url = json_server(|_, body| {
+            registry_metadata(body).or_else(|| match body["type"].as_str() {
+                Some("l2Book") if body["coin"] == "@107" => Some(serde_json::json!({
+                    "coin": "@107", "levels": [[], []], "time": 0
+                })),
+                _ => None,
+            })
+        })
+        .await;
+        let mut exchange_client = test_client(base_url)?;
+        let asset_registry =
+            AssetRegistry::new(&exchange_client.info_client(), exchange_client.network);
+        exchange_client.set_asset_registry(asset_registry.clone());
+
+        let info_client = exchange_client.info_client();
+        let book = info_client.l2_snapshot("HYPE/USDC".to_string()).await?;
+        assert_eq!(book.coin, "@107");
+        assert!(asset_registry.get("HYPE/USDC").is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stp_needs_self_cross_guard() -> Result<()> {
+        // nothing listens there, so an order that got past the check would fail to send
+        let exchange_client = test_client("http://127.0.0.1:1".to_string())?;
+        let order = ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 2000.0,
+            sz: 0.1,
+            cloid: None,
+            stp: Some(crate::StpMode::CancelNewest),
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        };
+        match exchange_client.place_order(order, None).await {
+            Err(Error::Config(err)) => assert!(err.contains("SelfCrossGuard")),
+            result => panic!("unexpected result {result:?}"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_chain_is_per_client() -> Result<()> {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let client = |network: NetworkConfig| -> Result<ExchangeClient> {
+            let mut client = test_client("http://127.0.0.1:1".to_string())?;
+            client.network = network;
+            let sent = Arc::clone(&sent);
+            client.add_pre_send_hook(move |action, context| {
+                if let Actions::UsdSend(usd_send) = action {
+                    sent.lock().unwrap().push((
+                        usd_send.hyperliquid_chain.clone(),
+                        usd_send.signature_chain_id,
+                        context.is_mainnet,
+                    ));
+                }
+                Err(Error::GenericRequest("not sent".to_string()))
+            });
+            Ok(client)
+        };
+        let mainnet = client(NetworkConfig {
+            hyperliquid_chain: Some(HyperliquidChain::Mainnet),
+            user_signature_chain_id: 42_161,
+            ..NetworkConfig::default()
+        })?;
+        let testnet = client(NetworkConfig::default())?;
+        assert_eq!(testnet.hyperliquid_chain(), HyperliquidChain::Testnet);
+
+        let destination = "0x0D1d9635D0640821d15e323ac8AdADfA9c111414";
+        assert!(mainnet.usdc_transfer("1", destination, None).await.is_err());
+        assert!(testnet.usdc_transfer("1", destination, None).await.is_err());
+        assert!(mainnet.usdc_transfer("1", destination, None).await.is_err());
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec![
+                ("Mainnet".to_string(), U256::from(42_161), true),
+                ("Testnet".to_string(), U256::from(421_614), false),
+                ("Mainnet".to_string(), U256::from(42_161), true),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_order_defaults() -> Result<()> {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut client = test_client("http://127.0.0.1:1".to_string())?;
+        client.set_order_defaults(OrderDefaults {
+            tif: Tif::Alo,
+            reduce_only: true,
+            builder: Some(BuilderInfo {
+                builder: "0xABC".to_string(),
+                fee: 10,
+            }),
+            ..OrderDefaults::default()
+        });
+        let reduce_only = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let builders = Arc::clone(&sent);
+        let sent_reduce_only = Arc::clone(&reduce_only);
+        client.add_pre_send_hook(move |action, _| {
+            if let Actions::Order(bulk_order) = action {
+                let builder = bulk_order.builder.as_ref().map(|b| b.builder.clone());
+                builders.lock().unwrap().push(builder);
+                sent_reduce_only
+                    .lock()
+                    .unwrap()
+                    .push(bulk_order.orders[0].reduce_only);
+            }
+            Err(Error::GenericRequest("not sent".to_string()))
+        });
+
+        let order = client.limit_order_request("ETH", true, 1800.0, 0.1);
+        assert!(order.reduce_only);
+        assert!(matches!(
+            order.order_type,
+            ClientOrder::Limit(ClientLimit { tif: Tif::Alo })
+        ));
+        assert!(client.place_order(order.clone(), None).await.is_err());
+        let builder = BuilderInfo {
+            builder: "0xdef".to_string(),
+            fee: 1,
+        };
+        assert!(client
+            .order_with_builder(order, None, builder)
+            .await
+            .is_err());
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec![Some("0xabc".to_string()), Some("0xdef".to_string())]
+        );
+
+        // market opens are never reduce only, whatever the default
+        let params = MarketOrderParams {
+            asset: "ETH",
+            is_buy: true,
+            sz: 0.1,
+            px: Some(1800.0),
+            slippage: None,
+            cloid: None,
+            wallet: None,
+        };
+        assert!(client.market_open(params).await.is_err());
+        assert_eq!(*reduce_only.lock().unwrap(), vec![true, true, false]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_chunk_is_of_unknown_fate() -> Result<()> {
+        // accepts connections and never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut streams = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+            }
+        });
+        let client = test_client(base_url)?.with_request_options(
+            RequestOptions::default().with_timeout(std::time::Duration::from_millis(50)),
+        );
 
+        let order = client.limit_order_request("ETH", true, 1800.0, 0.1);
+        let results = client
+            .chunked_order_results(vec![order.clone(), order], 1, None)
+            .await?;
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|result| result.fate_unknown && !result.is_error()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_intents_partially_sent_keep_sent_results() -> Result<()> {
+        let base_url = json_server(|_, _| {
+            Some(serde_json::json!({
+                "status": "ok",
+                "response": {"type": "cancel", "data": {"statuses": ["success"]}}
+            }))
+        })
+        .await;
+        let mut client = test_client(base_url)?;
+        client.add_pre_send_hook(|action, _| match action {
+            Actions::Order(_) => Err(Error::HookVeto("orders paused".to_string())),
+            _ => Ok(()),
+        });
+        let intents = Intent::parse(
+            r#"[
+                {"kind": "cancel", "coin": "ETH", "oid": 7},
+                {"kind": "order", "coin": "ETH", "side": "buy", "px": 1800.5, "sz": 0.1}
+            ]"#,
+        )?;
+
+        match client.execute_intents(&intents, None).await {
+            Err(Error::IntentsPartiallySent { results, error }) => {
+                assert!(results.cancels.is_some());
+                assert!(results.orders.is_none());
+                assert!(matches!(*error, Error::HookVeto(_)));
+            }
+            other => panic!("expected a partially sent error, got {other:?}"),
+        }
+        // nothing was sent before the failing batch, so its error is returned as is
+        assert!(matches!(
+            client.execute_intents(&intents[1..], None).await,
+            Err(Error::HookVeto(_))
+        ));
         Ok(())
     }
 }
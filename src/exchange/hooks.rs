@@ -0,0 +1,89 @@
+use crate::Address;
+use crate::{prelude::*, Actions, ExchangeResponseStatus};
+use std::{fmt, sync::Arc};
+
+/// Details of a signed request passed to hooks alongside its action
+#[derive(Debug, Clone)]
+pub struct HookContext {
+    pub nonce: u64,
+    pub vault_address: Option<Address>,
+    pub is_mainnet: bool,
+}
+
+/// Runs before a signed action is sent. Returning an error vetoes the send and is returned to the caller.
+pub type PreSendHook = Arc<dyn Fn(&Actions, &HookContext) -> Result<()> + Send + Sync>;
+/// Runs after the exchange responds to an action
+pub type PostReceiveHook =
+    Arc<dyn Fn(&Actions, &HookContext, &ExchangeResponseStatus) + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub(crate) struct ExchangeHooks {
+    pre_send: Vec<PreSendHook>,
+    post_receive: Vec<PostReceiveHook>,
+}
+
+impl ExchangeHooks {
+    pub(crate) fn add_pre_send(&mut self, hook: PreSendHook) {
+        self.pre_send.push(hook);
+    }
+
+    pub(crate) fn add_post_receive(&mut self, hook: PostReceiveHook) {
+        self.post_receive.push(hook);
+    }
+
+    /// Runs pre-send hooks in registration order, stopping at the first veto
+    pub(crate) fn pre_send(&self, action: &Actions, context: &HookContext) -> Result<()> {
+        self.pre_send
+            .iter()
+            .try_for_each(|hook| hook(action, context))
+    }
+
+    pub(crate) fn post_receive(
+        &self,
+        action: &Actions,
+        context: &HookContext,
+        response: &ExchangeResponseStatus,
+    ) {
+        for hook in &self.post_receive {
+            hook(action, context, response);
+        }
+    }
+}
+
+impl fmt::Debug for ExchangeHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExchangeHooks")
+            .field("pre_send", &self.pre_send.len())
+            .field("post_receive", &self.post_receive.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, SetReferrer};
+
+    #[test]
+    fn test_pre_send_veto() {
+        let mut hooks = ExchangeHooks::default();
+        hooks.add_pre_send(Arc::new(|action, _| match action {
+            Actions::SetReferrer(_) => {
+                Err(Error::HookVeto("referrer changes disabled".to_string()))
+            }
+            _ => Ok(()),
+        }));
+        let context = HookContext {
+            nonce: 0,
+            vault_address: None,
+            is_mainnet: false,
+        };
+        let action = Actions::SetReferrer(SetReferrer {
+            code: "CODE".to_string(),
+        });
+        assert!(matches!(
+            hooks.pre_send(&action, &context),
+            Err(Error::HookVeto(_))
+        ));
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{
+    prelude::*, ClientCancelRequest, ClientCancelRequestCloid, ClientLimit, ClientOrder,
+    ClientOrderRequest, Error, ExchangeResponseStatus, Tif,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Desired order or cancel, as sent by a strategy process, e.g.
+/// `{"kind": "order", "coin": "ETH", "side": "buy", "px": 1800.5, "sz": 0.1, "tif": "Alo"}` or
+/// `{"kind": "cancel", "coin": "ETH", "oid": 77738308}`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Intent {
+    Order(OrderIntent),
+    Cancel(CancelIntent),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum IntentSide {
+    Buy,
+    Sell,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct OrderIntent {
+    pub coin: String,
+    pub side: IntentSide,
+    pub px: f64,
+    pub sz: f64,
+    #[serde(default = "default_tif")]
+    pub tif: Tif,
+    #[serde(default)]
+    pub reduce_only: bool,
+    #[serde(default)]
+    pub cloid: Option<Uuid>,
+}
+
+/// Cancels by `oid` or by `cloid`, exactly one of which must be set
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CancelIntent {
+    pub coin: String,
+    #[serde(default)]
+    pub oid: Option<u64>,
+    #[serde(default)]
+    pub cloid: Option<Uuid>,
+}
+
+fn default_tif() -> Tif {
+    Tif::Gtc
+}
+
+impl Intent {
+    /// Parses a single intent or an array of them
+    pub fn parse(json: &str) -> Result<Vec<Intent>> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum OneOrMany {
+            Many(Vec<Intent>),
+            One(Intent),
+        }
+        match serde_json::from_str(json).map_err(|e| Error::JsonParse(e.to_string()))? {
+            OneOrMany::Many(intents) => Ok(intents),
+            OneOrMany::One(intent) => Ok(vec![intent]),
+        }
+    }
+}
+
+/// Responses of the batched actions [`crate::ExchangeClient::execute_intents`] sent, `None` for
+/// batches without intents
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentResults {
+    pub cancels: Option<ExchangeResponseStatus>,
+    pub cancels_by_cloid: Option<ExchangeResponseStatus>,
+    pub orders: Option<ExchangeResponseStatus>,
+}
+
+impl IntentResults {
+    /// `response` of the next batch, or if it failed an error carrying the responses of the
+    /// batches already sent
+    pub(crate) fn next(
+        &self,
+        response: Result<ExchangeResponseStatus>,
+    ) -> Result<ExchangeResponseStatus> {
+        let nothing_sent =
+            self.cancels.is_none() && self.cancels_by_cloid.is_none() && self.orders.is_none();
+        response.map_err(|error| {
+            if nothing_sent {
+                error
+            } else {
+                Error::IntentsPartiallySent {
+                    results: Box::new(self.clone()),
+                    error: Box::new(error),
+                }
+            }
+        })
+    }
+}
+
+/// Intents split into one batch per action, in the order they are sent
+#[derive(Debug, Default)]
+pub(crate) struct IntentBatches {
+    pub(crate) cancels: Vec<ClientCancelRequest>,
+    pub(crate) cancels_by_cloid: Vec<ClientCancelRequestCloid>,
+    pub(crate) orders: Vec<ClientOrderRequest>,
+}
+
+impl IntentBatches {
+    /// Validates every intent before anything is sent, so a bad intent rejects the whole batch
+    pub(crate) fn new(
+        intents: &[Intent],
+        coin_to_asset: &HashMap<String, u32>,
+    ) -> Result<IntentBatches> {
+        let mut batches = IntentBatches::default();
+        let mut cloids = HashSet::new();
+        for (index, intent) in intents.iter().enumerate() {
+            let invalid = |reason: &str| Error::Intent(format!("intent {index}: {reason}"));
+            match intent {
+                Intent::Order(order) => {
+                    if !coin_to_asset.contains_key(&order.coin) {
+                        return Err(invalid(&format!("unknown coin {}", order.coin)));
+                    }
+                    if !order.px.is_finite() || order.px <= 0.0 {
+                        return Err(invalid("px must be positive"));
+                    }
+                    if !order.sz.is_finite() || order.sz <= 0.0 {
+                        return Err(invalid("sz must be positive"));
+                    }
+                    if let Some(cloid) = order.cloid {
+                        if !cloids.insert(cloid) {
+                            return Err(invalid(&format!("duplicate cloid {cloid}")));
+                        }
+                    }
+                    batches.orders.push(ClientOrderRequest {
+                        asset: order.coin.clone(),
+                        is_buy: order.side == IntentSide::Buy,
+                        reduce_only: order.reduce_only,
+                        limit_px: order.px,
+                        sz: order.sz,
+                        cloid: order.cloid,
+                        stp: None,
+                        order_type: ClientOrder::Limit(ClientLimit { tif: order.tif }),
+                    });
+                }
+                Intent::Cancel(cancel) => {
+                    if !coin_to_asset.contains_key(&cancel.coin) {
+                        return Err(invalid(&format!("unknown coin {}", cancel.coin)));
+                    }
+                    match (cancel.oid, cancel.cloid) {
+                        (Some(oid), None) => batches.cancels.push(ClientCancelRequest {
+                            asset: cancel.coin.clone(),
+                            oid,
+                        }),
+                        (None, Some(cloid)) => {
+                            batches.cancels_by_cloid.push(ClientCancelRequestCloid {
+                                asset: cancel.coin.clone(),
+                                cloid,
+                            })
+                        }
+                        _ => return Err(invalid("cancel needs exactly one of oid and cloid")),
+                    }
+                }
+            }
+        }
+        Ok(batches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intent_batches() {
+        let intents = Intent::parse(
+            r#"[
+                {"kind": "cancel", "coin": "ETH", "oid": 7},
+                {"kind": "order", "coin": "ETH", "side": "buy", "px": 1800.5, "sz": 0.1, "tif": "Alo",
+                 "cloid": "1e60610f-0b3d-4205-97c8-8c1fed2ad5ee"},
+                {"kind": "order", "coin": "BTC", "side": "sell", "px": 60000, "sz": 0.01},
+                {"kind": "cancel", "coin": "BTC", "cloid": "2e60610f-0b3d-4205-97c8-8c1fed2ad5ee"}
+            ]"#,
+        )
+        .unwrap();
+        let coin_to_asset = HashMap::from([("ETH".to_string(), 1), ("BTC".to_string(), 0)]);
+
+        let batches = IntentBatches::new(&intents, &coin_to_asset).unwrap();
+        assert_eq!(batches.cancels.len(), 1);
+        assert_eq!(batches.cancels_by_cloid.len(), 1);
+        assert_eq!(batches.orders.len(), 2);
+        assert!(batches.orders[0].is_buy);
+        assert!(matches!(
+            batches.orders[1].order_type,
+            ClientOrder::Limit(ClientLimit { tif: Tif::Gtc })
+        ));
+
+        let single = Intent::parse(r#"{"kind": "cancel", "coin": "ETH", "oid": 7}"#).unwrap();
+        assert_eq!(single.len(), 1);
+
+        let invalid = [
+            r#"{"kind": "order", "coin": "DOGE", "side": "buy", "px": 0.1, "sz": 10}"#,
+            r#"{"kind": "order", "coin": "ETH", "side": "buy", "px": -1, "sz": 1}"#,
+            r#"{"kind": "cancel", "coin": "ETH"}"#,
+        ];
+        for json in invalid {
+            let intents = Intent::parse(json).unwrap();
+            assert!(matches!(
+                IntentBatches::new(&intents, &coin_to_asset),
+                Err(Error::Intent(_))
+            ));
+        }
+        assert!(Intent::parse(r#"{"kind": "order", "coin": "ETH", "side": "up"}"#).is_err());
+    }
+
+    #[test]
+    fn test_intent_results_json() {
+        // results serialize back to the exchange's own response format
+        let orders = serde_json::json!({
+            "status": "ok",
+            "response": {"type": "order", "data": {"statuses": [
+                {"resting": {"oid": 77738308}},
+                {"error": "Order must have minimum value of $10."}
+            ]}}
+        });
+        let results = IntentResults {
+            orders: Some(serde_json::from_value(orders.clone()).unwrap()),
+            ..IntentResults::default()
+        };
+        assert_eq!(
+            serde_json::to_value(results).unwrap(),
+            serde_json::json!({"cancels": null, "cancelsByCloid": null, "orders": orders})
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    prelude::*, ClientOrderRequest, Error, ExchangeClient, ExchangeResponseStatus, OrderBook,
+};
+use alloy::signers::local::PrivateKeySigner;
+
+use super::simulate::reachable_levels;
+
+/// Per-order checks against the current book, meant for thin books such as freshly listed
+/// spot tokens. Unset limits are not checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LiquidityGuard {
+    /// Minimum opposite-side size resting at or better than the order's limit price
+    pub min_depth: Option<f64>,
+    /// Maximum spread between the best bid and ask, in basis points of the mid
+    pub max_spread_bps: Option<f64>,
+}
+
+impl LiquidityGuard {
+    pub fn new() -> LiquidityGuard {
+        LiquidityGuard::default()
+    }
+
+    pub fn min_depth(mut self, min_depth: f64) -> LiquidityGuard {
+        self.min_depth = Some(min_depth);
+        self
+    }
+
+    pub fn max_spread_bps(mut self, max_spread_bps: f64) -> LiquidityGuard {
+        self.max_spread_bps = Some(max_spread_bps);
+        self
+    }
+
+    /// Checks `order` against `book`. A one-sided book fails any spread limit.
+    pub fn check(&self, order: &ClientOrderRequest, book: &OrderBook) -> Result<()> {
+        let refuse = |reason: String| {
+            Err(Error::LiquidityGuard {
+                asset: order.asset.clone(),
+                reason,
+            })
+        };
+        if let Some(max_spread_bps) = self.max_spread_bps {
+            let (Some(bid), Some(ask)) = (book.best_bid(), book.best_ask()) else {
+                return refuse("book is one-sided".to_string());
+            };
+            let spread_bps = (ask - bid) / ((ask + bid) / 2.0) * 10_000.0;
+            if spread_bps > max_spread_bps {
+                return refuse(format!(
+                    "spread {spread_bps:.1}bps exceeds {max_spread_bps}bps"
+                ));
+            }
+        }
+        if let Some(min_depth) = self.min_depth {
+            let depth = depth_at(order, book);
+            if depth < min_depth {
+                return refuse(format!(
+                    "depth {depth} at {} is below {min_depth}",
+                    order.limit_px
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Opposite-side size resting at or better than `order`'s limit price
+fn depth_at(order: &ClientOrderRequest, book: &OrderBook) -> f64 {
+    reachable_levels(order, book).map(|(_, sz)| sz).sum()
+}
+
+impl ExchangeClient {
+    /// Fetches the book of `order`'s asset and only sends the order if it passes `guard`.
+    /// Spot pairs may be given by their token names, e.g. "HFUN/USDC".
+    pub async fn order_with_liquidity_guard(
+        &self,
+        order: ClientOrderRequest,
+        guard: &LiquidityGuard,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<ExchangeResponseStatus> {
+        let coin = self.asset_info(&order.asset).await?.name;
+        let snapshot = self.info_client().l2_snapshot(coin).await?;
+        let book = OrderBook::try_from(snapshot).map_err(|e| Error::GenericParse(e.to_string()))?;
+        guard.check(&order, &book)?;
+        self.place_order(order, wallet).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BookLevel, ClientLimit, ClientOrder, Tif};
+
+    fn level(px: &str, sz: &str) -> BookLevel {
+        BookLevel {
+            px: px.to_string(),
+            sz: sz.to_string(),
+            n: 1,
+        }
+    }
+
+    fn buy(limit_px: f64) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: "@150".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px,
+            sz: 100.0,
+            cloid: None,
+            stp: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Ioc }),
+        }
+    }
+
+    #[test]
+    fn test_liquidity_guard() {
+        let book = OrderBook {
+            coin: "@150".to_string(),
+            time: 0,
+            bids: vec![level("0.99", "50")],
+            asks: vec![level("1.01", "40"), level("1.05", "100")],
+        };
+        let guard = LiquidityGuard::new().min_depth(100.0).max_spread_bps(250.0);
+
+        assert!(guard.check(&buy(1.05), &book).is_ok());
+        assert!(matches!(
+            guard.check(&buy(1.02), &book),
+            Err(Error::LiquidityGuard { .. })
+        ));
+        assert!(LiquidityGuard::new()
+            .max_spread_bps(100.0)
+            .check(&buy(1.05), &book)
+            .is_err());
+
+        let empty_bids = OrderBook {
+            bids: Vec::new(),
+            ..book
+        };
+        assert!(guard.check(&buy(1.05), &empty_bids).is_err());
+        assert!(LiquidityGuard::new().check(&buy(1.05), &empty_bids).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_spot_book_is_fetched_by_pair_name() -> Result<()> {
+        use crate::exchange::exchange_client::tests::{
+            json_server, registry_metadata, test_client,
+        };
+        use serde_json::json;
+
+        let base_url = json_server(|path, body| {
+            if path == "/exchange" {
+                return Some(json!({
+                    "status": "ok",
+                    "response": {"type": "order", "data": {"statuses": [{"resting": {"oid": 1}}]}}
+                }));
+            }
+            registry_metadata(body).or_else(|| match body["type"].as_str() {
+                Some("l2Book") if body["coin"] == "@107" => Some(json!({
+                    "coin": "@107",
+                    "levels": [[{"px": "0.99", "sz": "50", "n": 1}], [{"px": "1.01", "sz": "200", "n": 1}]],
+                    "time": 0
+                })),
+                _ => None,
+            })
+        })
+        .await;
+        let mut exchange_client = test_client(base_url)?;
+        let asset_registry = crate::AssetRegistry::new(
+            &exchange_client.info_client(),
+            *exchange_client.network_config(),
+        );
+        exchange_client.set_asset_registry(asset_registry);
+
+        let order = ClientOrderRequest {
+            asset: "HYPE/USDC".to_string(),
+            ..buy(1.01)
+        };
+        let guard = LiquidityGuard::new().min_depth(100.0);
+        let response = exchange_client
+            .order_with_liquidity_guard(order, &guard, None)
+            .await?;
+        assert!(matches!(response, ExchangeResponseStatus::Ok(_)));
+        Ok(())
+    }
+}
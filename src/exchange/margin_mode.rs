@@ -0,0 +1,61 @@
+use crate::{prelude::*, Error, Leverage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarginMode {
+    Cross { leverage: u32 },
+    Isolated { leverage: u32 },
+}
+
+impl MarginMode {
+    pub fn leverage(&self) -> u32 {
+        match self {
+            MarginMode::Cross { leverage } | MarginMode::Isolated { leverage } => *leverage,
+        }
+    }
+
+    pub fn is_cross(&self) -> bool {
+        matches!(self, MarginMode::Cross { .. })
+    }
+
+    /// `type` reported in a `leverage`
+    pub(crate) fn type_string(&self) -> &'static str {
+        if self.is_cross() {
+            "cross"
+        } else {
+            "isolated"
+        }
+    }
+
+    /// Errors if the reported leverage doesn't match this mode
+    pub(crate) fn verify(&self, leverage: Leverage) -> Result<Leverage> {
+        if leverage.type_string == self.type_string() && leverage.value == self.leverage() {
+            Ok(leverage)
+        } else {
+            Err(Error::MarginMode(format!(
+                "requested {self:?}, exchange reports {} leverage {}",
+                leverage.type_string, leverage.value
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_margin_mode() {
+        let leverage = |type_string: &str, value| Leverage {
+            type_string: type_string.to_string(),
+            value,
+            raw_usd: None,
+        };
+        let mode = MarginMode::Isolated { leverage: 5 };
+        assert_eq!(mode.verify(leverage("isolated", 5)).unwrap().value, 5);
+        assert!(mode.verify(leverage("cross", 5)).is_err());
+        assert!(mode.verify(leverage("isolated", 3)).is_err());
+        assert!(MarginMode::Cross { leverage: 3 }
+            .verify(leverage("cross", 3))
+            .is_ok());
+    }
+}
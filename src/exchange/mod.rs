@@ -1,18 +1,59 @@
 mod actions;
+mod address_book;
+mod agent_rotation;
+mod amend;
+mod audit;
+#[cfg(feature = "ws")]
+mod await_fill;
 mod builder;
 mod cancel;
 mod exchange_client;
 mod exchange_responses;
+mod hooks;
+mod intents;
+#[cfg(feature = "ws")]
+mod liquidity_guard;
+mod margin_mode;
 mod modify;
+mod multi_sig;
 mod order;
+mod order_ids;
+mod order_limits;
+mod order_results;
+mod recovery;
+mod self_cross;
+#[cfg(feature = "ws")]
+mod simulate;
+mod spot_deploy;
 
 pub use actions::*;
+pub use address_book::AddressBook;
+pub use agent_rotation::{AgentKeySink, AgentRotated, AgentRotator};
+pub use amend::{AmendMethod, AmendResult};
+#[cfg(feature = "audit-sqlite")]
+pub use audit::SqliteAuditSink;
+pub use audit::{AuditRecord, AuditSink, FileAuditSink};
 pub use builder::*;
 pub use cancel::{ClientCancelRequest, ClientCancelRequestCloid};
 pub use exchange_client::*;
 pub use exchange_responses::*;
+pub use hooks::{HookContext, PostReceiveHook, PreSendHook};
+pub use intents::{CancelIntent, Intent, IntentResults, IntentSide, OrderIntent};
+#[cfg(feature = "ws")]
+pub use liquidity_guard::LiquidityGuard;
+pub use margin_mode::MarginMode;
 pub use modify::{ClientModifyRequest, ModifyRequest};
 pub use order::{
-    ClientLimit, ClientOrder, ClientOrderRequest, ClientTrigger, MarketCloseParams,
-    MarketOrderParams, Order,
+    ClientLimit, ClientOrder, ClientOrderRequest, ClientTrigger, DecimalOrder, Limit,
+    MarketCloseParams, MarketOrderParams, Order, OrderDefaults, OrderRequest, StpMode, Trigger,
 };
+#[cfg(feature = "order-ids-sqlite")]
+pub use order_ids::SqliteOrderIdStore;
+pub use order_ids::{FileOrderIdStore, OrderIdRecord, OrderIdStore};
+pub use order_limits::OrderLimits;
+pub use order_results::{OrderPlacement, OrderRejection, OrderResult};
+pub use recovery::RecoveredOrder;
+pub use self_cross::{GuardedOrder, SelfCrossGuard, SelfCrossPolicy};
+#[cfg(feature = "ws")]
+pub use simulate::{estimated_fill, would_cross, FillEstimate};
+pub use spot_deploy::{Genesis, SpotDeploy, SpotGenesisBuilder, UserGenesis};
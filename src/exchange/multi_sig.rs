@@ -0,0 +1,119 @@
+use crate::{
+    prelude::*,
+    signature::{l1_signing_hash, recover_signer},
+    Address, Error, MultiSigSigners,
+};
+use alloy::primitives::{Signature, B256};
+
+impl MultiSigSigners {
+    /// Recovers the signer of each signature over `signing_hash` and returns the signatures in
+    /// the order of `authorized_users`, as the exchange expects. Errors if a signature is from
+    /// an unknown signer or fewer than `threshold` authorized users signed.
+    /// A second signature from the same signer is dropped.
+    pub fn order_signatures(
+        &self,
+        signing_hash: B256,
+        signatures: Vec<Signature>,
+    ) -> Result<Vec<Signature>> {
+        let mut signed: Vec<(usize, Signature)> = Vec::new();
+        let mut unknown = Vec::new();
+        for signature in signatures {
+            let signer = recover_signer(&signature, signing_hash)?;
+            match self
+                .authorized_users
+                .iter()
+                .position(|user| *user == signer)
+            {
+                Some(position) if signed.iter().any(|(p, _)| *p == position) => {}
+                Some(position) => signed.push((position, signature)),
+                None => unknown.push(signer),
+            }
+        }
+        let missing = || -> Vec<Address> {
+            self.authorized_users
+                .iter()
+                .enumerate()
+                .filter(|(position, _)| !signed.iter().any(|(p, _)| p == position))
+                .map(|(_, user)| *user)
+                .collect()
+        };
+
+        if !unknown.is_empty() {
+            return Err(Error::MultiSig {
+                reason: "signatures from signers that are not authorized".to_string(),
+                missing: missing(),
+                unknown,
+            });
+        }
+        if signed.len() < self.threshold {
+            return Err(Error::MultiSig {
+                reason: format!("{} of {} required signatures", signed.len(), self.threshold),
+                missing: missing(),
+                unknown,
+            });
+        }
+        signed.sort_by_key(|(position, _)| *position);
+        Ok(signed.into_iter().map(|(_, signature)| signature).collect())
+    }
+
+    /// [`MultiSigSigners::order_signatures`] for signatures of an L1 action
+    pub fn order_l1_signatures(
+        &self,
+        connection_id: B256,
+        is_mainnet: bool,
+        signatures: Vec<Signature>,
+    ) -> Result<Vec<Signature>> {
+        self.order_signatures(l1_signing_hash(connection_id, is_mainnet), signatures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature::sign_l1_action;
+    use alloy::signers::local::PrivateKeySigner;
+
+    #[test]
+    fn test_order_signatures() {
+        let wallets: Vec<PrivateKeySigner> = (1..=4).map(|_| PrivateKeySigner::random()).collect();
+        let signers = MultiSigSigners {
+            authorized_users: wallets[..3].iter().map(|wallet| wallet.address()).collect(),
+            threshold: 2,
+        };
+        let connection_id = B256::repeat_byte(7);
+        let sign = |wallet: &PrivateKeySigner| sign_l1_action(wallet, connection_id, true).unwrap();
+
+        let ordered = signers
+            .order_l1_signatures(
+                connection_id,
+                true,
+                vec![sign(&wallets[2]), sign(&wallets[0]), sign(&wallets[2])],
+            )
+            .unwrap();
+        assert_eq!(ordered, vec![sign(&wallets[0]), sign(&wallets[2])]);
+
+        match signers.order_l1_signatures(connection_id, true, vec![sign(&wallets[1])]) {
+            Err(Error::MultiSig {
+                missing, unknown, ..
+            }) => {
+                assert_eq!(missing, vec![wallets[0].address(), wallets[2].address()]);
+                assert!(unknown.is_empty());
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        match signers.order_l1_signatures(
+            connection_id,
+            true,
+            vec![sign(&wallets[0]), sign(&wallets[1]), sign(&wallets[3])],
+        ) {
+            Err(Error::MultiSig { unknown, .. }) => {
+                assert_eq!(unknown, vec![wallets[3].address()])
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        // signed for testnet, so the recovered signers are not authorized
+        assert!(signers
+            .order_l1_signatures(connection_id, false, ordered)
+            .is_err());
+    }
+}
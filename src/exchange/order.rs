@@ -2,15 +2,17 @@ use crate::{
     errors::Error,
     helpers::{float_to_string_for_hashing, uuid_to_hex_string},
     prelude::*,
+    BuilderInfo, Coin, Px, SpotPair, Sz, Tif,
 };
-use ethers::signers::LocalWallet;
+use alloy::signers::local::PrivateKeySigner;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Limit {
-    pub tif: String,
+    pub tif: Tif,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -47,18 +49,44 @@ pub struct OrderRequest {
     pub cloid: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ClientLimit {
-    pub tif: String,
+    pub tif: Tif,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ClientTrigger {
     pub is_market: bool,
     pub trigger_px: f64,
     pub tpsl: String,
 }
 
+/// Defaults an [`crate::ExchangeClient`] applies where a call leaves a field unset
+#[derive(Debug, Clone)]
+pub struct OrderDefaults {
+    /// Time in force of orders built with [`crate::ExchangeClient::limit_order`] and
+    /// [`crate::ExchangeClient::limit_order_request`]
+    pub tif: Tif,
+    /// Slippage of market orders without one, as a fraction of the price
+    pub slippage: f64,
+    /// Whether orders built with [`crate::ExchangeClient::limit_order`] and
+    /// [`crate::ExchangeClient::limit_order_request`] are reduce only. Market opens never are.
+    pub reduce_only: bool,
+    /// Builder of orders sent without one
+    pub builder: Option<BuilderInfo>,
+}
+
+impl Default for OrderDefaults {
+    fn default() -> OrderDefaults {
+        OrderDefaults {
+            tif: Tif::Gtc,
+            slippage: 0.05,
+            reduce_only: false,
+            builder: None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct MarketOrderParams<'a> {
     pub asset: &'a str,
@@ -67,7 +95,7 @@ pub struct MarketOrderParams<'a> {
     pub px: Option<f64>,
     pub slippage: Option<f64>,
     pub cloid: Option<Uuid>,
-    pub wallet: Option<&'a LocalWallet>,
+    pub wallet: Option<&'a PrivateKeySigner>,
 }
 
 #[derive(Debug)]
@@ -77,16 +105,77 @@ pub struct MarketCloseParams<'a> {
     pub px: Option<f64>,
     pub slippage: Option<f64>,
     pub cloid: Option<Uuid>,
-    pub wallet: Option<&'a LocalWallet>,
+    pub wallet: Option<&'a PrivateKeySigner>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ClientOrder {
     Limit(ClientLimit),
     Trigger(ClientTrigger),
 }
 
-#[derive(Debug)]
+/// What to do with an order that would trade against one of our own resting orders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StpMode {
+    /// Drop the incoming order and keep the resting one
+    CancelNewest,
+}
+
+/// Limit order with an exact price and size. [`crate::ExchangeClient::order`] checks
+/// them against the asset's tick and lot size, so no float rounding reaches the exchange.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecimalOrder {
+    pub asset: String,
+    pub is_buy: bool,
+    pub reduce_only: bool,
+    pub px: Px,
+    pub sz: Sz,
+    pub tif: Tif,
+    pub cloid: Option<Uuid>,
+}
+
+impl DecimalOrder {
+    pub fn new(asset: &str, is_buy: bool, px: Px, sz: Sz, tif: Tif) -> DecimalOrder {
+        DecimalOrder {
+            asset: asset.to_string(),
+            is_buy,
+            reduce_only: false,
+            px,
+            sz,
+            tif,
+            cloid: None,
+        }
+    }
+
+    /// The order as a [`ClientOrderRequest`], failing if its price or size would not be sent
+    /// exactly as given
+    pub fn to_client_order(&self) -> Result<ClientOrderRequest> {
+        let exact = |value: Decimal| -> Result<f64> {
+            let float = f64::try_from(value).map_err(|_| Error::FloatStringParse)?;
+            let value = value.normalize().to_string();
+            if float_to_string_for_hashing(float) != value {
+                return Err(Error::OrderLimit {
+                    asset: self.asset.clone(),
+                    reason: format!("{value} can't be sent exactly"),
+                });
+            }
+            Ok(float)
+        };
+        Ok(ClientOrderRequest {
+            asset: self.asset.clone(),
+            is_buy: self.is_buy,
+            reduce_only: self.reduce_only,
+            limit_px: exact(self.px.value())?,
+            sz: exact(self.sz.value())?,
+            cloid: self.cloid,
+            stp: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: self.tif }),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct ClientOrderRequest {
     pub asset: String,
     pub is_buy: bool,
@@ -94,10 +183,101 @@ pub struct ClientOrderRequest {
     pub limit_px: f64,
     pub sz: f64,
     pub cloid: Option<Uuid>,
+    /// Self-trade prevention. The exchange has no such option, so it is enforced before signing
+    /// against the orders known to the client's [`crate::SelfCrossGuard`], and never sent.
+    /// Orders setting it fail if the client has no guard.
+    pub stp: Option<StpMode>,
     pub order_type: ClientOrder,
 }
 
 impl ClientOrderRequest {
+    /// Limit order on a perp
+    pub fn limit(
+        asset: &Coin,
+        is_buy: bool,
+        limit_px: f64,
+        sz: f64,
+        tif: Tif,
+    ) -> ClientOrderRequest {
+        Self::limit_on(asset.to_string(), is_buy, limit_px, sz, tif)
+    }
+
+    /// Limit order on a spot pair, spot orders can't be reduce only
+    pub fn spot_limit(
+        pair: &SpotPair,
+        is_buy: bool,
+        limit_px: f64,
+        sz: f64,
+        tif: Tif,
+    ) -> ClientOrderRequest {
+        Self::limit_on(pair.to_string(), is_buy, limit_px, sz, tif)
+    }
+
+    fn limit_on(
+        asset: String,
+        is_buy: bool,
+        limit_px: f64,
+        sz: f64,
+        tif: Tif,
+    ) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset,
+            is_buy,
+            reduce_only: false,
+            limit_px,
+            sz,
+            cloid: None,
+            stp: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif }),
+        }
+    }
+
+    /// Reduce-only take profit trigger. `is_buy` is the side that closes the position.
+    pub fn take_profit(
+        asset: &Coin,
+        is_buy: bool,
+        trigger_px: f64,
+        sz: f64,
+        is_market: bool,
+    ) -> ClientOrderRequest {
+        Self::trigger(asset, is_buy, trigger_px, sz, is_market, "tp")
+    }
+
+    /// Reduce-only stop loss trigger. `is_buy` is the side that closes the position.
+    pub fn stop_loss(
+        asset: &Coin,
+        is_buy: bool,
+        trigger_px: f64,
+        sz: f64,
+        is_market: bool,
+    ) -> ClientOrderRequest {
+        Self::trigger(asset, is_buy, trigger_px, sz, is_market, "sl")
+    }
+
+    fn trigger(
+        asset: &Coin,
+        is_buy: bool,
+        trigger_px: f64,
+        sz: f64,
+        is_market: bool,
+        tpsl: &str,
+    ) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: asset.to_string(),
+            is_buy,
+            reduce_only: true,
+            limit_px: trigger_px,
+            sz,
+            cloid: None,
+            stp: None,
+            order_type: ClientOrder::Trigger(ClientTrigger {
+                is_market,
+                trigger_px,
+                tpsl: tpsl.to_string(),
+            }),
+        }
+    }
+
     pub(crate) fn convert(self, coin_to_asset: &HashMap<String, u32>) -> Result<OrderRequest> {
         let order_type = match self.order_type {
             ClientOrder::Limit(limit) => Order::Limit(Limit { tif: limit.tif }),
@@ -122,3 +302,41 @@ impl ClientOrderRequest {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_decimal_order_to_client_order() {
+        let px = Px::from_str("1800.5").unwrap();
+        let sz = Sz::from_str("0.1234").unwrap();
+        let order = DecimalOrder::new("ETH", true, px, sz, Tif::Alo)
+            .to_client_order()
+            .unwrap();
+        assert_eq!(float_to_string_for_hashing(order.limit_px), "1800.5");
+        assert_eq!(float_to_string_for_hashing(order.sz), "0.1234");
+        assert!(matches!(
+            order.order_type,
+            ClientOrder::Limit(ClientLimit { tif: Tif::Alo })
+        ));
+
+        let sz = Sz::from_str("0.123456789").unwrap();
+        assert!(matches!(
+            DecimalOrder::new("ETH", true, px, sz, Tif::Gtc).to_client_order(),
+            Err(Error::OrderLimit { .. })
+        ));
+    }
+
+    #[test]
+    fn test_typed_limit_orders() {
+        let eth = Coin::try_from("ETH").unwrap();
+        let order = ClientOrderRequest::limit(&eth, true, 1800.5, 0.1, Tif::Gtc);
+        assert_eq!(order.asset, "ETH");
+        let pair = SpotPair::try_from("PURR/USDC").unwrap();
+        let order = ClientOrderRequest::spot_limit(&pair, false, 0.2, 100.0, Tif::Ioc);
+        assert_eq!(order.asset, "PURR/USDC");
+        assert!(!order.is_buy && !order.reduce_only);
+    }
+}
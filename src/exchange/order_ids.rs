@@ -0,0 +1,311 @@
+use crate::{
+    helpers::now_timestamp_ms, prelude::*, Error, ExchangeDataStatus, ExchangeResponseStatus,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    sync::Mutex,
+};
+use uuid::Uuid;
+
+/// Links a client order id to the exchange order id and the strategy that placed it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderIdRecord {
+    pub cloid: Uuid,
+    /// `None` until the exchange accepts the order
+    pub oid: Option<u64>,
+    pub coin: String,
+    pub strategy: Option<String>,
+    /// Local time of the last update in milliseconds
+    pub updated_at: u64,
+}
+
+/// Store mapping cloids to oids, filled in by an [`ExchangeClient`](crate::ExchangeClient)
+/// configured with `set_order_id_store` from the responses to orders with a cloid.
+pub trait OrderIdStore: Debug + Send + Sync {
+    /// Inserts or replaces the record for `record.cloid`
+    fn upsert(&self, record: &OrderIdRecord) -> Result<()>;
+    fn by_cloid(&self, cloid: Uuid) -> Result<Option<OrderIdRecord>>;
+    fn by_oid(&self, oid: u64) -> Result<Option<OrderIdRecord>>;
+
+    /// Records what is known about `cloid`, keeping the oid and strategy already stored
+    /// when they are not given
+    fn link(
+        &self,
+        cloid: Uuid,
+        coin: &str,
+        oid: Option<u64>,
+        strategy: Option<&str>,
+    ) -> Result<()> {
+        let existing = self.by_cloid(cloid)?;
+        let record = OrderIdRecord {
+            cloid,
+            oid: oid.or(existing.as_ref().and_then(|record| record.oid)),
+            coin: coin.to_string(),
+            strategy: strategy
+                .map(str::to_string)
+                .or(existing.and_then(|record| record.strategy)),
+            updated_at: now_timestamp_ms(),
+        };
+        self.upsert(&record)
+    }
+
+    /// Links the cloids in order updates from the `orderUpdates` subscription, e.g. for
+    /// orders placed by another process
+    #[cfg(feature = "ws")]
+    fn on_order_updates(&self, updates: &[crate::OrderUpdate]) -> Result<()> {
+        for update in updates {
+            if let Some(cloid) = &update.order.cloid {
+                let cloid = parse_cloid(cloid)?;
+                self.link(cloid, &update.order.coin, Some(update.order.oid), None)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn parse_cloid(cloid: &str) -> Result<Uuid> {
+    Uuid::parse_str(cloid.trim_start_matches("0x")).map_err(|e| Error::GenericParse(e.to_string()))
+}
+
+/// Links the cloids of placed orders, given with their coins in request order, to the oids
+/// in the response
+pub(crate) fn record_placed_orders(
+    store: &dyn OrderIdStore,
+    orders: Vec<(Option<Uuid>, String)>,
+    response: &ExchangeResponseStatus,
+) -> Result<()> {
+    let ExchangeResponseStatus::Ok(response) = response else {
+        return Ok(());
+    };
+    let Some(data) = &response.data else {
+        return Ok(());
+    };
+    for ((cloid, coin), status) in orders.into_iter().zip(data.statuses.iter()) {
+        let Some(cloid) = cloid else {
+            continue;
+        };
+        let oid = match status {
+            ExchangeDataStatus::Resting(resting) => resting.oid,
+            ExchangeDataStatus::Filled(filled) => filled.oid,
+            _ => continue,
+        };
+        store.link(cloid, &coin, Some(oid), None)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct OrderIdIndex {
+    by_cloid: HashMap<Uuid, OrderIdRecord>,
+    by_oid: HashMap<u64, Uuid>,
+}
+
+impl OrderIdIndex {
+    fn insert(&mut self, record: OrderIdRecord) {
+        if let Some(oid) = record.oid {
+            self.by_oid.insert(oid, record.cloid);
+        }
+        self.by_cloid.insert(record.cloid, record);
+    }
+
+    fn by_oid(&self, oid: u64) -> Option<OrderIdRecord> {
+        self.by_oid
+            .get(&oid)
+            .and_then(|cloid| self.by_cloid.get(cloid))
+            .cloned()
+    }
+}
+
+/// Appends records to a file as JSON lines and replays them on open, keeping an index in memory
+#[derive(Debug)]
+pub struct FileOrderIdStore {
+    state: Mutex<(File, OrderIdIndex)>,
+}
+
+impl FileOrderIdStore {
+    pub fn new(path: impl AsRef<Path>) -> Result<FileOrderIdStore> {
+        let mut index = OrderIdIndex::default();
+        if path.as_ref().exists() {
+            let file = File::open(&path).map_err(|e| Error::Persistence(e.to_string()))?;
+            for line in BufReader::new(file).lines() {
+                let line = line.map_err(|e| Error::Persistence(e.to_string()))?;
+                if line.is_empty() {
+                    continue;
+                }
+                index.insert(
+                    serde_json::from_str(&line).map_err(|e| Error::JsonParse(e.to_string()))?,
+                );
+            }
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| Error::Persistence(e.to_string()))?;
+        Ok(FileOrderIdStore {
+            state: Mutex::new((file, index)),
+        })
+    }
+}
+
+impl OrderIdStore for FileOrderIdStore {
+    fn upsert(&self, record: &OrderIdRecord) -> Result<()> {
+        let mut line =
+            serde_json::to_string(record).map_err(|e| Error::JsonParse(e.to_string()))?;
+        line.push('\n');
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (file, index) = &mut *state;
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.flush())
+            .map_err(|e| Error::Persistence(e.to_string()))?;
+        index.insert(record.clone());
+        Ok(())
+    }
+
+    fn by_cloid(&self, cloid: Uuid) -> Result<Option<OrderIdRecord>> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(state.1.by_cloid.get(&cloid).cloned())
+    }
+
+    fn by_oid(&self, oid: u64) -> Result<Option<OrderIdRecord>> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(state.1.by_oid(oid))
+    }
+}
+
+/// Stores records in an `order_ids` sqlite table
+#[cfg(feature = "order-ids-sqlite")]
+#[derive(Debug)]
+pub struct SqliteOrderIdStore {
+    connection: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "order-ids-sqlite")]
+impl SqliteOrderIdStore {
+    pub fn new(path: impl AsRef<Path>) -> Result<SqliteOrderIdStore> {
+        let connection =
+            rusqlite::Connection::open(path).map_err(|e| Error::Persistence(e.to_string()))?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS order_ids (
+                    cloid TEXT PRIMARY KEY,
+                    oid INTEGER,
+                    coin TEXT NOT NULL,
+                    strategy TEXT,
+                    updated_at INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS order_ids_oid ON order_ids (oid);",
+            )
+            .map_err(|e| Error::Persistence(e.to_string()))?;
+        Ok(SqliteOrderIdStore {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn query(
+        &self,
+        condition: &str,
+        param: rusqlite::types::Value,
+    ) -> Result<Option<OrderIdRecord>> {
+        use rusqlite::OptionalExtension;
+
+        let connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        let row = connection
+            .query_row(
+                &format!(
+                    "SELECT cloid, oid, coin, strategy, updated_at FROM order_ids WHERE {condition}"
+                ),
+                [param],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, Option<i64>>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, i64>(4)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(|e| Error::Persistence(e.to_string()))?;
+        row.map(|(cloid, oid, coin, strategy, updated_at)| {
+            Ok(OrderIdRecord {
+                cloid: parse_cloid(&cloid)?,
+                oid: oid.map(|oid| oid as u64),
+                coin,
+                strategy,
+                updated_at: updated_at as u64,
+            })
+        })
+        .transpose()
+    }
+}
+
+#[cfg(feature = "order-ids-sqlite")]
+impl OrderIdStore for SqliteOrderIdStore {
+    fn upsert(&self, record: &OrderIdRecord) -> Result<()> {
+        let connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        connection
+            .execute(
+                "INSERT INTO order_ids (cloid, oid, coin, strategy, updated_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5)
+                    ON CONFLICT (cloid) DO UPDATE SET
+                        oid = excluded.oid,
+                        coin = excluded.coin,
+                        strategy = excluded.strategy,
+                        updated_at = excluded.updated_at",
+                rusqlite::params![
+                    record.cloid.to_string(),
+                    record.oid.map(|oid| oid as i64),
+                    record.coin,
+                    record.strategy,
+                    record.updated_at as i64,
+                ],
+            )
+            .map_err(|e| Error::Persistence(e.to_string()))?;
+        Ok(())
+    }
+
+    fn by_cloid(&self, cloid: Uuid) -> Result<Option<OrderIdRecord>> {
+        self.query("cloid = ?1", cloid.to_string().into())
+    }
+
+    fn by_oid(&self, oid: u64) -> Result<Option<OrderIdRecord>> {
+        self.query("oid = ?1", (oid as i64).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_order_id_store_replays_links() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("order-ids-{}.jsonl", Uuid::new_v4()));
+        let cloid = Uuid::from_u128(7);
+        {
+            let store = FileOrderIdStore::new(&path)?;
+            store.link(cloid, "ETH", None, Some("mm"))?;
+            let response: ExchangeResponseStatus = serde_json::from_str(
+                r#"{"status": "ok", "response": {"type": "order", "data": {"statuses": [{"resting": {"oid": 42}}]}}}"#,
+            )
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+            record_placed_orders(&store, vec![(Some(cloid), "ETH".to_string())], &response)?;
+        }
+
+        let store = FileOrderIdStore::new(&path)?;
+        std::fs::remove_file(&path).map_err(|e| Error::Persistence(e.to_string()))?;
+        let record = store.by_oid(42)?.unwrap();
+        assert_eq!(record.cloid, cloid);
+        assert_eq!(record.strategy.as_deref(), Some("mm"));
+        assert_eq!(store.by_cloid(cloid)?, Some(record));
+        assert_eq!(store.by_oid(1)?, None);
+        Ok(())
+    }
+}
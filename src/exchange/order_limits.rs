@@ -0,0 +1,142 @@
+use crate::{
+    limits::MIN_ORDER_NOTIONAL, meta::Meta, prelude::*, ClientOrder, ClientOrderRequest, Error, Tif,
+};
+use std::collections::HashMap;
+
+/// Exchange-wide order size limits, checked before orders are signed once set with
+/// [`crate::ExchangeClient::set_order_limits`] or loaded with
+/// [`crate::ExchangeClient::load_order_limits`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderLimits {
+    /// Minimum value of orders that are not reduce-only, in USDC
+    pub min_notional: f64,
+    /// Maximum value of IOC orders per coin, from the coin's max leverage tier in
+    /// `maxMarketOrderNtls`. Coins without an entry are not limited.
+    pub max_market_order_ntls: HashMap<String, f64>,
+}
+
+impl Default for OrderLimits {
+    fn default() -> OrderLimits {
+        OrderLimits {
+            min_notional: MIN_ORDER_NOTIONAL,
+            max_market_order_ntls: HashMap::new(),
+        }
+    }
+}
+
+impl OrderLimits {
+    /// Assigns each perp in `meta` the notional of the highest tier of `max_market_order_ntls`
+    /// whose leverage doesn't exceed the perp's max leverage
+    pub fn new(meta: &Meta, max_market_order_ntls: &[(u32, String)]) -> Result<OrderLimits> {
+        let mut tiers = max_market_order_ntls
+            .iter()
+            .map(|(leverage, ntl)| {
+                ntl.parse::<f64>()
+                    .map(|ntl| (*leverage, ntl))
+                    .map_err(|_| Error::FloatStringParse)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        tiers.sort_by_key(|(leverage, _)| std::cmp::Reverse(*leverage));
+
+        let max_market_order_ntls = meta
+            .universe
+            .iter()
+            .filter_map(|asset| {
+                let max_leverage = asset.max_leverage?;
+                tiers
+                    .iter()
+                    .find(|(leverage, _)| *leverage <= max_leverage)
+                    .map(|(_, ntl)| (asset.name.clone(), *ntl))
+            })
+            .collect();
+        Ok(OrderLimits {
+            max_market_order_ntls,
+            ..Default::default()
+        })
+    }
+
+    pub fn check(&self, order: &ClientOrderRequest) -> Result<()> {
+        let notional = order.limit_px * order.sz;
+        let refuse = |reason: String| {
+            Err(Error::OrderLimit {
+                asset: order.asset.clone(),
+                reason,
+            })
+        };
+        if !order.reduce_only && notional < self.min_notional {
+            return refuse(format!(
+                "notional {notional} is below the minimum of {}",
+                self.min_notional
+            ));
+        }
+        if let ClientOrder::Limit(limit) = &order.order_type {
+            if limit.tif == Tif::Ioc {
+                if let Some(max) = self.max_market_order_ntls.get(&order.asset) {
+                    if notional > *max {
+                        return refuse(format!(
+                            "market order notional {notional} exceeds the maximum of {max}"
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ClientLimit;
+
+    fn order(asset: &str, px: f64, sz: f64, tif: Tif, reduce_only: bool) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: asset.to_string(),
+            is_buy: true,
+            reduce_only,
+            limit_px: px,
+            sz,
+            cloid: None,
+            stp: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif }),
+        }
+    }
+
+    #[test]
+    fn test_order_limits() {
+        let meta: Meta = serde_json::from_value(serde_json::json!({
+            "universe": [
+                {"name": "BTC", "szDecimals": 5, "maxLeverage": 40},
+                {"name": "DOGE", "szDecimals": 0, "maxLeverage": 10},
+                {"name": "NEW", "szDecimals": 0, "maxLeverage": 2}
+            ]
+        }))
+        .unwrap();
+        let tiers = [
+            (3, "1000000.0".to_string()),
+            (40, "15000000.0".to_string()),
+            (10, "3000000.0".to_string()),
+        ];
+        let limits = OrderLimits::new(&meta, &tiers).unwrap();
+        assert_eq!(limits.max_market_order_ntls["BTC"], 15_000_000.0);
+        assert_eq!(limits.max_market_order_ntls["DOGE"], 3_000_000.0);
+        assert!(!limits.max_market_order_ntls.contains_key("NEW"));
+
+        assert!(limits
+            .check(&order("DOGE", 0.1, 200.0, Tif::Gtc, false))
+            .is_ok());
+        assert!(matches!(
+            limits.check(&order("DOGE", 0.1, 50.0, Tif::Gtc, false)),
+            Err(Error::OrderLimit { .. })
+        ));
+        assert!(limits
+            .check(&order("DOGE", 0.1, 50.0, Tif::Ioc, true))
+            .is_ok());
+        assert!(limits
+            .check(&order("DOGE", 0.1, 40_000_000.0, Tif::Ioc, false))
+            .is_err());
+        assert!(limits
+            .check(&order("DOGE", 0.1, 40_000_000.0, Tif::Gtc, false))
+            .is_ok());
+    }
+}
@@ -0,0 +1,266 @@
+use crate::{
+    prelude::*, ClientOrderRequest, Error, ExchangeDataStatus, ExchangeResponse,
+    ExchangeResponseStatus, FilledOrder, RestingOrder,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Status of one order of a bulk order, next to the request it was returned for
+#[derive(Debug, Clone)]
+pub struct OrderResult {
+    pub request: ClientOrderRequest,
+    pub status: ExchangeDataStatus,
+    /// The request timed out, so the order may or may not have been placed. `status` is then
+    /// an error describing the timeout; look the order up by cloid to find out.
+    pub fate_unknown: bool,
+}
+
+impl OrderResult {
+    pub fn cloid(&self) -> Option<Uuid> {
+        self.request.cloid
+    }
+
+    /// Whether the order was not placed. Orders of unknown fate are not errors.
+    pub fn is_error(&self) -> bool {
+        !self.fate_unknown && matches!(self.status, ExchangeDataStatus::Error(_))
+    }
+}
+
+/// Pairs the positional statuses of a bulk order response with `requests`. A response without
+/// statuses, a rejected action or a failed request gives every order an error status with the
+/// reason.
+pub(crate) fn zip_statuses(
+    requests: Vec<ClientOrderRequest>,
+    response: Result<ExchangeResponseStatus>,
+) -> Vec<OrderResult> {
+    let mut statuses = match response {
+        Ok(ExchangeResponseStatus::Ok(ExchangeResponse {
+            data: Some(data), ..
+        })) => Ok(data.statuses.into_iter()),
+        Ok(ExchangeResponseStatus::Ok(_)) => Err("Response has no order statuses".to_string()),
+        Ok(ExchangeResponseStatus::Err(err)) => Err(err),
+        Err(err) => Err(format!("Request failed: {err}")),
+    };
+    requests
+        .into_iter()
+        .map(|request| {
+            let status = match &mut statuses {
+                Ok(statuses) => statuses.next().unwrap_or_else(|| {
+                    ExchangeDataStatus::Error("No status returned for order".to_string())
+                }),
+                Err(err) => ExchangeDataStatus::Error(err.clone()),
+            };
+            OrderResult {
+                request,
+                status,
+                fate_unknown: false,
+            }
+        })
+        .collect()
+}
+
+/// Results of `requests` sent in a request that failed with `err` without telling whether
+/// they were placed
+pub(crate) fn unknown_fate(requests: Vec<ClientOrderRequest>, err: &Error) -> Vec<OrderResult> {
+    requests
+        .into_iter()
+        .map(|request| OrderResult {
+            request,
+            status: ExchangeDataStatus::Error(format!(
+                "Request failed, the order may have been placed: {err}"
+            )),
+            fate_unknown: true,
+        })
+        .collect()
+}
+
+/// Where an accepted order ended up
+#[derive(Debug, Clone)]
+pub enum OrderPlacement {
+    Resting(RestingOrder),
+    Filled(FilledOrder),
+    WaitingForFill,
+    WaitingForTrigger,
+}
+
+/// Why an order was not placed. The exchange only reports reasons as text, so the common ones
+/// are recognized from it and everything else is kept as [`OrderRejection::Other`].
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum OrderRejection {
+    #[error("Price is not a multiple of the tick size: {0}")]
+    TickSize(String),
+    #[error("Insufficient margin: {0}")]
+    InsufficientMargin(String),
+    #[error("Reduce only order would increase the position: {0}")]
+    ReduceOnly(String),
+    #[error("Order is below the minimum value: {0}")]
+    MinValue(String),
+    #[error("Post only order would have matched: {0}")]
+    PostOnlyWouldMatch(String),
+    #[error("Ioc order did not match: {0}")]
+    IocNoMatch(String),
+    #[error("Order rejected: {0}")]
+    Other(String),
+    /// The request failed or the response had no usable status
+    #[error(transparent)]
+    Request(Error),
+}
+
+impl OrderRejection {
+    pub fn from_message(message: &str) -> OrderRejection {
+        let reason = message.to_lowercase();
+        let message = message.to_string();
+        if reason.contains("tick size") {
+            OrderRejection::TickSize(message)
+        } else if reason.contains("insufficient margin") {
+            OrderRejection::InsufficientMargin(message)
+        } else if reason.contains("reduce only") {
+            OrderRejection::ReduceOnly(message)
+        } else if reason.contains("minimum value") {
+            OrderRejection::MinValue(message)
+        } else if reason.contains("post only") {
+            OrderRejection::PostOnlyWouldMatch(message)
+        } else if reason.contains("could not immediately match") {
+            OrderRejection::IocNoMatch(message)
+        } else {
+            OrderRejection::Other(message)
+        }
+    }
+}
+
+/// Result of a single order, from the response to it
+pub(crate) fn checked_status(
+    response: ExchangeResponseStatus,
+) -> std::result::Result<OrderPlacement, OrderRejection> {
+    let response = match response {
+        ExchangeResponseStatus::Ok(response) => response,
+        ExchangeResponseStatus::Err(err) => return Err(OrderRejection::from_message(&err)),
+    };
+    let status = response
+        .data
+        .and_then(|data| data.statuses.into_iter().next())
+        .ok_or_else(|| {
+            OrderRejection::Request(Error::GenericParse(
+                "Response has no order status".to_string(),
+            ))
+        })?;
+    match status {
+        ExchangeDataStatus::Resting(order) => Ok(OrderPlacement::Resting(order)),
+        ExchangeDataStatus::Filled(order) => Ok(OrderPlacement::Filled(order)),
+        ExchangeDataStatus::WaitingForFill => Ok(OrderPlacement::WaitingForFill),
+        ExchangeDataStatus::WaitingForTrigger => Ok(OrderPlacement::WaitingForTrigger),
+        ExchangeDataStatus::Error(err) => Err(OrderRejection::from_message(&err)),
+        status => Err(OrderRejection::Request(Error::GenericParse(format!(
+            "Unexpected order status {status:?}"
+        )))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClientLimit, ClientOrder, Error, Tif};
+
+    fn request(cloid: u128) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 1800.0,
+            sz: 0.1,
+            cloid: Some(Uuid::from_u128(cloid)),
+            stp: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        }
+    }
+
+    #[test]
+    fn test_zip_statuses() {
+        let response: ExchangeResponseStatus = serde_json::from_value(serde_json::json!({
+            "status": "ok",
+            "response": {"type": "order", "data": {"statuses": [
+                {"resting": {"oid": 7}},
+                {"error": "Order must have minimum value of $10."}
+            ]}}
+        }))
+        .unwrap();
+        let results = zip_statuses(vec![request(1), request(2), request(3)], Ok(response));
+        assert_eq!(results[0].cloid(), Some(Uuid::from_u128(1)));
+        assert!(matches!(
+            results[0].status,
+            ExchangeDataStatus::Resting(ref resting) if resting.oid == 7
+        ));
+        assert!(results[1].is_error());
+        assert_eq!(results[1].cloid(), Some(Uuid::from_u128(2)));
+        // a short response leaves the missing orders with an error
+        assert!(results[2].is_error());
+
+        let rejected = ExchangeResponseStatus::Err("Insufficient margin".to_string());
+        let results = zip_statuses(vec![request(1), request(2)], Ok(rejected));
+        assert!(results.iter().all(|result| matches!(
+            &result.status,
+            ExchangeDataStatus::Error(err) if err == "Insufficient margin"
+        )));
+
+        let results = zip_statuses(vec![request(1)], Err(Error::FloatStringParse));
+        assert!(results[0].is_error());
+        assert!(!results[0].fate_unknown);
+
+        let timeout = Error::Timeout(std::time::Duration::from_secs(5));
+        let results = unknown_fate(vec![request(1), request(2)], &timeout);
+        assert!(results
+            .iter()
+            .all(|result| result.fate_unknown && !result.is_error()));
+    }
+
+    #[test]
+    fn test_checked_status() {
+        let response = |status: serde_json::Value| -> ExchangeResponseStatus {
+            serde_json::from_value(serde_json::json!({
+                "status": "ok",
+                "response": {"type": "order", "data": {"statuses": [status]}}
+            }))
+            .unwrap()
+        };
+        assert!(matches!(
+            checked_status(response(serde_json::json!({"resting": {"oid": 7}}))),
+            Ok(OrderPlacement::Resting(RestingOrder { oid: 7 }))
+        ));
+        let rejections = [
+            "Price must be divisible by tick size. asset=0",
+            "Insufficient margin to place order. asset=0",
+            "Reduce only order would increase position. asset=0",
+            "Order must have minimum value of $10. asset=0",
+            "Post only order would have immediately matched, bbo was 1800.5. asset=0",
+            "Order could not immediately match against any resting orders. asset=0",
+        ];
+        let rejections: Vec<_> = rejections
+            .iter()
+            .map(|err| checked_status(response(serde_json::json!({ "error": err }))))
+            .collect();
+        assert!(matches!(rejections[0], Err(OrderRejection::TickSize(_))));
+        assert!(matches!(
+            rejections[1],
+            Err(OrderRejection::InsufficientMargin(_))
+        ));
+        assert!(matches!(rejections[2], Err(OrderRejection::ReduceOnly(_))));
+        assert!(matches!(rejections[3], Err(OrderRejection::MinValue(_))));
+        assert!(matches!(
+            rejections[4],
+            Err(OrderRejection::PostOnlyWouldMatch(_))
+        ));
+        assert!(matches!(rejections[5], Err(OrderRejection::IocNoMatch(_))));
+
+        let rejected =
+            ExchangeResponseStatus::Err("User or API Wallet does not exist.".to_string());
+        assert!(matches!(
+            checked_status(rejected),
+            Err(OrderRejection::Other(_))
+        ));
+        assert!(matches!(
+            checked_status(response(serde_json::json!("success"))),
+            Err(OrderRejection::Request(_))
+        ));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{
+    exchange::exchange_client::PostError, helpers::next_nonce, prelude::*, ClientOrderRequest,
+    Error, ExchangeClient, ExchangeResponseStatus, OrderInfo,
+};
+use alloy::signers::local::PrivateKeySigner;
+use log::warn;
+use uuid::Uuid;
+
+/// How [`ExchangeClient::submit_with_recovery`] settled an order
+#[derive(Debug, Clone)]
+pub enum RecoveredOrder {
+    /// The first attempt got a response
+    Sent(ExchangeResponseStatus),
+    /// The first attempt got no response but reached the exchange
+    Landed(Box<OrderInfo>),
+    /// The first attempt never landed, its nonce was used up with a noop and the order was
+    /// sent again
+    Resent(ExchangeResponseStatus),
+}
+
+/// Failures of a sent request after which it may or may not have reached the exchange. Errors
+/// raised before sending, like a status monitor gate or a hook veto, are never checked against
+/// it, see [`PostError`].
+pub(crate) fn is_unknown_fate(err: &Error) -> bool {
+    matches!(
+        err,
+        Error::Timeout(_)
+            | Error::GenericRequest(_)
+            | Error::ServerRequest { .. }
+            | Error::ExchangeUnavailable { .. }
+    )
+}
+
+impl ExchangeClient {
+    /// Places `order` and, if the request fails without telling whether the order was placed,
+    /// looks the order up by its cloid. An order that is not found is made impossible to land
+    /// by sending a noop with its nonce, then sent again with a new nonce, so it is never placed
+    /// twice. Orders are looked up under the account the signer acts for, so agent wallets are
+    /// covered. Orders without a cloid get a random one.
+    pub async fn submit_with_recovery(
+        &self,
+        mut order: ClientOrderRequest,
+        wallet: Option<&PrivateKeySigner>,
+    ) -> Result<RecoveredOrder> {
+        let cloid = *order.cloid.get_or_insert_with(Uuid::new_v4);
+        let nonce = next_nonce();
+        let err = match self
+            .bulk_order_with_nonce(vec![order.clone()], wallet, "na", None, nonce)
+            .await
+        {
+            Err(PostError::Request(err)) if is_unknown_fate(&err) => err,
+            response => return response.map(RecoveredOrder::Sent).map_err(Error::from),
+        };
+        warn!("Order {cloid} with nonce {nonce} may not have been placed: {err}");
+
+        let user = self
+            .account_of(wallet.unwrap_or(&self.wallet).address())
+            .await?;
+        let info_client = self.info_client();
+        if let Some(order_info) = info_client.query_order_by_cloid(user, cloid).await?.order {
+            return Ok(RecoveredOrder::Landed(Box::new(order_info)));
+        }
+        if let ExchangeResponseStatus::Err(noop_err) = self.noop(nonce, wallet).await? {
+            // the order may have landed after it was looked up
+            return match info_client.query_order_by_cloid(user, cloid).await?.order {
+                Some(order_info) => Ok(RecoveredOrder::Landed(Box::new(order_info))),
+                None => Err(Error::GenericRequest(format!(
+                    "Could not use up nonce {nonce} of order {cloid}: {noop_err}"
+                ))),
+            };
+        }
+        let response = self.bulk_order(vec![order], wallet).await?;
+        Ok(RecoveredOrder::Resent(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Actions;
+
+    #[test]
+    fn test_noop_action() {
+        assert_eq!(
+            serde_json::to_value(Actions::Noop).unwrap(),
+            serde_json::json!({"type": "noop"})
+        );
+        assert!(is_unknown_fate(&Error::Timeout(
+            std::time::Duration::from_secs(5)
+        )));
+        assert!(!is_unknown_fate(&Error::ClientRequest {
+            status_code: 422,
+            error_code: None,
+            error_message: String::new(),
+            error_data: None,
+        }));
+    }
+
+    fn order() -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 2000.0,
+            sz: 0.1,
+            cloid: None,
+            stp: None,
+            order_type: crate::ClientOrder::Limit(crate::ClientLimit {
+                tif: crate::Tif::Gtc,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gated_order_is_not_looked_up() -> Result<()> {
+        // nothing listens there, so a lookup would fail with a request error
+        let mut client =
+            crate::exchange::exchange_client::tests::test_client("http://127.0.0.1:1".to_string())?;
+        client.set_status_monitor(crate::ExchangeStatusMonitor::fixed(
+            crate::ExchangeAvailability::Unavailable { retry_after: None },
+        ));
+        let result = client.submit_with_recovery(order(), None).await;
+        assert!(matches!(result, Err(Error::ExchangeUnavailable { .. })));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_vetoed_order_is_not_looked_up() -> Result<()> {
+        let mut client =
+            crate::exchange::exchange_client::tests::test_client("http://127.0.0.1:1".to_string())?;
+        // request failures keep being reported as json errors outside of recovery
+        assert!(matches!(
+            client.place_order(order(), None).await,
+            Err(Error::JsonParse(_))
+        ));
+
+        client.add_pre_send_hook(|_, _| Err(Error::GenericRequest("vetoed".to_string())));
+        let result = client.submit_with_recovery(order(), None).await;
+        assert!(matches!(result, Err(Error::GenericRequest(reason)) if reason == "vetoed"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_agent_order_is_looked_up_under_master() -> Result<()> {
+        use crate::exchange::exchange_client::tests::{json_server, test_client};
+        use serde_json::json;
+
+        let master = crate::Address::repeat_byte(7);
+        let base_url = json_server(move |path, body| match (path, body["type"].as_str()) {
+            // the order request fails without telling whether it landed
+            ("/exchange", _) => None,
+            ("/info", Some("userRole")) => Some(json!({"role": "agent", "data": {"user": master}})),
+            ("/info", Some("orderStatus")) if body["user"] == json!(master) => Some(json!({
+                "status": "order",
+                "order": {
+                    "order": {
+                        "coin": "ETH", "side": "B", "limitPx": "2000.0", "sz": "0.1", "oid": 9,
+                        "timestamp": 1700000000000u64, "triggerCondition": "N/A",
+                        "isTrigger": false, "triggerPx": "0.0", "isPositionTpsl": false,
+                        "reduceOnly": false, "orderType": "Limit", "origSz": "0.1",
+                        "tif": "Gtc", "cloid": body["oid"]
+                    },
+                    "status": "open",
+                    "statusTimestamp": 1700000000000u64
+                }
+            })),
+            _ => Some(json!({"status": "unknownOid"})),
+        })
+        .await;
+
+        let client = test_client(base_url)?;
+        let recovered = client.submit_with_recovery(order(), None).await?;
+        assert!(matches!(recovered, RecoveredOrder::Landed(order) if order.order.oid == 9));
+        Ok(())
+    }
+}
@@ -0,0 +1,388 @@
+#[cfg(feature = "ws")]
+use crate::OrderUpdate;
+use crate::{
+    prelude::*, ClientOrder, ClientOrderRequest, Error, ExchangeDataStatus, ExchangeDataStatuses,
+    ExchangeResponse, ExchangeResponseStatus, StpMode,
+};
+use std::{collections::HashMap, sync::Mutex};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfCrossPolicy {
+    /// Refuse to send an order that would cross our own resting order
+    Reject,
+    /// Send a single crossing order as a modify of the resting order it would cross.
+    /// Bulk orders that cross are still rejected.
+    ModifyResting,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardedOrder {
+    pub oid: u64,
+    pub cloid: Option<Uuid>,
+    pub is_buy: bool,
+    pub px: f64,
+}
+
+/// Tracks our own resting limit orders per coin so that orders crossing them can be stopped before signing.
+/// The guard learns about orders from the responses of `ExchangeClient` calls; feed it ws order updates
+/// through [`SelfCrossGuard::on_order_updates`] to forget orders that fill or are cancelled elsewhere.
+#[derive(Debug)]
+pub struct SelfCrossGuard {
+    pub policy: SelfCrossPolicy,
+    resting: Mutex<HashMap<String, Vec<GuardedOrder>>>,
+}
+
+/// An order dropped by self-trade prevention, as its index in the batch and the oid of the
+/// resting order it would have crossed
+pub(crate) type PreventedOrder = (usize, u64);
+
+pub(crate) struct PendingOrder {
+    asset: String,
+    order: Option<GuardedOrder>,
+}
+
+impl PendingOrder {
+    pub(crate) fn from_request(order: &ClientOrderRequest) -> PendingOrder {
+        let order_info = match order.order_type {
+            ClientOrder::Limit(_) => Some(GuardedOrder {
+                oid: 0,
+                cloid: order.cloid,
+                is_buy: order.is_buy,
+                px: order.limit_px,
+            }),
+            // trigger orders only reach the book once triggered
+            ClientOrder::Trigger(_) => None,
+        };
+        PendingOrder {
+            asset: order.asset.clone(),
+            order: order_info,
+        }
+    }
+}
+
+fn crosses(resting: &GuardedOrder, is_buy: bool, px: f64) -> bool {
+    if is_buy {
+        !resting.is_buy && resting.px <= px
+    } else {
+        resting.is_buy && resting.px >= px
+    }
+}
+
+impl SelfCrossGuard {
+    pub fn new(policy: SelfCrossPolicy) -> SelfCrossGuard {
+        SelfCrossGuard {
+            policy,
+            resting: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Vec<GuardedOrder>>> {
+        self.resting.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the oid of our resting order that `order` would cross, ignoring `exclude_oid`
+    pub fn crossing_order(
+        &self,
+        order: &ClientOrderRequest,
+        exclude_oid: Option<u64>,
+    ) -> Option<u64> {
+        if let ClientOrder::Trigger(_) = order.order_type {
+            return None;
+        }
+        self.lock().get(&order.asset).and_then(|resting| {
+            resting
+                .iter()
+                .find(|resting| {
+                    Some(resting.oid) != exclude_oid
+                        && crosses(resting, order.is_buy, order.limit_px)
+                })
+                .map(|resting| resting.oid)
+        })
+    }
+
+    pub(crate) fn check(&self, order: &ClientOrderRequest, exclude_oid: Option<u64>) -> Result<()> {
+        match self.crossing_order(order, exclude_oid) {
+            Some(oid) => Err(Error::SelfCross {
+                asset: order.asset.clone(),
+                oid,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Splits off the orders that self-trade prevention drops. Other crossing orders are rejected
+    /// as in [`SelfCrossGuard::check`].
+    pub(crate) fn prevent_self_trades(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+    ) -> Result<(Vec<ClientOrderRequest>, Vec<PreventedOrder>)> {
+        let mut kept = Vec::with_capacity(orders.len());
+        let mut prevented = Vec::new();
+        for (index, order) in orders.into_iter().enumerate() {
+            match (self.crossing_order(&order, None), order.stp) {
+                (Some(oid), Some(StpMode::CancelNewest)) => prevented.push((index, oid)),
+                (Some(oid), None) => {
+                    return Err(Error::SelfCross {
+                        asset: order.asset,
+                        oid,
+                    })
+                }
+                (None, _) => kept.push(order),
+            }
+        }
+        Ok((kept, prevented))
+    }
+
+    pub fn resting_orders(&self, asset: &str) -> Vec<GuardedOrder> {
+        self.lock().get(asset).cloned().unwrap_or_default()
+    }
+
+    pub fn insert(&self, asset: &str, order: GuardedOrder) {
+        self.lock()
+            .entry(asset.to_string())
+            .or_default()
+            .push(order);
+    }
+
+    pub fn remove_oid(&self, oid: u64) {
+        for resting in self.lock().values_mut() {
+            resting.retain(|order| order.oid != oid);
+        }
+    }
+
+    pub fn remove_cloid(&self, cloid: Uuid) {
+        for resting in self.lock().values_mut() {
+            resting.retain(|order| order.cloid != Some(cloid));
+        }
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Forgets orders that are no longer open according to ws order updates
+    #[cfg(feature = "ws")]
+    pub fn on_order_updates(&self, updates: &[OrderUpdate]) {
+        for update in updates {
+            if update.status != "open" {
+                self.remove_oid(update.order.oid);
+            }
+        }
+    }
+
+    pub(crate) fn record_orders(
+        &self,
+        pending: Vec<PendingOrder>,
+        response: &ExchangeResponseStatus,
+    ) {
+        let ExchangeResponseStatus::Ok(response) = response else {
+            return;
+        };
+        let Some(data) = &response.data else {
+            return;
+        };
+        for (pending, status) in pending.into_iter().zip(data.statuses.iter()) {
+            if let (Some(mut order), ExchangeDataStatus::Resting(resting)) = (pending.order, status)
+            {
+                order.oid = resting.oid;
+                self.insert(&pending.asset, order);
+            }
+        }
+    }
+
+    pub(crate) fn record_cancels(&self, oids: Vec<u64>, response: &ExchangeResponseStatus) {
+        for (oid, status) in oids.into_iter().zip(successful_statuses(response)) {
+            if let ExchangeDataStatus::Success = status {
+                self.remove_oid(oid);
+            }
+        }
+    }
+
+    pub(crate) fn record_cancels_by_cloid(
+        &self,
+        cloids: Vec<Uuid>,
+        response: &ExchangeResponseStatus,
+    ) {
+        for (cloid, status) in cloids.into_iter().zip(successful_statuses(response)) {
+            if let ExchangeDataStatus::Success = status {
+                self.remove_cloid(cloid);
+            }
+        }
+    }
+
+    pub(crate) fn record_modifies(
+        &self,
+        modifies: Vec<(u64, PendingOrder)>,
+        response: &ExchangeResponseStatus,
+    ) {
+        if !matches!(response, ExchangeResponseStatus::Ok(_)) {
+            return;
+        }
+        let (oids, pending): (Vec<u64>, Vec<PendingOrder>) = modifies.into_iter().unzip();
+        for oid in oids {
+            self.remove_oid(oid);
+        }
+        self.record_orders(pending, response);
+    }
+}
+
+/// Puts an error status for each order dropped by self-trade prevention back at its place in the
+/// batch, so statuses still line up with the orders given. `response` is `None` when every order
+/// was dropped and nothing was sent.
+pub(crate) fn with_prevented_statuses(
+    response: Option<ExchangeResponseStatus>,
+    prevented: &[PreventedOrder],
+) -> ExchangeResponseStatus {
+    let mut response = response.unwrap_or_else(|| {
+        ExchangeResponseStatus::Ok(ExchangeResponse {
+            response_type: "order".to_string(),
+            data: Some(ExchangeDataStatuses {
+                statuses: Vec::new(),
+            }),
+        })
+    });
+    if let ExchangeResponseStatus::Ok(ExchangeResponse {
+        data: Some(data), ..
+    }) = &mut response
+    {
+        for &(index, oid) in prevented {
+            let status = ExchangeDataStatus::Error(format!(
+                "Order would self-trade with resting order {oid}"
+            ));
+            data.statuses.insert(index.min(data.statuses.len()), status);
+        }
+    }
+    response
+}
+
+fn successful_statuses(response: &ExchangeResponseStatus) -> Vec<ExchangeDataStatus> {
+    match response {
+        ExchangeResponseStatus::Ok(response) => response
+            .data
+            .as_ref()
+            .map(|data| data.statuses.clone())
+            .unwrap_or_default(),
+        ExchangeResponseStatus::Err(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClientLimit, Tif};
+
+    fn limit_order(is_buy: bool, limit_px: f64) -> ClientOrderRequest {
+        order_with_stp(is_buy, limit_px, None)
+    }
+
+    fn order_with_stp(is_buy: bool, limit_px: f64, stp: Option<StpMode>) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy,
+            reduce_only: false,
+            limit_px,
+            sz: 1.0,
+            cloid: None,
+            stp,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        }
+    }
+
+    #[test]
+    fn test_self_cross_detection() {
+        let guard = SelfCrossGuard::new(SelfCrossPolicy::Reject);
+        guard.insert(
+            "ETH",
+            GuardedOrder {
+                oid: 1,
+                cloid: None,
+                is_buy: true,
+                px: 2000.0,
+            },
+        );
+        guard.insert(
+            "ETH",
+            GuardedOrder {
+                oid: 2,
+                cloid: None,
+                is_buy: false,
+                px: 2010.0,
+            },
+        );
+
+        assert_eq!(
+            guard.crossing_order(&limit_order(false, 1999.0), None),
+            Some(1)
+        );
+        assert_eq!(
+            guard.crossing_order(&limit_order(false, 2001.0), None),
+            None
+        );
+        assert_eq!(
+            guard.crossing_order(&limit_order(true, 2010.0), None),
+            Some(2)
+        );
+        assert_eq!(
+            guard.crossing_order(&limit_order(true, 2010.0), Some(2)),
+            None
+        );
+        assert!(guard.check(&limit_order(true, 2005.0), None).is_ok());
+
+        guard.remove_oid(1);
+        assert_eq!(
+            guard.crossing_order(&limit_order(false, 1999.0), None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cancel_newest() {
+        let guard = SelfCrossGuard::new(SelfCrossPolicy::Reject);
+        guard.insert(
+            "ETH",
+            GuardedOrder {
+                oid: 1,
+                cloid: None,
+                is_buy: true,
+                px: 2000.0,
+            },
+        );
+
+        let orders = vec![
+            order_with_stp(true, 1990.0, Some(StpMode::CancelNewest)),
+            order_with_stp(false, 1999.0, Some(StpMode::CancelNewest)),
+            order_with_stp(false, 2001.0, Some(StpMode::CancelNewest)),
+        ];
+        let (kept, prevented) = guard.prevent_self_trades(orders).unwrap();
+        assert_eq!(kept.len(), 2);
+        assert_eq!(prevented, vec![(1, 1)]);
+        assert!(matches!(
+            guard.prevent_self_trades(vec![limit_order(false, 1999.0)]),
+            Err(Error::SelfCross { oid: 1, .. })
+        ));
+
+        let response: ExchangeResponseStatus = serde_json::from_value(serde_json::json!({
+            "status": "ok",
+            "response": {"type": "order", "data": {"statuses": [
+                {"resting": {"oid": 2}},
+                {"resting": {"oid": 3}}
+            ]}}
+        }))
+        .unwrap();
+        let ExchangeResponseStatus::Ok(response) =
+            with_prevented_statuses(Some(response), &prevented)
+        else {
+            panic!("expected an ok response");
+        };
+        let statuses = response.data.unwrap().statuses;
+        assert!(matches!(statuses[0], ExchangeDataStatus::Resting(_)));
+        assert!(matches!(statuses[1], ExchangeDataStatus::Error(_)));
+        assert!(matches!(statuses[2], ExchangeDataStatus::Resting(_)));
+
+        let ExchangeResponseStatus::Ok(response) = with_prevented_statuses(None, &prevented) else {
+            panic!("expected an ok response");
+        };
+        assert_eq!(response.data.unwrap().statuses.len(), 1);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{ClientOrderRequest, OrderBook};
+
+/// Expected execution of an order against a book snapshot, ignoring other orders in flight
+#[derive(Debug, Clone, PartialEq)]
+pub struct FillEstimate {
+    /// Size that would trade immediately
+    pub filled_sz: f64,
+    /// Average execution price, `None` if nothing would trade
+    pub avg_px: Option<f64>,
+    pub notional: f64,
+    /// Cost of the average price relative to the mid, in basis points.
+    /// `None` if nothing would trade or the book is one-sided.
+    pub slippage_bps: Option<f64>,
+    /// Opposite-side levels the order would trade against
+    pub levels: usize,
+}
+
+impl FillEstimate {
+    pub fn is_full(&self, sz: f64) -> bool {
+        self.filled_sz >= sz
+    }
+}
+
+/// Whether `order`'s limit price reaches the opposite side of `book`, in which case an ALO
+/// order would be rejected and any other limit order would take liquidity
+pub fn would_cross(order: &ClientOrderRequest, book: &OrderBook) -> bool {
+    if order.is_buy {
+        book.best_ask().is_some_and(|ask| order.limit_px >= ask)
+    } else {
+        book.best_bid().is_some_and(|bid| order.limit_px <= bid)
+    }
+}
+
+/// Price and size of the opposite-side levels of `book` at or better than `order`'s limit price,
+/// best first
+pub(crate) fn reachable_levels<'a>(
+    order: &'a ClientOrderRequest,
+    book: &'a OrderBook,
+) -> impl Iterator<Item = (f64, f64)> + 'a {
+    let opposite = if order.is_buy { &book.asks } else { &book.bids };
+    opposite.iter().map_while(|level| {
+        let (px, sz) = (level.px.parse::<f64>().ok()?, level.sz.parse::<f64>().ok()?);
+        let reachable = if order.is_buy {
+            px <= order.limit_px
+        } else {
+            px >= order.limit_px
+        };
+        reachable.then_some((px, sz))
+    })
+}
+
+/// Walks the opposite side of `book` up to `order`'s limit price and returns the fill an
+/// immediate execution would get
+pub fn estimated_fill(order: &ClientOrderRequest, book: &OrderBook) -> FillEstimate {
+    let mut remaining = order.sz;
+    let mut notional = 0.0;
+    let mut levels = 0;
+    for (px, sz) in reachable_levels(order, book) {
+        if remaining <= 0.0 {
+            break;
+        }
+        let traded = sz.min(remaining);
+        notional += traded * px;
+        remaining -= traded;
+        levels += 1;
+    }
+
+    let filled_sz = order.sz - remaining.max(0.0);
+    let avg_px = (filled_sz > 0.0).then(|| notional / filled_sz);
+    let slippage_bps = avg_px.zip(book.mid()).map(|(avg_px, mid)| {
+        let cost = if order.is_buy {
+            avg_px - mid
+        } else {
+            mid - avg_px
+        };
+        cost / mid * 10_000.0
+    });
+    FillEstimate {
+        filled_sz,
+        avg_px,
+        notional,
+        slippage_bps,
+        levels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BookLevel, ClientLimit, ClientOrder, Tif};
+
+    fn level(px: &str, sz: &str) -> BookLevel {
+        BookLevel {
+            px: px.to_string(),
+            sz: sz.to_string(),
+            n: 1,
+        }
+    }
+
+    fn order(is_buy: bool, limit_px: f64, sz: f64) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy,
+            reduce_only: false,
+            limit_px,
+            sz,
+            cloid: None,
+            stp: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Alo }),
+        }
+    }
+
+    #[test]
+    fn test_would_cross_and_estimated_fill() {
+        let book = OrderBook {
+            coin: "ETH".to_string(),
+            time: 0,
+            bids: vec![level("99", "1"), level("98", "2")],
+            asks: vec![level("101", "1"), level("102", "2"), level("110", "5")],
+        };
+        assert!(!would_cross(&order(true, 100.0, 1.0), &book));
+        assert!(would_cross(&order(true, 101.0, 1.0), &book));
+        assert!(!would_cross(&order(false, 100.0, 1.0), &book));
+        assert!(would_cross(&order(false, 99.0, 1.0), &book));
+
+        let estimate = estimated_fill(&order(true, 105.0, 2.0), &book);
+        assert_eq!(estimate.filled_sz, 2.0);
+        assert_eq!(estimate.avg_px, Some(101.5));
+        assert_eq!(estimate.levels, 2);
+        assert!((estimate.slippage_bps.unwrap() - 150.0).abs() < 1e-9);
+        assert!(estimate.is_full(2.0));
+
+        let estimate = estimated_fill(&order(false, 98.5, 3.0), &book);
+        assert_eq!(estimate.filled_sz, 1.0);
+        assert_eq!(estimate.avg_px, Some(99.0));
+        assert!(!estimate.is_full(3.0));
+
+        let estimate = estimated_fill(&order(true, 100.0, 1.0), &book);
+        assert_eq!(estimate.filled_sz, 0.0);
+        assert_eq!(estimate.avg_px, None);
+        assert_eq!(estimate.slippage_bps, None);
+    }
+}
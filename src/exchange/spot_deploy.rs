@@ -0,0 +1,253 @@
+use crate::Address;
+use crate::{helpers::parse_decimal, meta::SpotMeta, prelude::*, Error};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Steps of deploying a spot token, sent as the `spotDeploy` action
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SpotDeploy {
+    UserGenesis(UserGenesis),
+    Genesis(Genesis),
+}
+
+/// Balances credited to users, and to holders of existing tokens pro rata, at genesis.
+/// Amounts are in wei.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UserGenesis {
+    pub token: u32,
+    /// `(lowercase user address, wei)` pairs
+    pub user_and_wei: Vec<(String, String)>,
+    /// `(existing token index, wei)` pairs
+    pub existing_token_and_wei: Vec<(u32, String)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Genesis {
+    pub token: u32,
+    /// In wei
+    pub max_supply: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_hyperliquidity: bool,
+}
+
+/// Genesis balances of a registered spot token, checked against its wei decimals and max supply
+/// before anything is signed. Amounts are given in whole-token units.
+#[derive(Debug, Clone)]
+pub struct SpotGenesisBuilder {
+    token: u32,
+    wei_decimals: u32,
+    existing_tokens: HashSet<u32>,
+    max_supply: String,
+    no_hyperliquidity: bool,
+    users: Vec<(Address, String)>,
+    existing_token_holders: Vec<(u32, String)>,
+}
+
+/// Converts a whole-token amount to wei, rejecting amounts finer than a wei
+fn to_wei(amount: &str, wei_decimals: u32) -> Result<u128> {
+    let amount = parse_decimal(amount)?.normalize();
+    if amount.is_sign_negative() {
+        return Err(Error::SpotDeploy(format!("amount {amount} is negative")));
+    }
+    if amount.scale() > wei_decimals {
+        return Err(Error::SpotDeploy(format!(
+            "amount {amount} has more than {wei_decimals} decimals"
+        )));
+    }
+    let mut wei = amount;
+    wei.rescale(wei_decimals);
+    if wei.scale() != wei_decimals {
+        return Err(Error::SpotDeploy(format!("amount {amount} is too large")));
+    }
+    u128::try_from(wei.mantissa())
+        .map_err(|_| Error::SpotDeploy(format!("amount {amount} is negative")))
+}
+
+impl SpotGenesisBuilder {
+    /// `token` is the index of the token being deployed in `spot_meta`, `max_supply` its max
+    /// supply in whole-token units
+    pub fn new(spot_meta: &SpotMeta, token: usize, max_supply: &str) -> Result<SpotGenesisBuilder> {
+        let token_info = spot_meta
+            .tokens
+            .iter()
+            .find(|info| info.index == token)
+            .ok_or_else(|| Error::SpotDeploy(format!("token {token} is not registered")))?;
+        Ok(SpotGenesisBuilder {
+            token: token as u32,
+            wei_decimals: token_info.wei_decimals.into(),
+            existing_tokens: spot_meta
+                .tokens
+                .iter()
+                .map(|info| info.index as u32)
+                .collect(),
+            max_supply: max_supply.to_string(),
+            no_hyperliquidity: false,
+            users: Vec::new(),
+            existing_token_holders: Vec::new(),
+        })
+    }
+
+    /// Credits `amount` to `user`
+    pub fn user(mut self, user: Address, amount: &str) -> SpotGenesisBuilder {
+        self.users.push((user, amount.to_string()));
+        self
+    }
+
+    /// Splits `amount` between the holders of `existing_token` in proportion to their balances
+    pub fn existing_token_holders(
+        mut self,
+        existing_token: u32,
+        amount: &str,
+    ) -> SpotGenesisBuilder {
+        self.existing_token_holders
+            .push((existing_token, amount.to_string()));
+        self
+    }
+
+    /// Deploys without the hyperliquidity market making strategy
+    pub fn no_hyperliquidity(mut self) -> SpotGenesisBuilder {
+        self.no_hyperliquidity = true;
+        self
+    }
+
+    /// Validates the balances and returns the `userGenesis` and `genesis` actions, to be sent in
+    /// that order
+    pub fn build(self) -> Result<(UserGenesis, Genesis)> {
+        let max_supply = to_wei(&self.max_supply, self.wei_decimals)?;
+        if max_supply == 0 {
+            return Err(Error::SpotDeploy("max supply must be positive".to_string()));
+        }
+
+        let mut total: u128 = 0;
+        let mut add = |amount: &str| -> Result<String> {
+            let wei = to_wei(amount, self.wei_decimals)?;
+            if wei == 0 {
+                return Err(Error::SpotDeploy(format!("amount {amount} is zero")));
+            }
+            total = total
+                .checked_add(wei)
+                .filter(|&total| total <= max_supply)
+                .ok_or_else(|| {
+                    Error::SpotDeploy(format!(
+                        "genesis balances exceed the max supply of {}",
+                        self.max_supply
+                    ))
+                })?;
+            Ok(wei.to_string())
+        };
+
+        let mut seen_users = HashSet::new();
+        let mut user_and_wei = Vec::with_capacity(self.users.len());
+        for (user, amount) in &self.users {
+            if user.is_zero() {
+                return Err(Error::SpotDeploy(
+                    "genesis user is the zero address".to_string(),
+                ));
+            }
+            if !seen_users.insert(*user) {
+                return Err(Error::SpotDeploy(format!("duplicate genesis user {user}")));
+            }
+            user_and_wei.push((user.to_string().to_lowercase(), add(amount)?));
+        }
+
+        let mut seen_tokens = HashSet::new();
+        let mut existing_token_and_wei = Vec::with_capacity(self.existing_token_holders.len());
+        for (existing_token, amount) in &self.existing_token_holders {
+            if *existing_token == self.token || !self.existing_tokens.contains(existing_token) {
+                return Err(Error::SpotDeploy(format!(
+                    "{existing_token} is not an existing token"
+                )));
+            }
+            if !seen_tokens.insert(*existing_token) {
+                return Err(Error::SpotDeploy(format!(
+                    "duplicate existing token {existing_token}"
+                )));
+            }
+            existing_token_and_wei.push((*existing_token, add(amount)?));
+        }
+
+        Ok((
+            UserGenesis {
+                token: self.token,
+                user_and_wei,
+                existing_token_and_wei,
+            },
+            Genesis {
+                token: self.token,
+                max_supply: max_supply.to_string(),
+                no_hyperliquidity: self.no_hyperliquidity,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Actions;
+
+    fn spot_meta() -> SpotMeta {
+        serde_json::from_value(serde_json::json!({
+            "universe": [],
+            "tokens": [
+                {"name": "USDC", "szDecimals": 8, "weiDecimals": 8, "index": 0, "tokenId": "0x6d1e7cde53ba9467b783cb7c530ce054", "isCanonical": true},
+                {"name": "PURR", "szDecimals": 0, "weiDecimals": 5, "index": 1, "tokenId": "0xc1fb593aeffbeb02f85e0308e9956a90", "isCanonical": true},
+                {"name": "NEW", "szDecimals": 2, "weiDecimals": 6, "index": 2, "tokenId": "0x00000000000000000000000000000000", "isCanonical": false}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_spot_genesis_builder() -> Result<()> {
+        let spot_meta = spot_meta();
+        let user = Address::with_last_byte(0xab);
+        let (user_genesis, genesis) = SpotGenesisBuilder::new(&spot_meta, 2, "1000")?
+            .user(user, "600.5")
+            .existing_token_holders(1, "100")
+            .build()?;
+        assert_eq!(
+            user_genesis.user_and_wei,
+            vec![(
+                "0x00000000000000000000000000000000000000ab".to_string(),
+                "600500000".to_string()
+            )]
+        );
+        assert_eq!(
+            user_genesis.existing_token_and_wei,
+            vec![(1, "100000000".to_string())]
+        );
+        assert_eq!(genesis.max_supply, "1000000000");
+
+        let action = serde_json::to_value(Actions::SpotDeploy(SpotDeploy::Genesis(genesis)))
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(
+            action,
+            serde_json::json!({
+                "type": "spotDeploy",
+                "genesis": {"token": 2, "maxSupply": "1000000000"}
+            })
+        );
+
+        let builder = || SpotGenesisBuilder::new(&spot_meta, 2, "1000");
+        let invalid = [
+            builder()?.user(user, "0.0000001"),
+            builder()?
+                .user(user, "900")
+                .existing_token_holders(1, "200"),
+            builder()?.user(user, "1").user(user, "1"),
+            builder()?.user(Address::ZERO, "1"),
+            builder()?.existing_token_holders(2, "1"),
+            builder()?.existing_token_holders(7, "1"),
+            builder()?.user(user, "0"),
+        ];
+        for builder in invalid {
+            assert!(matches!(builder.build(), Err(Error::SpotDeploy(_))));
+        }
+        assert!(SpotGenesisBuilder::new(&spot_meta, 3, "1000").is_err());
+        Ok(())
+    }
+}
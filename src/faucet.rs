@@ -0,0 +1,92 @@
+use crate::Address;
+use crate::{prelude::*, req::HttpClient, Error, InfoClient};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Serialize)]
+struct DripRequest {
+    user: Address,
+}
+
+/// Requests testnet USDC from a faucet endpoint and waits for it to be credited.
+/// Faucet availability varies; the testnet faucet only drips to addresses that meet its eligibility rules.
+#[derive(Debug, Clone)]
+pub struct TestnetFaucet {
+    http_client: HttpClient,
+}
+
+impl TestnetFaucet {
+    pub fn new(client: Option<Client>, faucet_url: String) -> TestnetFaucet {
+        TestnetFaucet {
+            http_client: HttpClient {
+                client: client.unwrap_or_default(),
+                base_url: faucet_url,
+            },
+        }
+    }
+
+    /// Asks the faucet to drip USDC to `user`
+    pub async fn claim(&self, user: Address) -> Result<()> {
+        let data = serde_json::to_string(&DripRequest { user })
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        self.http_client.post("", data).await?;
+        Ok(())
+    }
+
+    /// Polls the perp account value of `user` until it reaches `min_usdc`, returning the account value
+    pub async fn wait_for_usdc(
+        info_client: &InfoClient,
+        user: Address,
+        min_usdc: Decimal,
+        timeout: Duration,
+    ) -> Result<Decimal> {
+        if info_client.http_client.is_mainnet() {
+            return Err(Error::ChainNotAllowed);
+        }
+        let deadline = Instant::now() + timeout;
+        loop {
+            let account_value = info_client
+                .margin_summary(user)
+                .await?
+                .margin_summary
+                .account_value;
+            if account_value >= min_usdc {
+                return Ok(account_value);
+            }
+            if Instant::now() + POLL_INTERVAL > deadline {
+                return Err(Error::GenericRequest(format!(
+                    "{user:?} has {account_value} USDC after {timeout:?}, expected at least {min_usdc}"
+                )));
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Claims from the faucet unless `user` already holds `min_usdc`, then waits for the balance
+    pub async fn claim_and_wait(
+        &self,
+        info_client: &InfoClient,
+        user: Address,
+        min_usdc: Decimal,
+        timeout: Duration,
+    ) -> Result<Decimal> {
+        if info_client.http_client.is_mainnet() {
+            return Err(Error::ChainNotAllowed);
+        }
+        let account_value = info_client
+            .margin_summary(user)
+            .await?
+            .margin_summary
+            .account_value;
+        if account_value >= min_usdc {
+            return Ok(account_value);
+        }
+        self.claim(user).await?;
+        Self::wait_for_usdc(info_client, user, min_usdc, timeout).await
+    }
+}
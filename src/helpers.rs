@@ -1,16 +1,28 @@
+#[cfg(feature = "exchange")]
+use crate::limits;
 use crate::{consts::*, prelude::*, Error};
+#[cfg(feature = "info")]
 use chrono::prelude::Utc;
+#[cfg(feature = "exchange")]
 use lazy_static::lazy_static;
+#[cfg(feature = "exchange")]
 use log::info;
+#[cfg(feature = "exchange")]
 use rand::{thread_rng, Rng};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+#[cfg(feature = "exchange")]
 use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "exchange")]
 use uuid::Uuid;
 
-fn now_timestamp_ms() -> u64 {
+#[cfg(feature = "info")]
+pub(crate) fn now_timestamp_ms() -> u64 {
     let now = Utc::now();
     now.timestamp_millis() as u64
 }
 
+#[cfg(feature = "exchange")]
 pub(crate) fn next_nonce() -> u64 {
     let nonce = CUR_NONCE.fetch_add(1, Ordering::Relaxed);
     let now_ms = now_timestamp_ms();
@@ -24,10 +36,9 @@ pub(crate) fn next_nonce() -> u64 {
     nonce
 }
 
-pub(crate) const WIRE_DECIMALS: u8 = 8;
-
+#[cfg(feature = "exchange")]
 pub(crate) fn float_to_string_for_hashing(x: f64) -> String {
-    let mut x = format!("{:.*}", WIRE_DECIMALS.into(), x);
+    let mut x = format!("{:.*}", limits::MAX_WIRE_DECIMALS as usize, x);
     while x.ends_with('0') {
         x.pop();
     }
@@ -41,6 +52,13 @@ pub(crate) fn float_to_string_for_hashing(x: f64) -> String {
     }
 }
 
+pub(crate) fn parse_decimal(value: &str) -> Result<Decimal> {
+    Decimal::from_str(value)
+        .or_else(|_| Decimal::from_scientific(value))
+        .map_err(|_| Error::FloatStringParse)
+}
+
+#[cfg(feature = "exchange")]
 pub(crate) fn uuid_to_hex_string(uuid: Uuid) -> String {
     let hex_string = uuid
         .as_bytes()
@@ -51,6 +69,7 @@ pub(crate) fn uuid_to_hex_string(uuid: Uuid) -> String {
     format!("0x{}", hex_string)
 }
 
+#[cfg(feature = "exchange")]
 pub(crate) fn generate_random_key() -> Result<[u8; 32]> {
     let mut arr = [0u8; 32];
     thread_rng()
@@ -59,6 +78,9 @@ pub(crate) fn generate_random_key() -> Result<[u8; 32]> {
     Ok(arr)
 }
 
+#[deprecated(
+    note = "float rounding drifts off the tick, use `Px::round_to_tick` or `Sz::round_to_lot`"
+)]
 pub fn truncate_float(float: f64, decimals: u32, round_up: bool) -> f64 {
     let pow10 = 10i64.pow(decimals) as f64;
     let mut float = (float * pow10) as u64;
@@ -84,6 +106,7 @@ pub enum BaseUrl {
 }
 
 impl BaseUrl {
+    #[cfg(feature = "info")]
     pub(crate) fn get_url(&self) -> String {
         match self {
             BaseUrl::Localhost => LOCAL_API_URL.to_string(),
@@ -93,11 +116,12 @@ impl BaseUrl {
     }
 }
 
+#[cfg(feature = "exchange")]
 lazy_static! {
     static ref CUR_NONCE: AtomicU64 = AtomicU64::new(now_timestamp_ms());
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "exchange"))]
 mod tests {
     use super::*;
 
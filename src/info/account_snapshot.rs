@@ -0,0 +1,380 @@
+use crate::Address;
+use crate::{
+    helpers::now_timestamp_ms, prelude::*, InfoClient, OpenOrdersResponse, UserStateResponse,
+    UserTokenBalanceResponse,
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionSnapshot {
+    pub szi: String,
+    pub entry_px: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct BalanceSnapshot {
+    pub total: String,
+    pub hold: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenOrderSnapshot {
+    pub coin: String,
+    pub side: String,
+    pub limit_px: String,
+    pub sz: String,
+}
+
+/// Positions, spot balances and open orders of a user at a point in time
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSnapshot {
+    pub user: Address,
+    /// Local capture time in milliseconds
+    pub time: u64,
+    pub account_value: String,
+    pub withdrawable: String,
+    pub positions: BTreeMap<String, PositionSnapshot>,
+    pub balances: BTreeMap<String, BalanceSnapshot>,
+    pub open_orders: BTreeMap<u64, OpenOrderSnapshot>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AccountChange {
+    AccountValue {
+        before: String,
+        after: String,
+    },
+    Withdrawable {
+        before: String,
+        after: String,
+    },
+    Position {
+        coin: String,
+        before: Option<PositionSnapshot>,
+        after: Option<PositionSnapshot>,
+    },
+    Balance {
+        coin: String,
+        before: Option<BalanceSnapshot>,
+        after: Option<BalanceSnapshot>,
+    },
+    OrderPlaced {
+        oid: u64,
+        order: OpenOrderSnapshot,
+    },
+    OrderRemoved {
+        oid: u64,
+        order: OpenOrderSnapshot,
+    },
+    OrderChanged {
+        oid: u64,
+        before: OpenOrderSnapshot,
+        after: OpenOrderSnapshot,
+    },
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDiff {
+    pub user: Address,
+    pub before_time: u64,
+    pub after_time: u64,
+    pub changes: Vec<AccountChange>,
+}
+
+impl AccountSnapshot {
+    pub async fn capture(info_client: &InfoClient, user: Address) -> Result<AccountSnapshot> {
+        let user_state = info_client.user_state(user).await?;
+        let token_balances = info_client.user_token_balances(user).await?;
+        let open_orders = info_client.open_orders(user).await?;
+        Ok(Self::from_responses(
+            user,
+            now_timestamp_ms(),
+            user_state,
+            token_balances,
+            open_orders,
+        ))
+    }
+
+    pub(crate) fn from_responses(
+        user: Address,
+        time: u64,
+        user_state: UserStateResponse,
+        token_balances: UserTokenBalanceResponse,
+        open_orders: Vec<OpenOrdersResponse>,
+    ) -> AccountSnapshot {
+        AccountSnapshot {
+            user,
+            time,
+            account_value: user_state.margin_summary.account_value,
+            withdrawable: user_state.withdrawable,
+            positions: user_state
+                .asset_positions
+                .into_iter()
+                .map(|asset_position| {
+                    let position = asset_position.position;
+                    (
+                        position.coin,
+                        PositionSnapshot {
+                            szi: position.szi,
+                            entry_px: position.entry_px,
+                        },
+                    )
+                })
+                .collect(),
+            balances: token_balances
+                .balances
+                .into_iter()
+                .map(|balance| {
+                    (
+                        balance.coin,
+                        BalanceSnapshot {
+                            total: balance.total,
+                            hold: balance.hold,
+                        },
+                    )
+                })
+                .collect(),
+            open_orders: open_orders
+                .into_iter()
+                .map(|order| {
+                    (
+                        order.oid,
+                        OpenOrderSnapshot {
+                            coin: order.coin,
+                            side: order.side,
+                            limit_px: order.limit_px,
+                            sz: order.sz,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Changes from this snapshot to `after`, ordered by account values, positions, balances then orders
+    pub fn diff(&self, after: &AccountSnapshot) -> AccountDiff {
+        let mut changes = Vec::new();
+        if self.account_value != after.account_value {
+            changes.push(AccountChange::AccountValue {
+                before: self.account_value.clone(),
+                after: after.account_value.clone(),
+            });
+        }
+        if self.withdrawable != after.withdrawable {
+            changes.push(AccountChange::Withdrawable {
+                before: self.withdrawable.clone(),
+                after: after.withdrawable.clone(),
+            });
+        }
+        for (coin, before, after) in diff_maps(&self.positions, &after.positions) {
+            changes.push(AccountChange::Position {
+                coin,
+                before,
+                after,
+            });
+        }
+        for (coin, before, after) in diff_maps(&self.balances, &after.balances) {
+            changes.push(AccountChange::Balance {
+                coin,
+                before,
+                after,
+            });
+        }
+        for (oid, before, after) in diff_maps(&self.open_orders, &after.open_orders) {
+            changes.push(match (before, after) {
+                (None, Some(order)) => AccountChange::OrderPlaced { oid, order },
+                (Some(order), None) => AccountChange::OrderRemoved { oid, order },
+                (Some(before), Some(after)) => AccountChange::OrderChanged { oid, before, after },
+                (None, None) => continue,
+            });
+        }
+        AccountDiff {
+            user: self.user,
+            before_time: self.time,
+            after_time: after.time,
+            changes,
+        }
+    }
+}
+
+fn diff_maps<K: Ord + Clone, V: PartialEq + Clone>(
+    before: &BTreeMap<K, V>,
+    after: &BTreeMap<K, V>,
+) -> Vec<(K, Option<V>, Option<V>)> {
+    let mut keys: Vec<&K> = before.keys().chain(after.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter_map(|key| {
+            let (before, after) = (before.get(key), after.get(key));
+            (before != after).then(|| (key.clone(), before.cloned(), after.cloned()))
+        })
+        .collect()
+}
+
+impl AccountDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for PositionSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.entry_px {
+            Some(entry_px) => write!(f, "{} @ {entry_px}", self.szi),
+            None => write!(f, "{}", self.szi),
+        }
+    }
+}
+
+impl fmt::Display for BalanceSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (hold {})", self.total, self.hold)
+    }
+}
+
+impl fmt::Display for OpenOrderSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = if self.side == "B" { "buy" } else { "sell" };
+        write!(f, "{side} {} {} @ {}", self.sz, self.coin, self.limit_px)
+    }
+}
+
+fn fmt_optional<T: fmt::Display>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "none".to_string(), ToString::to_string)
+}
+
+impl fmt::Display for AccountChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccountChange::AccountValue { before, after } => {
+                write!(f, "account value: {before} -> {after}")
+            }
+            AccountChange::Withdrawable { before, after } => {
+                write!(f, "withdrawable: {before} -> {after}")
+            }
+            AccountChange::Position {
+                coin,
+                before,
+                after,
+            } => write!(
+                f,
+                "position {coin}: {} -> {}",
+                fmt_optional(before),
+                fmt_optional(after)
+            ),
+            AccountChange::Balance {
+                coin,
+                before,
+                after,
+            } => write!(
+                f,
+                "balance {coin}: {} -> {}",
+                fmt_optional(before),
+                fmt_optional(after)
+            ),
+            AccountChange::OrderPlaced { oid, order } => write!(f, "order {oid} placed: {order}"),
+            AccountChange::OrderRemoved { oid, order } => {
+                write!(f, "order {oid} removed: {order}")
+            }
+            AccountChange::OrderChanged { oid, before, after } => {
+                write!(f, "order {oid} changed: {before} -> {after}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for AccountDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "account {:?} changes between {} and {}:",
+            self.user, self.before_time, self.after_time
+        )?;
+        if self.changes.is_empty() {
+            return write!(f, " none");
+        }
+        for change in &self.changes {
+            write!(f, "\n  {change}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    fn snapshot(
+        time: u64,
+        user_state: &str,
+        balances: &str,
+        open_orders: &str,
+    ) -> Result<AccountSnapshot> {
+        let parse = |e: serde_json::Error| Error::JsonParse(e.to_string());
+        Ok(AccountSnapshot::from_responses(
+            Address::ZERO,
+            time,
+            serde_json::from_str(user_state).map_err(parse)?,
+            serde_json::from_str(balances).map_err(parse)?,
+            serde_json::from_str(open_orders).map_err(parse)?,
+        ))
+    }
+
+    #[test]
+    fn test_account_diff() -> Result<()> {
+        let summary = r#"{"accountValue": "1000.0", "totalMarginUsed": "0.0", "totalNtlPos": "0.0", "totalRawUsd": "1000.0"}"#;
+        let before = snapshot(
+            1,
+            &format!(
+                r#"{{"assetPositions": [], "crossMarginSummary": {summary}, "marginSummary": {summary}, "crossMaintenanceMarginUsed": "0.0", "withdrawable": "1000.0"}}"#
+            ),
+            r#"{"balances": [{"coin": "USDC", "hold": "0.0", "total": "50.0"}]}"#,
+            r#"[{"coin": "ETH", "limitPx": "2000.0", "oid": 1, "side": "B", "sz": "1.0", "timestamp": 0}]"#,
+        )?;
+        let after = snapshot(
+            2,
+            &format!(
+                r#"{{"assetPositions": [{{"type": "oneWay", "position": {{"coin": "ETH", "entryPx": "2000.0", "leverage": {{"type": "cross", "value": 20}}, "liquidationPx": null, "marginUsed": "100.0", "positionValue": "2000.0", "returnOnEquity": "0.0", "szi": "1.0", "unrealizedPnl": "0.0"}}}}], "crossMarginSummary": {summary}, "marginSummary": {summary}, "crossMaintenanceMarginUsed": "0.0", "withdrawable": "900.0"}}"#
+            ),
+            r#"{"balances": [{"coin": "USDC", "hold": "0.0", "total": "50.0"}]}"#,
+            r#"[{"coin": "ETH", "limitPx": "2100.0", "oid": 2, "side": "A", "sz": "1.0", "timestamp": 0}]"#,
+        )?;
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.changes.len(), 4);
+        assert_eq!(
+            diff.changes[0],
+            AccountChange::Withdrawable {
+                before: "1000.0".to_string(),
+                after: "900.0".to_string()
+            }
+        );
+        assert!(
+            matches!(&diff.changes[1], AccountChange::Position { coin, before: None, after: Some(_) } if coin == "ETH")
+        );
+        assert!(matches!(
+            diff.changes[2],
+            AccountChange::OrderRemoved { oid: 1, .. }
+        ));
+        assert!(matches!(
+            diff.changes[3],
+            AccountChange::OrderPlaced { oid: 2, .. }
+        ));
+        assert_eq!(
+            diff.changes[1].to_string(),
+            "position ETH: none -> 1.0 @ 2000.0"
+        );
+        assert!(before.diff(&before).is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+use crate::{
+    helpers::{now_timestamp_ms, parse_decimal},
+    info::poller::Poller,
+    prelude::*,
+    Address, Error, InfoClient, UserStateResponse,
+};
+use log::error;
+use rust_decimal::Decimal;
+use std::{collections::BTreeMap, time::Duration};
+use tokio::{
+    sync::mpsc::{unbounded_channel, UnboundedReceiver},
+    task::JoinSet,
+};
+
+/// Combined exposure to one coin across accounts
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CoinExposure {
+    pub coin: String,
+    /// Sum of signed position sizes
+    pub net_size: Decimal,
+    /// Sum of signed position values, positive when net long
+    pub net_delta: Decimal,
+    /// Sum of absolute position values
+    pub gross_notional: Decimal,
+    pub margin_used: Decimal,
+    pub unrealized_pnl: Decimal,
+    /// Number of accounts with a position in the coin
+    pub accounts: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountRisk {
+    pub user: Address,
+    pub account_value: Decimal,
+    pub margin_used: Decimal,
+    /// `margin_used / account_value`, `None` for empty accounts
+    pub margin_usage: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskReport {
+    /// Time the states were collected in milliseconds
+    pub time: u64,
+    pub accounts: Vec<AccountRisk>,
+    /// Exposures sorted by coin
+    pub coins: Vec<CoinExposure>,
+    pub account_value: Decimal,
+    pub margin_used: Decimal,
+    pub margin_usage: Option<Decimal>,
+    /// Accounts whose state could not be fetched, left out of the totals
+    pub failed: Vec<Address>,
+}
+
+fn margin_usage(margin_used: Decimal, account_value: Decimal) -> Option<Decimal> {
+    (!account_value.is_zero()).then(|| margin_used / account_value)
+}
+
+impl RiskReport {
+    pub fn new(time: u64, states: &[(Address, UserStateResponse)]) -> Result<RiskReport> {
+        let mut accounts = Vec::with_capacity(states.len());
+        let mut coins: BTreeMap<String, CoinExposure> = BTreeMap::new();
+        for (user, state) in states {
+            let account_value = parse_decimal(&state.margin_summary.account_value)?;
+            let margin_used = parse_decimal(&state.margin_summary.total_margin_used)?;
+            accounts.push(AccountRisk {
+                user: *user,
+                account_value,
+                margin_used,
+                margin_usage: margin_usage(margin_used, account_value),
+            });
+
+            for asset_position in &state.asset_positions {
+                let position = &asset_position.position;
+                let size = parse_decimal(&position.szi)?;
+                if size.is_zero() {
+                    continue;
+                }
+                let value = parse_decimal(&position.position_value)?.abs();
+                let exposure = coins
+                    .entry(position.coin.clone())
+                    .or_insert_with(|| CoinExposure {
+                        coin: position.coin.clone(),
+                        ..Default::default()
+                    });
+                exposure.net_size += size;
+                exposure.net_delta += if size.is_sign_negative() {
+                    -value
+                } else {
+                    value
+                };
+                exposure.gross_notional += value;
+                exposure.margin_used += parse_decimal(&position.margin_used)?;
+                exposure.unrealized_pnl += parse_decimal(&position.unrealized_pnl)?;
+                exposure.accounts += 1;
+            }
+        }
+
+        let account_value = accounts.iter().map(|account| account.account_value).sum();
+        let margin_used = accounts.iter().map(|account| account.margin_used).sum();
+        Ok(RiskReport {
+            time,
+            accounts,
+            coins: coins.into_values().collect(),
+            account_value,
+            margin_used,
+            margin_usage: margin_usage(margin_used, account_value),
+            failed: Vec::new(),
+        })
+    }
+
+    pub fn coin(&self, coin: &str) -> Option<&CoinExposure> {
+        self.coins.iter().find(|exposure| exposure.coin == coin)
+    }
+}
+
+/// Periodically fetches the states of several accounts, e.g. a master account, its
+/// sub-accounts and vaults, concurrently and emits a combined [`RiskReport`]
+#[derive(Debug, Clone)]
+pub struct AggregatedRisk {
+    pub users: Vec<Address>,
+    pub interval: Duration,
+}
+
+impl AggregatedRisk {
+    pub fn new(users: Vec<Address>, interval: Duration) -> AggregatedRisk {
+        AggregatedRisk { users, interval }
+    }
+
+    /// Fetches every state at once, keeping the order of `users`
+    async fn collect(&self, info_client: &InfoClient) -> Result<RiskReport> {
+        let mut requests = JoinSet::new();
+        for (index, user) in self.users.iter().copied().enumerate() {
+            let info_client = info_client.detached();
+            requests.spawn(async move { (index, user, info_client.user_state(user).await) });
+        }
+
+        let mut states = Vec::with_capacity(self.users.len());
+        let mut failed = Vec::new();
+        while let Some(joined) = requests.join_next().await {
+            let (index, user, state) = joined.map_err(|e| Error::GenericRequest(e.to_string()))?;
+            match state {
+                Ok(state) => states.push((index, user, state)),
+                Err(err) => {
+                    error!("Could not fetch user state of {user}: {err}");
+                    failed.push((index, user));
+                }
+            }
+        }
+        states.sort_by_key(|(index, _, _)| *index);
+        failed.sort_by_key(|(index, _)| *index);
+
+        let states: Vec<_> = states
+            .into_iter()
+            .map(|(_, user, state)| (user, state))
+            .collect();
+        let mut report = RiskReport::new(now_timestamp_ms(), &states)?;
+        report.failed = failed.into_iter().map(|(_, user)| user).collect();
+        Ok(report)
+    }
+
+    /// Starts polling in the background and returns the report stream.
+    /// The task stops once the receiver is dropped.
+    pub fn start(self, info_client: &InfoClient) -> Result<UnboundedReceiver<RiskReport>> {
+        let poller = Poller::new("AggregatedRisk", self.interval)?;
+        let info_client = info_client.detached();
+        let (sender, receiver) = unbounded_channel();
+        poller.spawn(
+            (self, info_client, sender),
+            |(risk, info_client, sender)| async move {
+                match risk.collect(&info_client).await {
+                    Ok(report) => {
+                        if sender.send(report).is_err() {
+                            return None;
+                        }
+                    }
+                    Err(err) => error!("Error collecting aggregated risk: {err}"),
+                }
+                (!sender.is_closed()).then_some((risk, info_client, sender))
+            },
+        );
+        Ok(receiver)
+    }
+}
+
+impl InfoClient {
+    /// Combined exposure of `users` right now
+    pub async fn aggregated_risk(&self, users: Vec<Address>) -> Result<RiskReport> {
+        AggregatedRisk::new(users, Duration::ZERO)
+            .collect(self)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(
+        account_value: &str,
+        margin_used: &str,
+        positions: &[(&str, &str, &str)],
+    ) -> UserStateResponse {
+        let asset_positions: Vec<_> = positions
+            .iter()
+            .map(|(coin, szi, value)| {
+                serde_json::json!({
+                    "type": "oneWay",
+                    "position": {
+                        "coin": coin, "entryPx": "1", "leverage": {"type": "cross", "value": 5},
+                        "liquidationPx": null, "marginUsed": "10", "positionValue": value,
+                        "returnOnEquity": "0", "szi": szi, "unrealizedPnl": "1"
+                    }
+                })
+            })
+            .collect();
+        let summary = serde_json::json!({
+            "accountValue": account_value, "totalMarginUsed": margin_used,
+            "totalNtlPos": "0", "totalRawUsd": "0"
+        });
+        serde_json::from_value(serde_json::json!({
+            "assetPositions": asset_positions,
+            "crossMarginSummary": summary,
+            "marginSummary": summary,
+            "crossMaintenanceMarginUsed": "0",
+            "withdrawable": "0"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_risk_report() {
+        let states = [
+            (
+                Address::with_last_byte(1),
+                state(
+                    "1000",
+                    "20",
+                    &[("ETH", "1.5", "3000"), ("BTC", "-0.01", "600")],
+                ),
+            ),
+            (
+                Address::with_last_byte(2),
+                state("500", "10", &[("ETH", "-0.5", "1000")]),
+            ),
+            (Address::with_last_byte(3), state("0", "0", &[])),
+        ];
+        let report = RiskReport::new(0, &states).unwrap();
+
+        assert_eq!(report.account_value, Decimal::from(1500));
+        assert_eq!(report.margin_usage, Some(Decimal::new(2, 2)));
+        assert_eq!(report.accounts[2].margin_usage, None);
+        assert_eq!(
+            report
+                .coins
+                .iter()
+                .map(|c| c.coin.as_str())
+                .collect::<Vec<_>>(),
+            vec!["BTC", "ETH"]
+        );
+
+        let eth = report.coin("ETH").unwrap();
+        assert_eq!(eth.net_size, Decimal::from(1));
+        assert_eq!(eth.net_delta, Decimal::from(2000));
+        assert_eq!(eth.gross_notional, Decimal::from(4000));
+        assert_eq!(eth.margin_used, Decimal::from(20));
+        assert_eq!(eth.accounts, 2);
+        assert_eq!(report.coin("BTC").unwrap().net_delta, Decimal::from(-600));
+    }
+}
@@ -0,0 +1,251 @@
+use crate::{
+    meta::{Meta, PerpDexMeta, SpotMeta},
+    prelude::*,
+    Error, InfoClient, NetworkConfig,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex;
+
+/// Unknown names only trigger a refresh if the last one is at least this old, so a typo
+/// doesn't fetch the metadata on every call
+pub const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetInfo {
+    /// Perp name, e.g. "ETH" or "xyz:XYZ100", or spot pair name, e.g. "@107" or "PURR/USDC"
+    pub name: String,
+    /// Id orders and other actions refer to the asset by
+    pub asset: u32,
+    pub sz_decimals: u32,
+    pub is_spot: bool,
+}
+
+#[derive(Debug, Default)]
+struct Assets {
+    by_name: HashMap<String, AssetInfo>,
+    coin_to_asset: Arc<HashMap<String, u32>>,
+    loaded_at: Option<Instant>,
+}
+
+impl Assets {
+    fn new(
+        meta: &Meta,
+        spot_meta: &SpotMeta,
+        perp_dexs: &[PerpDexMeta],
+        network: &NetworkConfig,
+    ) -> Assets {
+        let mut by_name = HashMap::new();
+        let perp_universes = std::iter::once((0, meta))
+            .chain(perp_dexs.iter().map(|dex| (dex.dex_index, &dex.meta)));
+        for (dex_index, meta) in perp_universes {
+            for (asset_index, asset) in meta.universe.iter().enumerate() {
+                by_name.insert(
+                    asset.name.clone(),
+                    AssetInfo {
+                        name: asset.name.clone(),
+                        asset: network.perp_asset_id(dex_index, asset_index),
+                        sz_decimals: asset.sz_decimals,
+                        is_spot: false,
+                    },
+                );
+            }
+        }
+
+        let tokens: HashMap<usize, _> = spot_meta
+            .tokens
+            .iter()
+            .map(|token| (token.index, token))
+            .collect();
+        for pair in &spot_meta.universe {
+            let (Some(base), Some(quote)) =
+                (tokens.get(&pair.tokens[0]), tokens.get(&pair.tokens[1]))
+            else {
+                continue;
+            };
+            let info = AssetInfo {
+                name: pair.name.clone(),
+                asset: network.spot_asset_id(pair.index),
+                sz_decimals: base.sz_decimals.into(),
+                is_spot: true,
+            };
+            by_name.insert(format!("{}/{}", base.name, quote.name), info.clone());
+            by_name.insert(pair.name.clone(), info);
+        }
+
+        let coin_to_asset = by_name
+            .iter()
+            .map(|(name, info)| (name.clone(), info.asset))
+            .collect();
+        Assets {
+            by_name,
+            coin_to_asset: Arc::new(coin_to_asset),
+            loaded_at: Some(Instant::now()),
+        }
+    }
+
+    fn is_fresh(&self, refresh_interval: Option<Duration>) -> bool {
+        match (self.loaded_at, refresh_interval) {
+            (None, _) => false,
+            (Some(loaded_at), Some(interval)) => loaded_at.elapsed() < interval,
+            (Some(_), None) => true,
+        }
+    }
+}
+
+/// Names, asset ids and sz decimals of every perp, builder-deployed perp and spot pair, loaded
+/// from `meta`, `perpDexs` and `spotMeta` on first use and again when a name is not found or
+/// the refresh interval has passed. Clones share the cache, so one registry can serve any
+/// number of [`crate::ExchangeClient`]s and [`InfoClient`]s.
+#[derive(Debug, Clone)]
+pub struct AssetRegistry {
+    info_client: Arc<InfoClient>,
+    network: NetworkConfig,
+    refresh_interval: Option<Duration>,
+    assets: Arc<RwLock<Arc<Assets>>>,
+    refreshing: Arc<Mutex<()>>,
+}
+
+impl AssetRegistry {
+    pub fn new(info_client: &InfoClient, network: NetworkConfig) -> AssetRegistry {
+        AssetRegistry {
+            info_client: Arc::new(info_client.detached()),
+            network,
+            refresh_interval: None,
+            assets: Arc::default(),
+            refreshing: Arc::default(),
+        }
+    }
+
+    /// Reloads the metadata on the next lookup once it is older than `refresh_interval`
+    pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> AssetRegistry {
+        self.refresh_interval = Some(refresh_interval);
+        self
+    }
+
+    fn assets(&self) -> Arc<Assets> {
+        self.assets
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Fetches the metadata unless another caller finished doing so after `seen`
+    async fn load(&self, seen: Option<Instant>) -> Result<()> {
+        let _refreshing = self.refreshing.lock().await;
+        if self.assets().loaded_at != seen {
+            return Ok(());
+        }
+
+        let (meta, spot_meta, perp_dexs) = tokio::try_join!(
+            self.info_client.meta(),
+            self.info_client.spot_meta(),
+            self.info_client.perp_dexs(),
+        )?;
+        let mut dex_metas = Vec::new();
+        for (dex_index, perp_dex) in perp_dexs.into_iter().enumerate() {
+            let Some(perp_dex) = perp_dex else {
+                continue;
+            };
+            dex_metas.push(PerpDexMeta {
+                meta: self.info_client.meta_for_dex(&perp_dex.name).await?,
+                dex: perp_dex.name,
+                dex_index,
+            });
+        }
+
+        let assets = Assets::new(&meta, &spot_meta, &dex_metas, &self.network);
+        *self.assets.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(assets);
+        Ok(())
+    }
+
+    /// Fetches the metadata now
+    pub async fn refresh(&self) -> Result<()> {
+        self.load(self.assets().loaded_at).await
+    }
+
+    /// Looks `coin` up, loading the metadata first if it is missing or stale and again if
+    /// `coin` is not in it
+    pub async fn resolve(&self, coin: &str) -> Result<AssetInfo> {
+        let assets = self.assets();
+        if !assets.is_fresh(self.refresh_interval) {
+            self.load(assets.loaded_at).await?;
+        } else if let Some(info) = assets.by_name.get(coin) {
+            return Ok(info.clone());
+        } else if assets
+            .loaded_at
+            .is_some_and(|loaded_at| loaded_at.elapsed() >= MIN_REFRESH_INTERVAL)
+        {
+            self.load(assets.loaded_at).await?;
+        }
+        self.get(coin).ok_or(Error::AssetNotFound)
+    }
+
+    pub async fn asset_id(&self, coin: &str) -> Result<u32> {
+        self.resolve(coin).await.map(|info| info.asset)
+    }
+
+    pub async fn sz_decimals(&self, coin: &str) -> Result<u32> {
+        self.resolve(coin).await.map(|info| info.sz_decimals)
+    }
+
+    /// Looks `coin` up in what is loaded, without fetching anything
+    pub fn get(&self, coin: &str) -> Option<AssetInfo> {
+        self.assets().by_name.get(coin).cloned()
+    }
+
+    /// Asset id of every loaded name
+    pub fn coin_to_asset(&self) -> Arc<HashMap<String, u32>> {
+        self.assets().coin_to_asset.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assets_from_meta() {
+        let meta: Meta = serde_json::from_value(serde_json::json!({
+            "universe": [
+                {"name": "BTC", "szDecimals": 5, "maxLeverage": 40},
+                {"name": "ETH", "szDecimals": 4, "maxLeverage": 25}
+            ]
+        }))
+        .unwrap();
+        let spot_meta: SpotMeta = serde_json::from_value(serde_json::json!({
+            "universe": [{"name": "PURR/USDC", "tokens": [1, 0], "index": 0, "isCanonical": true},
+                         {"name": "@107", "tokens": [150, 0], "index": 107, "isCanonical": false}],
+            "tokens": [
+                {"name": "USDC", "szDecimals": 8, "weiDecimals": 8, "index": 0, "tokenId": "0x6d1e7cde53ba9467b783cb7c530ce054", "isCanonical": true},
+                {"name": "PURR", "szDecimals": 0, "weiDecimals": 5, "index": 1, "tokenId": "0xc1fb593aeffbeb02f85e0308e9956a90", "isCanonical": true},
+                {"name": "HYPE", "szDecimals": 2, "weiDecimals": 8, "index": 150, "tokenId": "0x0d01dc56dcaaca66ad901c959b4011ec", "isCanonical": false}
+            ]
+        }))
+        .unwrap();
+        let dex = PerpDexMeta {
+            dex: "xyz".to_string(),
+            dex_index: 1,
+            meta: serde_json::from_value(serde_json::json!({
+                "universe": [{"name": "xyz:XYZ100", "szDecimals": 3, "maxLeverage": 20}]
+            }))
+            .unwrap(),
+        };
+
+        let assets = Assets::new(&meta, &spot_meta, &[dex], &NetworkConfig::default());
+        assert_eq!(assets.by_name["ETH"].asset, 1);
+        assert_eq!(assets.by_name["ETH"].sz_decimals, 4);
+        assert_eq!(assets.by_name["xyz:XYZ100"].asset, 110_000);
+        assert_eq!(assets.by_name["@107"].asset, 10_107);
+        assert_eq!(assets.by_name["HYPE/USDC"].sz_decimals, 2);
+        assert!(assets.by_name["HYPE/USDC"].is_spot);
+        assert_eq!(assets.coin_to_asset["PURR/USDC"], 10_000);
+
+        assert!(assets.is_fresh(None));
+        assert!(!assets.is_fresh(Some(Duration::ZERO)));
+        assert!(!Assets::default().is_fresh(None));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{
+    info::poller::Poller, prelude::*, Error, InfoClient, PerpMarket, PerpMarketSnapshot,
+    SpotMarket, SpotMarketSnapshot,
+};
+use log::error;
+use rust_decimal::Decimal;
+use std::{collections::HashMap, time::Duration};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+/// Funding is paid hourly
+const FUNDING_PERIODS_PER_YEAR: i64 = 24 * 365;
+
+/// Spot pair and the perp on the same asset, e.g. `"PURR/USDC"` and `"PURR"`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BasisPair {
+    pub spot_pair: String,
+    pub perp: String,
+}
+
+impl BasisPair {
+    pub fn new(spot_pair: &str, perp: &str) -> BasisPair {
+        BasisPair {
+            spot_pair: spot_pair.to_string(),
+            perp: perp.to_string(),
+        }
+    }
+}
+
+/// Basis and carry of holding spot against a short perp
+#[derive(Debug, Clone, PartialEq)]
+pub struct Basis {
+    pub pair: BasisPair,
+    /// Mid price, mark price when a side of the book is empty
+    pub spot_px: Decimal,
+    pub perp_px: Decimal,
+    /// `(perp_px - spot_px) / spot_px`
+    pub basis: Decimal,
+    /// Current hourly funding rate of the perp, received by the short leg when positive
+    pub funding_rate: Decimal,
+    pub annualized_funding: Decimal,
+    /// Annual cost of funding the spot leg, as given to [`Basis::new`]
+    pub borrow_rate: Decimal,
+    /// `annualized_funding - borrow_rate`
+    pub annualized_carry: Decimal,
+}
+
+impl Basis {
+    pub fn new(spot: &SpotMarket, perp: &PerpMarket, borrow_rate: Decimal) -> Result<Basis> {
+        let spot_px = spot.mid_px.unwrap_or(spot.mark_px);
+        let perp_px = perp.mid_px.unwrap_or(perp.mark_px);
+        if spot_px.is_zero() {
+            return Err(Error::GenericParse(format!(
+                "spot pair {} has no price",
+                spot.name
+            )));
+        }
+        let annualized_funding = perp.funding * Decimal::from(FUNDING_PERIODS_PER_YEAR);
+        Ok(Basis {
+            pair: BasisPair::new(&spot.name, &perp.coin),
+            spot_px,
+            perp_px,
+            basis: (perp_px - spot_px) / spot_px,
+            funding_rate: perp.funding,
+            annualized_funding,
+            borrow_rate,
+            annualized_carry: annualized_funding - borrow_rate,
+        })
+    }
+
+    pub(crate) fn from_snapshots(
+        pair: &BasisPair,
+        spot_markets: &SpotMarketSnapshot,
+        perp_markets: &PerpMarketSnapshot,
+        borrow_rate: Decimal,
+    ) -> Result<Basis> {
+        let spot = spot_markets
+            .get(&pair.spot_pair)
+            .ok_or(Error::AssetNotFound)?;
+        let perp = perp_markets.get(&pair.perp).ok_or(Error::AssetNotFound)?;
+        Basis::new(spot, perp, borrow_rate)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasisEvent {
+    pub basis: Basis,
+    /// Whether the absolute annualized carry is at or above the monitor's threshold
+    pub above_threshold: bool,
+}
+
+/// Polls spot and perp markets, emitting a [`BasisEvent`] whenever the absolute annualized
+/// carry of a pair crosses `threshold`
+#[derive(Debug, Clone)]
+pub struct BasisMonitor {
+    pub pairs: Vec<BasisPair>,
+    /// Annual borrow cost of the spot leg, e.g. 0.05
+    pub borrow_rate: Decimal,
+    /// Annualized carry, e.g. 0.2 for 20%
+    pub threshold: Decimal,
+    pub interval: Duration,
+}
+
+impl BasisMonitor {
+    pub fn new(
+        pairs: Vec<BasisPair>,
+        borrow_rate: Decimal,
+        threshold: Decimal,
+        interval: Duration,
+    ) -> BasisMonitor {
+        BasisMonitor {
+            pairs,
+            borrow_rate,
+            threshold,
+            interval,
+        }
+    }
+
+    /// Events for the pairs whose side of the threshold differs from `above`, which is updated
+    fn changes(
+        &self,
+        spot_markets: &SpotMarketSnapshot,
+        perp_markets: &PerpMarketSnapshot,
+        above: &mut HashMap<BasisPair, bool>,
+    ) -> Vec<BasisEvent> {
+        let mut events = Vec::new();
+        for pair in &self.pairs {
+            let basis =
+                match Basis::from_snapshots(pair, spot_markets, perp_markets, self.borrow_rate) {
+                    Ok(basis) => basis,
+                    Err(err) => {
+                        error!("Could not compute basis of {pair:?}: {err}");
+                        continue;
+                    }
+                };
+            let above_threshold = basis.annualized_carry.abs() >= self.threshold;
+            if above.insert(pair.clone(), above_threshold).unwrap_or(false) != above_threshold {
+                events.push(BasisEvent {
+                    basis,
+                    above_threshold,
+                });
+            }
+        }
+        events
+    }
+
+    /// Starts polling in the background and returns the event stream.
+    /// Pairs already above the threshold are reported on the first poll.
+    /// The task stops once the receiver is dropped.
+    pub fn start(self, info_client: &InfoClient) -> Result<UnboundedReceiver<BasisEvent>> {
+        let poller = Poller::new("BasisMonitor", self.interval)?;
+        let info_client = info_client.detached();
+        let (sender, receiver) = unbounded_channel();
+        poller.spawn(
+            (self, info_client, sender, HashMap::new()),
+            |(monitor, info_client, sender, mut above)| async move {
+                let polled = tokio::try_join!(
+                    info_client.spot_market_snapshot(),
+                    info_client.perp_market_snapshot()
+                );
+                match polled {
+                    Ok((spot_markets, perp_markets)) => {
+                        for event in monitor.changes(&spot_markets, &perp_markets, &mut above) {
+                            if sender.send(event).is_err() {
+                                return None;
+                            }
+                        }
+                    }
+                    Err(err) => error!("Error polling markets for basis: {err}"),
+                }
+                (!sender.is_closed()).then_some((monitor, info_client, sender, above))
+            },
+        );
+        Ok(receiver)
+    }
+}
+
+impl InfoClient {
+    /// Current basis of `pair`, with `borrow_rate` the annual cost of the spot leg
+    pub async fn basis(&self, pair: &BasisPair, borrow_rate: Decimal) -> Result<Basis> {
+        let (spot_markets, perp_markets) =
+            tokio::try_join!(self.spot_market_snapshot(), self.perp_market_snapshot())?;
+        Basis::from_snapshots(pair, &spot_markets, &perp_markets, borrow_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::info::{
+        perp_markets::split_meta_and_asset_contexts,
+        spot_markets::split_spot_meta_and_asset_contexts,
+    };
+
+    fn markets(funding: &str) -> (SpotMarketSnapshot, PerpMarketSnapshot) {
+        let (spot_meta, spot_contexts) =
+            split_spot_meta_and_asset_contexts(serde_json::from_value(serde_json::json!([
+                {
+                    "universe": [{"tokens": [1, 0], "name": "PURR/USDC", "index": 0, "isCanonical": true}],
+                    "tokens": [
+                        {"name": "USDC", "szDecimals": 8, "weiDecimals": 8, "index": 0, "tokenId": "0x6d1e7cde53ba9467b783cb7c530ce054", "isCanonical": true},
+                        {"name": "PURR", "szDecimals": 0, "weiDecimals": 5, "index": 1, "tokenId": "0xc1fb593aeffbeb02f85e0308e9956a90", "isCanonical": true}
+                    ]
+                },
+                [{"dayNtlVlm": "0", "markPx": "0.2", "midPx": "0.2", "prevDayPx": "0.2", "circulatingSupply": "1", "coin": "PURR/USDC"}]
+            ])).unwrap())
+            .unwrap();
+        let (meta, perp_contexts) = split_meta_and_asset_contexts(
+            serde_json::from_value(serde_json::json!([
+                {"universe": [{"name": "PURR", "szDecimals": 0, "maxLeverage": 3}]},
+                [{"dayNtlVlm": "0", "funding": funding, "impactPxs": null, "markPx": "0.201",
+                  "midPx": "0.202", "openInterest": "0", "oraclePx": "0.2", "premium": null,
+                  "prevDayPx": "0.2"}]
+            ]))
+            .unwrap(),
+        )
+        .unwrap();
+        (
+            SpotMarketSnapshot::new(&spot_meta, &spot_contexts).unwrap(),
+            PerpMarketSnapshot::new(&meta, &perp_contexts).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_basis_and_threshold_events() {
+        let pair = BasisPair::new("PURR/USDC", "PURR");
+        let (spot_markets, perp_markets) = markets("0.0000125");
+        let borrow_rate = Decimal::new(5, 2);
+        let basis =
+            Basis::from_snapshots(&pair, &spot_markets, &perp_markets, borrow_rate).unwrap();
+        assert_eq!(basis.basis, Decimal::new(1, 2));
+        assert_eq!(basis.annualized_funding, Decimal::new(1095, 4));
+        assert_eq!(basis.annualized_carry, Decimal::new(595, 4));
+
+        let monitor = BasisMonitor::new(
+            vec![pair.clone(), BasisPair::new("HFUN/USDC", "HFUN")],
+            borrow_rate,
+            Decimal::new(5, 2),
+            Duration::from_secs(1),
+        );
+        let mut above = HashMap::new();
+        let events = monitor.changes(&spot_markets, &perp_markets, &mut above);
+        assert_eq!(events.len(), 1);
+        assert!(events[0].above_threshold);
+        assert!(monitor
+            .changes(&spot_markets, &perp_markets, &mut above)
+            .is_empty());
+
+        let (spot_markets, perp_markets) = markets("0.00001");
+        let events = monitor.changes(&spot_markets, &perp_markets, &mut above);
+        assert_eq!(events.len(), 1);
+        assert!(!events[0].above_threshold);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{prelude::*, CandlesSnapshotResponse, Error};
+
+/// Length in milliseconds of a candle interval such as `"15m"`. Only intervals up to `"1d"`
+/// are accepted, as longer ones don't start on multiples of their length since the epoch.
+pub fn candle_interval_ms(interval: &str) -> Result<u64> {
+    let minutes = match interval {
+        "1m" => 1,
+        "3m" => 3,
+        "5m" => 5,
+        "15m" => 15,
+        "30m" => 30,
+        "1h" => 60,
+        "2h" => 2 * 60,
+        "4h" => 4 * 60,
+        "8h" => 8 * 60,
+        "12h" => 12 * 60,
+        "1d" => 24 * 60,
+        _ => {
+            return Err(Error::GenericParse(format!(
+                "unsupported candle interval {interval}"
+            )))
+        }
+    };
+    Ok(minutes * 60_000)
+}
+
+/// How [`align_candles`] treats missing intervals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GapFill {
+    /// Leave gaps in the series
+    Leave,
+    /// Insert candles with every price at the previous close and no volume or trades
+    Flat,
+}
+
+/// Run of consecutive missing intervals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CandleGap {
+    /// Open time of the first missing candle
+    pub time_open: u64,
+    pub missing: u64,
+}
+
+fn align(time: u64, interval_ms: u64) -> u64 {
+    time - time % interval_ms
+}
+
+/// Gaps between the candles, which may be unaligned and unsorted
+pub fn candle_gaps(candles: &[CandlesSnapshotResponse], interval: &str) -> Result<Vec<CandleGap>> {
+    let interval_ms = candle_interval_ms(interval)?;
+    let mut times: Vec<u64> = candles
+        .iter()
+        .map(|candle| align(candle.time_open, interval_ms))
+        .collect();
+    times.sort_unstable();
+    times.dedup();
+    Ok(times
+        .windows(2)
+        .filter(|pair| pair[1] - pair[0] > interval_ms)
+        .map(|pair| CandleGap {
+            time_open: pair[0] + interval_ms,
+            missing: (pair[1] - pair[0]) / interval_ms - 1,
+        })
+        .collect())
+}
+
+/// Sorts `candles`, aligns their open and close times to `interval` boundaries and, with
+/// [`GapFill::Flat`], fills gaps between them. Of candles sharing an interval, the last is kept.
+pub fn align_candles(
+    candles: Vec<CandlesSnapshotResponse>,
+    interval: &str,
+    fill: GapFill,
+) -> Result<Vec<CandlesSnapshotResponse>> {
+    let interval_ms = candle_interval_ms(interval)?;
+    let mut candles: Vec<_> = candles
+        .into_iter()
+        .map(|mut candle| {
+            candle.time_open = align(candle.time_open, interval_ms);
+            candle.time_close = candle.time_open + interval_ms - 1;
+            candle
+        })
+        .collect();
+    // stable, so the last of duplicates stays last
+    candles.sort_by_key(|candle| candle.time_open);
+
+    let mut aligned: Vec<CandlesSnapshotResponse> = Vec::with_capacity(candles.len());
+    for candle in candles {
+        if let Some(previous) = aligned.last_mut() {
+            if previous.time_open == candle.time_open {
+                *previous = candle;
+                continue;
+            }
+            if fill == GapFill::Flat {
+                let close = previous.close.clone();
+                let mut time_open = previous.time_open + interval_ms;
+                while time_open < candle.time_open {
+                    aligned.push(CandlesSnapshotResponse {
+                        time_open,
+                        time_close: time_open + interval_ms - 1,
+                        coin: candle.coin.clone(),
+                        candle_interval: interval.to_string(),
+                        open: close.clone(),
+                        close: close.clone(),
+                        high: close.clone(),
+                        low: close.clone(),
+                        vlm: "0".to_string(),
+                        num_trades: 0,
+                    });
+                    time_open += interval_ms;
+                }
+            }
+        }
+        aligned.push(candle);
+    }
+    Ok(aligned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(time_open: u64, close: &str) -> CandlesSnapshotResponse {
+        CandlesSnapshotResponse {
+            time_open,
+            time_close: time_open + 59_999,
+            coin: "ETH".to_string(),
+            candle_interval: "1m".to_string(),
+            open: close.to_string(),
+            close: close.to_string(),
+            high: close.to_string(),
+            low: close.to_string(),
+            vlm: "1.0".to_string(),
+            num_trades: 1,
+        }
+    }
+
+    #[test]
+    fn test_align_and_fill_candles() {
+        let candles = vec![
+            candle(240_000, "4"),
+            candle(0, "1"),
+            candle(60_005, "2"),
+            candle(60_000, "2.5"),
+        ];
+        assert_eq!(
+            candle_gaps(&candles, "1m").unwrap(),
+            vec![CandleGap {
+                time_open: 120_000,
+                missing: 2
+            }]
+        );
+        assert!(candle_gaps(&candles, "1M").is_err());
+
+        let aligned = align_candles(candles, "1m", GapFill::Flat).unwrap();
+        let times: Vec<_> = aligned.iter().map(|candle| candle.time_open).collect();
+        assert_eq!(times, vec![0, 60_000, 120_000, 180_000, 240_000]);
+        assert_eq!(aligned[1].close, "2.5");
+        assert_eq!(aligned[1].time_close, 119_999);
+        assert_eq!(aligned[2].open, "2.5");
+        assert_eq!(aligned[3].num_trades, 0);
+        assert!(candle_gaps(&aligned, "1m").unwrap().is_empty());
+
+        let aligned = align_candles(
+            vec![candle(0, "1"), candle(180_000, "2")],
+            "1m",
+            GapFill::Leave,
+        )
+        .unwrap();
+        assert_eq!(aligned.len(), 2);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::Address;
+use crate::{
+    helpers::parse_decimal,
+    info::spot_markets::split_spot_meta_and_asset_contexts,
+    meta::{SpotAssetContext, SpotMeta},
+    prelude::*,
+    InfoClient, UserTokenBalance,
+};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Minimum value of a spot order in USDC
+pub const MIN_SPOT_ORDER_NOTIONAL: Decimal = Decimal::TEN;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DustReason {
+    /// Worth less than the minimum order value at the current mid
+    BelowMinNotional,
+    /// Smaller than one size step of the token, so any order rounds to zero
+    BelowLotSize,
+    /// No USDC-quoted market with a price to sell into
+    NoMarket,
+}
+
+/// A spot balance that can't be sold on its own.
+/// The exchange has no dust conversion action, so these can only be reported or topped up and sold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DustBalance {
+    pub token: String,
+    /// Amount not on hold
+    pub free: Decimal,
+    /// USDC-quoted pair the token trades on, e.g. `"PURR/USDC"` or `"@107"`
+    pub pair: Option<String>,
+    pub mid_px: Option<Decimal>,
+    /// Value of the free amount in USDC, when a price is known
+    pub notional: Option<Decimal>,
+    pub reason: DustReason,
+}
+
+pub(crate) fn find_spot_dust(
+    balances: &[UserTokenBalance],
+    spot_meta: &SpotMeta,
+    contexts: &[SpotAssetContext],
+    min_notional: Decimal,
+) -> Result<Vec<DustBalance>> {
+    let Some(usdc) = spot_meta.tokens.iter().find(|token| token.name == "USDC") else {
+        return Ok(Vec::new());
+    };
+    let contexts: HashMap<&str, &SpotAssetContext> = contexts
+        .iter()
+        .map(|context| (context.coin.as_str(), context))
+        .collect();
+
+    let mut dust = Vec::new();
+    for balance in balances {
+        let Some(token) = spot_meta
+            .tokens
+            .iter()
+            .find(|token| token.name == balance.coin)
+        else {
+            continue;
+        };
+        if token.index == usdc.index {
+            continue;
+        }
+        let free = parse_decimal(&balance.total)? - parse_decimal(&balance.hold)?;
+        if free <= Decimal::ZERO {
+            continue;
+        }
+
+        let pair = spot_meta
+            .universe
+            .iter()
+            .find(|pair| pair.tokens == [token.index, usdc.index])
+            .map(|pair| pair.name.clone());
+        let mid_px = match pair.as_deref().and_then(|pair| contexts.get(pair)) {
+            Some(context) => Some(parse_decimal(
+                context.mid_px.as_deref().unwrap_or(&context.mark_px),
+            )?),
+            None => None,
+        };
+        let notional = mid_px.map(|mid_px| free * mid_px);
+
+        let reason = if free.trunc_with_scale(token.sz_decimals.into()).is_zero() {
+            DustReason::BelowLotSize
+        } else if let Some(notional) = notional {
+            if notional >= min_notional {
+                continue;
+            }
+            DustReason::BelowMinNotional
+        } else {
+            DustReason::NoMarket
+        };
+        dust.push(DustBalance {
+            token: balance.coin.clone(),
+            free,
+            pair,
+            mid_px,
+            notional,
+            reason,
+        });
+    }
+    Ok(dust)
+}
+
+impl InfoClient {
+    /// Spot balances of `user` that are too small to sell, see [`DustBalance`]
+    pub async fn spot_dust(&self, user: Address) -> Result<Vec<DustBalance>> {
+        let balances = self.user_token_balances(user).await?.balances;
+        let (spot_meta, contexts) =
+            split_spot_meta_and_asset_contexts(self.spot_meta_and_asset_contexts().await?)?;
+        find_spot_dust(&balances, &spot_meta, &contexts, MIN_SPOT_ORDER_NOTIONAL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_spot_dust() {
+        let spot_meta: SpotMeta = serde_json::from_value(serde_json::json!({
+            "universe": [
+                {"tokens": [1, 0], "name": "PURR/USDC", "index": 0, "isCanonical": true},
+                {"tokens": [2, 0], "name": "@1", "index": 1, "isCanonical": false}
+            ],
+            "tokens": [
+                {"name": "USDC", "szDecimals": 8, "weiDecimals": 8, "index": 0, "tokenId": "0x6d1e7cde53ba9467b783cb7c530ce054", "isCanonical": true},
+                {"name": "PURR", "szDecimals": 0, "weiDecimals": 5, "index": 1, "tokenId": "0xc1fb593aeffbeb02f85e0308e9956a90", "isCanonical": true},
+                {"name": "HFUN", "szDecimals": 2, "weiDecimals": 8, "index": 2, "tokenId": "0xbaf265ef389da684513d98d68edf4eae", "isCanonical": false},
+                {"name": "LOST", "szDecimals": 2, "weiDecimals": 8, "index": 3, "tokenId": "0x00000000000000000000000000000000", "isCanonical": false}
+            ]
+        }))
+        .unwrap();
+        let context = |coin: &str, mid_px: &str| SpotAssetContext {
+            day_ntl_vlm: "0".to_string(),
+            mark_px: mid_px.to_string(),
+            mid_px: Some(mid_px.to_string()),
+            prev_day_px: mid_px.to_string(),
+            circulating_supply: "0".to_string(),
+            coin: coin.to_string(),
+        };
+        let contexts = vec![context("PURR/USDC", "0.2"), context("@1", "50")];
+        let balance = |coin: &str, total: &str| UserTokenBalance {
+            coin: coin.to_string(),
+            hold: "0".to_string(),
+            total: total.to_string(),
+        };
+        let balances = vec![
+            balance("USDC", "0.5"),
+            balance("PURR", "0.4"),
+            balance("HFUN", "0.1"),
+            balance("LOST", "3"),
+            balance("PURR", "100"),
+        ];
+
+        let dust =
+            find_spot_dust(&balances, &spot_meta, &contexts, MIN_SPOT_ORDER_NOTIONAL).unwrap();
+        let reasons: Vec<(&str, DustReason)> = dust
+            .iter()
+            .map(|dust| (dust.token.as_str(), dust.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                ("PURR", DustReason::BelowLotSize),
+                ("HFUN", DustReason::BelowMinNotional),
+                ("LOST", DustReason::NoMarket),
+            ]
+        );
+        assert_eq!(dust[1].notional, Some(Decimal::from(5)));
+        assert_eq!(dust[1].pair.as_deref(), Some("@1"));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::Address;
+use crate::{
+    helpers::parse_decimal,
+    info::spot_markets::split_spot_meta_and_asset_contexts,
+    meta::{SpotAssetContext, SpotMeta},
+    prelude::*,
+    InfoClient, UserStateResponse, UserTokenBalance, UserVaultEquity,
+};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// A spot token balance valued in USDC
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpotEquity {
+    pub token: String,
+    /// Amount including what is on hold for open orders
+    pub total: Decimal,
+    /// Mid of the token's USDC-quoted pair, one for USDC itself
+    pub mid_px: Option<Decimal>,
+    /// `None` if the token has no USDC-quoted market with a price
+    pub value: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultEquity {
+    pub vault_address: Address,
+    pub equity: Decimal,
+}
+
+/// Equity of a user across the perp clearinghouse, spot balances and vault deposits, in USDC
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TotalEquity {
+    /// Perp account value, including isolated margin and unrealized pnl
+    pub perp: Decimal,
+    pub spot: Vec<SpotEquity>,
+    pub vaults: Vec<VaultEquity>,
+}
+
+impl TotalEquity {
+    /// Value of the spot balances that have a price
+    pub fn spot_value(&self) -> Decimal {
+        self.spot.iter().filter_map(|spot| spot.value).sum()
+    }
+
+    pub fn vault_value(&self) -> Decimal {
+        self.vaults.iter().map(|vault| vault.equity).sum()
+    }
+
+    /// Perp, priced spot and vault equity together
+    pub fn total(&self) -> Decimal {
+        self.perp + self.spot_value() + self.vault_value()
+    }
+
+    /// Spot balances left out of [`TotalEquity::spot_value`] for lack of a price
+    pub fn unpriced_spot(&self) -> impl Iterator<Item = &SpotEquity> {
+        self.spot.iter().filter(|spot| spot.value.is_none())
+    }
+}
+
+pub(crate) fn total_equity(
+    user_state: &UserStateResponse,
+    balances: &[UserTokenBalance],
+    spot_meta: &SpotMeta,
+    contexts: &[SpotAssetContext],
+    vault_equities: &[UserVaultEquity],
+) -> Result<TotalEquity> {
+    let usdc = spot_meta.tokens.iter().find(|token| token.name == "USDC");
+    let contexts: HashMap<&str, &SpotAssetContext> = contexts
+        .iter()
+        .map(|context| (context.coin.as_str(), context))
+        .collect();
+
+    let mut spot = Vec::with_capacity(balances.len());
+    for balance in balances {
+        let total = parse_decimal(&balance.total)?;
+        if total.is_zero() {
+            continue;
+        }
+        let token = spot_meta
+            .tokens
+            .iter()
+            .find(|token| token.name == balance.coin);
+        let mid_px = match (token, usdc) {
+            (Some(token), Some(usdc)) if token.index == usdc.index => Some(Decimal::ONE),
+            (Some(token), Some(usdc)) => {
+                let context = spot_meta
+                    .universe
+                    .iter()
+                    .find(|pair| pair.tokens == [token.index, usdc.index])
+                    .and_then(|pair| contexts.get(pair.name.as_str()));
+                match context {
+                    Some(context) => Some(parse_decimal(
+                        context.mid_px.as_deref().unwrap_or(&context.mark_px),
+                    )?),
+                    None => None,
+                }
+            }
+            _ => None,
+        };
+        spot.push(SpotEquity {
+            token: balance.coin.clone(),
+            total,
+            mid_px,
+            value: mid_px.map(|mid_px| total * mid_px),
+        });
+    }
+
+    let vaults = vault_equities
+        .iter()
+        .map(|vault| {
+            Ok(VaultEquity {
+                vault_address: vault.vault_address,
+                equity: parse_decimal(&vault.equity)?,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(TotalEquity {
+        perp: parse_decimal(&user_state.margin_summary.account_value)?,
+        spot,
+        vaults,
+    })
+}
+
+impl InfoClient {
+    /// Perp, spot and vault equity of `user`, spot balances valued at the mids of their
+    /// USDC-quoted pairs
+    pub async fn total_equity(&self, user: Address) -> Result<TotalEquity> {
+        let (user_state, balances, spot_meta_and_contexts, vault_equities) = tokio::try_join!(
+            self.user_state(user),
+            self.user_token_balances(user),
+            self.spot_meta_and_asset_contexts(),
+            self.user_vault_equities(user)
+        )?;
+        let (spot_meta, contexts) = split_spot_meta_and_asset_contexts(spot_meta_and_contexts)?;
+        total_equity(
+            &user_state,
+            &balances.balances,
+            &spot_meta,
+            &contexts,
+            &vault_equities,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total_equity() -> Result<()> {
+        let user_state: UserStateResponse = serde_json::from_value(serde_json::json!({
+            "assetPositions": [],
+            "crossMarginSummary": {"accountValue": "900.0", "totalMarginUsed": "0.0", "totalNtlPos": "0.0", "totalRawUsd": "900.0"},
+            "marginSummary": {"accountValue": "1000.0", "totalMarginUsed": "0.0", "totalNtlPos": "0.0", "totalRawUsd": "1000.0"},
+            "crossMaintenanceMarginUsed": "0.0",
+            "withdrawable": "900.0"
+        }))
+        .unwrap();
+        let spot_meta: SpotMeta = serde_json::from_value(serde_json::json!({
+            "universe": [
+                {"tokens": [1, 0], "name": "PURR/USDC", "index": 0, "isCanonical": true}
+            ],
+            "tokens": [
+                {"name": "USDC", "szDecimals": 8, "weiDecimals": 8, "index": 0, "tokenId": "0x6d1e7cde53ba9467b783cb7c530ce054", "isCanonical": true},
+                {"name": "PURR", "szDecimals": 0, "weiDecimals": 5, "index": 1, "tokenId": "0xc1fb593aeffbeb02f85e0308e9956a90", "isCanonical": true},
+                {"name": "LOST", "szDecimals": 2, "weiDecimals": 8, "index": 2, "tokenId": "0x00000000000000000000000000000000", "isCanonical": false}
+            ]
+        }))
+        .unwrap();
+        let contexts = vec![SpotAssetContext {
+            day_ntl_vlm: "0".to_string(),
+            mark_px: "0.21".to_string(),
+            mid_px: Some("0.2".to_string()),
+            prev_day_px: "0.2".to_string(),
+            circulating_supply: "0".to_string(),
+            coin: "PURR/USDC".to_string(),
+        }];
+        let balance = |coin: &str, hold: &str, total: &str| UserTokenBalance {
+            coin: coin.to_string(),
+            hold: hold.to_string(),
+            total: total.to_string(),
+        };
+        let balances = vec![
+            balance("USDC", "20", "50"),
+            balance("PURR", "0", "100"),
+            balance("LOST", "0", "3"),
+            balance("HFUN", "0", "0"),
+        ];
+        let vault_equities = vec![UserVaultEquity {
+            vault_address: Address::with_last_byte(1),
+            equity: "250.5".to_string(),
+            locked_until_timestamp: None,
+        }];
+
+        let equity = total_equity(
+            &user_state,
+            &balances,
+            &spot_meta,
+            &contexts,
+            &vault_equities,
+        )?;
+        assert_eq!(equity.perp, Decimal::from(1000));
+        assert_eq!(equity.spot.len(), 3);
+        assert_eq!(equity.spot_value(), Decimal::from(70));
+        assert_eq!(equity.vault_value(), Decimal::new(2505, 1));
+        assert_eq!(equity.total(), Decimal::new(13205, 1));
+        let unpriced: Vec<&str> = equity
+            .unpriced_spot()
+            .map(|spot| spot.token.as_str())
+            .collect();
+        assert_eq!(unpriced, vec!["LOST"]);
+        Ok(())
+    }
+}
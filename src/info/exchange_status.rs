@@ -0,0 +1,152 @@
+use crate::{info::poller::Poller, prelude::*, Error, ExchangeStatusResponse, InfoClient};
+use log::{error, info, warn};
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Whether the exchange takes requests, as last seen by an [`ExchangeStatusMonitor`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExchangeAvailability {
+    Available,
+    /// Down for maintenance, with the server's hint of when to retry if it gave one
+    Unavailable {
+        retry_after: Option<Duration>,
+    },
+}
+
+/// Availability after a poll, `None` if the poll failed for a reason that says nothing about it
+fn polled_availability(polled: &Result<ExchangeStatusResponse>) -> Option<ExchangeAvailability> {
+    match polled {
+        Ok(status) if status.has_special_statuses() => {
+            Some(ExchangeAvailability::Unavailable { retry_after: None })
+        }
+        Ok(_) => Some(ExchangeAvailability::Available),
+        Err(Error::ExchangeUnavailable { retry_after }) => {
+            Some(ExchangeAvailability::Unavailable {
+                retry_after: *retry_after,
+            })
+        }
+        Err(_) => None,
+    }
+}
+
+/// Polls `exchangeStatus` to tell when the exchange is down for maintenance, either announced
+/// in its special statuses or from the request failing with [`Error::ExchangeUnavailable`].
+/// Set on an [`crate::ExchangeClient`] with `set_status_monitor`, orders and modifies fail with
+/// [`Error::ExchangeUnavailable`] while it is down instead of being sent.
+/// Clones share the state, polling stops once every clone is dropped.
+#[derive(Debug, Clone)]
+pub struct ExchangeStatusMonitor {
+    availability: watch::Receiver<ExchangeAvailability>,
+}
+
+impl ExchangeStatusMonitor {
+    /// Starts polling every `interval`, assuming the exchange is available until a poll says
+    /// otherwise
+    pub fn start(info_client: &InfoClient, interval: Duration) -> Result<ExchangeStatusMonitor> {
+        let poller = Poller::new("ExchangeStatusMonitor", interval)?;
+        let info_client = info_client.detached();
+        let (sender, availability) = watch::channel(ExchangeAvailability::Available);
+        poller.spawn((info_client, sender), |(info_client, sender)| async move {
+            let polled = info_client.exchange_status().await;
+            match polled_availability(&polled) {
+                Some(availability) => {
+                    sender.send_if_modified(|current| {
+                        if *current == availability {
+                            return false;
+                        }
+                        match availability {
+                            ExchangeAvailability::Available => info!("Exchange is available"),
+                            ExchangeAvailability::Unavailable { retry_after } => {
+                                warn!("Exchange is unavailable, retry after {retry_after:?}")
+                            }
+                        }
+                        *current = availability;
+                        true
+                    });
+                }
+                None => {
+                    if let Err(err) = polled {
+                        error!("Error polling exchange status: {err}");
+                    }
+                }
+            }
+            (!sender.is_closed()).then_some((info_client, sender))
+        });
+        Ok(ExchangeStatusMonitor { availability })
+    }
+
+    pub fn availability(&self) -> ExchangeAvailability {
+        *self.availability.borrow()
+    }
+
+    /// Fails with [`Error::ExchangeUnavailable`] while the exchange is down
+    pub fn check(&self) -> Result<()> {
+        match self.availability() {
+            ExchangeAvailability::Available => Ok(()),
+            ExchangeAvailability::Unavailable { retry_after } => {
+                Err(Error::ExchangeUnavailable { retry_after })
+            }
+        }
+    }
+
+    /// Monitor stuck at `availability`, without polling
+    #[cfg(all(test, feature = "exchange"))]
+    pub(crate) fn fixed(availability: ExchangeAvailability) -> ExchangeStatusMonitor {
+        ExchangeStatusMonitor {
+            availability: watch::channel(availability).1,
+        }
+    }
+
+    /// Waits until the exchange is available
+    pub async fn wait_available(&self) -> Result<()> {
+        let mut availability = self.availability.clone();
+        availability
+            .wait_for(|availability| *availability == ExchangeAvailability::Available)
+            .await
+            .map(|_| ())
+            .map_err(|_| Error::GenericRequest("ExchangeStatusMonitor stopped".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::spawn;
+
+    #[tokio::test]
+    async fn test_exchange_status_monitor() {
+        let retry_after = Some(Duration::from_secs(60));
+        let unavailable = polled_availability(&Err(Error::ExchangeUnavailable { retry_after }));
+        assert_eq!(
+            unavailable,
+            Some(ExchangeAvailability::Unavailable { retry_after })
+        );
+        assert_eq!(polled_availability(&Err(Error::FloatStringParse)), None);
+        let status: ExchangeStatusResponse =
+            serde_json::from_str(r#"{"time": 1700000000000, "specialStatuses": null}"#).unwrap();
+        assert_eq!(
+            polled_availability(&Ok(status)),
+            Some(ExchangeAvailability::Available)
+        );
+        let status: ExchangeStatusResponse = serde_json::from_str(
+            r#"{"time": 1700000000000, "specialStatuses": [{"type": "maintenance"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            polled_availability(&Ok(status)),
+            Some(ExchangeAvailability::Unavailable { retry_after: None })
+        );
+
+        let (sender, availability) = watch::channel(unavailable.unwrap());
+        let monitor = ExchangeStatusMonitor { availability };
+        assert!(matches!(
+            monitor.check(),
+            Err(Error::ExchangeUnavailable { .. })
+        ));
+        let waiting = monitor.clone();
+        let waiting = spawn(async move { waiting.wait_available().await });
+        sender.send_replace(ExchangeAvailability::Available);
+        assert!(waiting.await.unwrap().is_ok());
+        assert!(monitor.check().is_ok());
+    }
+}
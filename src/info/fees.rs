@@ -0,0 +1,194 @@
+#[cfg(feature = "exchange")]
+use crate::BuilderInfo;
+use crate::{prelude::*, Error, UserFeesResponse};
+
+fn parse_f64(value: &str) -> Result<f64> {
+    value.parse::<f64>().map_err(|_| Error::FloatStringParse)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeTierProjection {
+    /// Rolling volume (cross + add) summed over `daily_user_vlm`
+    pub rolling_volume: f64,
+    /// Index into `fee_schedule.tiers.vip` of the current tier, `None` if below the first tier
+    pub current_tier: Option<usize>,
+    /// Index into `fee_schedule.tiers.vip` of the next tier, `None` if already at the top tier
+    pub next_tier: Option<usize>,
+    /// Additional rolling volume needed to reach the next tier
+    pub volume_to_next_tier: Option<f64>,
+    pub current_rates: FeeRates,
+    pub next_tier_rates: Option<FeeRates>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeRates {
+    pub maker: f64,
+    pub taker: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlannedOrderFees {
+    /// Exchange fee rate after the referral discount
+    pub exchange_rate: f64,
+    /// Builder fee rate, zero when no builder is attached
+    pub builder_rate: f64,
+    pub total_rate: f64,
+    /// Total fee in quote currency for the planned notional
+    pub fee: f64,
+}
+
+impl UserFeesResponse {
+    pub fn rolling_volume(&self) -> Result<f64> {
+        self.daily_user_vlm.iter().try_fold(0.0, |acc, vlm| {
+            Ok(acc + parse_f64(&vlm.user_cross)? + parse_f64(&vlm.user_add)?)
+        })
+    }
+
+    pub fn project_fee_tier(&self) -> Result<FeeTierProjection> {
+        let rolling_volume = self.rolling_volume()?;
+
+        let mut cutoffs = Vec::with_capacity(self.fee_schedule.tiers.vip.len());
+        for vip in &self.fee_schedule.tiers.vip {
+            cutoffs.push(parse_f64(&vip.ntl_cutoff)?);
+        }
+
+        let current_tier = cutoffs.iter().rposition(|&cutoff| rolling_volume >= cutoff);
+        let next_tier = match current_tier {
+            Some(tier) if tier + 1 < cutoffs.len() => Some(tier + 1),
+            Some(_) => None,
+            None if !cutoffs.is_empty() => Some(0),
+            None => None,
+        };
+
+        let next_tier_rates = match next_tier {
+            Some(tier) => {
+                let vip = &self.fee_schedule.tiers.vip[tier];
+                Some(FeeRates {
+                    maker: parse_f64(&vip.add)?,
+                    taker: parse_f64(&vip.cross)?,
+                })
+            }
+            None => None,
+        };
+
+        Ok(FeeTierProjection {
+            rolling_volume,
+            current_tier,
+            next_tier,
+            volume_to_next_tier: next_tier.map(|tier| (cutoffs[tier] - rolling_volume).max(0.0)),
+            current_rates: self.user_rates()?,
+            next_tier_rates,
+        })
+    }
+
+    /// The user's current maker/taker rates before the referral discount
+    pub fn user_rates(&self) -> Result<FeeRates> {
+        Ok(FeeRates {
+            maker: parse_f64(&self.user_add_rate)?,
+            taker: parse_f64(&self.user_cross_rate)?,
+        })
+    }
+
+    /// The user's current maker/taker rates with the active referral discount applied.
+    /// Maker rebates (negative rates) are not discounted.
+    pub fn effective_rates(&self) -> Result<FeeRates> {
+        let rates = self.user_rates()?;
+        let discount = parse_f64(&self.active_referral_discount)?;
+        let apply_discount = |rate: f64| {
+            if rate > 0.0 {
+                rate * (1.0 - discount)
+            } else {
+                rate
+            }
+        };
+        Ok(FeeRates {
+            maker: apply_discount(rates.maker),
+            taker: apply_discount(rates.taker),
+        })
+    }
+
+    #[cfg(feature = "exchange")]
+    pub fn planned_order_fees(
+        &self,
+        notional: f64,
+        is_maker: bool,
+        builder: Option<&BuilderInfo>,
+    ) -> Result<PlannedOrderFees> {
+        let rates = self.effective_rates()?;
+        let exchange_rate = if is_maker { rates.maker } else { rates.taker };
+        // builder fee is denominated in tenths of a basis point
+        let builder_rate = builder.map_or(0.0, |builder| builder.fee as f64 / 100_000.0);
+        let total_rate = exchange_rate + builder_rate;
+
+        Ok(PlannedOrderFees {
+            exchange_rate,
+            builder_rate,
+            total_rate,
+            fee: notional.abs() * total_rate,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EPSILON;
+
+    fn user_fees() -> UserFeesResponse {
+        serde_json::from_str(
+            r#"{
+                "activeReferralDiscount": "0.04",
+                "dailyUserVlm": [
+                    {"date": "2024-01-01", "exchange": "1000000000.0", "userAdd": "2000000.0", "userCross": "1000000.0"},
+                    {"date": "2024-01-02", "exchange": "1000000000.0", "userAdd": "500000.0", "userCross": "1500000.0"}
+                ],
+                "feeSchedule": {
+                    "add": "0.0001",
+                    "cross": "0.00035",
+                    "referralDiscount": "0.04",
+                    "tiers": {
+                        "mm": [{"add": "-0.00001", "makerFractionCutoff": "0.005"}],
+                        "vip": [
+                            {"add": "0.00008", "cross": "0.0003", "ntlCutoff": "5000000.0"},
+                            {"add": "0.00004", "cross": "0.00025", "ntlCutoff": "25000000.0"}
+                        ]
+                    }
+                },
+                "userAddRate": "0.00008",
+                "userCrossRate": "0.0003"
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_project_fee_tier() -> Result<()> {
+        let projection = user_fees().project_fee_tier()?;
+        assert!((projection.rolling_volume - 5_000_000.0).abs() < EPSILON);
+        assert_eq!(projection.current_tier, Some(0));
+        assert_eq!(projection.next_tier, Some(1));
+        assert!((projection.volume_to_next_tier.unwrap() - 20_000_000.0).abs() < EPSILON);
+        assert_eq!(
+            projection.next_tier_rates,
+            Some(FeeRates {
+                maker: 0.00004,
+                taker: 0.00025
+            })
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "exchange")]
+    #[test]
+    fn test_planned_order_fees() -> Result<()> {
+        let builder = BuilderInfo {
+            builder: "0x1ab189b7801140900c711e458212f9c76f8dac79".to_string(),
+            fee: 10,
+        };
+        let fees = user_fees().planned_order_fees(10_000.0, false, Some(&builder))?;
+        assert!((fees.exchange_rate - 0.000288).abs() < EPSILON);
+        assert!((fees.builder_rate - 0.0001).abs() < EPSILON);
+        assert!((fees.fee - 3.88).abs() < 1e-6);
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{
+    helpers::now_timestamp_ms, info::PredictedFundingsResponse, prelude::*, Error, InfoClient,
+};
+use log::error;
+use std::{collections::HashMap, time::Duration};
+use tokio::{
+    spawn,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver},
+    time::sleep,
+};
+
+const HYPERLIQUID_VENUE: &str = "HlPerp";
+const HOUR_MS: u64 = 60 * 60 * 1000;
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NextFunding {
+    /// Funding time in milliseconds
+    pub time: u64,
+    /// Predicted funding rate for the interval
+    pub funding_rate: String,
+}
+
+pub(crate) fn next_fundings(
+    predicted_fundings: Vec<PredictedFundingsResponse>,
+) -> HashMap<String, NextFunding> {
+    predicted_fundings
+        .into_iter()
+        .filter_map(|PredictedFundingsResponse(coin, venues)| {
+            venues
+                .into_iter()
+                .find(|(venue, _)| venue == HYPERLIQUID_VENUE)
+                .and_then(|(_, funding)| funding)
+                .map(|funding| {
+                    (
+                        coin,
+                        NextFunding {
+                            time: funding.next_funding_time,
+                            funding_rate: funding.funding_rate,
+                        },
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Funding is paid on the hour, so the next funding time is the next hour boundary after `time_ms`
+pub(crate) fn next_hour_ms(time_ms: u64) -> u64 {
+    (time_ms / HOUR_MS + 1) * HOUR_MS
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundingEvent {
+    pub coin: String,
+    /// Funding time in milliseconds
+    pub funding_time: u64,
+    /// Predicted funding rate at the time of the event
+    pub funding_rate: String,
+}
+
+/// Emits a [`FundingEvent`] per coin `lead` ahead of each funding time.
+#[derive(Debug, Clone)]
+pub struct FundingClock {
+    /// Coins to watch, every listed perp if empty
+    pub coins: Vec<String>,
+    pub lead: Duration,
+}
+
+impl FundingClock {
+    pub fn new(coins: Vec<String>, lead: Duration) -> FundingClock {
+        FundingClock { coins, lead }
+    }
+
+    /// Returns the coins due at the earliest funding time not yet emitted, along with that time
+    fn next_due(
+        &self,
+        next_fundings: &HashMap<String, NextFunding>,
+        emitted: &HashMap<String, u64>,
+    ) -> Option<(u64, Vec<FundingEvent>)> {
+        let pending: Vec<_> = next_fundings
+            .iter()
+            .filter(|(coin, _)| self.coins.is_empty() || self.coins.contains(coin))
+            .filter(|(coin, funding)| emitted.get(*coin) != Some(&funding.time))
+            .collect();
+        let time = pending.iter().map(|(_, funding)| funding.time).min()?;
+        let events = pending
+            .into_iter()
+            .filter(|(_, funding)| funding.time == time)
+            .map(|(coin, funding)| FundingEvent {
+                coin: coin.clone(),
+                funding_time: time,
+                funding_rate: funding.funding_rate.clone(),
+            })
+            .collect();
+        Some((time, events))
+    }
+
+    /// Starts polling predicted fundings in the background and returns the event stream.
+    /// The task stops once the receiver is dropped.
+    pub fn start(self, info_client: &InfoClient) -> Result<UnboundedReceiver<FundingEvent>> {
+        if self.lead >= Duration::from_millis(HOUR_MS) {
+            return Err(Error::GenericRequest(
+                "FundingClock lead must be shorter than the funding interval".to_string(),
+            ));
+        }
+
+        let info_client = info_client.detached();
+        let (sender, receiver) = unbounded_channel();
+        spawn(async move {
+            let lead_ms = self.lead.as_millis() as u64;
+            let mut emitted = HashMap::new();
+            loop {
+                let next_fundings = match info_client.next_funding_times().await {
+                    Ok(next_fundings) => next_fundings,
+                    Err(err) => {
+                        error!("Error fetching predicted fundings: {err}");
+                        sleep(RETRY_DELAY).await;
+                        continue;
+                    }
+                };
+
+                let now = now_timestamp_ms();
+                let Some((time, events)) = self.next_due(&next_fundings, &emitted) else {
+                    // everything due has been emitted, check again once the funding has been paid
+                    let resume = next_hour_ms(now) + RETRY_DELAY.as_millis() as u64;
+                    sleep(Duration::from_millis(resume - now)).await;
+                    continue;
+                };
+
+                let emit_at = time.saturating_sub(lead_ms);
+                if emit_at > now {
+                    sleep(Duration::from_millis(emit_at - now)).await;
+                    // refresh so the event carries the latest predicted rate
+                    continue;
+                }
+
+                for event in events {
+                    emitted.insert(event.coin.clone(), event.funding_time);
+                    // a stale prediction for a funding that was already paid
+                    if time <= now {
+                        continue;
+                    }
+                    if sender.send(event).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_funding_times() -> Result<()> {
+        let predicted_fundings: Vec<PredictedFundingsResponse> = serde_json::from_str(
+            r#"[
+                ["BTC", [["BinPerp", {"fundingRate": "0.0001", "nextFundingTime": 1733961600000}], ["HlPerp", {"fundingRate": "0.0000125", "nextFundingTime": 1733958000000, "fundingIntervalHours": 1}]]],
+                ["ETH", [["HlPerp", {"fundingRate": "-0.00002", "nextFundingTime": 1733958000000}]]],
+                ["DOGE", [["HlPerp", null]]]
+            ]"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        let next_fundings = next_fundings(predicted_fundings);
+        assert_eq!(next_fundings.len(), 2);
+        assert_eq!(next_fundings["BTC"].time, 1733958000000);
+        assert_eq!(next_fundings["BTC"].funding_rate, "0.0000125");
+
+        let clock = FundingClock::new(vec!["BTC".to_string()], Duration::from_secs(30));
+        let (time, events) = clock.next_due(&next_fundings, &HashMap::new()).unwrap();
+        assert_eq!(time, 1733958000000);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].coin, "BTC");
+
+        let emitted = HashMap::from([("BTC".to_string(), time)]);
+        assert!(clock.next_due(&next_fundings, &emitted).is_none());
+
+        assert_eq!(next_hour_ms(1733958000000), 1733961600000);
+        assert_eq!(next_hour_ms(1733957999999), 1733958000000);
+        Ok(())
+    }
+}
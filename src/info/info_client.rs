@@ -1,20 +1,36 @@
 use crate::{
+    helpers::now_timestamp_ms,
     info::{
-        CandlesSnapshotResponse, FundingHistoryResponse, L2SnapshotResponse, OpenOrdersResponse,
-        OrderInfo, RecentTradesResponse, UserFillsResponse, UserStateResponse,
+        next_fundings, next_hour_ms, AccountMarginSummary, ActiveAssetDataResponse, AssetRegistry,
+        CandlesSnapshotResponse, ExchangeStatusResponse, FundingHistoryResponse,
+        L2SnapshotResponse, NextFunding, OpenOrdersResponse, OrderInfo, PredictedFundingsResponse,
+        RecentTradesResponse, SubAccount, UserFillsPager, UserFillsResponse, UserRole,
+        UserStateResponse,
+    },
+    meta::{
+        Meta, MetaAndAssetCtxs, PerpDexLimitsResponse, PerpDexMeta, PerpDexsResponse, SpotMeta,
+        SpotMetaAndAssetCtxs,
     },
-    meta::{Meta, SpotMeta, SpotMetaAndAssetCtxs},
     prelude::*,
-    req::HttpClient,
-    ws::{Subscription, WsManager},
-    BaseUrl, Error, Message, OrderStatusResponse, ReferralResponse, UserFeesResponse,
-    UserFundingResponse, UserTokenBalanceResponse,
+    req::{HttpClient, RequestOptions},
+    BaseUrl, Coin, Error, MultiSigSigners, OrderStatusResponse, RateLimitConfig, RateLimiter,
+    ReferralResponse, UserFeesResponse, UserFundingResponse, UserTokenBalanceResponse,
+    UserVaultEquity, VaultDetails,
+};
+#[cfg(feature = "ws")]
+use crate::{
+    ws::{Subscription, SubscriptionSender, WsManager},
+    CoinFilter, DeliveryKind, ExportedSubscription, Message, SubscriptionStats, WsConfig,
+    WsLatency, WsPostClient,
 };
 
-use ethers::types::H160;
+use crate::Address;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+#[cfg(feature = "ws")]
+use std::sync::Arc;
+#[cfg(feature = "ws")]
 use tokio::sync::mpsc::UnboundedSender;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -32,45 +48,79 @@ pub struct CandleSnapshotRequest {
 pub enum InfoRequest {
     #[serde(rename = "clearinghouseState")]
     UserState {
-        user: H160,
+        user: Address,
     },
     #[serde(rename = "batchClearinghouseStates")]
     UserStates {
-        users: Vec<H160>,
+        users: Vec<Address>,
     },
     #[serde(rename = "spotClearinghouseState")]
     UserTokenBalances {
-        user: H160,
+        user: Address,
     },
     UserFees {
-        user: H160,
+        user: Address,
+    },
+    SubAccounts {
+        user: Address,
     },
     OpenOrders {
-        user: H160,
+        user: Address,
     },
     OrderStatus {
-        user: H160,
+        user: Address,
         oid: u64,
     },
-    Meta,
+    /// `orderStatus` takes a cloid in place of the oid
+    #[serde(rename = "orderStatus", skip_deserializing)]
+    OrderStatusByCloid {
+        user: Address,
+        oid: String,
+    },
+    Meta {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        dex: Option<String>,
+    },
+    PerpDexs,
+    ExchangeStatus,
+    UserRole {
+        user: Address,
+    },
+    ActiveAssetData {
+        user: Address,
+        coin: String,
+    },
+    PerpDexLimits {
+        dex: String,
+    },
+    MetaAndAssetCtxs,
+    PerpsAtOpenInterestCap,
+    MaxMarketOrderNtls,
     SpotMeta,
     SpotMetaAndAssetCtxs,
     AllMids,
     UserFills {
-        user: H160,
+        user: Address,
+    },
+    #[serde(rename_all = "camelCase")]
+    UserFillsByTime {
+        user: Address,
+        start_time: u64,
+        end_time: Option<u64>,
     },
     #[serde(rename_all = "camelCase")]
     FundingHistory {
-        coin: String,
+        coin: Coin,
         start_time: u64,
         end_time: Option<u64>,
     },
     #[serde(rename_all = "camelCase")]
     UserFunding {
-        user: H160,
+        user: Address,
         start_time: u64,
         end_time: Option<u64>,
     },
+    PredictedFundings,
     L2Book {
         coin: String,
     },
@@ -82,56 +132,169 @@ pub enum InfoRequest {
         req: CandleSnapshotRequest,
     },
     Referral {
-        user: H160,
+        user: Address,
     },
     HistoricalOrders {
-        user: H160,
+        user: Address,
+    },
+    UserToMultiSigSigners {
+        user: Address,
+    },
+    #[serde(rename_all = "camelCase")]
+    VaultDetails {
+        vault_address: Address,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        user: Option<Address>,
+    },
+    UserVaultEquities {
+        user: Address,
     },
 }
 
 #[derive(Debug)]
 pub struct InfoClient {
     pub http_client: HttpClient,
+    request_options: RequestOptions,
+    asset_registry: Option<AssetRegistry>,
+    #[cfg(feature = "ws")]
     pub(crate) ws_manager: Option<WsManager>,
+    #[cfg(feature = "ws")]
     reconnect: bool,
+    #[cfg(feature = "ws")]
+    ws_config: WsConfig,
 }
 
 impl InfoClient {
     pub async fn new(client: Option<Client>, base_url: Option<BaseUrl>) -> Result<InfoClient> {
-        Self::new_internal(client, base_url, false).await
+        let client = client.unwrap_or_default();
+        let base_url = base_url.unwrap_or(BaseUrl::Mainnet).get_url();
+        Ok(Self::from_http_client(HttpClient { client, base_url }))
     }
 
-    pub async fn with_reconnect(
+    /// Like [`InfoClient::new`], with every request spaced out and retried by a rate limiter
+    /// built from `config`
+    pub async fn with_rate_limit(
         client: Option<Client>,
         base_url: Option<BaseUrl>,
+        config: RateLimitConfig,
     ) -> Result<InfoClient> {
-        Self::new_internal(client, base_url, true).await
+        let mut info_client = Self::new(client, base_url).await?;
+        info_client.set_request_options(
+            RequestOptions::default().with_rate_limit(RateLimiter::new(config)),
+        );
+        Ok(info_client)
     }
 
-    async fn new_internal(
+    pub(crate) fn from_http_client(http_client: HttpClient) -> InfoClient {
+        InfoClient {
+            http_client,
+            request_options: RequestOptions::default(),
+            asset_registry: None,
+            #[cfg(feature = "ws")]
+            ws_manager: None,
+            #[cfg(feature = "ws")]
+            reconnect: false,
+            #[cfg(feature = "ws")]
+            ws_config: WsConfig::default(),
+        }
+    }
+}
+
+#[cfg(feature = "ws")]
+impl InfoClient {
+    pub async fn with_reconnect(
         client: Option<Client>,
         base_url: Option<BaseUrl>,
-        reconnect: bool,
     ) -> Result<InfoClient> {
-        let client = client.unwrap_or_default();
-        let base_url = base_url.unwrap_or(BaseUrl::Mainnet).get_url();
+        let mut info_client = Self::new(client, base_url).await?;
+        info_client.reconnect = true;
+        Ok(info_client)
+    }
 
-        Ok(InfoClient {
-            http_client: HttpClient { client, base_url },
-            ws_manager: None,
-            reconnect,
-        })
+    /// This client, reconnecting and resubscribing if its websocket drops
+    pub(crate) fn reconnecting(mut self) -> InfoClient {
+        self.reconnect = true;
+        self
+    }
+
+    /// Sets the websocket tuning, e.g. `WsConfigPreset::LowLatency.into()`.
+    /// Only applies if called before the websocket is opened by the first subscription.
+    pub fn set_ws_config(&mut self, ws_config: WsConfig) {
+        self.ws_config = ws_config;
     }
 
     pub async fn subscribe(
         &mut self,
         subscription: Subscription,
         sender_channel: UnboundedSender<Message>,
+    ) -> Result<u32> {
+        self.add_subscription(
+            subscription,
+            SubscriptionSender::Typed(sender_channel),
+            CoinFilter::All,
+        )
+        .await
+    }
+
+    /// Like [`InfoClient::subscribe`], but only delivers the parts of user messages that concern
+    /// the coins in `filter`, e.g. `CoinFilter::Only(vec!["ETH".to_string()])` on `UserEvents`.
+    /// Several filtered subscriptions can share the `UserEvents` feed of one user.
+    pub async fn subscribe_filtered(
+        &mut self,
+        subscription: Subscription,
+        filter: CoinFilter,
+        sender_channel: UnboundedSender<Message>,
+    ) -> Result<u32> {
+        self.add_subscription(
+            subscription,
+            SubscriptionSender::Typed(sender_channel),
+            filter,
+        )
+        .await
+    }
+
+    /// Like [`InfoClient::subscribe`], but also delivers the raw JSON payload each message was parsed from.
+    /// Meant for debugging parsing mismatches, as every message carries a copy of the server payload.
+    pub async fn subscribe_with_raw(
+        &mut self,
+        subscription: Subscription,
+        sender_channel: UnboundedSender<(Message, Arc<str>)>,
+    ) -> Result<u32> {
+        self.add_subscription(
+            subscription,
+            SubscriptionSender::WithRaw(sender_channel),
+            CoinFilter::All,
+        )
+        .await
+    }
+
+    /// Like [`InfoClient::subscribe`], but tags each message with its [`DeliveryKind`], so
+    /// snapshots replayed after a reconnect can be told apart from the initial snapshot and
+    /// from live events
+    pub async fn subscribe_with_delivery(
+        &mut self,
+        subscription: Subscription,
+        sender_channel: UnboundedSender<(Message, DeliveryKind)>,
+    ) -> Result<u32> {
+        self.add_subscription(
+            subscription,
+            SubscriptionSender::WithDelivery(sender_channel),
+            CoinFilter::All,
+        )
+        .await
+    }
+
+    async fn add_subscription(
+        &mut self,
+        subscription: Subscription,
+        sender_channel: SubscriptionSender,
+        filter: CoinFilter,
     ) -> Result<u32> {
         if self.ws_manager.is_none() {
             let ws_manager = WsManager::new(
                 format!("ws{}/ws", &self.http_client.base_url[4..]),
                 self.reconnect,
+                self.ws_config,
             )
             .await?;
             self.ws_manager = Some(ws_manager);
@@ -143,15 +306,128 @@ impl InfoClient {
         self.ws_manager
             .as_mut()
             .ok_or(Error::WsManagerNotFound)?
-            .add_subscription(identifier, sender_channel)
+            .add_subscription(identifier, sender_channel, filter)
             .await
     }
 
+    /// Active subscriptions, serializable so a restarted process can pass them to
+    /// [`InfoClient::resume_subscriptions`]
+    pub async fn export_subscriptions(&self) -> Result<Vec<ExportedSubscription>> {
+        match &self.ws_manager {
+            Some(ws_manager) => ws_manager.export_subscriptions().await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Re-establishes exported subscriptions, all delivering to `sender_channel`.
+    /// Returns the new subscription ids in the same order.
+    pub async fn resume_subscriptions(
+        &mut self,
+        subscriptions: Vec<ExportedSubscription>,
+        sender_channel: UnboundedSender<Message>,
+    ) -> Result<Vec<u32>> {
+        let mut subscription_ids = Vec::with_capacity(subscriptions.len());
+        for exported in subscriptions {
+            subscription_ids.push(
+                self.add_subscription(
+                    exported.subscription,
+                    SubscriptionSender::Typed(sender_channel.clone()),
+                    exported.filter,
+                )
+                .await?,
+            );
+        }
+        Ok(subscription_ids)
+    }
+
+    /// Client that reconnects, with `subscriptions` re-established
+    pub async fn with_subscriptions(
+        client: Option<Client>,
+        base_url: Option<BaseUrl>,
+        subscriptions: Vec<ExportedSubscription>,
+        sender_channel: UnboundedSender<Message>,
+    ) -> Result<(InfoClient, Vec<u32>)> {
+        let mut info_client = Self::with_reconnect(client, base_url).await?;
+        let subscription_ids = info_client
+            .resume_subscriptions(subscriptions, sender_channel)
+            .await?;
+        Ok((info_client, subscription_ids))
+    }
+
+    /// Latest websocket latency measurements, `None` until a subscription opens the websocket
+    pub fn latency(&self) -> Option<WsLatency> {
+        self.ws_manager.as_ref().map(WsManager::latency)
+    }
+
+    /// Message counters for every active websocket subscription, sorted by identifier.
+    /// A stale `last_message_age` on a busy feed points at a silently dead stream.
+    pub fn subscription_stats(&self) -> Vec<SubscriptionStats> {
+        self.ws_manager
+            .as_ref()
+            .map(WsManager::subscription_stats)
+            .unwrap_or_default()
+    }
+
+    /// Opens the websocket now rather than on the first subscription
+    pub async fn connect_ws(&mut self) -> Result<()> {
+        if self.ws_manager.is_none() {
+            let ws_manager = WsManager::new(
+                format!("ws{}/ws", &self.http_client.base_url[4..]),
+                self.reconnect,
+                self.ws_config,
+            )
+            .await?;
+            self.ws_manager = Some(ws_manager);
+        }
+        Ok(())
+    }
+
+    /// Sends a latency measurement to `sender` after every ping round trip
+    pub async fn subscribe_latency(&mut self, sender: UnboundedSender<WsLatency>) -> Result<()> {
+        if self.ws_manager.is_none() {
+            let ws_manager = WsManager::new(
+                format!("ws{}/ws", &self.http_client.base_url[4..]),
+                self.reconnect,
+                self.ws_config,
+            )
+            .await?;
+            self.ws_manager = Some(ws_manager);
+        }
+
+        self.ws_manager
+            .as_ref()
+            .ok_or(Error::WsManagerNotFound)?
+            .add_latency_listener(sender);
+        Ok(())
+    }
+
+    /// Returns a [`WsPostClient`] that multiplexes over this client's subscription websocket,
+    /// opening it if needed, instead of a second connection
+    pub async fn ws_post_client(&mut self) -> Result<WsPostClient> {
+        if self.ws_manager.is_none() {
+            let ws_manager = WsManager::new(
+                format!("ws{}/ws", &self.http_client.base_url[4..]),
+                self.reconnect,
+                self.ws_config,
+            )
+            .await?;
+            self.ws_manager = Some(ws_manager);
+        }
+
+        let (writer, router) = self
+            .ws_manager
+            .as_ref()
+            .ok_or(Error::WsManagerNotFound)?
+            .post_handle();
+        Ok(WsPostClient::shared(writer, router))
+    }
+
     pub async fn unsubscribe(&mut self, subscription_id: u32) -> Result<()> {
         if self.ws_manager.is_none() {
             let ws_manager = WsManager::new(
                 format!("ws{}/ws", &self.http_client.base_url[4..]),
                 self.reconnect,
+                self.ws_config,
             )
             .await?;
             self.ws_manager = Some(ws_manager);
@@ -163,45 +439,182 @@ impl InfoClient {
             .remove_subscription(subscription_id)
             .await
     }
+}
 
+impl InfoClient {
     async fn send_info_request<T: for<'a> Deserialize<'a>>(
         &self,
-        info_request: InfoRequest,
+        info_request: impl Serialize,
     ) -> Result<T> {
         let data =
             serde_json::to_string(&info_request).map_err(|e| Error::JsonParse(e.to_string()))?;
 
-        let return_data = self.http_client.post("/info", data).await?;
+        let return_data = self
+            .http_client
+            .post_with_options("/info", data, &self.request_options)
+            .await?;
         serde_json::from_str(&return_data).map_err(|e| Error::JsonParse(e.to_string()))
     }
 
-    pub async fn open_orders(&self, address: H160) -> Result<Vec<OpenOrdersResponse>> {
+    /// Sends any info request, e.g. `{"type": "newEndpoint", "user": "0x..."}`, and parses the
+    /// response into `T`. For endpoints this SDK has no typed method for yet.
+    pub async fn send_info_request_typed<T: for<'a> Deserialize<'a>>(
+        &self,
+        request: serde_json::Value,
+    ) -> Result<T> {
+        if !request.get("type").is_some_and(|t| t.is_string()) {
+            return Err(Error::GenericRequest(
+                "Info request must be an object with a string \"type\"".to_string(),
+            ));
+        }
+        self.send_info_request(request).await
+    }
+
+    /// Sends any info request and returns the response as raw JSON
+    pub async fn raw_request(&self, request: serde_json::Value) -> Result<serde_json::Value> {
+        self.send_info_request_typed(request).await
+    }
+
+    /// HTTP-only client sharing this client's connection pool and asset registry whose requests
+    /// use `options`,
+    /// e.g. `info_client.with_request_options(RequestOptions::default().with_cancel(token))`
+    pub fn with_request_options(&self, options: RequestOptions) -> InfoClient {
+        let mut info_client = InfoClient::from_http_client(self.http_client.clone());
+        info_client.request_options = options;
+        info_client.asset_registry = self.asset_registry.clone();
+        info_client
+    }
+
+    /// Client sharing this one's http client and request options but not its websocket, for
+    /// requests made from background tasks
+    pub(crate) fn detached(&self) -> InfoClient {
+        self.with_request_options(self.request_options.clone())
+    }
+
+    /// Applies `options` to every later request of this client, keeping its websocket
+    pub fn set_request_options(&mut self, options: RequestOptions) {
+        self.request_options = options;
+    }
+
+    pub async fn open_orders(&self, address: Address) -> Result<Vec<OpenOrdersResponse>> {
         let input = InfoRequest::OpenOrders { user: address };
         self.send_info_request(input).await
     }
 
-    pub async fn user_state(&self, address: H160) -> Result<UserStateResponse> {
+    pub async fn user_state(&self, address: Address) -> Result<UserStateResponse> {
         let input = InfoRequest::UserState { user: address };
         self.send_info_request(input).await
     }
 
-    pub async fn user_states(&self, addresses: Vec<H160>) -> Result<Vec<UserStateResponse>> {
+    pub async fn margin_summary(&self, address: Address) -> Result<AccountMarginSummary> {
+        let user_state = self.user_state(address).await?;
+        AccountMarginSummary::try_from(&user_state)
+    }
+
+    pub async fn user_states(&self, addresses: Vec<Address>) -> Result<Vec<UserStateResponse>> {
         let input = InfoRequest::UserStates { users: addresses };
         self.send_info_request(input).await
     }
 
-    pub async fn user_token_balances(&self, address: H160) -> Result<UserTokenBalanceResponse> {
+    pub async fn user_token_balances(&self, address: Address) -> Result<UserTokenBalanceResponse> {
         let input = InfoRequest::UserTokenBalances { user: address };
         self.send_info_request(input).await
     }
 
-    pub async fn user_fees(&self, address: H160) -> Result<UserFeesResponse> {
+    pub async fn sub_accounts(&self, address: Address) -> Result<Vec<SubAccount>> {
+        let input = InfoRequest::SubAccounts { user: address };
+        let sub_accounts: Option<Vec<SubAccount>> = self.send_info_request(input).await?;
+        Ok(sub_accounts.unwrap_or_default())
+    }
+
+    pub async fn user_fees(&self, address: Address) -> Result<UserFeesResponse> {
         let input = InfoRequest::UserFees { user: address };
         self.send_info_request(input).await
     }
 
+    pub async fn user_role(&self, address: Address) -> Result<UserRole> {
+        let input = InfoRequest::UserRole { user: address };
+        self.send_info_request(input).await
+    }
+
+    pub async fn active_asset_data(
+        &self,
+        address: Address,
+        coin: &Coin,
+    ) -> Result<ActiveAssetDataResponse> {
+        let input = InfoRequest::ActiveAssetData {
+            user: address,
+            coin: coin.as_str().to_string(),
+        };
+        self.send_info_request(input).await
+    }
+
+    /// Fails with [`Error::ExchangeUnavailable`] while the exchange is down for maintenance
+    pub async fn exchange_status(&self) -> Result<ExchangeStatusResponse> {
+        let input = InfoRequest::ExchangeStatus;
+        self.send_info_request(input).await
+    }
+
     pub async fn meta(&self) -> Result<Meta> {
-        let input = InfoRequest::Meta;
+        let input = InfoRequest::Meta { dex: None };
+        self.send_info_request(input).await
+    }
+
+    /// Meta for a builder-deployed perp dex, or the default dex if `dex` is empty
+    pub async fn meta_for_dex(&self, dex: &str) -> Result<Meta> {
+        let input = InfoRequest::Meta {
+            dex: Some(dex.to_string()),
+        };
+        self.send_info_request(input).await
+    }
+
+    pub async fn meta_and_asset_contexts(&self) -> Result<Vec<MetaAndAssetCtxs>> {
+        let input = InfoRequest::MetaAndAssetCtxs;
+        self.send_info_request(input).await
+    }
+
+    /// Perps at their open interest cap, where orders that would increase positions are rejected
+    pub async fn perps_at_open_interest_cap(&self) -> Result<Vec<String>> {
+        let input = InfoRequest::PerpsAtOpenInterestCap;
+        self.send_info_request(input).await
+    }
+
+    /// `(max leverage, notional)` tiers: an IOC order on a perp may be worth at most the
+    /// notional of the highest tier not above the perp's max leverage
+    pub async fn max_market_order_ntls(&self) -> Result<Vec<(u32, String)>> {
+        let input = InfoRequest::MaxMarketOrderNtls;
+        self.send_info_request(input).await
+    }
+
+    pub async fn perp_dexs(&self) -> Result<PerpDexsResponse> {
+        let input = InfoRequest::PerpDexs;
+        self.send_info_request(input).await
+    }
+
+    /// Meta of the builder-deployed perp dex `dex` along with its index in `perpDexs`, which its
+    /// asset ids are derived from
+    pub async fn perp_dex_meta(&self, dex: &str) -> Result<PerpDexMeta> {
+        let (perp_dexs, meta) = tokio::try_join!(self.perp_dexs(), self.meta_for_dex(dex))?;
+        let dex_index = perp_dexs
+            .iter()
+            .position(|perp_dex| {
+                perp_dex
+                    .as_ref()
+                    .is_some_and(|perp_dex| perp_dex.name == dex)
+            })
+            .ok_or(Error::AssetNotFound)?;
+        Ok(PerpDexMeta {
+            dex: dex.to_string(),
+            dex_index,
+            meta,
+        })
+    }
+
+    /// Open interest and transfer caps of the builder-deployed perp dex `dex`
+    pub async fn perp_dex_limits(&self, dex: &str) -> Result<PerpDexLimitsResponse> {
+        let input = InfoRequest::PerpDexLimits {
+            dex: dex.to_string(),
+        };
         self.send_info_request(input).await
     }
 
@@ -220,14 +633,45 @@ impl InfoClient {
         self.send_info_request(input).await
     }
 
-    pub async fn user_fills(&self, address: H160) -> Result<Vec<UserFillsResponse>> {
+    pub async fn user_fills(&self, address: Address) -> Result<Vec<UserFillsResponse>> {
         let input = InfoRequest::UserFills { user: address };
         self.send_info_request(input).await
     }
 
+    /// One page of at most 2000 fills of `user` from `start_time`, oldest first
+    pub async fn user_fills_by_time_page(
+        &self,
+        user: Address,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> Result<Vec<UserFillsResponse>> {
+        let input = InfoRequest::UserFillsByTime {
+            user,
+            start_time,
+            end_time,
+        };
+        self.send_info_request(input).await
+    }
+
+    /// Every fill of `user` between `start_time` and `end_time`, paging past the 2000 fills per request limit.
+    /// Use [`UserFillsPager`] to process large histories page by page instead.
+    pub async fn user_fills_by_time(
+        &self,
+        user: Address,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> Result<Vec<UserFillsResponse>> {
+        let mut pager = UserFillsPager::new(self, user, start_time, end_time);
+        let mut fills = Vec::new();
+        while let Some(page) = pager.next_page().await? {
+            fills.extend(page);
+        }
+        Ok(fills)
+    }
+
     pub async fn funding_history(
         &self,
-        coin: String,
+        coin: Coin,
         start_time: u64,
         end_time: Option<u64>,
     ) -> Result<Vec<FundingHistoryResponse>> {
@@ -241,7 +685,7 @@ impl InfoClient {
 
     pub async fn user_funding_history(
         &self,
-        user: H160,
+        user: Address,
         start_time: u64,
         end_time: Option<u64>,
     ) -> Result<Vec<UserFundingResponse>> {
@@ -253,12 +697,54 @@ impl InfoClient {
         self.send_info_request(input).await
     }
 
+    pub async fn predicted_fundings(&self) -> Result<Vec<PredictedFundingsResponse>> {
+        let input = InfoRequest::PredictedFundings;
+        self.send_info_request(input).await
+    }
+
+    /// Next Hyperliquid funding time in milliseconds for every listed perp
+    pub async fn next_funding_times(&self) -> Result<HashMap<String, NextFunding>> {
+        Ok(next_fundings(self.predicted_fundings().await?))
+    }
+
+    /// Next Hyperliquid funding time in milliseconds for `coin`.
+    /// Falls back to the next hour boundary when the coin has no predicted funding.
+    pub async fn next_funding_time(&self, coin: &Coin) -> Result<u64> {
+        Ok(self
+            .next_funding_times()
+            .await?
+            .get(coin.as_str())
+            .map(|funding| funding.time)
+            .unwrap_or_else(|| next_hour_ms(now_timestamp_ms())))
+    }
+
+    /// Resolves the coins of trade, book and candle requests through `asset_registry`, so spot
+    /// pairs can be given by their token names, e.g. "HYPE/USDC" for "@107". Share the registry
+    /// with [`crate::ExchangeClient::set_asset_registry`] to load the metadata once for both.
+    pub fn set_asset_registry(&mut self, asset_registry: AssetRegistry) {
+        self.asset_registry = Some(asset_registry);
+    }
+
+    pub fn asset_registry(&self) -> Option<&AssetRegistry> {
+        self.asset_registry.as_ref()
+    }
+
+    /// Name the info endpoints know `coin` by, `coin` itself without an asset registry
+    async fn coin_name(&self, coin: String) -> Result<String> {
+        match &self.asset_registry {
+            Some(asset_registry) => Ok(asset_registry.resolve(&coin).await?.name),
+            None => Ok(coin),
+        }
+    }
+
     pub async fn recent_trades(&self, coin: String) -> Result<Vec<RecentTradesResponse>> {
+        let coin = self.coin_name(coin).await?;
         let input = InfoRequest::RecentTrades { coin };
         self.send_info_request(input).await
     }
 
     pub async fn l2_snapshot(&self, coin: String) -> Result<L2SnapshotResponse> {
+        let coin = self.coin_name(coin).await?;
         let input = InfoRequest::L2Book { coin };
         self.send_info_request(input).await
     }
@@ -270,6 +756,7 @@ impl InfoClient {
         start_time: u64,
         end_time: u64,
     ) -> Result<Vec<CandlesSnapshotResponse>> {
+        let coin = self.coin_name(coin).await?;
         let input = InfoRequest::CandleSnapshot {
             req: CandleSnapshotRequest {
                 coin,
@@ -281,18 +768,114 @@ impl InfoClient {
         self.send_info_request(input).await
     }
 
-    pub async fn query_order_by_oid(&self, address: H160, oid: u64) -> Result<OrderStatusResponse> {
+    pub async fn query_order_by_oid(
+        &self,
+        address: Address,
+        oid: u64,
+    ) -> Result<OrderStatusResponse> {
         let input = InfoRequest::OrderStatus { user: address, oid };
         self.send_info_request(input).await
     }
 
-    pub async fn query_referral_state(&self, address: H160) -> Result<ReferralResponse> {
+    #[cfg(feature = "exchange")]
+    pub async fn query_order_by_cloid(
+        &self,
+        address: Address,
+        cloid: uuid::Uuid,
+    ) -> Result<OrderStatusResponse> {
+        let input = InfoRequest::OrderStatusByCloid {
+            user: address,
+            oid: crate::helpers::uuid_to_hex_string(cloid),
+        };
+        self.send_info_request(input).await
+    }
+
+    pub async fn query_referral_state(&self, address: Address) -> Result<ReferralResponse> {
         let input = InfoRequest::Referral { user: address };
         self.send_info_request(input).await
     }
 
-    pub async fn historical_orders(&self, address: H160) -> Result<Vec<OrderInfo>> {
+    /// Details of a vault, with `user`'s follower state if given
+    pub async fn vault_details(
+        &self,
+        vault_address: Address,
+        user: Option<Address>,
+    ) -> Result<VaultDetails> {
+        let input = InfoRequest::VaultDetails {
+            vault_address,
+            user,
+        };
+        self.send_info_request(input).await
+    }
+
+    /// Vaults `address` has deposited into, with its equity in each
+    pub async fn user_vault_equities(&self, address: Address) -> Result<Vec<UserVaultEquity>> {
+        let input = InfoRequest::UserVaultEquities { user: address };
+        self.send_info_request(input).await
+    }
+
+    /// Signers of `address`, `None` if it is not a multi-sig user
+    pub async fn multi_sig_signers(&self, address: Address) -> Result<Option<MultiSigSigners>> {
+        let input = InfoRequest::UserToMultiSigSigners { user: address };
+        self.send_info_request(input).await
+    }
+
+    pub async fn historical_orders(&self, address: Address) -> Result<Vec<OrderInfo>> {
         let input = InfoRequest::HistoricalOrders { user: address };
         self.send_info_request(input).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_raw_request_requires_type() {
+        let info_client = InfoClient::from_http_client(HttpClient {
+            client: Client::new(),
+            base_url: String::new(),
+        });
+        let result = info_client
+            .raw_request(serde_json::json!({"user": Address::ZERO}))
+            .await;
+        assert!(matches!(result, Err(Error::GenericRequest(_))));
+    }
+
+    #[test]
+    fn test_user_role_deserialize() {
+        let master = Address::repeat_byte(1);
+        let role: UserRole = serde_json::from_value(serde_json::json!({
+            "role": "agent", "data": {"user": master}
+        }))
+        .unwrap();
+        assert_eq!(role, UserRole::Agent { user: master });
+        let role: UserRole = serde_json::from_str(r#"{"role": "user"}"#).unwrap();
+        assert_eq!(role, UserRole::User);
+        let role: UserRole = serde_json::from_str(r#"{"role": "someNewRole"}"#).unwrap();
+        assert_eq!(role, UserRole::Unknown);
+    }
+
+    #[test]
+    fn test_active_asset_data_deserialize() {
+        let data: ActiveAssetDataResponse = serde_json::from_value(serde_json::json!({
+            "user": Address::repeat_byte(1), "coin": "ETH",
+            "leverage": {"type": "isolated", "value": 5, "rawUsd": "0.0"},
+            "maxTradeSzs": ["12.5", "12.5"], "availableToTrade": ["4000.0", "4000.0"],
+            "markPx": "1800.5"
+        }))
+        .unwrap();
+        assert_eq!(data.leverage.type_string, "isolated");
+        assert_eq!(data.leverage.value, 5);
+    }
+
+    #[tokio::test]
+    async fn test_detached_client_keeps_rate_limit() {
+        let info_client =
+            InfoClient::with_rate_limit(None, Some(BaseUrl::Localhost), RateLimitConfig::default())
+                .await
+                .unwrap();
+        // background tasks spend from the same budget as the client they were started from
+        assert!(info_client.detached().request_options.rate_limit.is_some());
+    }
+}
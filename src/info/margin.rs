@@ -0,0 +1,98 @@
+use crate::{helpers::parse_decimal, info::MarginSummary, prelude::*, Error, UserStateResponse};
+use rust_decimal::Decimal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarginSummaryValues {
+    pub account_value: Decimal,
+    pub total_margin_used: Decimal,
+    pub total_ntl_pos: Decimal,
+    pub total_raw_usd: Decimal,
+}
+
+impl MarginSummaryValues {
+    /// Total notional position divided by account value, `None` if the account value is zero
+    pub fn account_leverage(&self) -> Option<Decimal> {
+        self.total_ntl_pos.checked_div(self.account_value)
+    }
+
+    /// Fraction of account value used as initial margin, `None` if the account value is zero
+    pub fn margin_usage(&self) -> Option<Decimal> {
+        self.total_margin_used.checked_div(self.account_value)
+    }
+}
+
+impl TryFrom<&MarginSummary> for MarginSummaryValues {
+    type Error = Error;
+
+    fn try_from(summary: &MarginSummary) -> Result<Self> {
+        Ok(MarginSummaryValues {
+            account_value: parse_decimal(&summary.account_value)?,
+            total_margin_used: parse_decimal(&summary.total_margin_used)?,
+            total_ntl_pos: parse_decimal(&summary.total_ntl_pos)?,
+            total_raw_usd: parse_decimal(&summary.total_raw_usd)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountMarginSummary {
+    pub margin_summary: MarginSummaryValues,
+    pub cross_margin_summary: MarginSummaryValues,
+    pub cross_maintenance_margin_used: Decimal,
+    pub withdrawable: Decimal,
+}
+
+impl AccountMarginSummary {
+    /// Cross maintenance margin divided by cross account value. The account is liquidatable once this reaches one.
+    pub fn maintenance_margin_usage(&self) -> Option<Decimal> {
+        self.cross_maintenance_margin_used
+            .checked_div(self.cross_margin_summary.account_value)
+    }
+}
+
+impl TryFrom<&UserStateResponse> for AccountMarginSummary {
+    type Error = Error;
+
+    fn try_from(user_state: &UserStateResponse) -> Result<Self> {
+        Ok(AccountMarginSummary {
+            margin_summary: (&user_state.margin_summary).try_into()?,
+            cross_margin_summary: (&user_state.cross_margin_summary).try_into()?,
+            cross_maintenance_margin_used: parse_decimal(
+                &user_state.cross_maintenance_margin_used,
+            )?,
+            withdrawable: parse_decimal(&user_state.withdrawable)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_margin_summary() -> Result<()> {
+        let user_state: UserStateResponse = serde_json::from_str(
+            r#"{
+                "assetPositions": [],
+                "crossMarginSummary": {"accountValue": "1000.0", "totalMarginUsed": "200.0", "totalNtlPos": "4000.0", "totalRawUsd": "-3000.0"},
+                "marginSummary": {"accountValue": "1250.0", "totalMarginUsed": "250.0", "totalNtlPos": "5000.0", "totalRawUsd": "-3750.0"},
+                "crossMaintenanceMarginUsed": "100.0",
+                "withdrawable": "800.0"
+            }"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        let summary = AccountMarginSummary::try_from(&user_state)?;
+        assert_eq!(
+            summary.margin_summary.account_leverage(),
+            Some(Decimal::from(4))
+        );
+        assert_eq!(
+            summary.margin_summary.margin_usage(),
+            Some(Decimal::new(2, 1))
+        );
+        assert_eq!(summary.maintenance_margin_usage(), Some(Decimal::new(1, 1)));
+        assert_eq!(summary.withdrawable, Decimal::from(800));
+        Ok(())
+    }
+}
@@ -1,6 +1,49 @@
+mod account_snapshot;
+mod aggregated_risk;
+mod asset_registry;
+mod basis;
+mod candles;
+mod dust;
+mod equity;
+mod exchange_status;
+mod fees;
+mod funding;
 pub(super) mod info_client;
+mod margin;
+mod oi_cap;
+mod perp_markets;
+pub(super) mod poller;
 mod response_structs;
+mod snapshot_export;
+mod spot_balances;
+mod spot_markets;
 mod sub_structs;
+mod user_fills;
+mod vault;
 
+pub use account_snapshot::{
+    AccountChange, AccountDiff, AccountSnapshot, BalanceSnapshot, OpenOrderSnapshot,
+    PositionSnapshot,
+};
+pub use aggregated_risk::{AccountRisk, AggregatedRisk, CoinExposure, RiskReport};
+pub use asset_registry::{AssetInfo, AssetRegistry, MIN_REFRESH_INTERVAL};
+pub use basis::{Basis, BasisEvent, BasisMonitor, BasisPair};
+pub use candles::{align_candles, candle_gaps, candle_interval_ms, CandleGap, GapFill};
+pub use dust::{DustBalance, DustReason, MIN_SPOT_ORDER_NOTIONAL};
+pub use equity::{SpotEquity, TotalEquity, VaultEquity};
+pub use exchange_status::{ExchangeAvailability, ExchangeStatusMonitor};
+pub use fees::*;
+pub(crate) use funding::{next_fundings, next_hour_ms};
+pub use funding::{FundingClock, FundingEvent, NextFunding};
+pub use margin::*;
+pub use oi_cap::{OiCapEvent, OiCapMonitor, OiCapState};
+pub use perp_markets::{PerpMarket, PerpMarketSnapshot};
 pub use response_structs::*;
+pub use snapshot_export::{
+    verify_export_chain, ExportFormat, SnapshotExport, SnapshotExporter, EXPORT_CHAIN_FILE,
+};
+pub use spot_balances::{SpotBalance, SpotBalances};
+pub use spot_markets::{SpotMarket, SpotMarketSnapshot};
 pub use sub_structs::*;
+pub use user_fills::{UserFillsPager, USER_FILLS_PAGE_SIZE};
+pub use vault::{VaultDetails, VaultFollower, VaultPortfolio};
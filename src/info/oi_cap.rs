@@ -0,0 +1,177 @@
+use crate::{info::poller::Poller, prelude::*, InfoClient, PerpMarketSnapshot};
+use log::error;
+use rust_decimal::Decimal;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OiCapState {
+    Normal,
+    /// Open interest notional is at or above the configured threshold of the coin's cap
+    Approaching,
+    /// Listed by `perpsAtOpenInterestCap`, orders that increase positions are rejected
+    AtCap,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OiCapEvent {
+    pub coin: String,
+    pub state: OiCapState,
+    /// Open interest valued at the oracle price
+    pub open_interest_ntl: Decimal,
+    /// Open interest notional as a fraction of the configured cap, `None` without a cap for the coin
+    pub utilization: Option<Decimal>,
+}
+
+/// Polls open interest and the coins at their open interest cap, emitting an [`OiCapEvent`]
+/// whenever a coin changes [`OiCapState`].
+/// The exchange doesn't publish the caps themselves, so `Approaching` is only emitted for coins
+/// in `caps`; every coin can reach `AtCap`.
+#[derive(Debug, Clone)]
+pub struct OiCapMonitor {
+    /// Open interest notional cap per coin in USDC
+    pub caps: HashMap<String, Decimal>,
+    /// Fraction of the cap from which a coin is `Approaching`, e.g. 0.9
+    pub threshold: Decimal,
+    pub interval: Duration,
+}
+
+impl OiCapMonitor {
+    pub fn new(
+        caps: HashMap<String, Decimal>,
+        threshold: Decimal,
+        interval: Duration,
+    ) -> OiCapMonitor {
+        OiCapMonitor {
+            caps,
+            threshold,
+            interval,
+        }
+    }
+
+    /// Events for the coins whose state differs from `states`, which is updated to match
+    fn changes(
+        &self,
+        snapshot: &PerpMarketSnapshot,
+        at_cap: &HashSet<String>,
+        states: &mut HashMap<String, OiCapState>,
+    ) -> Vec<OiCapEvent> {
+        let mut events = Vec::new();
+        for market in snapshot.markets() {
+            let open_interest_ntl = market.open_interest * market.oracle_px;
+            let utilization = self
+                .caps
+                .get(&market.coin)
+                .filter(|cap| !cap.is_zero())
+                .map(|cap| open_interest_ntl / cap);
+            let state = if at_cap.contains(&market.coin) {
+                OiCapState::AtCap
+            } else if utilization.is_some_and(|utilization| utilization >= self.threshold) {
+                OiCapState::Approaching
+            } else {
+                OiCapState::Normal
+            };
+
+            let previous = states
+                .insert(market.coin.clone(), state)
+                .unwrap_or(OiCapState::Normal);
+            if previous != state {
+                events.push(OiCapEvent {
+                    coin: market.coin.clone(),
+                    state,
+                    open_interest_ntl,
+                    utilization,
+                });
+            }
+        }
+        events
+    }
+
+    /// Starts polling in the background and returns the event stream.
+    /// Coins already approaching or at their cap are reported on the first poll.
+    /// The task stops once the receiver is dropped.
+    pub fn start(self, info_client: &InfoClient) -> Result<UnboundedReceiver<OiCapEvent>> {
+        let poller = Poller::new("OiCapMonitor", self.interval)?;
+        let info_client = info_client.detached();
+        let (sender, receiver) = unbounded_channel();
+        poller.spawn(
+            (self, info_client, sender, HashMap::new()),
+            |(monitor, info_client, sender, mut states)| async move {
+                let polled = tokio::try_join!(
+                    info_client.perp_market_snapshot(),
+                    info_client.perps_at_open_interest_cap()
+                );
+                match polled {
+                    Ok((snapshot, at_cap)) => {
+                        let at_cap = at_cap.into_iter().collect();
+                        for event in monitor.changes(&snapshot, &at_cap, &mut states) {
+                            if sender.send(event).is_err() {
+                                return None;
+                            }
+                        }
+                    }
+                    Err(err) => error!("Error polling open interest caps: {err}"),
+                }
+                (!sender.is_closed()).then_some((monitor, info_client, sender, states))
+            },
+        );
+        Ok(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::info::perp_markets::split_meta_and_asset_contexts;
+
+    fn snapshot(btc_oi: &str) -> PerpMarketSnapshot {
+        let ctx = |oi: &str, px: &str| serde_json::json!({"dayNtlVlm": "0", "funding": "0", "impactPxs": null, "markPx": px, "midPx": null, "openInterest": oi, "oraclePx": px, "premium": null, "prevDayPx": px});
+        let response = serde_json::from_value(serde_json::json!([
+            {"universe": [{"name": "BTC", "szDecimals": 5, "maxLeverage": 40}, {"name": "NEW", "szDecimals": 0, "maxLeverage": 3}]},
+            [ctx(btc_oi, "50000"), ctx("10", "1")]
+        ]))
+        .unwrap();
+        let (meta, contexts) = split_meta_and_asset_contexts(response).unwrap();
+        PerpMarketSnapshot::new(&meta, &contexts).unwrap()
+    }
+
+    #[test]
+    fn test_oi_cap_state_changes() {
+        let monitor = OiCapMonitor::new(
+            HashMap::from([("BTC".to_string(), Decimal::from(1_000_000))]),
+            Decimal::new(9, 1),
+            Duration::from_secs(10),
+        );
+        let mut states = HashMap::new();
+
+        assert!(monitor
+            .changes(&snapshot("10"), &HashSet::new(), &mut states)
+            .is_empty());
+
+        let events = monitor.changes(&snapshot("19"), &HashSet::new(), &mut states);
+        assert_eq!(
+            events,
+            vec![OiCapEvent {
+                coin: "BTC".to_string(),
+                state: OiCapState::Approaching,
+                open_interest_ntl: Decimal::from(950_000),
+                utilization: Some(Decimal::new(95, 2)),
+            }]
+        );
+        // no event while the state holds
+        assert!(monitor
+            .changes(&snapshot("19.5"), &HashSet::new(), &mut states)
+            .is_empty());
+
+        let at_cap = HashSet::from(["NEW".to_string()]);
+        let events = monitor.changes(&snapshot("10"), &at_cap, &mut states);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].state, OiCapState::Normal);
+        assert_eq!(events[1].coin, "NEW");
+        assert_eq!(events[1].state, OiCapState::AtCap);
+        assert_eq!(events[1].utilization, None);
+    }
+}
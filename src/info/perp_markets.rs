@@ -0,0 +1,155 @@
+use crate::{
+    helpers::parse_decimal,
+    meta::{AssetContext, Meta, MetaAndAssetCtxs},
+    prelude::*,
+    Error, InfoClient,
+};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// A perp from `meta` joined with its asset context
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerpMarket {
+    pub coin: String,
+    /// Asset index used in orders
+    pub asset: u32,
+    pub sz_decimals: u32,
+    pub mark_px: Decimal,
+    /// `None` when the book is empty on a side
+    pub mid_px: Option<Decimal>,
+    pub oracle_px: Decimal,
+    /// Current hourly funding rate
+    pub funding: Decimal,
+    /// Open interest in coin units
+    pub open_interest: Decimal,
+    /// `None` for markets without a premium sample yet
+    pub premium: Option<Decimal>,
+    /// Average execution price of a buy and a sell of the impact notional, `None` when the book is too thin
+    pub impact_bid_px: Option<Decimal>,
+    pub impact_ask_px: Option<Decimal>,
+    pub prev_day_px: Decimal,
+    /// 24h notional volume in USDC
+    pub day_ntl_vlm: Decimal,
+}
+
+/// Every perp with typed prices, funding and open interest, looked up by coin
+#[derive(Debug, Clone, Default)]
+pub struct PerpMarketSnapshot {
+    markets: Vec<PerpMarket>,
+    by_coin: HashMap<String, usize>,
+}
+
+pub(crate) fn split_meta_and_asset_contexts(
+    response: Vec<MetaAndAssetCtxs>,
+) -> Result<(Meta, Vec<AssetContext>)> {
+    let mut meta = None;
+    let mut contexts = None;
+    for item in response {
+        match item {
+            MetaAndAssetCtxs::Meta(m) => meta = Some(m),
+            MetaAndAssetCtxs::Context(ctxs) => contexts = Some(ctxs),
+        }
+    }
+    match (meta, contexts) {
+        (Some(meta), Some(contexts)) => Ok((meta, contexts)),
+        _ => Err(Error::GenericParse(
+            "metaAndAssetCtxs response is missing meta or contexts".to_string(),
+        )),
+    }
+}
+
+fn parse_optional_decimal(value: Option<&str>) -> Result<Option<Decimal>> {
+    value.map(parse_decimal).transpose()
+}
+
+impl PerpMarketSnapshot {
+    /// Joins universe entries with contexts, which the exchange returns in universe order
+    pub fn new(meta: &Meta, contexts: &[AssetContext]) -> Result<PerpMarketSnapshot> {
+        if meta.universe.len() != contexts.len() {
+            return Err(Error::GenericParse(format!(
+                "perp universe has {} assets but {} asset contexts",
+                meta.universe.len(),
+                contexts.len()
+            )));
+        }
+
+        let mut snapshot = PerpMarketSnapshot::default();
+        for (asset, (asset_meta, context)) in meta.universe.iter().zip(contexts).enumerate() {
+            let impact_pxs = context.impact_pxs.as_deref().unwrap_or_default();
+            let market = PerpMarket {
+                coin: asset_meta.name.clone(),
+                asset: asset as u32,
+                sz_decimals: asset_meta.sz_decimals,
+                mark_px: parse_decimal(&context.mark_px)?,
+                mid_px: parse_optional_decimal(context.mid_px.as_deref())?,
+                oracle_px: parse_decimal(&context.oracle_px)?,
+                funding: parse_decimal(&context.funding)?,
+                open_interest: parse_decimal(&context.open_interest)?,
+                premium: parse_optional_decimal(context.premium.as_deref())?,
+                impact_bid_px: parse_optional_decimal(impact_pxs.first().map(String::as_str))?,
+                impact_ask_px: parse_optional_decimal(impact_pxs.get(1).map(String::as_str))?,
+                prev_day_px: parse_decimal(&context.prev_day_px)?,
+                day_ntl_vlm: parse_decimal(&context.day_ntl_vlm)?,
+            };
+            snapshot
+                .by_coin
+                .insert(market.coin.clone(), snapshot.markets.len());
+            snapshot.markets.push(market);
+        }
+        Ok(snapshot)
+    }
+
+    pub fn get(&self, coin: &str) -> Option<&PerpMarket> {
+        self.by_coin
+            .get(coin)
+            .map(|&position| &self.markets[position])
+    }
+
+    pub fn markets(&self) -> &[PerpMarket] {
+        &self.markets
+    }
+}
+
+impl InfoClient {
+    pub async fn perp_market_snapshot(&self) -> Result<PerpMarketSnapshot> {
+        let (meta, contexts) =
+            split_meta_and_asset_contexts(self.meta_and_asset_contexts().await?)?;
+        PerpMarketSnapshot::new(&meta, &contexts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perp_market_snapshot() {
+        let response: Vec<MetaAndAssetCtxs> = serde_json::from_value(serde_json::json!([
+            {
+                "universe": [
+                    {"name": "BTC", "szDecimals": 5, "maxLeverage": 40},
+                    {"name": "NEW", "szDecimals": 0, "maxLeverage": 3}
+                ]
+            },
+            [
+                {"dayNtlVlm": "1169046.29", "funding": "0.0000125", "impactPxs": ["64100.0", "64101.0"], "markPx": "64100.5", "midPx": "64100.5", "openInterest": "688.11", "oraclePx": "64110.0", "premium": "-0.00014", "prevDayPx": "63000.0"},
+                {"dayNtlVlm": "0", "funding": "0", "impactPxs": null, "markPx": "1.2", "midPx": null, "openInterest": "0", "oraclePx": "1.2", "premium": null, "prevDayPx": "1.2"}
+            ]
+        ]))
+        .unwrap();
+        let (meta, contexts) = split_meta_and_asset_contexts(response).unwrap();
+        let snapshot = PerpMarketSnapshot::new(&meta, &contexts).unwrap();
+
+        let btc = snapshot.get("BTC").unwrap();
+        assert_eq!(btc.asset, 0);
+        assert_eq!(btc.premium, Some(Decimal::new(-14, 5)));
+        assert_eq!(btc.impact_ask_px, Some(Decimal::from(64101)));
+
+        let new = snapshot.get("NEW").unwrap();
+        assert_eq!(new.asset, 1);
+        assert_eq!(new.mid_px, None);
+        assert_eq!(new.premium, None);
+        assert_eq!(new.impact_bid_px, None);
+        assert!(snapshot.get("ETH").is_none());
+    }
+}
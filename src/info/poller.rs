@@ -0,0 +1,87 @@
+use crate::{prelude::*, Error};
+use std::{future::Future, time::Duration};
+use tokio::{spawn, task::JoinHandle, time::sleep};
+
+/// Runs a background task's polls `interval` apart, measured from the end of one poll to the
+/// start of the next. Each poll takes the task's state and hands it back to go on, or `None` to
+/// stop.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Poller {
+    interval: Duration,
+}
+
+impl Poller {
+    /// Fails with [`Error::Config`] naming `task` if `interval` is zero
+    pub(crate) fn new(task: &str, interval: Duration) -> Result<Poller> {
+        if interval.is_zero() {
+            return Err(Error::Config(format!("{task} interval must be non-zero")));
+        }
+        Ok(Poller { interval })
+    }
+
+    #[cfg(feature = "ws")]
+    pub(crate) fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Polls right away, then after every interval
+    pub(crate) fn spawn<S, F, Fut>(self, state: S, poll: F) -> JoinHandle<()>
+    where
+        S: Send + 'static,
+        F: FnMut(S) -> Fut + Send + 'static,
+        Fut: Future<Output = Option<S>> + Send + 'static,
+    {
+        spawn(self.run(state, poll, false))
+    }
+
+    /// Polls once the first interval has passed, then after every interval
+    #[cfg(feature = "exchange")]
+    pub(crate) fn spawn_after_interval<S, F, Fut>(self, state: S, poll: F) -> JoinHandle<()>
+    where
+        S: Send + 'static,
+        F: FnMut(S) -> Fut + Send + 'static,
+        Fut: Future<Output = Option<S>> + Send + 'static,
+    {
+        spawn(self.run(state, poll, true))
+    }
+
+    async fn run<S, F, Fut>(self, mut state: S, mut poll: F, wait_first: bool)
+    where
+        F: FnMut(S) -> Fut,
+        Fut: Future<Output = Option<S>>,
+    {
+        if wait_first {
+            sleep(self.interval).await;
+        }
+        loop {
+            match poll(state).await {
+                Some(next) => state = next,
+                None => return,
+            }
+            sleep(self.interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_interval_is_a_config_error() {
+        assert!(matches!(
+            Poller::new("Test", Duration::ZERO),
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_polls_until_stopped() {
+        let poller = Poller::new("Test", Duration::from_millis(10)).unwrap();
+        let started = std::time::Instant::now();
+        let polls = poller.spawn(0, |polls| async move { (polls < 3).then_some(polls + 1) });
+        polls.await.unwrap();
+        // four polls, the first right away
+        assert!(started.elapsed() >= Duration::from_millis(30));
+    }
+}
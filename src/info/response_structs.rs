@@ -1,5 +1,6 @@
+use crate::Address;
 use crate::{
-    info::{AssetPosition, Level, MarginSummary},
+    info::{AssetPosition, Level, Leverage, MarginSummary},
     DailyUserVlm, Delta, FeeSchedule, OrderInfo, Referrer, ReferrerState, UserTokenBalance,
 };
 use serde::Deserialize;
@@ -10,9 +11,20 @@ pub struct UserStateResponse {
     pub asset_positions: Vec<AssetPosition>,
     pub cross_margin_summary: MarginSummary,
     pub margin_summary: MarginSummary,
+    pub cross_maintenance_margin_used: String,
     pub withdrawable: String,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccount {
+    pub name: String,
+    pub sub_account_user: Address,
+    pub master: Address,
+    pub clearinghouse_state: UserStateResponse,
+    pub spot_state: UserTokenBalanceResponse,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct UserTokenBalanceResponse {
     pub balances: Vec<UserTokenBalance>,
@@ -28,6 +40,60 @@ pub struct UserFeesResponse {
     pub user_cross_rate: String,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeStatusResponse {
+    pub time: u64,
+    /// Announced conditions like maintenance, `null` when the exchange runs normally
+    #[serde(default)]
+    pub special_statuses: Option<serde_json::Value>,
+}
+
+impl ExchangeStatusResponse {
+    /// Whether the exchange announced a special status, e.g. maintenance
+    pub fn has_special_statuses(&self) -> bool {
+        match &self.special_statuses {
+            None | Some(serde_json::Value::Null) => false,
+            Some(serde_json::Value::Array(statuses)) => !statuses.is_empty(),
+            Some(serde_json::Value::Object(statuses)) => !statuses.is_empty(),
+            Some(_) => true,
+        }
+    }
+}
+
+/// Trading limits of a user on one coin, from `activeAssetData`, reported with or without a position
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveAssetDataResponse {
+    pub user: Address,
+    pub coin: String,
+    pub leverage: Leverage,
+    /// Max buy and sell size
+    pub max_trade_szs: Vec<String>,
+    /// Buy and sell notional available to trade
+    pub available_to_trade: Vec<String>,
+    pub mark_px: String,
+}
+
+/// What kind of account an address is, from `userRole`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "role", content = "data", rename_all = "camelCase")]
+pub enum UserRole {
+    /// Never used on the exchange
+    Missing,
+    User,
+    /// Agent wallet approved by `user`
+    Agent {
+        user: Address,
+    },
+    Vault,
+    SubAccount {
+        master: Address,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
 #[derive(serde::Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenOrdersResponse {
@@ -48,12 +114,18 @@ pub struct UserFillsResponse {
     pub dir: String,
     pub hash: String,
     pub oid: u64,
+    #[serde(default)]
+    pub cloid: Option<String>,
     pub px: String,
     pub side: String,
     pub start_position: String,
     pub sz: String,
     pub time: u64,
     pub fee: String,
+    /// Token `fee` is charged in, e.g. the base token of spot buys. `None` from older servers.
+    #[serde(default)]
+    pub fee_token: Option<String>,
+    pub tid: u64,
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -65,6 +137,18 @@ pub struct FundingHistoryResponse {
     pub time: u64,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PredictedFunding {
+    pub funding_rate: String,
+    pub next_funding_time: u64,
+    pub funding_interval_hours: Option<u64>,
+}
+
+/// A coin with the predicted funding for each venue, `None` where the venue doesn't list the coin
+#[derive(Deserialize, Debug, Clone)]
+pub struct PredictedFundingsResponse(pub String, pub Vec<(String, Option<PredictedFunding>)>);
+
 #[derive(Deserialize, Debug)]
 pub struct UserFundingResponse {
     pub time: u64,
@@ -123,6 +207,26 @@ pub struct OrderStatusResponse {
     pub order: Option<OrderInfo>,
 }
 
+/// Signers of a multi-sig user, as returned by `userToMultiSigSigners`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigSigners {
+    pub authorized_users: Vec<Address>,
+    /// Signatures required for an action
+    pub threshold: usize,
+}
+
+/// A vault deposit, as returned by `userVaultEquities`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UserVaultEquity {
+    pub vault_address: Address,
+    pub equity: String,
+    /// Time in milliseconds until which the deposit can't be withdrawn
+    #[serde(default)]
+    pub locked_until_timestamp: Option<u64>,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ReferralResponse {
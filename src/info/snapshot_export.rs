@@ -0,0 +1,273 @@
+use crate::Address;
+use crate::{
+    helpers::now_timestamp_ms, info::poller::Poller, prelude::*, AccountSnapshot, Error, InfoClient,
+};
+use alloy::primitives::{keccak256, B256};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Write as _,
+    fs::{self, OpenOptions},
+    io::Write as _,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+/// Name of the file in the export directory linking the exports into a hash chain
+pub const EXPORT_CHAIN_FILE: &str = "chain.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// The full [`AccountSnapshot`] of every user
+    Json,
+    /// One row per position and open order
+    Csv,
+}
+
+/// An export file and its link in the hash chain. `hash` is the keccak256 of `previous_hash`
+/// followed by the file's bytes, so changing, removing or reordering files breaks the chain.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotExport {
+    /// File name within the export directory
+    pub file: String,
+    /// Capture time in milliseconds
+    pub time: u64,
+    pub previous_hash: B256,
+    pub hash: B256,
+}
+
+/// Periodically writes the open orders and positions of configured users to timestamped files,
+/// for records that have to survive an audit. Every export is appended to [`EXPORT_CHAIN_FILE`];
+/// check the directory with [`verify_export_chain`].
+#[derive(Debug)]
+pub struct SnapshotExporter {
+    pub users: Vec<Address>,
+    pub dir: PathBuf,
+    pub format: ExportFormat,
+    pub interval: Duration,
+    last_hash: B256,
+}
+
+fn chain_hash(previous_hash: B256, data: &[u8]) -> B256 {
+    keccak256([previous_hash.as_slice(), data].concat())
+}
+
+fn read_chain(dir: &Path) -> Result<Vec<SnapshotExport>> {
+    let path = dir.join(EXPORT_CHAIN_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    fs::read_to_string(path)
+        .map_err(|e| Error::SnapshotExport(e.to_string()))?
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|e| Error::JsonParse(e.to_string())))
+        .collect()
+}
+
+/// Checks every export listed in the chain file of `dir` against its file and the export
+/// before it, returning the number of exports
+pub fn verify_export_chain(dir: impl AsRef<Path>) -> Result<usize> {
+    let dir = dir.as_ref();
+    let chain = read_chain(dir)?;
+    let mut previous_hash = B256::ZERO;
+    for export in &chain {
+        let data =
+            fs::read(dir.join(&export.file)).map_err(|e| Error::SnapshotExport(e.to_string()))?;
+        if export.previous_hash != previous_hash || chain_hash(previous_hash, &data) != export.hash
+        {
+            return Err(Error::SnapshotExport(format!(
+                "hash chain broken at {}",
+                export.file
+            )));
+        }
+        previous_hash = export.hash;
+    }
+    Ok(chain.len())
+}
+
+fn csv_rows(snapshots: &[AccountSnapshot]) -> String {
+    let mut csv = "time,user,kind,coin,oid,side,sz,px\n".to_string();
+    for snapshot in snapshots {
+        let (time, user) = (snapshot.time, snapshot.user);
+        for (coin, position) in &snapshot.positions {
+            let entry_px = position.entry_px.as_deref().unwrap_or_default();
+            let _ = writeln!(
+                csv,
+                "{time},{user},position,{coin},,,{},{entry_px}",
+                position.szi
+            );
+        }
+        for (oid, order) in &snapshot.open_orders {
+            let _ = writeln!(
+                csv,
+                "{time},{user},order,{},{oid},{},{},{}",
+                order.coin, order.side, order.sz, order.limit_px
+            );
+        }
+    }
+    csv
+}
+
+impl SnapshotExporter {
+    /// Continues the hash chain already in `dir`, if any
+    pub fn new(
+        users: Vec<Address>,
+        dir: impl Into<PathBuf>,
+        format: ExportFormat,
+        interval: Duration,
+    ) -> Result<SnapshotExporter> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| Error::SnapshotExport(e.to_string()))?;
+        let last_hash = read_chain(&dir)?
+            .last()
+            .map_or(B256::ZERO, |export| export.hash);
+        Ok(SnapshotExporter {
+            users,
+            dir,
+            format,
+            interval,
+            last_hash,
+        })
+    }
+
+    /// Writes `snapshots` taken at `time` to a new file and links it into the chain
+    pub fn write(&mut self, time: u64, snapshots: &[AccountSnapshot]) -> Result<SnapshotExport> {
+        let (data, extension) = match self.format {
+            ExportFormat::Json => (
+                serde_json::to_vec_pretty(snapshots)
+                    .map_err(|e| Error::JsonParse(e.to_string()))?,
+                "json",
+            ),
+            ExportFormat::Csv => (csv_rows(snapshots).into_bytes(), "csv"),
+        };
+        let file = format!("snapshots-{time}.{extension}");
+        fs::write(self.dir.join(&file), &data).map_err(|e| Error::SnapshotExport(e.to_string()))?;
+
+        let export = SnapshotExport {
+            file,
+            time,
+            previous_hash: self.last_hash,
+            hash: chain_hash(self.last_hash, &data),
+        };
+        let mut line =
+            serde_json::to_string(&export).map_err(|e| Error::JsonParse(e.to_string()))?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(EXPORT_CHAIN_FILE))
+            .and_then(|mut chain| chain.write_all(line.as_bytes()))
+            .map_err(|e| Error::SnapshotExport(e.to_string()))?;
+        self.last_hash = export.hash;
+        Ok(export)
+    }
+
+    /// Captures every user once and writes the export
+    pub async fn export(&mut self, info_client: &InfoClient) -> Result<SnapshotExport> {
+        let time = now_timestamp_ms();
+        let mut snapshots = Vec::with_capacity(self.users.len());
+        for &user in &self.users {
+            snapshots.push(AccountSnapshot::capture(info_client, user).await?);
+        }
+        self.write(time, &snapshots)
+    }
+
+    /// Exports right away and then every `interval` in the background, sending each completed
+    /// export. Failed exports are logged and retried at the next interval.
+    /// The task stops once the receiver is dropped.
+    pub fn start(self, info_client: &InfoClient) -> Result<UnboundedReceiver<SnapshotExport>> {
+        let poller = Poller::new("SnapshotExporter", self.interval)?;
+        let info_client = info_client.detached();
+        let (sender, receiver) = unbounded_channel();
+        poller.spawn(
+            (self, info_client, sender),
+            |(mut exporter, info_client, sender)| async move {
+                match exporter.export(&info_client).await {
+                    Ok(export) => {
+                        if sender.send(export).is_err() {
+                            return None;
+                        }
+                    }
+                    Err(err) => error!("Snapshot export failed: {err}"),
+                }
+                (!sender.is_closed()).then_some((exporter, info_client, sender))
+            },
+        );
+        Ok(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OpenOrdersResponse, UserStateResponse, UserTokenBalanceResponse};
+
+    fn snapshot(time: u64) -> AccountSnapshot {
+        let user_state: UserStateResponse = serde_json::from_value(serde_json::json!({
+            "assetPositions": [{
+                "type": "oneWay",
+                "position": {
+                    "coin": "ETH", "entryPx": "2000.0", "leverage": {"type": "cross", "value": 5},
+                    "liquidationPx": null, "marginUsed": "40.0", "positionValue": "200.0",
+                    "returnOnEquity": "0.0", "szi": "0.1", "unrealizedPnl": "0.0",
+                    "maxLeverage": 50, "cumFunding": {"allTime": "0.0", "sinceOpen": "0.0", "sinceChange": "0.0"}
+                }
+            }],
+            "crossMarginSummary": {"accountValue": "1000.0", "totalMarginUsed": "40.0", "totalNtlPos": "200.0", "totalRawUsd": "800.0"},
+            "marginSummary": {"accountValue": "1000.0", "totalMarginUsed": "40.0", "totalNtlPos": "200.0", "totalRawUsd": "800.0"},
+            "crossMaintenanceMarginUsed": "4.0",
+            "withdrawable": "960.0"
+        }))
+        .unwrap();
+        let open_orders = vec![OpenOrdersResponse {
+            coin: "ETH".to_string(),
+            limit_px: "1900.0".to_string(),
+            oid: 7,
+            side: "B".to_string(),
+            sz: "0.5".to_string(),
+            timestamp: time,
+        }];
+        AccountSnapshot::from_responses(
+            Address::with_last_byte(1),
+            time,
+            user_state,
+            UserTokenBalanceResponse {
+                balances: Vec::new(),
+            },
+            open_orders,
+        )
+    }
+
+    #[test]
+    fn test_export_hash_chain() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("hl_snapshot_export_{}", now_timestamp_ms()));
+        let mut exporter =
+            SnapshotExporter::new(vec![], &dir, ExportFormat::Csv, Duration::from_secs(60))?;
+        let first = exporter.write(1000, &[snapshot(1000)])?;
+        assert_eq!(first.previous_hash, B256::ZERO);
+        assert_eq!(
+            fs::read_to_string(dir.join(&first.file)).unwrap(),
+            "time,user,kind,coin,oid,side,sz,px\n\
+             1000,0x0000000000000000000000000000000000000001,position,ETH,,,0.1,2000.0\n\
+             1000,0x0000000000000000000000000000000000000001,order,ETH,7,B,0.5,1900.0\n"
+        );
+
+        // a new exporter picks the chain up where the last one stopped
+        let mut exporter =
+            SnapshotExporter::new(vec![], &dir, ExportFormat::Json, Duration::from_secs(60))?;
+        let second = exporter.write(2000, &[snapshot(2000)])?;
+        assert_eq!(second.previous_hash, first.hash);
+        assert_eq!(verify_export_chain(&dir)?, 2);
+
+        fs::write(dir.join(&first.file), "tampered").unwrap();
+        assert!(matches!(
+            verify_export_chain(&dir),
+            Err(Error::SnapshotExport(_))
+        ));
+        fs::remove_dir_all(dir).unwrap();
+        Ok(())
+    }
+}
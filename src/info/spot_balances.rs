@@ -0,0 +1,217 @@
+use crate::Address;
+#[cfg(feature = "ws")]
+use crate::OrderUpdate;
+use crate::{helpers::parse_decimal, prelude::*, InfoClient};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpotBalance {
+    pub total: Decimal,
+    /// Amount on hold as reported by the exchange
+    pub hold: Decimal,
+    /// Amount reserved by the open orders this tracker knows about
+    pub reserved: Decimal,
+}
+
+impl SpotBalance {
+    /// Amount available for new orders or transfers
+    pub fn free(&self) -> Decimal {
+        (self.total - self.hold.max(self.reserved)).max(Decimal::ZERO)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SpotOpenOrder {
+    pair: String,
+    is_buy: bool,
+    limit_px: Decimal,
+    sz: Decimal,
+}
+
+/// Tracks free vs reserved spot balances per token for a user.
+/// Balances are loaded from `user_token_balances` and kept up to date from ws order updates.
+#[derive(Debug)]
+pub struct SpotBalances {
+    pub user: Address,
+    pair_to_tokens: HashMap<String, (String, String)>,
+    balances: HashMap<String, SpotBalance>,
+    open_orders: HashMap<u64, SpotOpenOrder>,
+}
+
+impl SpotBalances {
+    pub async fn new(info_client: &InfoClient, user: Address) -> Result<SpotBalances> {
+        let pair_to_tokens = info_client.spot_meta().await?.pair_to_token_names();
+        let mut spot_balances = SpotBalances {
+            user,
+            pair_to_tokens,
+            balances: HashMap::new(),
+            open_orders: HashMap::new(),
+        };
+        spot_balances.refresh(info_client).await?;
+        Ok(spot_balances)
+    }
+
+    /// Reloads token balances and open spot orders from the exchange
+    pub async fn refresh(&mut self, info_client: &InfoClient) -> Result<()> {
+        let token_balances = info_client.user_token_balances(self.user).await?;
+        let open_orders = info_client.open_orders(self.user).await?;
+
+        let mut balances = HashMap::new();
+        for balance in token_balances.balances {
+            balances.insert(
+                balance.coin,
+                SpotBalance {
+                    total: parse_decimal(&balance.total)?,
+                    hold: parse_decimal(&balance.hold)?,
+                    reserved: Decimal::ZERO,
+                },
+            );
+        }
+        self.balances = balances;
+
+        self.open_orders.clear();
+        for order in open_orders {
+            if self.pair_to_tokens.contains_key(&order.coin) {
+                self.open_orders.insert(
+                    order.oid,
+                    SpotOpenOrder {
+                        pair: order.coin,
+                        is_buy: order.side == "B",
+                        limit_px: parse_decimal(&order.limit_px)?,
+                        sz: parse_decimal(&order.sz)?,
+                    },
+                );
+            }
+        }
+        self.recompute_reserved();
+        Ok(())
+    }
+
+    /// Applies ws order updates to the reserved amounts, refreshing balances once an order fills
+    #[cfg(feature = "ws")]
+    pub async fn apply_order_updates(
+        &mut self,
+        info_client: &InfoClient,
+        updates: &[OrderUpdate],
+    ) -> Result<()> {
+        let mut filled = false;
+        for update in updates {
+            if !self.pair_to_tokens.contains_key(&update.order.coin) {
+                continue;
+            }
+            match update.status.as_str() {
+                "open" => {
+                    self.open_orders.insert(
+                        update.order.oid,
+                        SpotOpenOrder {
+                            pair: update.order.coin.clone(),
+                            is_buy: update.order.side == "B",
+                            limit_px: parse_decimal(&update.order.limit_px)?,
+                            sz: parse_decimal(&update.order.sz)?,
+                        },
+                    );
+                }
+                status => {
+                    self.open_orders.remove(&update.order.oid);
+                    filled |= status == "filled";
+                }
+            }
+        }
+
+        if filled {
+            self.refresh(info_client).await
+        } else {
+            self.recompute_reserved();
+            Ok(())
+        }
+    }
+
+    fn recompute_reserved(&mut self) {
+        for balance in self.balances.values_mut() {
+            balance.reserved = Decimal::ZERO;
+        }
+        for order in self.open_orders.values() {
+            let Some((base, quote)) = self.pair_to_tokens.get(&order.pair) else {
+                continue;
+            };
+            let (token, amount) = if order.is_buy {
+                (quote, order.sz * order.limit_px)
+            } else {
+                (base, order.sz)
+            };
+            self.balances.entry(token.clone()).or_default().reserved += amount;
+        }
+    }
+
+    pub fn balance(&self, token: &str) -> Option<&SpotBalance> {
+        self.balances.get(token)
+    }
+
+    pub fn balances(&self) -> &HashMap<String, SpotBalance> {
+        &self.balances
+    }
+
+    pub fn free(&self, token: &str) -> Decimal {
+        self.balances
+            .get(token)
+            .map(SpotBalance::free)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserved_and_free_balances() {
+        let mut spot_balances = SpotBalances {
+            user: Address::ZERO,
+            pair_to_tokens: HashMap::from([(
+                "PURR/USDC".to_string(),
+                ("PURR".to_string(), "USDC".to_string()),
+            )]),
+            balances: HashMap::from([
+                (
+                    "USDC".to_string(),
+                    SpotBalance {
+                        total: Decimal::from(100),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "PURR".to_string(),
+                    SpotBalance {
+                        total: Decimal::from(50),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            open_orders: HashMap::new(),
+        };
+        spot_balances.open_orders.insert(
+            1,
+            SpotOpenOrder {
+                pair: "PURR/USDC".to_string(),
+                is_buy: true,
+                limit_px: Decimal::new(5, 1),
+                sz: Decimal::from(40),
+            },
+        );
+        spot_balances.open_orders.insert(
+            2,
+            SpotOpenOrder {
+                pair: "PURR/USDC".to_string(),
+                is_buy: false,
+                limit_px: Decimal::new(6, 1),
+                sz: Decimal::from(10),
+            },
+        );
+        spot_balances.recompute_reserved();
+
+        assert_eq!(spot_balances.free("USDC"), Decimal::from(80));
+        assert_eq!(spot_balances.free("PURR"), Decimal::from(40));
+        assert_eq!(spot_balances.free("HYPE"), Decimal::ZERO);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    helpers::parse_decimal,
+    meta::{SpotAssetContext, SpotMeta, SpotMetaAndAssetCtxs},
+    prelude::*,
+    Error, InfoClient,
+};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// A spot pair from `spotMeta` joined with its asset context
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpotMarket {
+    /// Name used by the exchange, e.g. `"PURR/USDC"` or `"@107"`
+    pub name: String,
+    pub index: usize,
+    pub base: String,
+    pub quote: String,
+    pub base_sz_decimals: u8,
+    pub is_canonical: bool,
+    pub mark_px: Decimal,
+    /// `None` when the book is empty on a side
+    pub mid_px: Option<Decimal>,
+    pub prev_day_px: Decimal,
+    /// 24h notional volume in the quote token
+    pub day_ntl_vlm: Decimal,
+    pub circulating_supply: Decimal,
+}
+
+/// Every spot market with typed prices and volumes, looked up by pair name
+#[derive(Debug, Clone, Default)]
+pub struct SpotMarketSnapshot {
+    markets: Vec<SpotMarket>,
+    by_name: HashMap<String, usize>,
+}
+
+pub(crate) fn split_spot_meta_and_asset_contexts(
+    response: Vec<SpotMetaAndAssetCtxs>,
+) -> Result<(SpotMeta, Vec<SpotAssetContext>)> {
+    let mut spot_meta = None;
+    let mut contexts = None;
+    for item in response {
+        match item {
+            SpotMetaAndAssetCtxs::SpotMeta(meta) => spot_meta = Some(meta),
+            SpotMetaAndAssetCtxs::Context(ctxs) => contexts = Some(ctxs),
+        }
+    }
+    match (spot_meta, contexts) {
+        (Some(spot_meta), Some(contexts)) => Ok((spot_meta, contexts)),
+        _ => Err(Error::GenericParse(
+            "spotMetaAndAssetCtxs response is missing meta or contexts".to_string(),
+        )),
+    }
+}
+
+impl SpotMarketSnapshot {
+    /// Joins universe entries with contexts, which the exchange returns in universe order
+    pub fn new(spot_meta: &SpotMeta, contexts: &[SpotAssetContext]) -> Result<SpotMarketSnapshot> {
+        if spot_meta.universe.len() != contexts.len() {
+            return Err(Error::GenericParse(format!(
+                "spot universe has {} pairs but {} asset contexts",
+                spot_meta.universe.len(),
+                contexts.len()
+            )));
+        }
+        let tokens: HashMap<usize, (&str, u8)> = spot_meta
+            .tokens
+            .iter()
+            .map(|token| (token.index, (token.name.as_str(), token.sz_decimals)))
+            .collect();
+
+        let mut snapshot = SpotMarketSnapshot::default();
+        for (pair, context) in spot_meta.universe.iter().zip(contexts) {
+            let (Some(&(base, base_sz_decimals)), Some(&(quote, _))) =
+                (tokens.get(&pair.tokens[0]), tokens.get(&pair.tokens[1]))
+            else {
+                continue;
+            };
+            let market = SpotMarket {
+                name: pair.name.clone(),
+                index: pair.index,
+                base: base.to_string(),
+                quote: quote.to_string(),
+                base_sz_decimals,
+                is_canonical: pair.is_canonical,
+                mark_px: parse_decimal(&context.mark_px)?,
+                mid_px: context.mid_px.as_deref().map(parse_decimal).transpose()?,
+                prev_day_px: parse_decimal(&context.prev_day_px)?,
+                day_ntl_vlm: parse_decimal(&context.day_ntl_vlm)?,
+                circulating_supply: parse_decimal(&context.circulating_supply)?,
+            };
+            let position = snapshot.markets.len();
+            snapshot.by_name.insert(market.name.clone(), position);
+            snapshot
+                .by_name
+                .insert(format!("{}/{}", market.base, market.quote), position);
+            snapshot.markets.push(market);
+        }
+        Ok(snapshot)
+    }
+
+    /// Looks up a market by its exchange name (`"@107"`) or by `"BASE/QUOTE"`
+    pub fn get(&self, pair: &str) -> Option<&SpotMarket> {
+        self.by_name
+            .get(pair)
+            .map(|&position| &self.markets[position])
+    }
+
+    pub fn markets(&self) -> &[SpotMarket] {
+        &self.markets
+    }
+}
+
+impl InfoClient {
+    pub async fn spot_market_snapshot(&self) -> Result<SpotMarketSnapshot> {
+        let (spot_meta, contexts) =
+            split_spot_meta_and_asset_contexts(self.spot_meta_and_asset_contexts().await?)?;
+        SpotMarketSnapshot::new(&spot_meta, &contexts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spot_market_snapshot() {
+        let response: Vec<SpotMetaAndAssetCtxs> = serde_json::from_value(serde_json::json!([
+            {
+                "universe": [
+                    {"tokens": [1, 0], "name": "PURR/USDC", "index": 0, "isCanonical": true},
+                    {"tokens": [2, 0], "name": "@1", "index": 1, "isCanonical": false}
+                ],
+                "tokens": [
+                    {"name": "USDC", "szDecimals": 8, "weiDecimals": 8, "index": 0, "tokenId": "0x6d1e7cde53ba9467b783cb7c530ce054", "isCanonical": true},
+                    {"name": "PURR", "szDecimals": 0, "weiDecimals": 5, "index": 1, "tokenId": "0xc1fb593aeffbeb02f85e0308e9956a90", "isCanonical": true},
+                    {"name": "HFUN", "szDecimals": 2, "weiDecimals": 8, "index": 2, "tokenId": "0xbaf265ef389da684513d98d68edf4eae", "isCanonical": false}
+                ]
+            },
+            [
+                {"dayNtlVlm": "1000.5", "markPx": "0.2", "midPx": "0.2001", "prevDayPx": "0.19", "circulatingSupply": "600000000", "coin": "PURR/USDC"},
+                {"dayNtlVlm": "0", "markPx": "50", "midPx": null, "prevDayPx": "50", "circulatingSupply": "1000", "coin": "@1"}
+            ]
+        ]))
+        .unwrap();
+        let (spot_meta, contexts) = split_spot_meta_and_asset_contexts(response).unwrap();
+        let snapshot = SpotMarketSnapshot::new(&spot_meta, &contexts).unwrap();
+
+        assert_eq!(snapshot.markets().len(), 2);
+        let purr = snapshot.get("PURR/USDC").unwrap();
+        assert_eq!(purr.mid_px, Some(Decimal::new(2001, 4)));
+        assert_eq!(purr.day_ntl_vlm, Decimal::new(10005, 1));
+        let hfun = snapshot.get("HFUN/USDC").unwrap();
+        assert_eq!(hfun, snapshot.get("@1").unwrap());
+        assert_eq!(hfun.base_sz_decimals, 2);
+        assert_eq!(hfun.mid_px, None);
+        assert!(snapshot.get("HFUN").is_none());
+    }
+}
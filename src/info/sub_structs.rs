@@ -1,5 +1,29 @@
-use ethers::types::H160;
-use serde::Deserialize;
+use crate::Address;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Time in force of a limit order
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tif {
+    /// Add liquidity only, cancelled instead of crossing the book
+    Alo,
+    /// Immediate or cancel
+    Ioc,
+    /// Good til cancelled
+    Gtc,
+}
+
+/// Tif of a limit order, `None` for trigger orders, which have none, and for the tifs only the
+/// exchange gives orders, like `FrontendMarket`
+fn limit_tif<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Tif>, D::Error> {
+    Ok(
+        match Option::<String>::deserialize(deserializer)?.as_deref() {
+            Some("Alo") => Some(Tif::Alo),
+            Some("Ioc") => Some(Tif::Ioc),
+            Some("Gtc") => Some(Tif::Gtc),
+            _ => None,
+        },
+    )
+}
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -129,14 +153,17 @@ pub struct BasicOrderInfo {
     pub reduce_only: bool,
     pub order_type: String,
     pub orig_sz: String,
-    pub tif: String,
+    /// `None` for trigger orders and orders the exchange placed with a tif of its own, like
+    /// `FrontendMarket` or `LiquidationMarket`
+    #[serde(deserialize_with = "limit_tif")]
+    pub tif: Option<Tif>,
     pub cloid: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Referrer {
-    pub referrer: H160,
+    pub referrer: Address,
     pub code: String,
 }
 
@@ -0,0 +1,156 @@
+use crate::{prelude::*, Address, InfoClient, UserFillsResponse};
+use futures_util::{stream, Stream};
+
+/// Max fills returned by one `userFillsByTime` request
+pub const USER_FILLS_PAGE_SIZE: usize = 2000;
+
+/// Walks the fills of a user in a time range one page at a time, oldest first.
+/// Each page starts at the time of the previous page's last fill, so fills sharing
+/// that millisecond are not lost; fills already returned are skipped by trade id.
+#[derive(Debug)]
+pub struct UserFillsPager<'a> {
+    info_client: &'a InfoClient,
+    user: Address,
+    start_time: u64,
+    end_time: Option<u64>,
+    /// Trade ids already returned at `start_time`
+    seen_at_start: Vec<u64>,
+    done: bool,
+}
+
+impl<'a> UserFillsPager<'a> {
+    pub fn new(
+        info_client: &'a InfoClient,
+        user: Address,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> UserFillsPager<'a> {
+        UserFillsPager {
+            info_client,
+            user,
+            start_time,
+            end_time,
+            seen_at_start: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Next page of fills, `None` once the range is exhausted
+    pub async fn next_page(&mut self) -> Result<Option<Vec<UserFillsResponse>>> {
+        if self.done {
+            return Ok(None);
+        }
+        let page = self
+            .info_client
+            .user_fills_by_time_page(self.user, self.start_time, self.end_time)
+            .await?;
+        let page = self.advance(page);
+        Ok(if page.is_empty() { None } else { Some(page) })
+    }
+
+    /// The pages as a stream, ending once the range is exhausted or after the first error
+    pub fn into_stream(self) -> impl Stream<Item = Result<Vec<UserFillsResponse>>> + 'a {
+        stream::unfold(Some(self), |pager| async move {
+            let mut pager = pager?;
+            match pager.next_page().await {
+                Ok(Some(page)) => Some((Ok(page), Some(pager))),
+                Ok(None) => None,
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+    }
+
+    /// Drops fills returned by the previous page and moves the cursor past this one
+    fn advance(&mut self, page: Vec<UserFillsResponse>) -> Vec<UserFillsResponse> {
+        let full_page = page.len() >= USER_FILLS_PAGE_SIZE;
+        let page: Vec<UserFillsResponse> = page
+            .into_iter()
+            .filter(|fill| fill.time != self.start_time || !self.seen_at_start.contains(&fill.tid))
+            .collect();
+
+        // a full page of already seen fills means more fills share one millisecond than fit in a page
+        if !full_page || page.is_empty() {
+            self.done = true;
+            return page;
+        }
+
+        let last_time = page[page.len() - 1].time;
+        if last_time != self.start_time {
+            self.start_time = last_time;
+            self.seen_at_start.clear();
+        }
+        self.seen_at_start.extend(
+            page.iter()
+                .filter(|fill| fill.time == last_time)
+                .map(|fill| fill.tid),
+        );
+        page
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::Client;
+
+    fn fill(time: u64, tid: u64) -> UserFillsResponse {
+        UserFillsResponse {
+            closed_pnl: "0".to_string(),
+            coin: "ETH".to_string(),
+            crossed: true,
+            dir: "Open Long".to_string(),
+            hash: "0x".to_string(),
+            oid: tid,
+            cloid: None,
+            px: "2000".to_string(),
+            side: "B".to_string(),
+            start_position: "0".to_string(),
+            sz: "1".to_string(),
+            time,
+            fee: "0".to_string(),
+            fee_token: None,
+            tid,
+        }
+    }
+
+    #[test]
+    fn test_pager_skips_fills_at_page_boundary() {
+        let info_client = InfoClient::from_http_client(crate::req::HttpClient {
+            client: Client::new(),
+            base_url: String::new(),
+        });
+        let mut pager = UserFillsPager::new(&info_client, Address::ZERO, 0, None);
+
+        // full page ending with two fills at t=100
+        let mut page: Vec<_> = (0..USER_FILLS_PAGE_SIZE as u64 - 2)
+            .map(|tid| fill(tid / 100, tid))
+            .collect();
+        page.push(fill(100, 5000));
+        page.push(fill(100, 5001));
+        assert_eq!(pager.advance(page).len(), USER_FILLS_PAGE_SIZE);
+        assert_eq!(pager.start_time, 100);
+        assert!(!pager.done);
+
+        // the next page repeats the fills at t=100
+        let page = pager.advance(vec![fill(100, 5000), fill(100, 5001), fill(101, 5002)]);
+        assert_eq!(page.iter().map(|fill| fill.tid).collect::<Vec<_>>(), [5002]);
+        assert!(pager.done);
+    }
+
+    #[tokio::test]
+    async fn test_stream_ends_after_an_error() {
+        use futures_util::StreamExt;
+
+        // nothing listens there
+        let info_client = InfoClient::from_http_client(crate::req::HttpClient {
+            client: Client::new(),
+            base_url: "http://127.0.0.1:1".to_string(),
+        });
+        let pages: Vec<_> = UserFillsPager::new(&info_client, Address::ZERO, 0, None)
+            .into_stream()
+            .collect()
+            .await;
+        assert_eq!(pages.len(), 1);
+        assert!(pages[0].is_err());
+    }
+}
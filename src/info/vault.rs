@@ -0,0 +1,167 @@
+use crate::{helpers::parse_decimal, prelude::*, Address};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+/// Response of `vaultDetails`
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDetails {
+    pub name: String,
+    pub vault_address: Address,
+    pub leader: Address,
+    #[serde(default)]
+    pub description: String,
+    /// `(period, portfolio)` pairs, periods being `"day"`, `"week"`, `"month"`, `"allTime"` and
+    /// their `"perp"` prefixed variants
+    #[serde(default)]
+    pub portfolio: Vec<(String, VaultPortfolio)>,
+    /// Current annualized return as a fraction
+    pub apr: f64,
+    /// State of the user given in the request, if they follow the vault
+    #[serde(default)]
+    pub follower_state: Option<VaultFollower>,
+    /// Share of the vault owned by the leader
+    pub leader_fraction: f64,
+    /// Share of follower profits paid to the leader
+    pub leader_commission: f64,
+    #[serde(default)]
+    pub followers: Vec<VaultFollower>,
+    #[serde(default)]
+    pub is_closed: bool,
+    #[serde(default)]
+    pub allow_deposits: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultPortfolio {
+    /// `(time in ms, account value)` points
+    pub account_value_history: Vec<(u64, String)>,
+    /// `(time in ms, cumulative pnl)` points, at the same times as `account_value_history`
+    pub pnl_history: Vec<(u64, String)>,
+    pub vlm: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultFollower {
+    pub user: Address,
+    pub vault_equity: String,
+    pub pnl: String,
+    pub all_time_pnl: String,
+    pub days_following: u64,
+    /// Time of the first deposit in milliseconds
+    pub vault_entry_time: u64,
+    pub lockup_until: u64,
+}
+
+impl VaultDetails {
+    pub fn portfolio(&self, period: &str) -> Option<&VaultPortfolio> {
+        self.portfolio
+            .iter()
+            .find(|(name, _)| name == period)
+            .map(|(_, portfolio)| portfolio)
+    }
+
+    pub fn follower(&self, user: Address) -> Option<&VaultFollower> {
+        self.followers.iter().find(|follower| follower.user == user)
+    }
+}
+
+impl VaultPortfolio {
+    /// Value of one unit invested at the first point, at every point: each step compounds the
+    /// pnl made over it relative to the account value at its start, so deposits and withdrawals
+    /// don't count as returns
+    pub fn share_index(&self) -> Result<Vec<(u64, Decimal)>> {
+        let mut index = Vec::with_capacity(self.pnl_history.len());
+        let mut value = Decimal::ONE;
+        let mut previous: Option<(Decimal, Decimal)> = None;
+        for ((time, account_value), (_, pnl)) in
+            self.account_value_history.iter().zip(&self.pnl_history)
+        {
+            let account_value = parse_decimal(account_value)?;
+            let pnl = parse_decimal(pnl)?;
+            if let Some((previous_account_value, previous_pnl)) = previous {
+                if !previous_account_value.is_zero() {
+                    value *= Decimal::ONE + (pnl - previous_pnl) / previous_account_value;
+                }
+            }
+            index.push((*time, value));
+            previous = Some((account_value, pnl));
+        }
+        Ok(index)
+    }
+
+    /// Value of `follower`'s current equity at every point since they entered, assuming no
+    /// deposits or withdrawals of theirs in between
+    pub fn follower_share_value(&self, follower: &VaultFollower) -> Result<Vec<(u64, Decimal)>> {
+        let index = self.share_index()?;
+        let Some(&(_, latest)) = index.last() else {
+            return Ok(Vec::new());
+        };
+        if latest.is_zero() {
+            return Ok(Vec::new());
+        }
+        let shares = parse_decimal(&follower.vault_equity)? / latest;
+        Ok(index
+            .into_iter()
+            .filter(|(time, _)| *time >= follower.vault_entry_time)
+            .map(|(time, value)| (time, shares * value))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follower_share_value() {
+        let details: VaultDetails = serde_json::from_value(serde_json::json!({
+            "name": "Test vault",
+            "vaultAddress": "0xdfc24b077bc1425ad1dea75bcb6f8158e10df303",
+            "leader": "0x677d831aef5328190852e24f13c46cac05f984e7",
+            "description": "",
+            "portfolio": [["allTime", {
+                "accountValueHistory": [[1000, "100.0"], [2000, "210.0"], [3000, "231.0"]],
+                "pnlHistory": [[1000, "0.0"], [2000, "10.0"], [3000, "31.0"]],
+                "vlm": "0.0"
+            }]],
+            "apr": 0.5,
+            "followerState": null,
+            "leaderFraction": 0.1,
+            "leaderCommission": 0.1,
+            "followers": [{
+                "user": "0x0000000000000000000000000000000000000001",
+                "vaultEquity": "110.0",
+                "pnl": "10.0",
+                "allTimePnl": "10.0",
+                "daysFollowing": 1,
+                "vaultEntryTime": 2000,
+                "lockupUntil": 2000
+            }],
+            "maxDistributable": 0.0,
+            "isClosed": false,
+            "allowDeposits": true
+        }))
+        .unwrap();
+
+        let portfolio = details.portfolio("allTime").unwrap();
+        // +10% on 100, then +10% on 210 after a deposit of 100
+        assert_eq!(
+            portfolio.share_index().unwrap(),
+            vec![
+                (1000, Decimal::ONE),
+                (2000, Decimal::new(11, 1)),
+                (3000, Decimal::new(121, 2))
+            ]
+        );
+
+        let follower = details.follower(Address::with_last_byte(1)).unwrap();
+        assert_eq!(
+            portfolio.follower_share_value(follower).unwrap(),
+            vec![(2000, Decimal::new(100, 0)), (3000, Decimal::new(110, 0))]
+        );
+        assert!(details.follower(Address::with_last_byte(2)).is_none());
+    }
+}
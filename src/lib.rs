@@ -1,21 +1,65 @@
 #![deny(unreachable_pub)]
+mod address;
+#[cfg(feature = "bridge")]
+pub mod bridge;
+#[cfg(feature = "ethers-compat")]
+pub mod compat;
 mod consts;
 mod errors;
+#[cfg(feature = "exchange")]
 mod exchange;
+#[cfg(feature = "info")]
+mod faucet;
 mod helpers;
+#[cfg(feature = "info")]
 mod info;
-mod market_maker;
+pub mod limits;
 mod meta;
+#[cfg(feature = "notify")]
+pub mod notify;
 mod prelude;
-mod proxy_digest;
+#[cfg(all(feature = "exchange", feature = "ws"))]
+mod quickstart;
+#[cfg(feature = "info")]
+mod rate_limit;
+#[cfg(feature = "info")]
 mod req;
+#[cfg(feature = "exchange")]
 mod signature;
+#[cfg(all(feature = "exchange", feature = "ws"))]
+mod strategies;
+mod symbol;
+mod units;
+#[cfg(feature = "ws")]
 mod ws;
+pub use address::Address;
 pub use consts::{EPSILON, LOCAL_API_URL, MAINNET_API_URL, TESTNET_API_URL};
 pub use errors::Error;
+#[cfg(feature = "exchange")]
 pub use exchange::*;
+#[cfg(feature = "info")]
+pub use faucet::TestnetFaucet;
+#[allow(deprecated)]
 pub use helpers::{bps_diff, truncate_float, BaseUrl};
+#[cfg(feature = "info")]
 pub use info::{info_client::*, *};
-pub use market_maker::{MarketMaker, MarketMakerInput, MarketMakerRestingOrder};
-pub use meta::{AssetMeta, Meta};
+pub use limits::{HyperliquidChain, NetworkConfig};
+pub use meta::{
+    AssetContext, AssetMeta, EvmContract, MarginTable, MarginTier, Meta, MetaAndAssetCtxs, PerpDex,
+    PerpDexLimits, PerpDexLimitsResponse, PerpDexMeta, PerpDexsResponse, SpotAssetMeta, SpotMeta,
+    TokenInfo,
+};
+#[cfg(all(feature = "exchange", feature = "ws"))]
+pub use quickstart::{Hyperliquid, Network, DEFAULT_REQUEST_TIMEOUT};
+#[cfg(feature = "info")]
+pub use rate_limit::{RateLimitConfig, RateLimiter};
+#[cfg(feature = "info")]
+pub use req::RequestOptions;
+#[cfg(feature = "exchange")]
+pub use signature::{verify_l1_action_signature, verify_user_signed_action_signature};
+#[cfg(all(feature = "exchange", feature = "ws"))]
+pub use strategies::*;
+pub use symbol::{Coin, SpotPair};
+pub use units::{Px, Sz, Usd};
+#[cfg(feature = "ws")]
 pub use ws::*;
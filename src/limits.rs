@@ -0,0 +1,135 @@
+//! Limits and numbering schemes the exchange enforces. [`NetworkConfig`] overrides the ones
+//! that differ on forks and test environments.
+
+/// Decimals of sizes and prices sent to the exchange
+pub const MAX_WIRE_DECIMALS: u32 = 8;
+/// Perp prices have at most `MAX_PERP_PX_DECIMALS - sz_decimals` decimals
+pub const MAX_PERP_PX_DECIMALS: u32 = 6;
+/// Spot prices have at most `MAX_SPOT_PX_DECIMALS - sz_decimals` decimals
+pub const MAX_SPOT_PX_DECIMALS: u32 = 8;
+/// Significant figures allowed in prices that are not integers
+pub const MAX_PX_SIG_FIGS: u32 = 5;
+
+/// Minimum value in USDC of orders that are not reduce-only
+pub const MIN_ORDER_NOTIONAL: f64 = 10.0;
+
+/// Spot asset ids are this plus the index of the pair in the spot universe
+pub const SPOT_ASSET_OFFSET: u32 = 10_000;
+/// Builder-deployed perp asset ids are `BUILDER_PERP_ASSET_OFFSET + dex_index *
+/// PERP_DEX_ASSET_STRIDE + asset_index`
+pub const BUILDER_PERP_ASSET_OFFSET: u32 = 100_000;
+pub const PERP_DEX_ASSET_STRIDE: u32 = 10_000;
+
+/// Chain id in the EIP-712 domain of user-signed actions (Arbitrum Sepolia)
+pub const USER_SIGNATURE_CHAIN_ID: u64 = 421_614;
+/// Chain id in the EIP-712 domain of L1 actions, fixed by the exchange
+pub const L1_SIGNATURE_CHAIN_ID: u64 = 1337;
+
+/// Network named in the `hyperliquidChain` field of user-signed actions and in the source of
+/// L1 action signatures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HyperliquidChain {
+    Mainnet,
+    Testnet,
+}
+
+impl HyperliquidChain {
+    pub fn is_mainnet(self) -> bool {
+        self == HyperliquidChain::Mainnet
+    }
+}
+
+impl std::fmt::Display for HyperliquidChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HyperliquidChain::Mainnet => write!(f, "Mainnet"),
+            HyperliquidChain::Testnet => write!(f, "Testnet"),
+        }
+    }
+}
+
+/// Per-client overrides of the limits above, defaulting to the mainnet and testnet values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkConfig {
+    pub spot_asset_offset: u32,
+    pub builder_perp_asset_offset: u32,
+    pub perp_dex_asset_stride: u32,
+    pub max_perp_px_decimals: u32,
+    pub max_spot_px_decimals: u32,
+    pub max_px_sig_figs: u32,
+    pub user_signature_chain_id: u64,
+    /// Chain actions are signed for, `None` to sign for mainnet exactly when the client uses
+    /// the mainnet url. Set it when reaching a network through a proxy or local node.
+    pub hyperliquid_chain: Option<HyperliquidChain>,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> NetworkConfig {
+        NetworkConfig {
+            spot_asset_offset: SPOT_ASSET_OFFSET,
+            builder_perp_asset_offset: BUILDER_PERP_ASSET_OFFSET,
+            perp_dex_asset_stride: PERP_DEX_ASSET_STRIDE,
+            max_perp_px_decimals: MAX_PERP_PX_DECIMALS,
+            max_spot_px_decimals: MAX_SPOT_PX_DECIMALS,
+            max_px_sig_figs: MAX_PX_SIG_FIGS,
+            user_signature_chain_id: USER_SIGNATURE_CHAIN_ID,
+            hyperliquid_chain: None,
+        }
+    }
+}
+
+impl NetworkConfig {
+    pub fn spot_asset_id(&self, pair_index: usize) -> u32 {
+        self.spot_asset_offset + pair_index as u32
+    }
+
+    /// Asset id of the `asset_index`th perp of the dex at `dex_index` in `perpDexs`,
+    /// 0 being the default dex
+    pub fn perp_asset_id(&self, dex_index: usize, asset_index: usize) -> u32 {
+        if dex_index == 0 {
+            asset_index as u32
+        } else {
+            self.builder_perp_asset_offset
+                + dex_index as u32 * self.perp_dex_asset_stride
+                + asset_index as u32
+        }
+    }
+
+    pub fn is_spot_asset(&self, asset: u32) -> bool {
+        asset >= self.spot_asset_offset && asset < self.builder_perp_asset_offset
+    }
+
+    /// Most decimals a price of an asset with `sz_decimals` may have
+    pub fn max_px_decimals(&self, asset: u32, sz_decimals: u32) -> u32 {
+        let max_decimals = if self.is_spot_asset(asset) {
+            self.max_spot_px_decimals
+        } else {
+            self.max_perp_px_decimals
+        };
+        max_decimals.saturating_sub(sz_decimals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_config_asset_ids() {
+        let network = NetworkConfig::default();
+        assert_eq!(network.spot_asset_id(3), 10_003);
+        assert_eq!(network.perp_asset_id(0, 3), 3);
+        assert_eq!(network.perp_asset_id(1, 3), 110_003);
+        assert!(network.is_spot_asset(10_003));
+        assert!(!network.is_spot_asset(110_003));
+        assert_eq!(network.max_px_decimals(3, 4), 2);
+        assert_eq!(network.max_px_decimals(10_003, 4), 4);
+
+        let fork = NetworkConfig {
+            spot_asset_offset: 20_000,
+            ..network
+        };
+        assert_eq!(fork.spot_asset_id(3), 20_003);
+        assert!(!fork.is_spot_asset(10_003));
+    }
+}
@@ -1,11 +1,165 @@
 use std::collections::HashMap;
 
-use ethers::abi::ethereum_types::H128;
+use crate::{helpers::parse_decimal, limits, prelude::*, Address, Error, NetworkConfig};
+use alloy::primitives::B128;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct Meta {
     pub universe: Vec<AssetMeta>,
+    /// `(margin_table_id, table)` pairs, empty for responses without margin tables
+    #[serde(default)]
+    pub margin_tables: Vec<(u32, MarginTable)>,
+}
+
+impl Meta {
+    /// Ids below this with no listed table stand for a single tier with that max leverage
+    pub const SINGLE_TIER_MARGIN_TABLE_IDS: u32 = 50;
+
+    /// Margin table of `coin`, resolved from its `margin_table_id`
+    pub fn margin_table(&self, coin: &str) -> Option<MarginTable> {
+        let asset = self.universe.iter().find(|asset| asset.name == coin)?;
+        let id = asset.margin_table_id?;
+        if let Some((_, table)) = self
+            .margin_tables
+            .iter()
+            .find(|(table_id, _)| *table_id == id)
+        {
+            return Some(table.clone());
+        }
+        (id > 0 && id < Self::SINGLE_TIER_MARGIN_TABLE_IDS).then(|| MarginTable {
+            description: String::new(),
+            margin_tiers: vec![MarginTier {
+                lower_bound: Decimal::ZERO,
+                max_leverage: id,
+            }],
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginTable {
+    pub description: String,
+    /// Sorted by ascending `lower_bound`
+    pub margin_tiers: Vec<MarginTier>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginTier {
+    /// Position notional in USDC from which this tier applies
+    pub lower_bound: Decimal,
+    pub max_leverage: u32,
+}
+
+impl MarginTier {
+    /// Maintenance margin rate, half of the initial margin at max leverage
+    pub fn maintenance_margin_rate(&self) -> Decimal {
+        Decimal::ONE / Decimal::from(2 * self.max_leverage.max(1))
+    }
+}
+
+impl MarginTable {
+    fn tier(&self, notional: Decimal) -> Option<(usize, &MarginTier)> {
+        self.margin_tiers
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, tier)| notional >= tier.lower_bound)
+    }
+
+    /// Max leverage allowed for a position of `notional` USDC
+    pub fn max_leverage(&self, notional: Decimal) -> Option<u32> {
+        self.tier(notional.abs()).map(|(_, tier)| tier.max_leverage)
+    }
+
+    /// Maintenance margin of a position of `notional` USDC.
+    /// Each tier's rate applies to the whole notional, less a deduction that keeps margin continuous across tiers.
+    pub fn maintenance_margin(&self, notional: Decimal) -> Decimal {
+        let notional = notional.abs();
+        let Some((index, tier)) = self.tier(notional) else {
+            return Decimal::ZERO;
+        };
+        let deduction: Decimal = self.margin_tiers[1..=index]
+            .iter()
+            .zip(&self.margin_tiers[..index])
+            .map(|(tier, previous)| {
+                tier.lower_bound
+                    * (tier.maintenance_margin_rate() - previous.maintenance_margin_rate())
+            })
+            .sum();
+        notional * tier.maintenance_margin_rate() - deduction
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PerpDex {
+    pub name: String,
+    pub full_name: String,
+    pub deployer: Address,
+    pub oracle_updater: Option<Address>,
+}
+
+/// The first entry is always `None`, representing the default perp dex
+pub type PerpDexsResponse = Vec<Option<PerpDex>>;
+
+/// Open interest and transfer caps of a builder-deployed perp dex
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PerpDexLimits {
+    pub total_oi_cap: String,
+    pub oi_sz_cap_per_perp: String,
+    pub max_transfer_ntl: String,
+    /// `(coin, open interest cap)` of perps with a cap of their own
+    #[serde(default)]
+    pub coin_to_oi_cap: Vec<(String, String)>,
+}
+
+/// `None` for the default perp dex and unknown dexes
+pub type PerpDexLimitsResponse = Option<PerpDexLimits>;
+
+/// Meta for a single perp dex along with its position in `perpDexs`
+#[derive(Debug, Clone)]
+pub struct PerpDexMeta {
+    pub dex: String,
+    pub dex_index: usize,
+    pub meta: Meta,
+}
+
+impl PerpDexMeta {
+    pub const BUILDER_PERP_ASSET_OFFSET: u32 = limits::BUILDER_PERP_ASSET_OFFSET;
+    pub const PERP_DEX_ASSET_STRIDE: u32 = limits::PERP_DEX_ASSET_STRIDE;
+
+    /// Asset id of the `asset_index`th entry in this dex's universe.
+    /// Builder-deployed dexes use `100000 + dex_index * 10000 + asset_index`.
+    pub fn asset_id(&self, asset_index: usize) -> u32 {
+        NetworkConfig::default().perp_asset_id(self.dex_index, asset_index)
+    }
+
+    pub fn add_perp_to_asset_map(
+        &self,
+        coin_to_asset: HashMap<String, u32>,
+    ) -> HashMap<String, u32> {
+        self.add_perp_to_asset_map_for(coin_to_asset, &NetworkConfig::default())
+    }
+
+    pub(crate) fn add_perp_to_asset_map_for(
+        &self,
+        mut coin_to_asset: HashMap<String, u32>,
+        network: &NetworkConfig,
+    ) -> HashMap<String, u32> {
+        for (asset_index, asset) in self.meta.universe.iter().enumerate() {
+            coin_to_asset.insert(
+                asset.name.clone(),
+                network.perp_asset_id(self.dex_index, asset_index),
+            );
+        }
+        coin_to_asset
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -16,8 +170,16 @@ pub struct SpotMeta {
 
 impl SpotMeta {
     pub fn add_pair_and_name_to_index_map(
+        &self,
+        coin_to_asset: HashMap<String, u32>,
+    ) -> HashMap<String, u32> {
+        self.add_pair_and_name_to_index_map_for(coin_to_asset, &NetworkConfig::default())
+    }
+
+    pub(crate) fn add_pair_and_name_to_index_map_for(
         &self,
         mut coin_to_asset: HashMap<String, u32>,
+        network: &NetworkConfig,
     ) -> HashMap<String, u32> {
         let index_to_name: HashMap<usize, &str> = self
             .tokens
@@ -26,7 +188,7 @@ impl SpotMeta {
             .collect();
 
         for asset in self.universe.iter() {
-            let spot_ind: u32 = 10000 + asset.index as u32;
+            let spot_ind = network.spot_asset_id(asset.index);
             let name_to_ind = (asset.name.clone(), spot_ind);
 
             let Some(token_1_name) = index_to_name.get(&asset.tokens[0]) else {
@@ -43,8 +205,91 @@ impl SpotMeta {
 
         coin_to_asset
     }
+
+    pub fn token(&self, name: &str) -> Option<&TokenInfo> {
+        self.tokens.iter().find(|token| token.name == name)
+    }
+
+    /// Token linked to the HyperEVM contract at `address`
+    pub fn token_by_evm_address(&self, address: Address) -> Option<&TokenInfo> {
+        self.tokens
+            .iter()
+            .find(|token| token.evm_address() == Some(address))
+    }
+
+    pub fn token_by_id(&self, token_id: B128) -> Option<&TokenInfo> {
+        self.tokens.iter().find(|token| token.token_id == token_id)
+    }
+
+    /// Spot pairs with `token` as base or quote
+    pub fn pairs_with_token(&self, token: &TokenInfo) -> Vec<&SpotAssetMeta> {
+        self.universe
+            .iter()
+            .filter(|pair| pair.tokens.contains(&token.index))
+            .collect()
+    }
+
+    /// Token with id `token_id` and the spot pairs it trades in
+    pub fn pairs_by_token_id(&self, token_id: B128) -> Option<(&TokenInfo, Vec<&SpotAssetMeta>)> {
+        let token = self.token_by_id(token_id)?;
+        Some((token, self.pairs_with_token(token)))
+    }
+
+    /// Token linked to the HyperEVM contract at `address` and the spot pairs it trades in
+    pub fn pairs_by_evm_address(
+        &self,
+        address: Address,
+    ) -> Option<(&TokenInfo, Vec<&SpotAssetMeta>)> {
+        let token = self.token_by_evm_address(address)?;
+        Some((token, self.pairs_with_token(token)))
+    }
+
+    /// Maps each spot pair name to its (base, quote) token names
+    pub fn pair_to_token_names(&self) -> HashMap<String, (String, String)> {
+        let index_to_name: HashMap<usize, &str> = self
+            .tokens
+            .iter()
+            .map(|info| (info.index, info.name.as_str()))
+            .collect();
+
+        let mut pair_to_tokens = HashMap::new();
+        for asset in self.universe.iter() {
+            let (Some(base), Some(quote)) = (
+                index_to_name.get(&asset.tokens[0]),
+                index_to_name.get(&asset.tokens[1]),
+            ) else {
+                continue;
+            };
+            let tokens = (base.to_string(), quote.to_string());
+            pair_to_tokens.insert(format!("{base}/{quote}"), tokens.clone());
+            pair_to_tokens.insert(asset.name.clone(), tokens);
+        }
+        pair_to_tokens
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum MetaAndAssetCtxs {
+    Meta(Meta),
+    Context(Vec<AssetContext>),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetContext {
+    pub day_ntl_vlm: String,
+    pub funding: String,
+    pub impact_pxs: Option<Vec<String>>,
+    pub mark_px: String,
+    pub mid_px: Option<String>,
+    pub open_interest: String,
+    pub oracle_px: String,
+    pub premium: Option<String>,
+    pub prev_day_px: String,
 }
 
+#[cfg(feature = "info")]
 #[derive(Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum SpotMetaAndAssetCtxs {
@@ -52,6 +297,7 @@ pub enum SpotMetaAndAssetCtxs {
     Context(Vec<SpotAssetContext>),
 }
 
+#[cfg(feature = "info")]
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SpotAssetContext {
@@ -68,6 +314,10 @@ pub struct SpotAssetContext {
 pub struct AssetMeta {
     pub name: String,
     pub sz_decimals: u32,
+    #[serde(default)]
+    pub max_leverage: Option<u32>,
+    #[serde(default)]
+    pub margin_table_id: Option<u32>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -86,6 +336,313 @@ pub struct TokenInfo {
     pub sz_decimals: u8,
     pub wei_decimals: u8,
     pub index: usize,
-    pub token_id: H128,
+    pub token_id: B128,
     pub is_canonical: bool,
+    /// HyperEVM ERC-20 contract, if the token is linked
+    #[serde(default)]
+    pub evm_contract: Option<EvmContract>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct EvmContract {
+    pub address: Address,
+    /// EVM decimals minus the spot `wei_decimals`
+    pub evm_extra_wei_decimals: i32,
+}
+
+impl TokenInfo {
+    /// System address that moves the token between spot and HyperEVM when it receives a spot send
+    pub fn system_address(&self) -> Address {
+        if self.name == "HYPE" {
+            return Address::repeat_byte(0x22);
+        }
+        let mut bytes = [0u8; 20];
+        bytes[0] = 0x20;
+        bytes[12..].copy_from_slice(&(self.index as u64).to_be_bytes());
+        Address::from(bytes)
+    }
+
+    /// `NAME:0x<token id>` form used as the `token` of spot sends
+    pub fn spot_send_token(&self) -> String {
+        format!("{}:{}", self.name, self.token_id)
+    }
+
+    pub fn evm_address(&self) -> Option<Address> {
+        self.evm_contract.as_ref().map(|contract| contract.address)
+    }
+
+    /// Decimals of the ERC-20 contract, if linked
+    pub fn evm_decimals(&self) -> Option<i32> {
+        self.evm_contract
+            .as_ref()
+            .map(|contract| self.wei_decimals as i32 + contract.evm_extra_wei_decimals)
+    }
+
+    /// `amount` as sent in spot sends, rounded toward zero to `wei_decimals`. Fails for
+    /// negative amounts and amounts that round to zero.
+    pub fn format_amount(&self, amount: Decimal) -> Result<String> {
+        let rounded = round_toward_zero(amount, self.wei_decimals);
+        if rounded <= Decimal::ZERO {
+            return Err(Error::TokenAmount {
+                token: self.name.clone(),
+                reason: format!(
+                    "amount {amount} is not positive at {} decimals",
+                    self.wei_decimals
+                ),
+            });
+        }
+        Ok(rounded.to_string())
+    }
+
+    /// `size` as sent in spot orders with the token as base, rounded toward zero to
+    /// `sz_decimals`
+    pub fn format_size(&self, size: Decimal) -> String {
+        round_toward_zero(size, self.sz_decimals).to_string()
+    }
+
+    /// Parses a spot send amount, failing if it is finer than `wei_decimals`
+    pub fn parse_amount(&self, amount: &str) -> Result<Decimal> {
+        self.parse_with_decimals("amount", amount, self.wei_decimals)
+    }
+
+    /// Parses a spot order size, failing if it is finer than `sz_decimals`
+    pub fn parse_size(&self, size: &str) -> Result<Decimal> {
+        self.parse_with_decimals("size", size, self.sz_decimals)
+    }
+
+    fn parse_with_decimals(&self, kind: &str, value: &str, decimals: u8) -> Result<Decimal> {
+        let parsed = parse_decimal(value)?.normalize();
+        if parsed.scale() > decimals.into() {
+            return Err(Error::TokenAmount {
+                token: self.name.clone(),
+                reason: format!("{kind} {value} has more than {decimals} decimals"),
+            });
+        }
+        Ok(parsed)
+    }
+}
+
+fn round_toward_zero(value: Decimal, decimals: u8) -> Decimal {
+    value
+        .round_dp_with_strategy(decimals.into(), RoundingStrategy::ToZero)
+        .normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_perp_dex_asset_ids() {
+        let perp_dex_meta = PerpDexMeta {
+            dex: "xyz".to_string(),
+            dex_index: 1,
+            meta: Meta {
+                universe: vec![
+                    AssetMeta {
+                        name: "xyz:XYZ100".to_string(),
+                        sz_decimals: 4,
+                        max_leverage: Some(20),
+                        margin_table_id: Some(20),
+                    },
+                    AssetMeta {
+                        name: "xyz:XYZ200".to_string(),
+                        sz_decimals: 2,
+                        max_leverage: Some(10),
+                        margin_table_id: Some(10),
+                    },
+                ],
+                margin_tables: Vec::new(),
+            },
+        };
+        let coin_to_asset = perp_dex_meta.add_perp_to_asset_map(HashMap::new());
+        assert_eq!(coin_to_asset["xyz:XYZ100"], 110_000);
+        assert_eq!(coin_to_asset["xyz:XYZ200"], 110_001);
+
+        let default_dex = PerpDexMeta {
+            dex_index: 0,
+            ..perp_dex_meta
+        };
+        assert_eq!(default_dex.asset_id(3), 3);
+    }
+
+    #[test]
+    fn test_perp_dex_limits_deserialize() {
+        let limits: PerpDexLimitsResponse = serde_json::from_value(serde_json::json!({
+            "totalOiCap": "10000000.0",
+            "oiSzCapPerPerp": "10000000.0",
+            "maxTransferNtl": "100000000.0",
+            "coinToOiCap": [["xyz:XYZ100", "1000000.0"]]
+        }))
+        .unwrap();
+        let limits = limits.unwrap();
+        assert_eq!(limits.total_oi_cap, "10000000.0");
+        assert_eq!(
+            limits.coin_to_oi_cap,
+            vec![("xyz:XYZ100".to_string(), "1000000.0".to_string())]
+        );
+        let limits: PerpDexLimitsResponse = serde_json::from_str("null").unwrap();
+        assert!(limits.is_none());
+    }
+
+    #[test]
+    fn test_pairs_by_token_identifiers() {
+        let spot_meta: SpotMeta = serde_json::from_value(serde_json::json!({
+            "universe": [
+                {"tokens": [1, 0], "name": "PURR/USDC", "index": 0, "isCanonical": true},
+                {"tokens": [2, 0], "name": "@1", "index": 1, "isCanonical": false},
+                {"tokens": [1, 2], "name": "@2", "index": 2, "isCanonical": false}
+            ],
+            "tokens": [
+                {"name": "USDC", "szDecimals": 8, "weiDecimals": 8, "index": 0,
+                 "tokenId": "0x6d1e7cde53ba9467b783cb7c530ce054", "isCanonical": true},
+                {"name": "PURR", "szDecimals": 0, "weiDecimals": 5, "index": 1,
+                 "tokenId": "0xc1fb593aeffbeb02f85e0308e9956a90", "isCanonical": true,
+                 "evmContract": {"address": "0x9b498c3c8a0b8cd8ba1d9851d40d186f1872b44e",
+                                 "evm_extra_wei_decimals": 13}},
+                {"name": "HFUN", "szDecimals": 2, "weiDecimals": 8, "index": 2,
+                 "tokenId": "0xbaf265ef389da684513d98d68edf4eae", "isCanonical": false}
+            ]
+        }))
+        .unwrap();
+
+        let (token, pairs) = spot_meta
+            .pairs_by_token_id("0xbaf265ef389da684513d98d68edf4eae".parse().unwrap())
+            .unwrap();
+        assert_eq!(token.name, "HFUN");
+        assert_eq!(
+            pairs
+                .iter()
+                .map(|pair| pair.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["@1", "@2"]
+        );
+
+        let address = "0x9b498c3c8a0b8cd8ba1d9851d40d186f1872b44e"
+            .parse()
+            .unwrap();
+        let (token, pairs) = spot_meta.pairs_by_evm_address(address).unwrap();
+        assert_eq!(token.name, "PURR");
+        assert_eq!(pairs.len(), 2);
+
+        assert!(spot_meta.pairs_by_token_id(B128::ZERO).is_none());
+        assert!(spot_meta.pairs_by_evm_address(Address::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_evm_linked_tokens() {
+        let spot_meta: SpotMeta = serde_json::from_value(serde_json::json!({
+            "universe": [],
+            "tokens": [
+                {"name": "USDC", "szDecimals": 8, "weiDecimals": 8, "index": 0,
+                 "tokenId": "0x6d1e7cde53ba9467b783cb7c530ce054", "isCanonical": true,
+                 "evmContract": null},
+                {"name": "HYPE", "szDecimals": 2, "weiDecimals": 8, "index": 150,
+                 "tokenId": "0x0d01dc56dcaaca66ad901c959b4011ec", "isCanonical": false,
+                 "evmContract": null},
+                {"name": "PURR", "szDecimals": 0, "weiDecimals": 5, "index": 200,
+                 "tokenId": "0xc1fb593aeffbeb02f85e0308e9956a90", "isCanonical": true,
+                 "evmContract": {"address": "0x9b498c3c8a0b8cd8ba1d9851d40d186f1872b44e",
+                                 "evm_extra_wei_decimals": 13}}
+            ]
+        }))
+        .unwrap();
+
+        let usdc = spot_meta.token("USDC").unwrap();
+        assert_eq!(usdc.evm_address(), None);
+        assert_eq!(usdc.evm_decimals(), None);
+        assert_eq!(
+            usdc.system_address().to_string(),
+            "0x2000000000000000000000000000000000000000"
+        );
+
+        let hype = spot_meta.token("HYPE").unwrap();
+        assert_eq!(hype.system_address(), Address::repeat_byte(0x22));
+        assert_eq!(hype.evm_decimals(), None);
+
+        let purr = spot_meta.token("PURR").unwrap();
+        assert_eq!(purr.evm_decimals(), Some(18));
+
+        let amount = Decimal::from_str("1.123456789").unwrap();
+        assert_eq!(purr.format_amount(amount).unwrap(), "1.12345");
+        assert_eq!(purr.format_size(amount), "1");
+        assert!(matches!(
+            hype.format_amount(Decimal::from_str("-0.5").unwrap()),
+            Err(Error::TokenAmount { .. })
+        ));
+        assert!(matches!(
+            purr.format_amount(Decimal::from_str("0.000001").unwrap()),
+            Err(Error::TokenAmount { .. })
+        ));
+        assert_eq!(
+            purr.parse_amount("0.100000").unwrap(),
+            Decimal::from_str("0.1").unwrap()
+        );
+        assert!(matches!(
+            purr.parse_amount("0.000001"),
+            Err(Error::TokenAmount { .. })
+        ));
+        assert!(matches!(
+            purr.parse_size("1.5"),
+            Err(Error::TokenAmount { .. })
+        ));
+        assert_eq!(hype.parse_size("1.25").unwrap().to_string(), "1.25");
+        assert_eq!(
+            purr.system_address().to_string().to_lowercase(),
+            "0x20000000000000000000000000000000000000c8"
+        );
+        assert_eq!(
+            purr.spot_send_token(),
+            "PURR:0xc1fb593aeffbeb02f85e0308e9956a90"
+        );
+        let purr_evm = purr.evm_address().unwrap();
+        assert_eq!(
+            spot_meta.token_by_evm_address(purr_evm).unwrap().name,
+            "PURR"
+        );
+    }
+
+    #[test]
+    fn test_margin_tables() {
+        let meta: Meta = serde_json::from_value(serde_json::json!({
+            "universe": [
+                {"name": "BTC", "szDecimals": 5, "maxLeverage": 40, "marginTableId": 56},
+                {"name": "DOGE", "szDecimals": 0, "maxLeverage": 10, "marginTableId": 10},
+                {"name": "OLD", "szDecimals": 0}
+            ],
+            "marginTables": [
+                [56, {"description": "tiered 40x", "marginTiers": [
+                    {"lowerBound": "0.0", "maxLeverage": 40},
+                    {"lowerBound": "150000000.0", "maxLeverage": 20}
+                ]}]
+            ]
+        }))
+        .unwrap();
+
+        let btc = meta.margin_table("BTC").unwrap();
+        assert_eq!(btc.max_leverage(Decimal::from(1_000)), Some(40));
+        assert_eq!(btc.max_leverage(Decimal::from(-200_000_000)), Some(20));
+        // 1/80 of notional in the first tier
+        assert_eq!(
+            btc.maintenance_margin(Decimal::from(80_000)),
+            Decimal::from(1_000)
+        );
+        // 1/40 above 150M, continuous at the boundary
+        let boundary = Decimal::from(150_000_000);
+        assert_eq!(
+            btc.maintenance_margin(boundary),
+            boundary / Decimal::from(80)
+        );
+        assert_eq!(
+            btc.maintenance_margin(Decimal::from(200_000_000)),
+            Decimal::from(5_000_000) - Decimal::from(1_875_000)
+        );
+
+        let doge = meta.margin_table("DOGE").unwrap();
+        assert_eq!(doge.max_leverage(Decimal::from(1_000_000)), Some(10));
+        assert!(meta.margin_table("OLD").is_none());
+        assert!(meta.margin_table("ETH").is_none());
+    }
 }
@@ -0,0 +1,347 @@
+use crate::{
+    helpers::parse_decimal, prelude::*, req::HttpClient, Address, Error, InfoClient, Message,
+    Subscription,
+};
+use log::{error, warn};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::json;
+use tokio::{spawn, sync::mpsc::unbounded_channel, task::JoinHandle};
+
+/// Shape of the JSON body posted to the webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookFormat {
+    /// `{"text": ...}` for Slack incoming webhooks
+    Slack,
+    /// `{"content": ...}` for Discord webhooks
+    Discord,
+    /// The serialized [`NotifyEvent`] with a `summary` field
+    Generic,
+}
+
+/// Which events are posted to the webhook
+#[derive(Debug, Clone)]
+pub struct NotifyConfig {
+    pub format: WebhookFormat,
+    /// Notify on fills of at least this size, `None` disables fill notifications
+    pub min_fill_sz: Option<Decimal>,
+    /// Notify on exchange notifications that mention liquidation
+    pub liquidation_warnings: bool,
+    /// Notify once each time the websocket drops
+    pub disconnects: bool,
+    pub rejections: bool,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> NotifyConfig {
+        NotifyConfig {
+            format: WebhookFormat::Generic,
+            min_fill_sz: Some(Decimal::ZERO),
+            liquidation_warnings: true,
+            disconnects: true,
+            rejections: true,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum NotifyEvent {
+    Fill {
+        user: Address,
+        coin: String,
+        side: String,
+        px: String,
+        sz: String,
+        oid: u64,
+        time: u64,
+    },
+    LiquidationWarning {
+        user: Address,
+        message: String,
+    },
+    Disconnected {
+        user: Address,
+    },
+    #[serde(rename_all = "camelCase")]
+    OrderRejected {
+        user: Address,
+        coin: String,
+        oid: u64,
+        status: String,
+        status_timestamp: u64,
+    },
+}
+
+impl NotifyEvent {
+    /// One line description used as the Slack and Discord message text
+    pub fn summary(&self) -> String {
+        match self {
+            NotifyEvent::Fill {
+                user,
+                coin,
+                side,
+                px,
+                sz,
+                oid,
+                ..
+            } => {
+                let side = if side == "B" { "bought" } else { "sold" };
+                format!("{user} {side} {sz} {coin} at {px} (oid {oid})")
+            }
+            NotifyEvent::LiquidationWarning { user, message } => {
+                format!("Liquidation warning for {user}: {message}")
+            }
+            NotifyEvent::Disconnected { user } => {
+                format!("Websocket for {user} disconnected")
+            }
+            NotifyEvent::OrderRejected {
+                user,
+                coin,
+                oid,
+                status,
+                ..
+            } => format!("Order {oid} on {coin} for {user} rejected: {status}"),
+        }
+    }
+}
+
+impl NotifyConfig {
+    /// Turns a ws message into the events enabled by this config.
+    /// `disconnected` dedupes the `NoData` each subscription receives when the websocket drops.
+    fn events(
+        &self,
+        user: Address,
+        message: &Message,
+        disconnected: &mut bool,
+    ) -> Result<Vec<NotifyEvent>> {
+        if matches!(message, Message::NoData) {
+            let first = !*disconnected;
+            *disconnected = true;
+            return Ok(if first && self.disconnects {
+                vec![NotifyEvent::Disconnected { user }]
+            } else {
+                Vec::new()
+            });
+        }
+        *disconnected = false;
+
+        let mut events = Vec::new();
+        match message {
+            Message::UserFills(user_fills) => {
+                let Some(min_fill_sz) = self.min_fill_sz else {
+                    return Ok(events);
+                };
+                // the first message replays recent fills
+                if user_fills.data.is_snapshot == Some(true) {
+                    return Ok(events);
+                }
+                for fill in &user_fills.data.fills {
+                    if parse_decimal(&fill.sz)? >= min_fill_sz {
+                        events.push(NotifyEvent::Fill {
+                            user,
+                            coin: fill.coin.clone(),
+                            side: fill.side.clone(),
+                            px: fill.px.clone(),
+                            sz: fill.sz.clone(),
+                            oid: fill.oid,
+                            time: fill.time,
+                        });
+                    }
+                }
+            }
+            Message::OrderUpdates(order_updates) if self.rejections => {
+                for update in &order_updates.data {
+                    if update.status == "rejected" || update.status.ends_with("Rejected") {
+                        events.push(NotifyEvent::OrderRejected {
+                            user,
+                            coin: update.order.coin.clone(),
+                            oid: update.order.oid,
+                            status: update.status.clone(),
+                            status_timestamp: update.status_timestamp,
+                        });
+                    }
+                }
+            }
+            Message::Notification(notification) if self.liquidation_warnings => {
+                let message = &notification.data.notification;
+                if message.to_lowercase().contains("liquidat") {
+                    events.push(NotifyEvent::LiquidationWarning {
+                        user,
+                        message: message.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+        Ok(events)
+    }
+}
+
+/// Posts structured JSON to a webhook when events from a user's ws subscriptions match a [`NotifyConfig`].
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    http_client: HttpClient,
+    pub config: NotifyConfig,
+}
+
+impl Notifier {
+    pub fn new(client: Option<Client>, webhook_url: String, config: NotifyConfig) -> Notifier {
+        Notifier {
+            http_client: HttpClient {
+                client: client.unwrap_or_default(),
+                base_url: webhook_url,
+            },
+            config,
+        }
+    }
+
+    fn payload(&self, event: &NotifyEvent) -> Result<serde_json::Value> {
+        let summary = event.summary();
+        Ok(match self.config.format {
+            WebhookFormat::Slack => json!({ "text": summary }),
+            WebhookFormat::Discord => json!({ "content": summary }),
+            WebhookFormat::Generic => {
+                let mut payload =
+                    serde_json::to_value(event).map_err(|e| Error::JsonParse(e.to_string()))?;
+                payload["summary"] = summary.into();
+                payload
+            }
+        })
+    }
+
+    pub async fn send(&self, event: &NotifyEvent) -> Result<()> {
+        let data = serde_json::to_string(&self.payload(event)?)
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        self.http_client
+            .post("", data)
+            .await
+            .map_err(|e| Error::Notify(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Subscribes to the fills, order updates and notifications of `user` on `info_client`
+    /// and posts matching events until the subscriptions are dropped.
+    /// Webhook failures are logged and do not stop the task.
+    pub async fn start(
+        self,
+        info_client: &mut InfoClient,
+        user: Address,
+    ) -> Result<JoinHandle<()>> {
+        let (sender, mut receiver) = unbounded_channel();
+        info_client
+            .subscribe(Subscription::UserFills { user }, sender.clone())
+            .await?;
+        info_client
+            .subscribe(Subscription::OrderUpdates { user }, sender.clone())
+            .await?;
+        info_client
+            .subscribe(Subscription::Notification { user }, sender)
+            .await?;
+
+        Ok(spawn(async move {
+            let mut disconnected = false;
+            while let Some(message) = receiver.recv().await {
+                let events = match self.config.events(user, &message, &mut disconnected) {
+                    Ok(events) => events,
+                    Err(err) => {
+                        error!("Notifier could not process message: {err}");
+                        continue;
+                    }
+                };
+                for event in events {
+                    if let Err(err) = self.send(&event).await {
+                        error!("Notifier could not post {}: {err}", event.summary());
+                    }
+                }
+            }
+            warn!("Notifier for {user} stopped");
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(raw: &str) -> Message {
+        serde_json::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn test_notify_events() -> Result<()> {
+        let config = NotifyConfig {
+            min_fill_sz: Some(Decimal::from(2)),
+            ..Default::default()
+        };
+        let mut disconnected = false;
+
+        let fills = message(
+            r#"{"channel": "userFills", "data": {"user": "0x0000000000000000000000000000000000000000", "fills": [
+                {"coin": "ETH", "side": "B", "px": "2000", "sz": "1.5", "time": 1, "hash": "0x", "startPosition": "0", "dir": "Open Long", "closedPnl": "0", "oid": 1, "crossed": true, "fee": "0", "tid": 1},
+                {"coin": "ETH", "side": "A", "px": "2001", "sz": "2", "time": 2, "hash": "0x", "startPosition": "0", "dir": "Close Long", "closedPnl": "0", "oid": 2, "crossed": true, "fee": "0", "tid": 2}
+            ]}}"#,
+        );
+        let events = config.events(Address::ZERO, &fills, &mut disconnected)?;
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].summary(),
+            format!("{} sold 2 ETH at 2001 (oid 2)", Address::ZERO)
+        );
+
+        let updates = message(
+            r#"{"channel": "orderUpdates", "data": [
+                {"order": {"coin": "BTC", "side": "B", "limitPx": "1", "sz": "1", "oid": 3, "timestamp": 1, "origSz": "1"}, "status": "minTradeNtlRejected", "statusTimestamp": 5},
+                {"order": {"coin": "BTC", "side": "B", "limitPx": "1", "sz": "1", "oid": 4, "timestamp": 1, "origSz": "1"}, "status": "open", "statusTimestamp": 5}
+            ]}"#,
+        );
+        let events = config.events(Address::ZERO, &updates, &mut disconnected)?;
+        assert!(matches!(
+            events.as_slice(),
+            [NotifyEvent::OrderRejected { oid: 3, .. }]
+        ));
+
+        let notification = message(
+            r#"{"channel": "notification", "data": {"notification": "Your position is close to liquidation"}}"#,
+        );
+        let events = config.events(Address::ZERO, &notification, &mut disconnected)?;
+        assert!(matches!(
+            events.as_slice(),
+            [NotifyEvent::LiquidationWarning { .. }]
+        ));
+
+        // every subscription receives NoData on disconnect, only the first is reported
+        assert_eq!(
+            config
+                .events(Address::ZERO, &Message::NoData, &mut disconnected)?
+                .len(),
+            1
+        );
+        assert!(config
+            .events(Address::ZERO, &Message::NoData, &mut disconnected)?
+            .is_empty());
+        config.events(Address::ZERO, &Message::Pong, &mut disconnected)?;
+        assert!(!disconnected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_webhook_payloads() -> Result<()> {
+        let event = NotifyEvent::Disconnected {
+            user: Address::ZERO,
+        };
+        let summary = event.summary();
+
+        let mut notifier = Notifier::new(None, String::new(), NotifyConfig::default());
+        let payload = notifier.payload(&event)?;
+        assert_eq!(payload["event"], "disconnected");
+        assert_eq!(payload["summary"], summary.as_str());
+
+        notifier.config.format = WebhookFormat::Slack;
+        assert_eq!(notifier.payload(&event)?, json!({ "text": summary }));
+        notifier.config.format = WebhookFormat::Discord;
+        assert_eq!(notifier.payload(&event)?, json!({ "content": summary }));
+        Ok(())
+    }
+}
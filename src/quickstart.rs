@@ -0,0 +1,102 @@
+use crate::{
+    prelude::*, Address, BaseUrl, Error, ExchangeClient, InfoClient, RateLimitConfig, RateLimiter,
+    RequestOptions,
+};
+use alloy::signers::local::PrivateKeySigner;
+use std::time::Duration;
+
+/// Timeout of every request of the clients built by [`Hyperliquid::connect`]
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Network [`Hyperliquid::connect`] connects to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+impl From<Network> for BaseUrl {
+    fn from(network: Network) -> BaseUrl {
+        match network {
+            Network::Mainnet => BaseUrl::Mainnet,
+            Network::Testnet => BaseUrl::Testnet,
+        }
+    }
+}
+
+/// Info and exchange clients for one account, set up with defaults suited to most programs
+#[derive(Debug)]
+pub struct Hyperliquid {
+    /// Reconnecting client with its websocket already open
+    pub info: InfoClient,
+    pub exchange: ExchangeClient,
+}
+
+impl Hyperliquid {
+    /// Connects with a hex private key, e.g. `Hyperliquid::connect(key, Network::Testnet)`,
+    /// using [`Hyperliquid::default_request_options`]. The websocket reconnects and
+    /// resubscribes on its own.
+    pub async fn connect(private_key: &str, network: Network) -> Result<Hyperliquid> {
+        Self::connect_with_options(private_key, network, Self::default_request_options()).await
+    }
+
+    /// Requests time out after [`DEFAULT_REQUEST_TIMEOUT`] and share one rate limiter with the
+    /// default [`RateLimitConfig`], which also retries requests rejected with status 429
+    pub fn default_request_options() -> RequestOptions {
+        RequestOptions::default()
+            .with_timeout(DEFAULT_REQUEST_TIMEOUT)
+            .with_rate_limit(RateLimiter::new(RateLimitConfig::default()))
+    }
+
+    /// Like [`Hyperliquid::connect`], with `request_options` for both clients, e.g. to retry
+    /// rate limited requests more often
+    pub async fn connect_with_options(
+        private_key: &str,
+        network: Network,
+        request_options: RequestOptions,
+    ) -> Result<Hyperliquid> {
+        let base_url = BaseUrl::from(network);
+        let wallet: PrivateKeySigner =
+            private_key
+                .parse()
+                .map_err(|e: alloy::signers::local::LocalSignerError| {
+                    Error::PrivateKeyParse(e.to_string())
+                })?;
+        let mut info = InfoClient::with_reconnect(None, Some(base_url)).await?;
+        info.set_request_options(request_options.clone());
+        let meta = info.meta().await?;
+        let exchange = ExchangeClient::new(None, wallet, Some(base_url), Some(meta), None)
+            .await?
+            .with_request_options(request_options);
+        info.connect_ws().await?;
+        Ok(Hyperliquid { info, exchange })
+    }
+
+    /// Address of the account the exchange client signs for
+    pub fn address(&self) -> Address {
+        self.exchange.wallet.address()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_connect_rejects_invalid_key() {
+        let result = Hyperliquid::connect("not a key", Network::Testnet).await;
+        assert!(matches!(result, Err(Error::PrivateKeyParse(_))));
+    }
+
+    #[test]
+    fn test_default_request_options_retry() {
+        let options = Hyperliquid::default_request_options();
+        assert_eq!(options.timeout, Some(DEFAULT_REQUEST_TIMEOUT));
+        let rate_limit = options.rate_limit.expect("no rate limiter");
+        assert!(rate_limit.config().max_retries > 0);
+        assert_eq!(
+            BaseUrl::from(Network::Testnet).get_url(),
+            BaseUrl::Testnet.get_url()
+        );
+    }
+}
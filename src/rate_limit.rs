@@ -0,0 +1,179 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Info request types the exchange charges a weight of 2 for, most others cost 20
+const LIGHT_INFO_REQUESTS: [&str; 6] = [
+    "l2Book",
+    "allMids",
+    "clearinghouseState",
+    "orderStatus",
+    "spotClearinghouseState",
+    "exchangeStatus",
+];
+
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Request weight that may be spent per minute, 1200 is the exchange's limit per IP
+    pub weight_per_minute: u32,
+    /// Retries of requests rejected with status 429
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each later one up to `max_backoff`, plus up
+    /// to as much again of random jitter. A `Retry-After` from the server takes precedence.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> RateLimitConfig {
+        RateLimitConfig {
+            weight_per_minute: 1200,
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    weight: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    /// Spends `weight` if the bucket holds it at `now`, else returns how long until it does
+    fn take(&mut self, weight: f64, capacity: f64, now: Instant) -> Option<Duration> {
+        let per_second = capacity / 60.0;
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.weight = (self.weight + elapsed * per_second).min(capacity);
+        self.updated = now;
+        if self.weight >= weight {
+            self.weight -= weight;
+            return None;
+        }
+        Some(Duration::from_secs_f64((weight - self.weight) / per_second))
+    }
+}
+
+/// Token bucket of request weight, refilled continuously at `weight_per_minute`.
+/// Clones share the bucket, so clients built from one another stay within one budget.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> RateLimiter {
+        RateLimiter {
+            bucket: Arc::new(Mutex::new(Bucket {
+                weight: config.weight_per_minute as f64,
+                updated: Instant::now(),
+            })),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
+    /// Waits until `weight` can be spent and spends it
+    pub async fn acquire(&self, weight: u32) {
+        let capacity = self.config.weight_per_minute.max(1) as f64;
+        // a request heavier than the whole budget waits for a full bucket
+        let weight = (weight as f64).min(capacity);
+        loop {
+            let wait = self.bucket.lock().unwrap_or_else(|e| e.into_inner()).take(
+                weight,
+                capacity,
+                Instant::now(),
+            );
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return,
+            }
+        }
+    }
+
+    /// Delay before retry number `attempt`, counting from 0
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .config
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.config.max_backoff);
+        let jitter = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+        backoff + backoff.mul_f64(jitter)
+    }
+}
+
+/// Weight the exchange charges for a request to `url_path` with body `data`: 1 plus 1 per 40
+/// orders, cancels or modifies for actions, 2 or 20 for info requests depending on the type.
+/// Info responses with many items cost extra weight afterwards, which is not accounted for.
+pub(crate) fn request_weight(url_path: &str, data: &str) -> u32 {
+    let Ok(body) = serde_json::from_str::<serde_json::Value>(data) else {
+        return 20;
+    };
+    if url_path == "/exchange" {
+        let action = &body["action"];
+        let batch = ["orders", "cancels", "modifies"]
+            .iter()
+            .find_map(|key| action[key].as_array())
+            .map_or(0, |batch| batch.len());
+        return 1 + batch as u32 / 40;
+    }
+    match body["type"].as_str() {
+        Some(request) if LIGHT_INFO_REQUESTS.contains(&request) => 2,
+        Some("userRole") => 60,
+        _ => 20,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_weight() {
+        assert_eq!(
+            request_weight("/info", r#"{"type": "l2Book", "coin": "ETH"}"#),
+            2
+        );
+        assert_eq!(request_weight("/info", r#"{"type": "meta"}"#), 20);
+        assert_eq!(request_weight("/info", r#"{"type": "userRole"}"#), 60);
+        let orders = vec![serde_json::json!({}); 85];
+        let action = serde_json::json!({"action": {"type": "order", "orders": orders}});
+        assert_eq!(request_weight("/exchange", &action.to_string()), 3);
+        let action = serde_json::json!({"action": {"type": "usdSend"}});
+        assert_eq!(request_weight("/exchange", &action.to_string()), 1);
+    }
+
+    #[test]
+    fn test_bucket_and_backoff() {
+        let start = Instant::now();
+        let mut bucket = Bucket {
+            weight: 60.0,
+            updated: start,
+        };
+        assert_eq!(bucket.take(40.0, 60.0, start), None);
+        // 1 weight per second refills the missing 20
+        assert_eq!(
+            bucket.take(40.0, 60.0, start),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            bucket.take(40.0, 60.0, start + Duration::from_secs(20)),
+            None
+        );
+
+        let limiter = RateLimiter::new(RateLimitConfig::default());
+        let backoff = limiter.backoff(2);
+        assert!(backoff >= Duration::from_secs(2) && backoff <= Duration::from_secs(4));
+        assert!(limiter.backoff(10) <= Duration::from_secs(20));
+    }
+}
@@ -1,6 +1,13 @@
-use crate::{prelude::*, BaseUrl, Error};
-use reqwest::{Client, Response};
+use crate::{
+    prelude::*,
+    rate_limit::{request_weight, RateLimiter},
+    BaseUrl, Error,
+};
+use log::warn;
+use reqwest::{header::RETRY_AFTER, Client, Response};
 use serde::Deserialize;
+use std::{future::Future, time::Duration};
+use tokio_util::sync::CancellationToken;
 
 #[derive(Deserialize, Debug)]
 struct ErrorData {
@@ -9,14 +16,99 @@ struct ErrorData {
     msg: String,
 }
 
-#[derive(Debug)]
+/// Limits applied to each request of a client, see `InfoClient::with_request_options` and
+/// `ExchangeClient::with_request_options`
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Fails requests with [`Error::Timeout`] after this long, no limit besides the
+    /// `reqwest::Client`'s own by default
+    pub timeout: Option<Duration>,
+    /// Fails in-flight requests with [`Error::Cancelled`] once cancelled, e.g. on shutdown
+    pub cancel: Option<CancellationToken>,
+    /// Spaces requests out to stay within the exchange's weight limits and retries those
+    /// rejected with status 429, after the server's `Retry-After` when it gives one
+    pub rate_limit: Option<RateLimiter>,
+}
+
+impl RequestOptions {
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Clients given clones of one `rate_limiter` share its budget
+    pub fn with_rate_limit(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limit = Some(rate_limiter);
+        self
+    }
+
+    /// Runs `request` within the timeout, stopping early if the token is cancelled
+    pub(crate) async fn run<T>(&self, request: impl Future<Output = Result<T>>) -> Result<T> {
+        if self
+            .cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.is_cancelled())
+        {
+            return Err(Error::Cancelled);
+        }
+        let request = async {
+            match self.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, request)
+                    .await
+                    .map_err(|_| Error::Timeout(timeout))?,
+                None => request.await,
+            }
+        };
+        match &self.cancel {
+            Some(cancel) => tokio::select! {
+                result = request => result,
+                _ = cancel.cancelled() => Err(Error::Cancelled),
+            },
+            None => request.await,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct HttpClient {
     pub client: Client,
     pub base_url: String,
 }
 
+/// Whether a server error says the exchange is down for maintenance
+fn is_maintenance(text: &str) -> bool {
+    let text = text.to_lowercase();
+    text.contains("maintenance") || text.contains("temporarily unavailable")
+}
+
+fn server_error(status_code: u16, retry_after: Option<Duration>, text: String) -> Error {
+    if status_code == 503 || is_maintenance(&text) {
+        return Error::ExchangeUnavailable { retry_after };
+    }
+    Error::ServerRequest {
+        status_code,
+        error_message: text,
+    }
+}
+
+/// Delay asked for by the server in a `Retry-After` header given in seconds
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
 async fn parse_response(response: Response) -> Result<String> {
     let status_code = response.status().as_u16();
+    let retry_after = retry_after(&response);
     let text = response
         .text()
         .await
@@ -44,14 +136,58 @@ async fn parse_response(response: Response) -> Result<String> {
         return Err(client_error);
     }
 
-    Err(Error::ServerRequest {
-        status_code,
-        error_message: text,
-    })
+    Err(server_error(status_code, retry_after, text))
 }
 
 impl HttpClient {
     pub async fn post(&self, url_path: &'static str, data: String) -> Result<String> {
+        self.post_with_options(url_path, data, &RequestOptions::default())
+            .await
+    }
+
+    pub async fn post_with_options(
+        &self,
+        url_path: &'static str,
+        data: String,
+        options: &RequestOptions,
+    ) -> Result<String> {
+        match &options.rate_limit {
+            Some(rate_limiter) => {
+                options
+                    .run(self.send_rate_limited(url_path, data, rate_limiter))
+                    .await
+            }
+            None => options.run(self.send(url_path, data)).await,
+        }
+    }
+
+    async fn send_rate_limited(
+        &self,
+        url_path: &'static str,
+        data: String,
+        rate_limiter: &RateLimiter,
+    ) -> Result<String> {
+        let weight = request_weight(url_path, &data);
+        let mut attempt = 0;
+        loop {
+            rate_limiter.acquire(weight).await;
+            let response = self.execute(url_path, data.clone()).await?;
+            if response.status().as_u16() == 429 && attempt < rate_limiter.config().max_retries {
+                let delay = retry_after(&response).unwrap_or_else(|| rate_limiter.backoff(attempt));
+                warn!("Rate limited on {url_path}, retrying in {delay:?}");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+            return parse_response(response).await;
+        }
+    }
+
+    async fn send(&self, url_path: &'static str, data: String) -> Result<String> {
+        parse_response(self.execute(url_path, data).await?).await
+    }
+
+    async fn execute(&self, url_path: &'static str, data: String) -> Result<Response> {
         let full_url = format!("{}{url_path}", self.base_url);
         let request = self
             .client
@@ -60,15 +196,126 @@ impl HttpClient {
             .body(data)
             .build()
             .map_err(|e| Error::GenericRequest(e.to_string()))?;
-        let result = self
-            .client
+        self.client
             .execute(request)
             .await
-            .map_err(|e| Error::GenericRequest(e.to_string()))?;
-        parse_response(result).await
+            .map_err(|e| Error::GenericRequest(e.to_string()))
     }
 
     pub fn is_mainnet(&self) -> bool {
         self.base_url == BaseUrl::Mainnet.get_url()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rate_limit::RateLimitConfig;
+
+    #[tokio::test]
+    async fn test_request_options_timeout_and_cancel() {
+        let slow = || async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(())
+        };
+
+        let options = RequestOptions::default().with_timeout(Duration::from_millis(10));
+        assert!(matches!(options.run(slow()).await, Err(Error::Timeout(_))));
+
+        let cancel = CancellationToken::new();
+        let options = RequestOptions::default().with_cancel(cancel.clone());
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            canceller.cancel();
+        });
+        assert!(matches!(options.run(slow()).await, Err(Error::Cancelled)));
+        // already cancelled requests are not started
+        assert!(matches!(
+            options.run(async { Ok(()) }).await,
+            Err(Error::Cancelled)
+        ));
+    }
+
+    /// Serves one request with each raw response head, all with an empty json body
+    async fn serve_responses(heads: Vec<&'static str>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for head in heads {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = [0; 4096];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 {head}\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{{}}"
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        base_url
+    }
+
+    async fn post_rate_limited(base_url: String, config: RateLimitConfig) -> Result<String> {
+        let http_client = HttpClient {
+            client: Client::new(),
+            base_url,
+        };
+        let options = RequestOptions::default().with_rate_limit(RateLimiter::new(config));
+        http_client
+            .post_with_options("/info", r#"{"type": "allMids"}"#.to_string(), &options)
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_requests_retry() {
+        let base_url = serve_responses(vec![
+            "429 Too Many Requests",
+            "429 Too Many Requests",
+            "200 OK",
+        ])
+        .await;
+        let config = RateLimitConfig {
+            initial_backoff: Duration::from_millis(1),
+            ..RateLimitConfig::default()
+        };
+        assert_eq!(post_rate_limited(base_url, config).await.unwrap(), "{}");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_retry_after() {
+        let base_url =
+            serve_responses(vec!["429 Too Many Requests\r\nRetry-After: 0", "200 OK"]).await;
+        // the local backoff alone would outlast the timeout
+        let config = RateLimitConfig {
+            initial_backoff: Duration::from_secs(60),
+            ..RateLimitConfig::default()
+        };
+        let response =
+            tokio::time::timeout(Duration::from_secs(5), post_rate_limited(base_url, config))
+                .await
+                .expect("Retry-After was not honored");
+        assert_eq!(response.unwrap(), "{}");
+    }
+
+    #[test]
+    fn test_server_error_maintenance() {
+        let retry_after = Some(Duration::from_secs(30));
+        assert!(matches!(
+            server_error(503, retry_after, String::new()),
+            Error::ExchangeUnavailable { retry_after: Some(after) } if after.as_secs() == 30
+        ));
+        assert!(matches!(
+            server_error(502, None, "Exchange under maintenance".to_string()),
+            Error::ExchangeUnavailable { retry_after: None }
+        ));
+        assert!(matches!(
+            server_error(500, None, "internal error".to_string()),
+            Error::ServerRequest {
+                status_code: 500,
+                ..
+            }
+        ));
+    }
+}
@@ -1,19 +1,32 @@
-use ethers::{
-    contract::{Eip712, EthAbiType},
-    types::H256,
+use alloy::{
+    primitives::{keccak256, Address, B256},
+    sol_types::{eip712_domain, Eip712Domain, SolValue},
 };
 
+use crate::{limits, signature::eip712::Eip712};
+
 pub(crate) mod l1 {
     use super::*;
-    #[derive(Debug, Eip712, Clone, EthAbiType)]
-    #[eip712(
-        name = "Exchange",
-        version = "1",
-        chain_id = 1337,
-        verifying_contract = "0x0000000000000000000000000000000000000000"
-    )]
+
+    #[derive(Debug, Clone)]
     pub(crate) struct Agent {
         pub(crate) source: String,
-        pub(crate) connection_id: H256,
+        pub(crate) connection_id: B256,
+    }
+
+    impl Eip712 for Agent {
+        fn domain(&self) -> Eip712Domain {
+            eip712_domain! {
+                name: "Exchange",
+                version: "1",
+                chain_id: limits::L1_SIGNATURE_CHAIN_ID,
+                verifying_contract: Address::ZERO,
+            }
+        }
+
+        fn struct_hash(&self) -> B256 {
+            let type_hash = keccak256("Agent(string source,bytes32 connectionId)");
+            keccak256((type_hash, keccak256(&self.source), self.connection_id).abi_encode())
+        }
     }
 }
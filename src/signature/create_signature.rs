@@ -1,60 +1,61 @@
-use ethers::{
-    core::k256::{elliptic_curve::FieldBytes, Secp256k1},
-    signers::LocalWallet,
-    types::{transaction::eip712::Eip712, Signature, H256, U256},
+use alloy::{
+    primitives::{Address, Signature, B256},
+    signers::{local::PrivateKeySigner, SignerSync},
 };
 
-use crate::{prelude::*, proxy_digest::Sha256Proxy, signature::agent::l1, Error};
+use crate::{
+    prelude::*,
+    signature::{agent::l1, Eip712},
+    Error,
+};
+
+fn l1_agent(connection_id: B256, is_mainnet: bool) -> l1::Agent {
+    let source = if is_mainnet { "a" } else { "b" }.to_string();
+    l1::Agent {
+        source,
+        connection_id,
+    }
+}
 
 pub(crate) fn sign_l1_action(
-    wallet: &LocalWallet,
-    connection_id: H256,
+    wallet: &PrivateKeySigner,
+    connection_id: B256,
     is_mainnet: bool,
 ) -> Result<Signature> {
-    let source = if is_mainnet { "a" } else { "b" }.to_string();
-    sign_typed_data(
-        &l1::Agent {
-            source,
-            connection_id,
-        },
-        wallet,
-    )
+    sign_typed_data(&l1_agent(connection_id, is_mainnet), wallet)
 }
 
-pub(crate) fn sign_typed_data<T: Eip712>(payload: &T, wallet: &LocalWallet) -> Result<Signature> {
-    let encoded = payload
-        .encode_eip712()
-        .map_err(|e| Error::Eip712(e.to_string()))?;
-
-    sign_hash(H256::from(encoded), wallet)
+/// Hash signed for an L1 action with the given connection id
+pub(crate) fn l1_signing_hash(connection_id: B256, is_mainnet: bool) -> B256 {
+    l1_agent(connection_id, is_mainnet).eip712_signing_hash()
 }
 
-fn sign_hash(hash: H256, wallet: &LocalWallet) -> Result<Signature> {
-    let (sig, rec_id) = wallet
-        .signer()
-        .sign_digest_recoverable(Sha256Proxy::from(hash))
-        .map_err(|e| Error::SignatureFailure(e.to_string()))?;
-
-    let v = u8::from(rec_id) as u64 + 27;
-
-    let r_bytes: FieldBytes<Secp256k1> = sig.r().into();
-    let s_bytes: FieldBytes<Secp256k1> = sig.s().into();
-    let r = U256::from_big_endian(r_bytes.as_slice());
-    let s = U256::from_big_endian(s_bytes.as_slice());
+pub(crate) fn recover_signer(signature: &Signature, signing_hash: B256) -> Result<Address> {
+    signature
+        .recover_address_from_prehash(&signing_hash)
+        .map_err(|e| Error::SignatureFailure(e.to_string()))
+}
 
-    Ok(Signature { r, s, v })
+pub(crate) fn sign_typed_data<T: Eip712>(
+    payload: &T,
+    wallet: &PrivateKeySigner,
+) -> Result<Signature> {
+    wallet
+        .sign_hash_sync(&payload.eip712_signing_hash())
+        .map_err(|e| Error::SignatureFailure(e.to_string()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{UsdSend, Withdraw3};
+    use alloy::primitives::U256;
     use std::str::FromStr;
 
-    fn get_wallet() -> Result<LocalWallet> {
+    fn get_wallet() -> Result<PrivateKeySigner> {
         let priv_key = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e";
         priv_key
-            .parse::<LocalWallet>()
+            .parse::<PrivateKeySigner>()
             .map_err(|e| Error::Wallet(e.to_string()))
     }
 
@@ -62,17 +63,17 @@ mod tests {
     fn test_sign_l1_action() -> Result<()> {
         let wallet = get_wallet()?;
         let connection_id =
-            H256::from_str("0xde6c4037798a4434ca03cd05f00e3b803126221375cd1e7eaaaf041768be06eb")
+            B256::from_str("0xde6c4037798a4434ca03cd05f00e3b803126221375cd1e7eaaaf041768be06eb")
                 .map_err(|e| Error::GenericParse(e.to_string()))?;
 
         let expected_mainnet_sig = "fa8a41f6a3fa728206df80801a83bcbfbab08649cd34d9c0bfba7c7b2f99340f53a00226604567b98a1492803190d65a201d6805e5831b7044f17fd530aec7841c";
         assert_eq!(
-            sign_l1_action(&wallet, connection_id, true)?.to_string(),
+            hex::encode(sign_l1_action(&wallet, connection_id, true)?.as_bytes()),
             expected_mainnet_sig
         );
         let expected_testnet_sig = "1713c0fc661b792a50e8ffdd59b637b1ed172d9a3aa4d801d9d88646710fb74b33959f4d075a7ccbec9f2374a6da21ffa4448d58d0413a0d335775f680a881431c";
         assert_eq!(
-            sign_l1_action(&wallet, connection_id, false)?.to_string(),
+            hex::encode(sign_l1_action(&wallet, connection_id, false)?.as_bytes()),
             expected_testnet_sig
         );
         Ok(())
@@ -83,7 +84,7 @@ mod tests {
         let wallet = get_wallet()?;
 
         let usd_send = UsdSend {
-            signature_chain_id: 421614.into(),
+            signature_chain_id: U256::from(421614),
             hyperliquid_chain: "Testnet".to_string(),
             destination: "0x0D1d9635D0640821d15e323ac8AdADfA9c111414".to_string(),
             amount: "1".to_string(),
@@ -92,7 +93,7 @@ mod tests {
 
         let expected_sig = "214d507bbdaebba52fa60928f904a8b2df73673e3baba6133d66fe846c7ef70451e82453a6d8db124e7ed6e60fa00d4b7c46e4d96cb2bd61fd81b6e8953cc9d21b";
         assert_eq!(
-            sign_typed_data(&usd_send, &wallet)?.to_string(),
+            hex::encode(sign_typed_data(&usd_send, &wallet)?.as_bytes()),
             expected_sig
         );
         Ok(())
@@ -103,7 +104,7 @@ mod tests {
         let wallet = get_wallet()?;
 
         let usd_send = Withdraw3 {
-            signature_chain_id: 421614.into(),
+            signature_chain_id: U256::from(421614),
             hyperliquid_chain: "Testnet".to_string(),
             destination: "0x0D1d9635D0640821d15e323ac8AdADfA9c111414".to_string(),
             amount: "1".to_string(),
@@ -112,7 +113,7 @@ mod tests {
 
         let expected_sig = "b3172e33d2262dac2b4cb135ce3c167fda55dafa6c62213564ab728b9f9ba76b769a938e9f6d603dae7154c83bf5a4c3ebab81779dc2db25463a3ed663c82ae41c";
         assert_eq!(
-            sign_typed_data(&usd_send, &wallet)?.to_string(),
+            hex::encode(sign_typed_data(&usd_send, &wallet)?.as_bytes()),
             expected_sig
         );
         Ok(())
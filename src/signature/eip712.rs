@@ -0,0 +1,21 @@
+use alloy::{
+    primitives::{keccak256, B256},
+    sol_types::Eip712Domain,
+};
+
+/// Typed data signed by a wallet. Hyperliquid's type names contain a `:`, which rules out
+/// `sol!` structs, so the struct hashes are written out by hand.
+pub(crate) trait Eip712 {
+    fn domain(&self) -> Eip712Domain;
+
+    /// `hashStruct` of the message, including its type hash
+    fn struct_hash(&self) -> B256;
+
+    fn eip712_signing_hash(&self) -> B256 {
+        let mut digest_input = [0u8; 66];
+        digest_input[..2].copy_from_slice(&[0x19, 0x01]);
+        digest_input[2..34].copy_from_slice(self.domain().hash_struct().as_slice());
+        digest_input[34..].copy_from_slice(self.struct_hash().as_slice());
+        keccak256(digest_input)
+    }
+}
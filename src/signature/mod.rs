@@ -1,4 +1,10 @@
 pub(crate) mod agent;
 mod create_signature;
+mod eip712;
+mod verify;
 
-pub(crate) use create_signature::{sign_l1_action, sign_typed_data};
+pub(crate) use create_signature::{
+    l1_signing_hash, recover_signer, sign_l1_action, sign_typed_data,
+};
+pub(crate) use eip712::Eip712;
+pub use verify::{verify_l1_action_signature, verify_user_signed_action_signature};
@@ -0,0 +1,89 @@
+use crate::{
+    prelude::*,
+    signature::{l1_signing_hash, recover_signer},
+    Actions, Address, Error,
+};
+use alloy::primitives::{Signature, B256};
+
+fn verify_signer(signature: &Signature, signing_hash: B256, expected: Address) -> Result<()> {
+    let recovered = recover_signer(signature, signing_hash)?;
+    if recovered == expected {
+        Ok(())
+    } else {
+        Err(Error::SignerMismatch {
+            expected,
+            recovered,
+        })
+    }
+}
+
+/// Checks that `signature` is `expected`'s signature of the L1 action hashed to `connection_id`,
+/// so a bad signature is rejected before it is submitted and burns a nonce
+pub fn verify_l1_action_signature(
+    signature: &Signature,
+    connection_id: B256,
+    expected: Address,
+    is_mainnet: bool,
+) -> Result<()> {
+    verify_signer(
+        signature,
+        l1_signing_hash(connection_id, is_mainnet),
+        expected,
+    )
+}
+
+/// Checks that `signature` is `expected`'s EIP-712 signature of a user-signed action such as
+/// `UsdSend` or `Withdraw3`. Errors for L1 actions.
+pub fn verify_user_signed_action_signature(
+    signature: &Signature,
+    action: &Actions,
+    expected: Address,
+) -> Result<()> {
+    if !action.is_user_signed() {
+        return Err(Error::GenericRequest(
+            "action is an L1 action, not a user-signed one".to_string(),
+        ));
+    }
+    verify_signer(signature, action.signed_hash(0, None)?, expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        signature::{sign_l1_action, sign_typed_data},
+        UsdSend,
+    };
+    use alloy::{primitives::U256, signers::local::PrivateKeySigner};
+
+    #[test]
+    fn test_verify_signatures() -> Result<()> {
+        let wallet = PrivateKeySigner::random();
+        let other = PrivateKeySigner::random().address();
+        let connection_id = B256::repeat_byte(3);
+
+        let signature = sign_l1_action(&wallet, connection_id, true)?;
+        verify_l1_action_signature(&signature, connection_id, wallet.address(), true)?;
+        assert!(matches!(
+            verify_l1_action_signature(&signature, connection_id, wallet.address(), false),
+            Err(Error::SignerMismatch { .. })
+        ));
+        assert!(matches!(
+            verify_l1_action_signature(&signature, connection_id, other, true),
+            Err(Error::SignerMismatch { recovered, .. }) if recovered == wallet.address()
+        ));
+
+        let usd_send = UsdSend {
+            signature_chain_id: U256::from(421614),
+            hyperliquid_chain: "Testnet".to_string(),
+            destination: "0x0D1d9635D0640821d15e323ac8AdADfA9c111414".to_string(),
+            amount: "1".to_string(),
+            time: 1690393044548,
+        };
+        let signature = sign_typed_data(&usd_send, &wallet)?;
+        let action = Actions::UsdSend(usd_send);
+        verify_user_signed_action_signature(&signature, &action, wallet.address())?;
+        assert!(verify_user_signed_action_signature(&signature, &action, other).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,321 @@
+use super::quote_engine::round_px;
+use crate::{
+    prelude::*, ClientCancelRequest, ClientOrderRequest, Coin, ExchangeClient, ExchangeDataStatus,
+    MonitorEvent, MultiUserMonitor, Sz, TradeInfo, EPSILON,
+};
+use log::{error, info, warn};
+use std::collections::{HashMap, HashSet};
+use tokio::{spawn, task::JoinHandle};
+
+#[derive(Debug, Clone)]
+pub struct BracketConfig {
+    /// Take profit distance from the entry price, e.g. 0.02 for 2%, `None` for no take profit
+    pub take_profit: Option<f64>,
+    /// Stop loss distance from the entry price, e.g. 0.01 for 1%, `None` for no stop loss
+    pub stop_loss: Option<f64>,
+    /// Only bracket these coins, `None` brackets every perp
+    pub coins: Option<HashSet<String>>,
+}
+
+impl BracketConfig {
+    /// Take profit and stop loss trigger prices of a position entered at `entry_px`
+    fn trigger_pxs(&self, is_long: bool, entry_px: f64) -> (Option<f64>, Option<f64>) {
+        let side = if is_long { 1.0 } else { -1.0 };
+        (
+            self.take_profit
+                .map(|take_profit| entry_px * (1.0 + side * take_profit)),
+            self.stop_loss
+                .map(|stop_loss| entry_px * (1.0 - side * stop_loss)),
+        )
+    }
+}
+
+/// Position opened while the [`BracketManager`] was watching
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BracketedPosition {
+    /// Signed size, negative for shorts
+    pub szi: f64,
+    /// Average price of the fills that opened and increased the position
+    pub entry_px: f64,
+}
+
+/// Updates `positions` with `fills`, returning the coins whose bracket has to be replaced or
+/// cancelled. Positions already open when watching started are left alone.
+fn apply_fills(
+    positions: &mut HashMap<String, BracketedPosition>,
+    fills: &[TradeInfo],
+) -> Vec<String> {
+    let mut changed = Vec::new();
+    for fill in fills {
+        let (Ok(start), Ok(px), Ok(sz)) = (
+            fill.start_position.parse::<f64>(),
+            fill.px.parse::<f64>(),
+            fill.sz.parse::<f64>(),
+        ) else {
+            error!("Could not parse fill {fill:?}");
+            continue;
+        };
+        let szi = if fill.side == "B" {
+            start + sz
+        } else {
+            start - sz
+        };
+
+        if szi.abs() < EPSILON {
+            if positions.remove(&fill.coin).is_none() {
+                continue;
+            }
+        } else if start.abs() < EPSILON || start.signum() != szi.signum() {
+            // opened from flat or flipped
+            positions.insert(fill.coin.clone(), BracketedPosition { szi, entry_px: px });
+        } else if let Some(position) = positions.get_mut(&fill.coin) {
+            if szi.abs() > start.abs() {
+                position.entry_px = (position.entry_px * start.abs() + px * sz) / szi.abs();
+            }
+            position.szi = szi;
+        } else {
+            continue;
+        }
+        if !changed.contains(&fill.coin) {
+            changed.push(fill.coin.clone());
+        }
+    }
+    changed
+}
+
+/// Keeps take profit and stop loss triggers on every position opened from flat in the account
+/// of `exchange_client`. The triggers are placed once the position opens, replaced at the new
+/// size and average entry price when it changes, and cancelled when it closes, including when
+/// one of them closes it.
+#[derive(Debug)]
+pub struct BracketManager {
+    pub config: BracketConfig,
+    exchange_client: ExchangeClient,
+    positions: HashMap<String, BracketedPosition>,
+    /// Oids of the triggers resting for each coin
+    brackets: HashMap<String, Vec<u64>>,
+}
+
+impl BracketManager {
+    pub fn new(exchange_client: ExchangeClient, config: BracketConfig) -> BracketManager {
+        BracketManager {
+            config,
+            exchange_client,
+            positions: HashMap::new(),
+            brackets: HashMap::new(),
+        }
+    }
+
+    pub fn positions(&self) -> &HashMap<String, BracketedPosition> {
+        &self.positions
+    }
+
+    /// Price decimals and sz decimals of `coin`
+    fn decimals(&self, coin: &str) -> Option<(u32, u32)> {
+        let asset = *self.exchange_client.coin_to_asset.get(coin)?;
+        let sz_decimals = self
+            .exchange_client
+            .meta
+            .universe
+            .iter()
+            .find(|asset| asset.name == coin)?
+            .sz_decimals;
+        let network = self.exchange_client.network_config();
+        Some((network.max_px_decimals(asset, sz_decimals), sz_decimals))
+    }
+
+    fn triggers(&self, coin: &Coin, position: &BracketedPosition) -> Vec<ClientOrderRequest> {
+        let Some((px_decimals, sz_decimals)) = self.decimals(coin.as_str()) else {
+            error!("No metadata for {coin:?}, not bracketing it");
+            return Vec::new();
+        };
+        let is_long = position.szi > 0.0;
+        let sz = Sz::try_from(position.szi.abs())
+            .ok()
+            .and_then(|sz| f64::try_from(sz.round_to_lot(sz_decimals).value()).ok())
+            .unwrap_or(position.szi.abs());
+        let (tp, sl) = self.config.trigger_pxs(is_long, position.entry_px);
+        let mut triggers = Vec::new();
+        if let Some(tp) = tp {
+            let tp = round_px(tp, px_decimals, is_long);
+            triggers.push(ClientOrderRequest::take_profit(
+                coin, !is_long, tp, sz, true,
+            ));
+        }
+        if let Some(sl) = sl {
+            let sl = round_px(sl, px_decimals, !is_long);
+            triggers.push(ClientOrderRequest::stop_loss(coin, !is_long, sl, sz, true));
+        }
+        triggers
+    }
+
+    async fn cancel_bracket(&mut self, coin: &str) {
+        let Some(oids) = self.brackets.remove(coin) else {
+            return;
+        };
+        let cancels = oids
+            .into_iter()
+            .map(|oid| ClientCancelRequest {
+                asset: coin.to_string(),
+                oid,
+            })
+            .collect();
+        // a trigger that closed the position is already gone, so errors are expected
+        if let Err(err) = self.exchange_client.bulk_cancel(cancels, None).await {
+            warn!("Could not cancel bracket of {coin}: {err}");
+        }
+    }
+
+    /// Replaces the bracket of `coin` with one matching its position, or cancels it if the
+    /// position closed
+    async fn update_bracket(&mut self, coin: &str) {
+        self.cancel_bracket(coin).await;
+        let Some(position) = self.positions.get(coin).copied() else {
+            info!("Position in {coin} closed, cancelled its bracket");
+            return;
+        };
+        let Ok(coin) = Coin::try_from(coin) else {
+            return;
+        };
+        let triggers = self.triggers(&coin, &position);
+        if triggers.is_empty() {
+            return;
+        }
+        match self
+            .exchange_client
+            .bulk_order_results(triggers, None)
+            .await
+        {
+            Ok(results) => {
+                let mut oids = Vec::new();
+                for result in results {
+                    match result.status {
+                        ExchangeDataStatus::Resting(order) => oids.push(order.oid),
+                        status => error!("Could not place bracket trigger of {coin:?}: {status:?}"),
+                    }
+                }
+                info!("Bracketed {position:?} in {coin:?} with {oids:?}");
+                self.brackets.insert(coin.as_str().to_string(), oids);
+            }
+            Err(err) => error!("Could not place bracket of {coin:?}: {err}"),
+        }
+    }
+
+    fn is_bracketed(&self, coin: &str) -> bool {
+        Coin::try_from(coin).is_ok()
+            && self
+                .config
+                .coins
+                .as_ref()
+                .is_none_or(|coins| coins.contains(coin))
+    }
+
+    /// Starts watching the fills of the account the client trades for, on the client's network,
+    /// and managing brackets until the task is aborted. Fills replayed when the subscription
+    /// opens are ignored.
+    pub async fn start(mut self) -> Result<JoinHandle<()>> {
+        let user = self
+            .exchange_client
+            .account_of(self.exchange_client.wallet.address())
+            .await?;
+        let (monitor, mut receiver) =
+            MultiUserMonitor::start_from(vec![user], &self.exchange_client.info_client(), true)
+                .await?;
+
+        Ok(spawn(async move {
+            // keeps the account's connection open
+            let _monitor = monitor;
+            while let Some(event) = receiver.recv().await {
+                match event.event {
+                    MonitorEvent::Fills {
+                        fills,
+                        is_snapshot: false,
+                    } => {
+                        let fills: Vec<_> = fills
+                            .into_iter()
+                            .filter(|fill| self.is_bracketed(&fill.coin))
+                            .collect();
+                        for coin in apply_fills(&mut self.positions, &fills) {
+                            self.update_bracket(&coin).await;
+                        }
+                    }
+                    MonitorEvent::Disconnected => {
+                        warn!("Lost fills, brackets may not match positions until reconnect")
+                    }
+                    _ => {}
+                }
+            }
+            warn!("BracketManager for {user} stopped");
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws::trade_info;
+
+    fn fill(coin: &str, side: &str, start: &str, px: &str, sz: &str) -> TradeInfo {
+        TradeInfo {
+            start_position: start.to_string(),
+            ..trade_info(coin, side, px, sz)
+        }
+    }
+
+    #[test]
+    fn test_apply_fills() {
+        let mut positions = HashMap::new();
+        // partial fills of one order open the position
+        let changed = apply_fills(
+            &mut positions,
+            &[
+                fill("ETH", "B", "0", "2000", "0.5"),
+                fill("ETH", "B", "0.5", "2100", "0.5"),
+                // already open before watching
+                fill("BTC", "A", "1", "60000", "0.1"),
+            ],
+        );
+        assert_eq!(changed, vec!["ETH".to_string()]);
+        assert_eq!(
+            positions["ETH"],
+            BracketedPosition {
+                szi: 1.0,
+                entry_px: 2050.0
+            }
+        );
+
+        // a decrease keeps the entry price
+        apply_fills(&mut positions, &[fill("ETH", "A", "1", "2200", "0.25")]);
+        assert_eq!(positions["ETH"].szi, 0.75);
+        assert_eq!(positions["ETH"].entry_px, 2050.0);
+
+        // flipping short opens a new position at the fill price
+        apply_fills(&mut positions, &[fill("ETH", "A", "0.75", "2200", "1")]);
+        assert_eq!(
+            positions["ETH"],
+            BracketedPosition {
+                szi: -0.25,
+                entry_px: 2200.0
+            }
+        );
+
+        let changed = apply_fills(&mut positions, &[fill("ETH", "B", "-0.25", "2150", "0.25")]);
+        assert_eq!(changed, vec!["ETH".to_string()]);
+        assert!(positions.is_empty());
+    }
+
+    #[test]
+    fn test_trigger_pxs() {
+        let config = BracketConfig {
+            take_profit: Some(0.02),
+            stop_loss: Some(0.01),
+            coins: None,
+        };
+        let (tp, sl) = config.trigger_pxs(true, 2000.0);
+        assert!((tp.unwrap() - 2040.0).abs() < EPSILON);
+        assert!((sl.unwrap() - 1980.0).abs() < EPSILON);
+        let (tp, sl) = config.trigger_pxs(false, 2000.0);
+        assert!((tp.unwrap() - 1960.0).abs() < EPSILON);
+        assert!((sl.unwrap() - 2020.0).abs() < EPSILON);
+    }
+}
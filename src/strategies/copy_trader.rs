@@ -0,0 +1,169 @@
+use crate::{
+    prelude::*, Address, BaseUrl, ExchangeClient, ExchangeResponseStatus, MarketOrderParams,
+    MonitorEvent, MultiUserMonitor, TradeInfo,
+};
+use log::{error, info, warn};
+use std::collections::HashSet;
+use tokio::{spawn, task::JoinHandle};
+
+#[derive(Debug, Clone)]
+pub struct CopyTradeConfig {
+    /// Follower size as a multiple of the leader's fill size
+    pub scale: f64,
+    /// Only copy these coins, `None` copies every perp
+    pub coins: Option<HashSet<String>>,
+    pub excluded_coins: HashSet<String>,
+    /// Orders worth more than this at the leader's price are reduced to it
+    pub max_notional: f64,
+    /// Max distance of the follower's IOC limit price from the leader's fill price, e.g. 0.01 for 1%
+    pub slippage: f64,
+}
+
+impl CopyTradeConfig {
+    /// Follower order for a leader fill, `None` if the fill is filtered out
+    pub fn plan(&self, fill: &TradeInfo) -> Option<CopyOrder> {
+        // spot fills use "@{index}" or "BASE/QUOTE" names
+        if fill.coin.starts_with('@') || fill.coin.contains('/') {
+            return None;
+        }
+        if self.excluded_coins.contains(&fill.coin)
+            || self
+                .coins
+                .as_ref()
+                .is_some_and(|coins| !coins.contains(&fill.coin))
+        {
+            return None;
+        }
+
+        let (Ok(px), Ok(sz)) = (fill.px.parse::<f64>(), fill.sz.parse::<f64>()) else {
+            error!("Could not parse leader fill {fill:?}");
+            return None;
+        };
+        let mut sz = sz * self.scale;
+        if sz * px > self.max_notional {
+            sz = self.max_notional / px;
+        }
+        if sz <= 0.0 {
+            return None;
+        }
+        Some(CopyOrder {
+            coin: fill.coin.clone(),
+            is_buy: fill.side == "B",
+            sz,
+            px,
+        })
+    }
+}
+
+/// Order placed for the follower in response to one leader fill
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyOrder {
+    pub coin: String,
+    pub is_buy: bool,
+    pub sz: f64,
+    /// Leader's fill price, the reference for the slippage limit
+    pub px: f64,
+}
+
+/// Mirrors the perp fills of a leader into the follower account of `exchange_client`
+/// with IOC orders, using a [`MultiUserMonitor`] on the leader for the fills.
+#[derive(Debug)]
+pub struct CopyTrader {
+    pub leader: Address,
+    pub config: CopyTradeConfig,
+    exchange_client: ExchangeClient,
+}
+
+impl CopyTrader {
+    pub fn new(
+        leader: Address,
+        exchange_client: ExchangeClient,
+        config: CopyTradeConfig,
+    ) -> CopyTrader {
+        CopyTrader {
+            leader,
+            config,
+            exchange_client,
+        }
+    }
+
+    async fn copy(&self, order: &CopyOrder) -> Result<ExchangeResponseStatus> {
+        self.exchange_client
+            .market_open(MarketOrderParams {
+                asset: &order.coin,
+                is_buy: order.is_buy,
+                sz: order.sz,
+                px: Some(order.px),
+                slippage: Some(self.config.slippage),
+                cloid: None,
+                wallet: None,
+            })
+            .await
+    }
+
+    /// Starts watching the leader and copying its fills until the task is aborted.
+    /// Fills replayed when the subscription opens are not copied.
+    pub async fn start(self, base_url: Option<BaseUrl>) -> Result<JoinHandle<()>> {
+        let (monitor, mut receiver) =
+            MultiUserMonitor::start(vec![self.leader], base_url, true).await?;
+
+        Ok(spawn(async move {
+            // keeps the leader's connection open
+            let _monitor = monitor;
+            while let Some(event) = receiver.recv().await {
+                match event.event {
+                    MonitorEvent::Fills {
+                        fills,
+                        is_snapshot: false,
+                    } => {
+                        for order in fills.iter().filter_map(|fill| self.config.plan(fill)) {
+                            match self.copy(&order).await {
+                                Ok(ExchangeResponseStatus::Ok(_)) => info!("Copied {order:?}"),
+                                Ok(ExchangeResponseStatus::Err(err)) => {
+                                    error!("Exchange rejected copy of {order:?}: {err}")
+                                }
+                                Err(err) => error!("Could not copy {order:?}: {err}"),
+                            }
+                        }
+                    }
+                    MonitorEvent::Disconnected => {
+                        warn!("Lost leader fills, fills until reconnect are not copied")
+                    }
+                    _ => {}
+                }
+            }
+            warn!("CopyTrader for {} stopped", self.leader);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws::trade_info as fill;
+
+    #[test]
+    fn test_copy_order_plan() {
+        let config = CopyTradeConfig {
+            scale: 0.5,
+            coins: None,
+            excluded_coins: HashSet::from(["DOGE".to_string()]),
+            max_notional: 1000.0,
+            slippage: 0.01,
+        };
+
+        assert_eq!(
+            config.plan(&fill("ETH", "B", "2000", "0.5")),
+            Some(CopyOrder {
+                coin: "ETH".to_string(),
+                is_buy: true,
+                sz: 0.25,
+                px: 2000.0
+            })
+        );
+        // capped at max notional
+        assert_eq!(config.plan(&fill("ETH", "A", "2000", "4")).unwrap().sz, 0.5);
+        assert_eq!(config.plan(&fill("DOGE", "B", "0.1", "100")), None);
+        assert_eq!(config.plan(&fill("@107", "B", "10", "1")), None);
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{TradeInfo, UserData};
+use log::error;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyFill {
+    pub oid: u64,
+    pub is_buy: bool,
+    pub px: f64,
+    pub sz: f64,
+}
+
+/// Extracts the fills for one asset from user events
+#[derive(Debug, Clone)]
+pub struct FillHandler {
+    pub asset: String,
+}
+
+impl FillHandler {
+    pub fn new(asset: String) -> FillHandler {
+        FillHandler { asset }
+    }
+
+    pub fn fills(&self, user_data: &UserData) -> Vec<StrategyFill> {
+        let UserData::Fills(fills) = user_data else {
+            return Vec::new();
+        };
+        fills
+            .iter()
+            .filter(|fill| fill.coin == self.asset)
+            .filter_map(|fill| match Self::parse(fill) {
+                Ok(fill) => Some(fill),
+                Err(err) => {
+                    error!("Could not parse fill {fill:?}: {err}");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn parse(fill: &TradeInfo) -> std::result::Result<StrategyFill, std::num::ParseFloatError> {
+        Ok(StrategyFill {
+            oid: fill.oid,
+            is_buy: fill.side == "B",
+            px: fill.px.parse()?,
+            sz: fill.sz.parse()?,
+        })
+    }
+}
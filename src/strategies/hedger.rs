@@ -0,0 +1,207 @@
+use crate::{
+    info::poller::Poller, prelude::*, Address, Error, ExchangeClient, ExchangeResponseStatus,
+    InfoClient, MarketOrderParams, RiskReport,
+};
+use log::{error, info, warn};
+use rust_decimal::Decimal;
+use std::{collections::HashSet, time::Duration};
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Clone)]
+pub struct HedgeConfig {
+    /// Only hedge these coins, `None` hedges every perp with exposure
+    pub coins: Option<HashSet<String>>,
+    /// Net delta in USDC, long or short, left unhedged
+    pub band: Decimal,
+    /// Hedges worth more than this are reduced to it, the rest is left for the next interval
+    pub max_notional: Decimal,
+    /// Max distance of the IOC limit price from the mid, e.g. 0.01 for 1%
+    pub slippage: f64,
+    pub interval: Duration,
+    /// Log the hedges instead of placing them
+    pub dry_run: bool,
+}
+
+/// IOC order offsetting the net delta of one coin
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeOrder {
+    pub coin: String,
+    pub is_buy: bool,
+    pub sz: Decimal,
+    /// Value of the order at the mark price of the report
+    pub notional: Decimal,
+}
+
+impl HedgeConfig {
+    /// Orders bringing every coin whose net delta is outside the band back to neutral
+    pub fn plan(&self, report: &RiskReport) -> Vec<HedgeOrder> {
+        let mut orders = Vec::new();
+        for exposure in &report.coins {
+            if self
+                .coins
+                .as_ref()
+                .is_some_and(|coins| !coins.contains(&exposure.coin))
+            {
+                continue;
+            }
+            if exposure.net_size.is_zero() || exposure.net_delta.abs() <= self.band {
+                continue;
+            }
+            let px = exposure.net_delta / exposure.net_size;
+            let (mut sz, mut notional) = (exposure.net_size.abs(), exposure.net_delta.abs());
+            if notional > self.max_notional {
+                sz = self.max_notional / px;
+                notional = self.max_notional;
+            }
+            orders.push(HedgeOrder {
+                coin: exposure.coin.clone(),
+                is_buy: exposure.net_size.is_sign_negative(),
+                sz,
+                notional,
+            });
+        }
+        orders
+    }
+}
+
+/// Keeps the combined perp exposure of several accounts delta neutral by trading in the account
+/// of `exchange_client`. `users` should include that account so its hedges count as offsets.
+#[derive(Debug)]
+pub struct Hedger {
+    pub users: Vec<Address>,
+    pub config: HedgeConfig,
+    exchange_client: ExchangeClient,
+}
+
+impl Hedger {
+    pub fn new(
+        users: Vec<Address>,
+        exchange_client: ExchangeClient,
+        config: HedgeConfig,
+    ) -> Hedger {
+        Hedger {
+            users,
+            config,
+            exchange_client,
+        }
+    }
+
+    async fn hedge(&self, order: &HedgeOrder) -> Result<ExchangeResponseStatus> {
+        let sz = f64::try_from(order.sz).map_err(|_| Error::FloatStringParse)?;
+        self.exchange_client
+            .market_open(MarketOrderParams {
+                asset: &order.coin,
+                is_buy: order.is_buy,
+                sz,
+                px: None,
+                slippage: Some(self.config.slippage),
+                cloid: None,
+                wallet: None,
+            })
+            .await
+    }
+
+    /// Fetches the current exposure and places the hedges it calls for, returning them.
+    /// Nothing is hedged while any account's state is missing.
+    pub async fn hedge_once(&self, info_client: &InfoClient) -> Result<Vec<HedgeOrder>> {
+        let report = info_client.aggregated_risk(self.users.clone()).await?;
+        if !report.failed.is_empty() {
+            warn!("Not hedging without the states of {:?}", report.failed);
+            return Ok(Vec::new());
+        }
+
+        let orders = self.config.plan(&report);
+        for order in &orders {
+            if self.config.dry_run {
+                info!("Dry run hedge {order:?}");
+                continue;
+            }
+            match self.hedge(order).await {
+                Ok(ExchangeResponseStatus::Ok(_)) => info!("Hedged {order:?}"),
+                Ok(ExchangeResponseStatus::Err(err)) => {
+                    error!("Exchange rejected hedge {order:?}: {err}")
+                }
+                Err(err) => error!("Could not hedge {order:?}: {err}"),
+            }
+        }
+        Ok(orders)
+    }
+
+    /// Hedges every `interval` until the task is aborted
+    pub fn start(self, info_client: &InfoClient) -> Result<JoinHandle<()>> {
+        let poller = Poller::new("Hedger", self.config.interval)?;
+        let info_client = info_client.detached();
+        Ok(
+            poller.spawn((self, info_client), |(hedger, info_client)| async move {
+                if let Err(err) = hedger.hedge_once(&info_client).await {
+                    error!("Error hedging: {err}");
+                }
+                Some((hedger, info_client))
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CoinExposure;
+
+    fn exposure(coin: &str, net_size: i64, net_delta: i64) -> CoinExposure {
+        CoinExposure {
+            coin: coin.to_string(),
+            net_size: Decimal::from(net_size),
+            net_delta: Decimal::from(net_delta),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_hedge_plan() {
+        let config = HedgeConfig {
+            coins: None,
+            band: Decimal::from(500),
+            max_notional: Decimal::from(10_000),
+            slippage: 0.01,
+            interval: Duration::from_secs(10),
+            dry_run: true,
+        };
+        let report = RiskReport {
+            time: 0,
+            accounts: Vec::new(),
+            coins: vec![
+                exposure("BTC", -1, -50_000),
+                exposure("ETH", 2, 4_000),
+                exposure("SOL", 2, 300),
+            ],
+            account_value: Decimal::ZERO,
+            margin_used: Decimal::ZERO,
+            margin_usage: None,
+            failed: Vec::new(),
+        };
+
+        assert_eq!(
+            config.plan(&report),
+            vec![
+                HedgeOrder {
+                    coin: "BTC".to_string(),
+                    is_buy: true,
+                    sz: Decimal::new(2, 1),
+                    notional: Decimal::from(10_000),
+                },
+                HedgeOrder {
+                    coin: "ETH".to_string(),
+                    is_buy: false,
+                    sz: Decimal::from(2),
+                    notional: Decimal::from(4_000),
+                },
+            ]
+        );
+
+        let config = HedgeConfig {
+            coins: Some(HashSet::from(["ETH".to_string()])),
+            ..config
+        };
+        assert_eq!(config.plan(&report).len(), 1);
+    }
+}
@@ -0,0 +1,94 @@
+/// Tracks the strategy's position and sizes quotes so it never exceeds the max absolute position
+#[derive(Debug, Clone, Copy)]
+pub struct InventoryManager {
+    pub position: f64,
+    /// Amount of liquidity on both sides to target
+    pub target_liquidity: f64,
+    /// Absolute value of the max position we can take on
+    pub max_absolute_position_size: f64,
+}
+
+impl InventoryManager {
+    pub fn new(target_liquidity: f64, max_absolute_position_size: f64) -> InventoryManager {
+        InventoryManager {
+            position: 0.0,
+            target_liquidity,
+            max_absolute_position_size,
+        }
+    }
+
+    pub fn bid_size(&self) -> f64 {
+        (self.max_absolute_position_size - self.position)
+            .min(self.target_liquidity)
+            .max(0.0)
+    }
+
+    pub fn ask_size(&self) -> f64 {
+        (self.max_absolute_position_size + self.position)
+            .min(self.target_liquidity)
+            .max(0.0)
+    }
+
+    pub fn on_fill(&mut self, is_buy: bool, sz: f64) {
+        if is_buy {
+            self.position += sz;
+        } else {
+            self.position -= sz;
+        }
+    }
+}
+
+/// Moves quotes against the position, so a long inventory is more likely to be sold than added
+/// to. Each side moves by its coefficient (in BPS) times the position as a fraction of the max
+/// absolute position size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InventorySkew {
+    pub bid_bps: f64,
+    pub ask_bps: f64,
+}
+
+impl InventorySkew {
+    /// `(bid, ask)` shifts in BPS for the current position
+    pub fn shifts_bps(&self, inventory: &InventoryManager) -> (f64, f64) {
+        if inventory.max_absolute_position_size <= 0.0 {
+            return (0.0, 0.0);
+        }
+        let fraction = (inventory.position / inventory.max_absolute_position_size).clamp(-1.0, 1.0);
+        (-self.bid_bps * fraction, -self.ask_bps * fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_sizes() {
+        let mut inventory = InventoryManager::new(0.25, 0.5);
+        assert_eq!(inventory.bid_size(), 0.25);
+        assert_eq!(inventory.ask_size(), 0.25);
+
+        inventory.on_fill(true, 0.4);
+        assert!((inventory.bid_size() - 0.1).abs() < 1e-9);
+        assert_eq!(inventory.ask_size(), 0.25);
+
+        inventory.on_fill(true, 0.2);
+        assert_eq!(inventory.bid_size(), 0.0);
+    }
+
+    #[test]
+    fn test_inventory_skew() {
+        let skew = InventorySkew {
+            bid_bps: 4.0,
+            ask_bps: 2.0,
+        };
+        let mut inventory = InventoryManager::new(0.25, 0.5);
+        assert_eq!(skew.shifts_bps(&inventory), (0.0, 0.0));
+
+        inventory.on_fill(true, 0.25);
+        assert_eq!(skew.shifts_bps(&inventory), (-2.0, -1.0));
+
+        inventory.on_fill(false, 1.5);
+        assert_eq!(skew.shifts_bps(&inventory), (4.0, 2.0));
+    }
+}
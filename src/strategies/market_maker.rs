@@ -0,0 +1,385 @@
+use alloy::signers::local::PrivateKeySigner;
+use log::{error, info, warn};
+
+use std::collections::BTreeMap;
+use tokio::sync::mpsc::unbounded_channel;
+
+use crate::{
+    bps_diff,
+    helpers::now_timestamp_ms,
+    limits::MAX_PX_SIG_FIGS,
+    strategies::{
+        cancel_order, place_limit_order, FairValueOffset, FillHandler, InventoryManager,
+        InventorySkew, PersistedOrder, QuoteEngine, Reconciliation, StrategyState,
+    },
+    Address, BaseUrl, ExchangeClient, InfoClient, Message, Px, Subscription, Sz, EPSILON,
+};
+#[derive(Debug)]
+pub struct MarketMakerRestingOrder {
+    pub oid: u64,
+    pub position: f64,
+    pub price: f64,
+}
+
+impl MarketMakerRestingOrder {
+    /// Whether to cancel this order and quote `amount` at `price` instead. An order left smaller
+    /// than `amount` by partial fills keeps resting, and its queue position, while the shortfall
+    /// is at most `size_tolerance` of `amount`.
+    pub fn needs_requote(
+        &self,
+        amount: f64,
+        price: f64,
+        max_bps_diff: u16,
+        size_tolerance: f64,
+    ) -> bool {
+        if bps_diff(price, self.price) > max_bps_diff {
+            return true;
+        }
+        let shortfall = amount - self.position;
+        if shortfall < -EPSILON {
+            // Resting more than we can take on now
+            return true;
+        }
+        shortfall > EPSILON && shortfall > amount * size_tolerance
+    }
+}
+
+#[derive(Debug)]
+pub struct MarketMakerInput {
+    pub asset: String,
+    pub target_liquidity: Sz, // Amount of liquidity on both sides to target
+    pub half_spread: u16,     // Half of the spread for our market making (in BPS)
+    pub max_bps_diff: u16, // Max deviation before we cancel and put new orders on the book (in BPS)
+    pub size_tolerance: f64, // Fraction of the target size fills may take before requoting
+    pub max_absolute_position_size: Sz, // Absolute value of the max position we can take on
+    pub decimals: u32,     // Decimals to round to for pricing
+    pub wallet: PrivateKeySigner, // Wallet containing private key
+    pub inventory_skew: Option<InventorySkew>, // Moves quotes against our position
+    pub fair_value_offset: Option<FairValueOffset>, // Quotes around the mid moved by this offset
+}
+
+#[derive(Debug)]
+pub struct MarketMaker {
+    pub asset: String,
+    pub max_bps_diff: u16,
+    pub size_tolerance: f64,
+    pub quote_engine: QuoteEngine,
+    /// Decimals quoted sizes are rounded down to
+    pub sz_decimals: u32,
+    pub inventory: InventoryManager,
+    pub fill_handler: FillHandler,
+    pub inventory_skew: Option<InventorySkew>,
+    pub fair_value_offset: Option<FairValueOffset>,
+    pub lower_resting: MarketMakerRestingOrder,
+    pub upper_resting: MarketMakerRestingOrder,
+    pub latest_mid_price: f64,
+    pub info_client: InfoClient,
+    pub exchange_client: ExchangeClient,
+    pub user_address: Address,
+}
+
+impl MarketMaker {
+    pub async fn new(input: MarketMakerInput) -> MarketMaker {
+        let user_address = input.wallet.address();
+
+        let info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
+        let exchange_client =
+            ExchangeClient::new(None, input.wallet, Some(BaseUrl::Testnet), None, None)
+                .await
+                .unwrap();
+        let sz_decimals = exchange_client.sz_decimals(&input.asset).await.unwrap();
+
+        MarketMaker {
+            fill_handler: FillHandler::new(input.asset.clone()),
+            asset: input.asset,
+            max_bps_diff: input.max_bps_diff,
+            size_tolerance: input.size_tolerance,
+            quote_engine: QuoteEngine::new(input.half_spread, input.decimals),
+            sz_decimals,
+            inventory: InventoryManager::new(
+                f64::try_from(input.target_liquidity.value()).unwrap(),
+                f64::try_from(input.max_absolute_position_size.value()).unwrap(),
+            ),
+            inventory_skew: input.inventory_skew,
+            fair_value_offset: input.fair_value_offset,
+            lower_resting: MarketMakerRestingOrder {
+                oid: 0,
+                position: 0.0,
+                price: -1.0,
+            },
+            upper_resting: MarketMakerRestingOrder {
+                oid: 0,
+                position: 0.0,
+                price: -1.0,
+            },
+            latest_mid_price: -1.0,
+            info_client,
+            exchange_client,
+            user_address,
+        }
+    }
+
+    /// Resting orders and position to hand to [`crate::StatePersistence::save`] on shutdown
+    pub fn strategy_state(&self) -> StrategyState {
+        let mut open_orders = BTreeMap::new();
+        for (resting, is_buy) in [(&self.lower_resting, true), (&self.upper_resting, false)] {
+            if resting.oid != 0 && resting.position > EPSILON {
+                open_orders.insert(
+                    resting.oid,
+                    PersistedOrder {
+                        coin: self.asset.clone(),
+                        is_buy,
+                        limit_px: resting.price,
+                        sz: resting.position,
+                    },
+                );
+            }
+        }
+        StrategyState {
+            user: self.user_address,
+            saved_at: now_timestamp_ms(),
+            open_orders,
+            positions: BTreeMap::from([(self.asset.clone(), self.inventory.position)]),
+        }
+    }
+
+    /// Adopts the position and resting orders found on the exchange at startup, so `start`
+    /// requotes them instead of placing duplicates
+    pub fn restore(&mut self, reconciliation: &Reconciliation) {
+        for discrepancy in &reconciliation.discrepancies {
+            warn!("State changed while stopped: {discrepancy:?}");
+        }
+        let state = &reconciliation.state;
+        self.inventory.position = state
+            .positions
+            .get(&self.asset)
+            .copied()
+            .unwrap_or_default();
+        for (oid, order) in &state.open_orders {
+            if order.coin != self.asset {
+                continue;
+            }
+            let resting = if order.is_buy {
+                &mut self.lower_resting
+            } else {
+                &mut self.upper_resting
+            };
+            if resting.oid != 0 {
+                warn!("Ignoring extra resting order {oid} on {}", self.asset);
+                continue;
+            }
+            *resting = MarketMakerRestingOrder {
+                oid: *oid,
+                position: order.sz,
+                price: order.limit_px,
+            };
+        }
+    }
+
+    pub async fn start(&mut self) {
+        let (sender, mut receiver) = unbounded_channel();
+
+        // Subscribe to UserEvents for fills
+        self.info_client
+            .subscribe(
+                Subscription::UserEvents {
+                    user: self.user_address,
+                },
+                sender.clone(),
+            )
+            .await
+            .unwrap();
+
+        // Subscribe to AllMids so we can market make around the mid price
+        self.info_client
+            .subscribe(Subscription::AllMids, sender)
+            .await
+            .unwrap();
+
+        loop {
+            let message = receiver.recv().await.unwrap();
+            match message {
+                Message::AllMids(all_mids) => {
+                    let all_mids = all_mids.data.mids;
+                    let mid = all_mids.get(&self.asset);
+                    if let Some(mid) = mid {
+                        let mid: f64 = mid.parse().unwrap();
+                        self.latest_mid_price = mid;
+                        // Check to see if we need to cancel or place any new orders
+                        self.potentially_update().await;
+                    } else {
+                        error!(
+                            "could not get mid for asset {}: {all_mids:?}",
+                            self.asset.clone()
+                        );
+                    }
+                }
+                Message::User(user_events) => {
+                    // We haven't seen the first mid price event yet, so just continue
+                    if self.latest_mid_price < 0.0 {
+                        continue;
+                    }
+                    for fill in self.fill_handler.fills(&user_events.data) {
+                        // Update our resting positions whenever we see a fill
+                        self.inventory.on_fill(fill.is_buy, fill.sz);
+                        if fill.is_buy {
+                            self.lower_resting.position -= fill.sz;
+                            info!("Fill: bought {} {}", fill.sz, self.asset.clone());
+                        } else {
+                            self.upper_resting.position -= fill.sz;
+                            info!("Fill: sold {} {}", fill.sz, self.asset.clone());
+                        }
+                    }
+                    // Check to see if we need to cancel or place any new orders
+                    self.potentially_update().await;
+                }
+                _ => {
+                    panic!("Unsupported message type");
+                }
+            }
+        }
+    }
+
+    /// `amount` rounded down to the lot size and `price` rounded away from the mid to the tick
+    fn order_sz_px(&self, amount: f64, price: f64, is_buy: bool) -> (Sz, Px) {
+        let sz = Sz::try_from(amount).unwrap_or_default();
+        let px = Px::try_from(price).unwrap_or_default();
+        (
+            sz.round_to_lot(self.sz_decimals),
+            px.round_to_tick(self.quote_engine.decimals, MAX_PX_SIG_FIGS, !is_buy),
+        )
+    }
+
+    async fn potentially_update(&mut self) {
+        // Determine prices to target from the half spread around the fair value, skewed by our position
+        let fair_value = match &self.fair_value_offset {
+            Some(offset) => offset.fair_value(self.latest_mid_price),
+            None => self.latest_mid_price,
+        };
+        let (bid_shift, ask_shift) = self
+            .inventory_skew
+            .map(|skew| skew.shifts_bps(&self.inventory))
+            .unwrap_or_default();
+        let quote = self
+            .quote_engine
+            .skewed_quote(fair_value, bid_shift, ask_shift);
+        let (lower_price, upper_price) = (quote.bid_px, quote.ask_px);
+
+        // Determine amounts we can put on the book without exceeding the max absolute position size
+        let lower_order_amount = self.inventory.bid_size();
+        let upper_order_amount = self.inventory.ask_size();
+
+        // Determine if we need to cancel the resting order and put a new order up due to deviation
+        let lower_change = self.lower_resting.needs_requote(
+            lower_order_amount,
+            lower_price,
+            self.max_bps_diff,
+            self.size_tolerance,
+        );
+        let upper_change = self.upper_resting.needs_requote(
+            upper_order_amount,
+            upper_price,
+            self.max_bps_diff,
+            self.size_tolerance,
+        );
+
+        // Consider cancelling
+        // TODO: Don't block on cancels
+        if self.lower_resting.oid != 0 && self.lower_resting.position > EPSILON && lower_change {
+            let cancel = cancel_order(
+                &self.exchange_client,
+                self.asset.clone(),
+                self.lower_resting.oid,
+            )
+            .await;
+            // If we were unable to cancel, it means we got a fill, so wait until we receive that event to do anything
+            if !cancel {
+                return;
+            }
+            info!("Cancelled buy order: {:?}", self.lower_resting);
+        }
+
+        if self.upper_resting.oid != 0 && self.upper_resting.position > EPSILON && upper_change {
+            let cancel = cancel_order(
+                &self.exchange_client,
+                self.asset.clone(),
+                self.upper_resting.oid,
+            )
+            .await;
+            if !cancel {
+                return;
+            }
+            info!("Cancelled sell order: {:?}", self.upper_resting);
+        }
+
+        // Consider putting a new order up
+        if lower_order_amount > EPSILON && lower_change {
+            let (sz, px) = self.order_sz_px(lower_order_amount, lower_price, true);
+            let (sz_resting, oid) =
+                place_limit_order(&self.exchange_client, self.asset.clone(), sz, px, true).await;
+            self.lower_resting.oid = oid;
+            self.lower_resting.position = f64::try_from(sz_resting.value()).unwrap_or_default();
+            self.lower_resting.price = lower_price;
+
+            if !sz_resting.is_zero() {
+                info!(
+                    "Buy for {sz_resting} {} resting at {px}",
+                    self.asset.clone()
+                );
+            }
+        }
+
+        if upper_order_amount > EPSILON && upper_change {
+            let (sz, px) = self.order_sz_px(upper_order_amount, upper_price, false);
+            let (sz_resting, oid) =
+                place_limit_order(&self.exchange_client, self.asset.clone(), sz, px, false).await;
+            self.upper_resting.oid = oid;
+            self.upper_resting.position = f64::try_from(sz_resting.value()).unwrap_or_default();
+            self.upper_resting.price = upper_price;
+
+            if !sz_resting.is_zero() {
+                info!(
+                    "Sell for {sz_resting} {} resting at {px}",
+                    self.asset.clone()
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs_requote() {
+        let resting = MarketMakerRestingOrder {
+            oid: 1,
+            position: 0.8,
+            price: 2000.0,
+        };
+        // Partially filled but within tolerance, so it keeps its queue position
+        assert!(!resting.needs_requote(1.0, 2000.0, 2, 0.25));
+        assert!(resting.needs_requote(1.0, 2000.0, 2, 0.1));
+        // Price moved too far
+        assert!(resting.needs_requote(1.0, 2001.0, 2, 0.25));
+        assert!(!resting.needs_requote(1.0, 2000.2, 2, 0.25));
+        // Resting more than the inventory allows
+        assert!(resting.needs_requote(0.5, 2000.0, 2, 0.25));
+
+        let filled = MarketMakerRestingOrder {
+            oid: 1,
+            position: 0.0,
+            price: 2000.0,
+        };
+        assert!(filled.needs_requote(1.0, 2000.0, 2, 0.25));
+        assert!(!filled.needs_requote(0.0, 2000.0, 2, 0.25));
+
+        let unplaced = MarketMakerRestingOrder {
+            oid: 0,
+            position: 0.0,
+            price: -1.0,
+        };
+        assert!(unplaced.needs_requote(1.0, 2000.0, 2, 0.25));
+    }
+}
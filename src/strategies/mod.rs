@@ -0,0 +1,22 @@
+mod bracket;
+mod copy_trader;
+mod fill_handler;
+mod hedger;
+mod inventory;
+mod market_maker;
+mod orders;
+mod persistence;
+mod quote_engine;
+
+pub use bracket::{BracketConfig, BracketManager, BracketedPosition};
+pub use copy_trader::{CopyOrder, CopyTradeConfig, CopyTrader};
+pub use fill_handler::{FillHandler, StrategyFill};
+pub use hedger::{HedgeConfig, HedgeOrder, Hedger};
+pub use inventory::{InventoryManager, InventorySkew};
+pub use market_maker::{MarketMaker, MarketMakerInput, MarketMakerRestingOrder};
+#[allow(deprecated)]
+pub use orders::{cancel_order, place_limit_order, place_limit_order_f64};
+pub use persistence::{
+    PersistedOrder, Reconciliation, StateDiscrepancy, StatePersistence, StrategyState,
+};
+pub use quote_engine::{FairValueOffset, Quote, QuoteEngine};
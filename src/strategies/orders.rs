@@ -0,0 +1,80 @@
+use crate::{
+    ClientCancelRequest, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus,
+    OrderPlacement, Px, Sz,
+};
+use log::error;
+
+/// Cancels `oid`, returning false if the cancel failed, e.g. because the order already filled
+pub async fn cancel_order(exchange_client: &ExchangeClient, asset: String, oid: u64) -> bool {
+    let cancel = exchange_client
+        .cancel(ClientCancelRequest { asset, oid }, None)
+        .await;
+
+    match cancel {
+        Ok(cancel) => match cancel {
+            ExchangeResponseStatus::Ok(cancel) => {
+                if let Some(cancel) = cancel.data {
+                    if !cancel.statuses.is_empty() {
+                        match cancel.statuses[0].clone() {
+                            ExchangeDataStatus::Success => {
+                                return true;
+                            }
+                            ExchangeDataStatus::Error(e) => {
+                                error!("Error with cancelling: {e}")
+                            }
+                            status => {
+                                error!("Unexpected status when cancelling: {status:?}")
+                            }
+                        }
+                    } else {
+                        error!("Exchange data statuses is empty when cancelling: {cancel:?}")
+                    }
+                } else {
+                    error!("Exchange response data is empty when cancelling: {cancel:?}")
+                }
+            }
+            ExchangeResponseStatus::Err(e) => error!("Error with cancelling: {e}"),
+        },
+        Err(e) => error!("Error with cancelling: {e}"),
+    }
+    false
+}
+
+/// Places a limit order with the client's default tif and reduce only, returning the placed
+/// size and oid, or `(Sz::ZERO, 0)` if it failed
+pub async fn place_limit_order(
+    exchange_client: &ExchangeClient,
+    asset: String,
+    sz: Sz,
+    px: Px,
+    is_buy: bool,
+) -> (Sz, u64) {
+    let order = exchange_client.limit_order(&asset, is_buy, px, sz);
+    match exchange_client.order_checked(order, None).await {
+        Ok(OrderPlacement::Filled(order)) => return (sz, order.oid),
+        Ok(OrderPlacement::Resting(order)) => return (sz, order.oid),
+        Ok(placement) => error!("Unexpected status when placing order: {placement:?}"),
+        Err(e) => error!("Error with placing order: {e}"),
+    }
+    (Sz::ZERO, 0)
+}
+
+#[deprecated(
+    note = "f64 prices and sizes aren't checked against the tick and lot size, use `place_limit_order`"
+)]
+pub async fn place_limit_order_f64(
+    exchange_client: &ExchangeClient,
+    asset: String,
+    amount: f64,
+    price: f64,
+    is_buy: bool,
+) -> (f64, u64) {
+    let order = exchange_client.limit_order_request(&asset, is_buy, price, amount);
+    match exchange_client.place_order_checked(order, None).await {
+        Ok(OrderPlacement::Filled(order)) => return (amount, order.oid),
+        Ok(OrderPlacement::Resting(order)) => return (amount, order.oid),
+        Ok(placement) => error!("Unexpected status when placing order: {placement:?}"),
+        Err(e) => error!("Error with placing order: {e}"),
+    }
+    (0.0, 0)
+}
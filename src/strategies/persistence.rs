@@ -0,0 +1,284 @@
+use crate::{
+    helpers::now_timestamp_ms, prelude::*, Address, Error, InfoClient, OpenOrdersResponse,
+    UserStateResponse, EPSILON,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedOrder {
+    pub coin: String,
+    pub is_buy: bool,
+    pub limit_px: f64,
+    pub sz: f64,
+}
+
+/// Open orders and positions of a strategy, as saved on shutdown
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyState {
+    pub user: Address,
+    /// Local save time in milliseconds
+    pub saved_at: u64,
+    pub open_orders: BTreeMap<u64, PersistedOrder>,
+    /// Signed position size per coin
+    pub positions: BTreeMap<String, f64>,
+}
+
+/// Difference between the saved state and the exchange found on startup
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum StateDiscrepancy {
+    /// Saved as open but no longer open on the exchange, e.g. filled or cancelled while down
+    OrderClosed { oid: u64, order: PersistedOrder },
+    /// Open on the exchange but unknown to the saved state
+    UnknownOrder { oid: u64, order: PersistedOrder },
+    /// Open in both, with a different remaining size or price
+    OrderChanged {
+        oid: u64,
+        saved: PersistedOrder,
+        actual: PersistedOrder,
+    },
+    PositionChanged {
+        coin: String,
+        saved: f64,
+        actual: f64,
+    },
+}
+
+/// Exchange state to resume from, and how it differs from what was saved
+#[derive(Debug, Clone)]
+pub struct Reconciliation {
+    pub state: StrategyState,
+    pub discrepancies: Vec<StateDiscrepancy>,
+}
+
+/// Saves strategy state to a JSON file on shutdown and reconciles it with the exchange on startup,
+/// so a restarted bot adopts its resting orders instead of placing them again.
+#[derive(Debug, Clone)]
+pub struct StatePersistence {
+    pub path: PathBuf,
+}
+
+fn parse_f64(value: &str) -> Result<f64> {
+    value
+        .parse()
+        .map_err(|e: std::num::ParseFloatError| Error::GenericParse(e.to_string()))
+}
+
+impl StatePersistence {
+    pub fn new(path: impl AsRef<Path>) -> StatePersistence {
+        StatePersistence {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Writes `state` to a temporary file and renames it over the previous state,
+    /// so a crash while saving leaves the previous state intact
+    pub fn save(&self, state: &StrategyState) -> Result<()> {
+        let data =
+            serde_json::to_string_pretty(state).map_err(|e| Error::JsonParse(e.to_string()))?;
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, data)
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|e| Error::Persistence(e.to_string()))
+    }
+
+    /// Loads the saved state, `None` if nothing was saved yet
+    pub fn load(&self) -> Result<Option<StrategyState>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let data = fs::read_to_string(&self.path).map_err(|e| Error::Persistence(e.to_string()))?;
+        serde_json::from_str(&data)
+            .map(Some)
+            .map_err(|e| Error::JsonParse(e.to_string()))
+    }
+
+    /// Fetches the open orders and positions of `saved.user` and compares them with `saved`
+    pub async fn reconcile(
+        info_client: &InfoClient,
+        saved: &StrategyState,
+    ) -> Result<Reconciliation> {
+        let open_orders = info_client.open_orders(saved.user).await?;
+        let user_state = info_client.user_state(saved.user).await?;
+        let actual = Self::exchange_state(saved.user, open_orders, user_state)?;
+        Ok(Reconciliation {
+            discrepancies: Self::discrepancies(saved, &actual),
+            state: actual,
+        })
+    }
+
+    fn exchange_state(
+        user: Address,
+        open_orders: Vec<OpenOrdersResponse>,
+        user_state: UserStateResponse,
+    ) -> Result<StrategyState> {
+        let mut state = StrategyState {
+            user,
+            saved_at: now_timestamp_ms(),
+            open_orders: BTreeMap::new(),
+            positions: BTreeMap::new(),
+        };
+        for order in open_orders {
+            state.open_orders.insert(
+                order.oid,
+                PersistedOrder {
+                    coin: order.coin,
+                    is_buy: order.side == "B",
+                    limit_px: parse_f64(&order.limit_px)?,
+                    sz: parse_f64(&order.sz)?,
+                },
+            );
+        }
+        for asset_position in user_state.asset_positions {
+            let position = asset_position.position;
+            state
+                .positions
+                .insert(position.coin, parse_f64(&position.szi)?);
+        }
+        Ok(state)
+    }
+
+    fn discrepancies(saved: &StrategyState, actual: &StrategyState) -> Vec<StateDiscrepancy> {
+        let mut discrepancies = Vec::new();
+        for (oid, order) in &saved.open_orders {
+            match actual.open_orders.get(oid) {
+                None => discrepancies.push(StateDiscrepancy::OrderClosed {
+                    oid: *oid,
+                    order: order.clone(),
+                }),
+                Some(actual_order) if actual_order != order => {
+                    discrepancies.push(StateDiscrepancy::OrderChanged {
+                        oid: *oid,
+                        saved: order.clone(),
+                        actual: actual_order.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for (oid, order) in &actual.open_orders {
+            if !saved.open_orders.contains_key(oid) {
+                discrepancies.push(StateDiscrepancy::UnknownOrder {
+                    oid: *oid,
+                    order: order.clone(),
+                });
+            }
+        }
+
+        let mut coins: Vec<&String> = saved.positions.keys().collect();
+        coins.extend(actual.positions.keys());
+        coins.sort();
+        coins.dedup();
+        for coin in coins {
+            let saved = saved.positions.get(coin).copied().unwrap_or_default();
+            let actual = actual.positions.get(coin).copied().unwrap_or_default();
+            if (saved - actual).abs() > EPSILON {
+                discrepancies.push(StateDiscrepancy::PositionChanged {
+                    coin: coin.clone(),
+                    saved,
+                    actual,
+                });
+            }
+        }
+        discrepancies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(coin: &str, is_buy: bool, limit_px: f64, sz: f64) -> PersistedOrder {
+        PersistedOrder {
+            coin: coin.to_string(),
+            is_buy,
+            limit_px,
+            sz,
+        }
+    }
+
+    fn state(orders: Vec<(u64, PersistedOrder)>, positions: Vec<(&str, f64)>) -> StrategyState {
+        StrategyState {
+            user: Address::ZERO,
+            saved_at: 0,
+            open_orders: orders.into_iter().collect(),
+            positions: positions
+                .into_iter()
+                .map(|(coin, szi)| (coin.to_string(), szi))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_discrepancies() {
+        let saved = state(
+            vec![
+                (1, order("ETH", true, 1900.0, 1.0)),
+                (2, order("ETH", false, 2100.0, 1.0)),
+                (3, order("BTC", true, 60000.0, 0.1)),
+            ],
+            vec![("ETH", 0.5), ("SOL", 3.0)],
+        );
+        let actual = state(
+            vec![
+                (2, order("ETH", false, 2100.0, 1.0)),
+                (3, order("BTC", true, 60000.0, 0.05)),
+                (4, order("BTC", false, 61000.0, 0.1)),
+            ],
+            vec![("ETH", 1.5)],
+        );
+
+        assert_eq!(
+            StatePersistence::discrepancies(&saved, &actual),
+            vec![
+                StateDiscrepancy::OrderClosed {
+                    oid: 1,
+                    order: order("ETH", true, 1900.0, 1.0)
+                },
+                StateDiscrepancy::OrderChanged {
+                    oid: 3,
+                    saved: order("BTC", true, 60000.0, 0.1),
+                    actual: order("BTC", true, 60000.0, 0.05)
+                },
+                StateDiscrepancy::UnknownOrder {
+                    oid: 4,
+                    order: order("BTC", false, 61000.0, 0.1)
+                },
+                StateDiscrepancy::PositionChanged {
+                    coin: "ETH".to_string(),
+                    saved: 0.5,
+                    actual: 1.5
+                },
+                StateDiscrepancy::PositionChanged {
+                    coin: "SOL".to_string(),
+                    saved: 3.0,
+                    actual: 0.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_save_and_load() -> Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("hl_strategy_state_{}.json", now_timestamp_ms()));
+        let persistence = StatePersistence::new(&path);
+        assert_eq!(persistence.load()?, None);
+
+        let saved = state(
+            vec![(7, order("ETH", true, 1900.5, 0.25))],
+            vec![("ETH", -0.25)],
+        );
+        persistence.save(&saved)?;
+        assert_eq!(persistence.load()?, Some(saved));
+        fs::remove_file(path).unwrap();
+        Ok(())
+    }
+}
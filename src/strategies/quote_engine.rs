@@ -0,0 +1,126 @@
+#[allow(deprecated)]
+use crate::{limits::MAX_PX_SIG_FIGS, truncate_float, Px, EPSILON};
+use std::{fmt, sync::Arc};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quote {
+    pub bid_px: f64,
+    pub ask_px: f64,
+}
+
+/// Offset in BPS of the fair value from a mid price, e.g. from an external signal
+#[derive(Clone)]
+pub struct FairValueOffset(Arc<dyn Fn(f64) -> f64 + Send + Sync>);
+
+impl FairValueOffset {
+    pub fn new(offset_bps: impl Fn(f64) -> f64 + Send + Sync + 'static) -> FairValueOffset {
+        FairValueOffset(Arc::new(offset_bps))
+    }
+
+    /// Fair value for `mid`
+    pub fn fair_value(&self, mid: f64) -> f64 {
+        mid * (1.0 + (self.0)(mid) / 10000.0)
+    }
+}
+
+impl fmt::Debug for FairValueOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FairValueOffset")
+    }
+}
+
+/// Builds a symmetric two-sided quote around a mid price
+#[derive(Debug, Clone, Copy)]
+pub struct QuoteEngine {
+    /// Half of the spread to quote (in BPS)
+    pub half_spread: u16,
+    /// Decimals to round prices to
+    pub decimals: u32,
+}
+
+impl QuoteEngine {
+    pub fn new(half_spread: u16, decimals: u32) -> QuoteEngine {
+        QuoteEngine {
+            half_spread,
+            decimals,
+        }
+    }
+
+    pub fn quote(&self, mid: f64) -> Quote {
+        self.skewed_quote(mid, 0.0, 0.0)
+    }
+
+    /// Quote with the bid and ask moved from the symmetric prices by the given BPS of `mid`.
+    /// The ask is kept at least a tick above the bid, even when the shifts would cross them.
+    #[allow(deprecated)]
+    pub fn skewed_quote(&self, mid: f64, bid_shift_bps: f64, ask_shift_bps: f64) -> Quote {
+        let half_spread = (mid * self.half_spread as f64) / 10000.0;
+        let (bid_px, ask_px) = (
+            mid - half_spread + mid * bid_shift_bps / 10000.0,
+            mid + half_spread + mid * ask_shift_bps / 10000.0,
+        );
+        let (bid_px, mut ask_px) = (
+            truncate_float(bid_px, self.decimals, true),
+            truncate_float(ask_px, self.decimals, false),
+        );
+
+        // Rounding optimistically to make our market tighter, or shifting the sides by different
+        // amounts, might cross the quote, so account for that
+        let pow10 = 10f64.powi(self.decimals as i32);
+        if ask_px - bid_px < 1.0 / pow10 - EPSILON {
+            ask_px = ((bid_px * pow10).round() + 1.0) / pow10;
+        }
+        Quote { bid_px, ask_px }
+    }
+}
+
+/// `px` rounded to `decimals` and the significant figures the exchange accepts
+pub(crate) fn round_px(px: f64, decimals: u32, round_up: bool) -> f64 {
+    Px::try_from(px)
+        .ok()
+        .and_then(|px| {
+            let px = px.round_to_tick(decimals, MAX_PX_SIG_FIGS, round_up);
+            f64::try_from(px.value()).ok()
+        })
+        .unwrap_or(px)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote() {
+        let quote = QuoteEngine::new(10, 1).quote(2000.0);
+        assert!((quote.bid_px - 1998.1).abs() < EPSILON);
+        assert!((quote.ask_px - 2002.0).abs() < EPSILON);
+        assert!(quote.bid_px < quote.ask_px);
+    }
+
+    #[test]
+    fn test_skewed_quote() {
+        let engine = QuoteEngine::new(10, 1);
+        let fair_value = FairValueOffset::new(|_| 5.0).fair_value(2000.0);
+        assert!((fair_value - 2001.0).abs() < EPSILON);
+
+        let quote = engine.skewed_quote(fair_value, -10.0, -5.0);
+        assert!((quote.bid_px - 1997.0).abs() < EPSILON);
+        assert!((quote.ask_px - 2002.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_uneven_skew_does_not_cross() {
+        let skew = crate::InventorySkew {
+            bid_bps: 0.0,
+            ask_bps: 50.0,
+        };
+        let mut inventory = crate::InventoryManager::new(0.25, 0.5);
+        inventory.on_fill(true, 0.5);
+        let (bid_shift, ask_shift) = skew.shifts_bps(&inventory);
+
+        // the ask moves 50 BPS down, past the bid 10 BPS below the mid
+        let quote = QuoteEngine::new(10, 1).skewed_quote(2000.0, bid_shift, ask_shift);
+        assert!((quote.bid_px - 1998.1).abs() < EPSILON);
+        assert!((quote.ask_px - 1998.2).abs() < EPSILON);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use std::{borrow::Borrow, fmt, str::FromStr};
+
+/// Name of a perp, e.g. `"ETH"` or `"xyz:TSLA"` for a builder-deployed dex.
+/// Spot names such as `"PURR/USDC"` or `"@107"` are rejected.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(try_from = "String", into = "String")]
+pub struct Coin(String);
+
+/// Name of a spot market, either `"BASE/QUOTE"` (e.g. `"PURR/USDC"`) or `"@{index}"`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(try_from = "String", into = "String")]
+pub struct SpotPair(String);
+
+impl Coin {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl SpotPair {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Index of the pair in the spot universe, for `"@{index}"` names
+    pub fn index(&self) -> Option<u32> {
+        self.0.strip_prefix('@')?.parse().ok()
+    }
+}
+
+impl TryFrom<String> for Coin {
+    type Error = Error;
+
+    fn try_from(coin: String) -> Result<Coin, Error> {
+        if coin.is_empty() || coin.contains(char::is_whitespace) {
+            return Err(Error::InvalidSymbol(coin));
+        }
+        if coin.contains('/') || coin.starts_with('@') {
+            return Err(Error::InvalidSymbol(format!(
+                "{coin} is a spot pair, not a perp"
+            )));
+        }
+        Ok(Coin(coin))
+    }
+}
+
+impl TryFrom<String> for SpotPair {
+    type Error = Error;
+
+    fn try_from(pair: String) -> Result<SpotPair, Error> {
+        let valid = match pair.strip_prefix('@') {
+            Some(index) => !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()),
+            None => match pair.split_once('/') {
+                Some((base, quote)) => {
+                    !base.is_empty()
+                        && !quote.is_empty()
+                        && !quote.contains('/')
+                        && !pair.contains(char::is_whitespace)
+                }
+                None => false,
+            },
+        };
+        if valid {
+            Ok(SpotPair(pair))
+        } else {
+            Err(Error::InvalidSymbol(format!(
+                "{pair} is not a spot pair, expected BASE/QUOTE or @index"
+            )))
+        }
+    }
+}
+
+macro_rules! impl_symbol {
+    ($name:ident) => {
+        impl TryFrom<&str> for $name {
+            type Error = Error;
+
+            fn try_from(value: &str) -> Result<$name, Error> {
+                $name::try_from(value.to_string())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(value: &str) -> Result<$name, Error> {
+                $name::try_from(value)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> String {
+                value.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+    };
+}
+
+impl_symbol!(Coin);
+impl_symbol!(SpotPair);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_validation() {
+        assert_eq!(Coin::try_from("ETH").unwrap().as_str(), "ETH");
+        assert!("xyz:TSLA".parse::<Coin>().is_ok());
+        assert!(Coin::try_from("PURR/USDC").is_err());
+        assert!(Coin::try_from("@107").is_err());
+        assert!(Coin::try_from("").is_err());
+
+        assert!(SpotPair::try_from("PURR/USDC").is_ok());
+        assert_eq!(SpotPair::try_from("@107").unwrap().index(), Some(107));
+        assert!(SpotPair::try_from("ETH").is_err());
+        assert!(SpotPair::try_from("@").is_err());
+        assert!(SpotPair::try_from("PURR/").is_err());
+
+        let coin: Coin = serde_json::from_str(r#""BTC""#).unwrap();
+        assert_eq!(serde_json::to_string(&coin).unwrap(), r#""BTC""#);
+        assert!(serde_json::from_str::<Coin>(r#""PURR/USDC""#).is_err());
+    }
+}
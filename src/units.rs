@@ -0,0 +1,304 @@
+use crate::{helpers::parse_decimal, limits, Error};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+/// An amount of USD(C), e.g. a notional, a fee or an account value
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct Usd(Decimal);
+
+/// A price in USD(C) per unit of the asset
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct Px(Decimal);
+
+/// A size in units of the asset
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct Sz(Decimal);
+
+macro_rules! impl_unit {
+    ($name:ident) => {
+        impl $name {
+            pub const ZERO: $name = $name(Decimal::ZERO);
+
+            pub fn new(value: Decimal) -> $name {
+                $name(value)
+            }
+
+            pub fn value(self) -> Decimal {
+                self.0
+            }
+
+            pub fn abs(self) -> $name {
+                $name(self.0.abs())
+            }
+
+            pub fn is_zero(self) -> bool {
+                self.0.is_zero()
+            }
+
+            pub fn checked_add(self, other: $name) -> Option<$name> {
+                self.0.checked_add(other.0).map($name)
+            }
+
+            pub fn checked_sub(self, other: $name) -> Option<$name> {
+                self.0.checked_sub(other.0).map($name)
+            }
+
+            /// Scales by a unitless factor, e.g. a fee rate or a leverage
+            pub fn checked_scale(self, factor: Decimal) -> Option<$name> {
+                self.0.checked_mul(factor).map($name)
+            }
+
+            /// String accepted by the exchange: at most 8 decimals, no trailing zeros and no `-0`.
+            /// Extra decimals are rounded half away from zero.
+            pub fn to_wire(self) -> String {
+                let value = self.0.round_dp(limits::MAX_WIRE_DECIMALS).normalize();
+                if value.is_zero() {
+                    "0".to_string()
+                } else {
+                    value.to_string()
+                }
+            }
+        }
+
+        impl Add for $name {
+            type Output = $name;
+
+            fn add(self, other: $name) -> $name {
+                $name(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+
+            fn sub(self, other: $name) -> $name {
+                $name(self.0 - other.0)
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+
+            fn neg(self) -> $name {
+                $name(-self.0)
+            }
+        }
+
+        impl From<Decimal> for $name {
+            fn from(value: Decimal) -> $name {
+                $name(value)
+            }
+        }
+
+        impl From<$name> for Decimal {
+            fn from(value: $name) -> Decimal {
+                value.0
+            }
+        }
+
+        impl TryFrom<f64> for $name {
+            type Error = Error;
+
+            fn try_from(value: f64) -> Result<$name, Error> {
+                Decimal::try_from(value)
+                    .map($name)
+                    .map_err(|_| Error::FloatStringParse)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = Error;
+
+            fn try_from(value: String) -> Result<$name, Error> {
+                value.parse()
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(value: &str) -> Result<$name, Error> {
+                parse_decimal(value).map($name)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> String {
+                value.to_wire()
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.to_wire())
+            }
+        }
+    };
+}
+
+impl_unit!(Usd);
+impl_unit!(Px);
+impl_unit!(Sz);
+
+impl Px {
+    /// Notional of `sz` at this price, `None` on overflow
+    pub fn checked_notional(self, sz: Sz) -> Option<Usd> {
+        self.0.checked_mul(sz.0).map(Usd)
+    }
+
+    /// Decimals this price may have: at most `max_decimals`, and no more than leave it with
+    /// `max_sig_figs` significant figures
+    fn tick_decimals(self, max_decimals: u32, max_sig_figs: u32) -> u32 {
+        let value = self.0.abs().normalize();
+        if value.is_zero() {
+            return max_decimals;
+        }
+        // value is in [10^exponent, 10^(exponent + 1))
+        let digits = value.mantissa().unsigned_abs().to_string().len() as i64;
+        let exponent = digits - 1 - value.scale() as i64;
+        let sig_fig_decimals = (max_sig_figs as i64 - 1 - exponent).max(0) as u32;
+        sig_fig_decimals.min(max_decimals)
+    }
+
+    /// Whether the exchange accepts this price: integers always, other prices with at most
+    /// `max_decimals` decimals and `max_sig_figs` significant figures. See
+    /// [`crate::NetworkConfig::max_px_decimals`] for the decimals of an asset.
+    pub fn is_on_tick(self, max_decimals: u32, max_sig_figs: u32) -> bool {
+        self.0.fract().is_zero()
+            || self.0.normalize().scale() <= self.tick_decimals(max_decimals, max_sig_figs)
+    }
+
+    /// Nearest price above or below this one that [`Px::is_on_tick`] accepts
+    pub fn round_to_tick(self, max_decimals: u32, max_sig_figs: u32, round_up: bool) -> Px {
+        let strategy = if round_up {
+            RoundingStrategy::ToPositiveInfinity
+        } else {
+            RoundingStrategy::ToNegativeInfinity
+        };
+        let decimals = self.tick_decimals(max_decimals, max_sig_figs);
+        Px(self
+            .0
+            .round_dp_with_strategy(decimals, strategy)
+            .normalize())
+    }
+}
+
+impl Sz {
+    /// Whether the exchange accepts this size of an asset with `sz_decimals`
+    pub fn is_on_lot(self, sz_decimals: u32) -> bool {
+        self.0.normalize().scale() <= sz_decimals
+    }
+
+    /// This size rounded towards zero to `sz_decimals` decimals
+    pub fn round_to_lot(self, sz_decimals: u32) -> Sz {
+        Sz(self
+            .0
+            .round_dp_with_strategy(sz_decimals, RoundingStrategy::ToZero)
+            .normalize())
+    }
+}
+
+impl Usd {
+    /// Size this amount buys at `px`, `None` if `px` is zero or on overflow
+    pub fn checked_sz_at(self, px: Px) -> Option<Sz> {
+        self.0.checked_div(px.0).map(Sz)
+    }
+
+    /// Price at which `sz` is worth this amount, `None` if `sz` is zero or on overflow
+    pub fn checked_px_for(self, sz: Sz) -> Option<Px> {
+        self.0.checked_div(sz.0).map(Px)
+    }
+}
+
+impl Mul<Sz> for Px {
+    type Output = Usd;
+
+    fn mul(self, sz: Sz) -> Usd {
+        Usd(self.0 * sz.0)
+    }
+}
+
+impl Mul<Px> for Sz {
+    type Output = Usd;
+
+    fn mul(self, px: Px) -> Usd {
+        Usd(self.0 * px.0)
+    }
+}
+
+impl Div<Px> for Usd {
+    type Output = Sz;
+
+    fn div(self, px: Px) -> Sz {
+        Sz(self.0 / px.0)
+    }
+}
+
+impl Div<Sz> for Usd {
+    type Output = Px;
+
+    fn div(self, sz: Sz) -> Px {
+        Px(self.0 / sz.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_arithmetic_and_wire_format() {
+        let px: Px = "2000.50".parse().unwrap();
+        let sz: Sz = "0.25".parse().unwrap();
+        let notional = px * sz;
+        assert_eq!(notional, Usd::new(Decimal::new(500125, 3)));
+        assert_eq!(notional / px, sz);
+        assert_eq!(notional.checked_px_for(sz), Some(px));
+        assert_eq!(notional.checked_sz_at(Px::ZERO), None);
+        assert_eq!(Usd::new(Decimal::MAX).checked_add(notional), None);
+
+        assert_eq!(px.to_wire(), "2000.5");
+        assert_eq!(Sz::new(Decimal::new(-0, 3)).to_wire(), "0");
+        assert_eq!(Sz::try_from(0.123456789).unwrap().to_wire(), "0.12345679");
+        assert_eq!(serde_json::to_string(&sz).unwrap(), r#""0.25""#);
+        assert_eq!(
+            serde_json::from_str::<Px>(r#""1e3""#).unwrap().to_wire(),
+            "1000"
+        );
+        assert!("abc".parse::<Usd>().is_err());
+    }
+    #[test]
+    fn test_tick_and_lot() {
+        let px = |value: &str| value.parse::<Px>().unwrap();
+        // perp with 1 sz decimal: 5 price decimals, 5 significant figures
+        assert!(px("1998.1").is_on_tick(5, 5));
+        assert!(!px("1998.15").is_on_tick(5, 5));
+        assert!(px("123456").is_on_tick(5, 5));
+        assert!(px("0.00012345").is_on_tick(8, 5));
+        assert!(!px("0.00012345").is_on_tick(6, 5));
+        assert_eq!(px("1998.15").round_to_tick(5, 5, true), px("1998.2"));
+        assert_eq!(px("1998.15").round_to_tick(5, 5, false), px("1998.1"));
+        assert_eq!(px("0.123456").round_to_tick(3, 5, false), px("0.123"));
+        assert_eq!(px("99999.5").round_to_tick(5, 5, true), px("100000"));
+
+        let sz = |value: &str| value.parse::<Sz>().unwrap();
+        assert!(sz("0.120").is_on_lot(2));
+        assert!(!sz("0.123").is_on_lot(2));
+        assert_eq!(sz("0.129").round_to_lot(2), sz("0.12"));
+        assert_eq!(sz("-0.129").round_to_lot(2), sz("-0.12"));
+    }
+}
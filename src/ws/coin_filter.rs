@@ -0,0 +1,94 @@
+use crate::{Message, UserData};
+use serde::{Deserialize, Serialize};
+
+/// Restricts a user subscription to some coins. Applied in the websocket reader before messages
+/// are sent to the subscriber, so tasks only wake up for their own coins.
+/// Covers fills, fundings, non-user cancels and order updates; other messages pass through.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CoinFilter {
+    #[default]
+    All,
+    Only(Vec<String>),
+}
+
+impl CoinFilter {
+    pub fn matches(&self, coin: &str) -> bool {
+        match self {
+            CoinFilter::All => true,
+            CoinFilter::Only(coins) => coins.iter().any(|c| c == coin),
+        }
+    }
+
+    /// The part of `message` this filter lets through, `None` if nothing is left
+    pub(crate) fn apply(&self, message: &Message) -> Option<Message> {
+        if *self == CoinFilter::All {
+            return Some(message.clone());
+        }
+        let mut message = message.clone();
+        let is_empty = match &mut message {
+            Message::User(user) => match &mut user.data {
+                UserData::Fills(fills) => {
+                    fills.retain(|fill| self.matches(&fill.coin));
+                    fills.is_empty()
+                }
+                UserData::Funding(funding) => !self.matches(&funding.coin),
+                UserData::NonUserCancel(cancels) => {
+                    cancels.retain(|cancel| self.matches(&cancel.coin));
+                    cancels.is_empty()
+                }
+                UserData::Liquidation(_) | UserData::Unknown(_) => false,
+            },
+            Message::UserFills(user_fills) => {
+                user_fills
+                    .data
+                    .fills
+                    .retain(|fill| self.matches(&fill.coin));
+                // snapshots are kept even when empty so subscribers know the replay is done
+                user_fills.data.fills.is_empty() && user_fills.data.is_snapshot != Some(true)
+            }
+            Message::UserFundings(user_fundings) => {
+                user_fundings
+                    .data
+                    .fundings
+                    .retain(|funding| self.matches(&funding.coin));
+                user_fundings.data.fundings.is_empty()
+                    && user_fundings.data.is_snapshot != Some(true)
+            }
+            Message::OrderUpdates(order_updates) => {
+                order_updates
+                    .data
+                    .retain(|update| self.matches(&update.order.coin));
+                order_updates.data.is_empty()
+            }
+            _ => false,
+        };
+        (!is_empty).then_some(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coin_filter() {
+        let filter = CoinFilter::Only(vec!["ETH".to_string()]);
+        let message: Message = serde_json::from_str(
+            r#"{"channel": "user", "data": {"nonUserCancel": [{"coin": "ETH", "oid": 1}, {"coin": "BTC", "oid": 2}]}}"#,
+        )
+        .unwrap();
+        let Some(Message::User(user)) = filter.apply(&message) else {
+            panic!("ETH cancel was filtered out");
+        };
+        assert!(matches!(&user.data, UserData::NonUserCancel(cancels) if cancels.len() == 1));
+
+        let message: Message = serde_json::from_str(
+            r#"{"channel": "orderUpdates", "data": [{"order": {"coin": "BTC", "side": "B", "limitPx": "1", "sz": "1", "oid": 1, "timestamp": 1, "origSz": "1"}, "status": "open", "statusTimestamp": 1}]}"#,
+        )
+        .unwrap();
+        assert!(filter.apply(&message).is_none());
+        assert!(CoinFilter::All.apply(&message).is_some());
+        assert!(filter.apply(&Message::NoData).is_some());
+    }
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+
+/// Presets for [`WsConfig`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WsConfigPreset {
+    /// Writes every frame immediately with Nagle's algorithm disabled. Suited to order flow and
+    /// latency sensitive market data on a handful of subscriptions.
+    LowLatency,
+    /// Larger write buffer and message limits for many subscriptions or large `webData2` payloads
+    HighThroughput,
+    /// Small buffers and message limits for constrained environments with few subscriptions.
+    /// Messages over 8 MiB close the connection.
+    LowMemory,
+}
+
+/// Tuning for the websocket connections opened by `InfoClient` and `WsPostClient`.
+/// The default matches tungstenite's defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WsConfig {
+    /// Bytes buffered before writing to the socket, 0 writes each message eagerly
+    pub write_buffer_size: usize,
+    /// Bytes the write buffer may grow to while writes are failing
+    pub max_write_buffer_size: usize,
+    pub max_message_size: Option<usize>,
+    pub max_frame_size: Option<usize>,
+    /// Sets `TCP_NODELAY` on the socket
+    pub disable_nagle: bool,
+    /// Time between application level pings
+    pub ping_interval: Duration,
+    /// Reconnects when a ping is not answered within this time, capped at `ping_interval`.
+    /// `None` never treats the connection as stale. Ignored by clients that don't reconnect,
+    /// like `InfoClient::new`, as a stale connection would end their subscriptions for good.
+    pub pong_timeout: Option<Duration>,
+}
+
+impl Default for WsConfig {
+    fn default() -> WsConfig {
+        WsConfig {
+            write_buffer_size: 128 * 1024,
+            max_write_buffer_size: usize::MAX,
+            max_message_size: Some(64 << 20),
+            max_frame_size: Some(16 << 20),
+            disable_nagle: false,
+            ping_interval: Duration::from_secs(50),
+            pong_timeout: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
+impl From<WsConfigPreset> for WsConfig {
+    fn from(preset: WsConfigPreset) -> WsConfig {
+        match preset {
+            WsConfigPreset::LowLatency => WsConfig {
+                write_buffer_size: 0,
+                disable_nagle: true,
+                ..Default::default()
+            },
+            WsConfigPreset::HighThroughput => WsConfig {
+                write_buffer_size: 512 * 1024,
+                max_message_size: Some(256 << 20),
+                max_frame_size: Some(64 << 20),
+                ..Default::default()
+            },
+            WsConfigPreset::LowMemory => WsConfig {
+                write_buffer_size: 16 * 1024,
+                max_write_buffer_size: 1 << 20,
+                max_message_size: Some(8 << 20),
+                max_frame_size: Some(4 << 20),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl WsConfig {
+    pub(crate) fn websocket_config(&self) -> WebSocketConfig {
+        WebSocketConfig {
+            write_buffer_size: self.write_buffer_size,
+            max_write_buffer_size: self.max_write_buffer_size,
+            max_message_size: self.max_message_size,
+            max_frame_size: self.max_frame_size,
+            ..Default::default()
+        }
+    }
+}
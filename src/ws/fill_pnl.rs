@@ -0,0 +1,269 @@
+use crate::{
+    helpers::parse_decimal, prelude::*, Address, Error, InfoClient, Message, SpotMeta,
+    Subscription, TradeInfo,
+};
+use log::{error, warn};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet, VecDeque};
+use tokio::{
+    spawn,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver},
+};
+
+const USDC: &str = "USDC";
+/// Fill ids [`FillPnlTracker::start`] remembers to skip fills replayed after a reconnect
+const RECENT_FILLS: usize = 2000;
+
+/// A fill's closed PnL net of its fee, both in USDC
+#[derive(Debug, Clone, PartialEq)]
+pub struct FillPnl {
+    pub coin: String,
+    pub tid: u64,
+    pub time: u64,
+    pub closed_pnl: Decimal,
+    /// Fee in `fee_token`, negative for rebates
+    pub fee: Decimal,
+    pub fee_token: String,
+    /// Fee converted at the fee token's mid when the fill was processed
+    pub fee_usdc: Decimal,
+    /// `closed_pnl - fee_usdc`
+    pub net_pnl: Decimal,
+    /// Net PnL of every fill processed so far
+    pub cumulative_net_pnl: Decimal,
+}
+
+/// Computes fee-adjusted PnL of fills, converting fees charged in other tokens to USDC at the
+/// mid of the token's USDC pair
+#[derive(Debug, Clone, Default)]
+pub struct FillPnlTracker {
+    /// Spot pair quoting each token in USDC, by token name
+    token_pairs: HashMap<String, String>,
+    /// USDC price of each token
+    token_pxs: HashMap<String, Decimal>,
+    cumulative_net_pnl: Decimal,
+}
+
+/// Ids of recent fills, so the snapshot replayed after a reconnect only yields the fills missed
+/// while disconnected
+#[derive(Debug, Default)]
+struct SeenFills {
+    tids: HashSet<u64>,
+    order: VecDeque<u64>,
+    snapshot_seen: bool,
+}
+
+impl SeenFills {
+    /// Fills of a `userFills` message not seen before. The first snapshot holds fills made
+    /// before the subscription and yields none.
+    fn new_fills<'a>(&mut self, is_snapshot: bool, fills: &'a [TradeInfo]) -> Vec<&'a TradeInfo> {
+        let initial = is_snapshot && !self.snapshot_seen;
+        self.snapshot_seen |= is_snapshot;
+        fills
+            .iter()
+            .filter(|fill| self.insert(fill.tid) && !initial)
+            .collect()
+    }
+
+    fn insert(&mut self, tid: u64) -> bool {
+        if !self.tids.insert(tid) {
+            return false;
+        }
+        if self.order.len() == RECENT_FILLS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.tids.remove(&oldest);
+            }
+        }
+        self.order.push_back(tid);
+        true
+    }
+}
+
+impl FillPnlTracker {
+    pub fn new(spot_meta: &SpotMeta) -> FillPnlTracker {
+        let token_names: HashMap<usize, &str> = spot_meta
+            .tokens
+            .iter()
+            .map(|token| (token.index, token.name.as_str()))
+            .collect();
+        // keyed by the exchange name of the pair, as in AllMids
+        let token_pairs = spot_meta
+            .universe
+            .iter()
+            .filter_map(|pair| {
+                let base = token_names.get(&pair.tokens[0])?;
+                let quote = token_names.get(&pair.tokens[1])?;
+                (*quote == USDC).then(|| (base.to_string(), pair.name.clone()))
+            })
+            .collect();
+        FillPnlTracker {
+            token_pairs,
+            ..Default::default()
+        }
+    }
+
+    /// Updates token prices from an AllMids update
+    pub fn on_mids(&mut self, mids: &HashMap<String, String>) {
+        for (token, pair) in &self.token_pairs {
+            let Some(mid) = mids.get(pair) else {
+                continue;
+            };
+            match parse_decimal(mid) {
+                Ok(mid) => {
+                    self.token_pxs.insert(token.clone(), mid);
+                }
+                Err(err) => error!("Could not parse mid {mid} of {pair}: {err}"),
+            }
+        }
+    }
+
+    /// Sets the USDC price of `token`, e.g. for tokens without a USDC pair
+    pub fn set_token_px(&mut self, token: &str, px: Decimal) {
+        self.token_pxs.insert(token.to_string(), px);
+    }
+
+    pub fn fee_in_usdc(&self, fee: Decimal, fee_token: &str) -> Result<Decimal> {
+        if fee_token == USDC || fee.is_zero() {
+            return Ok(fee);
+        }
+        let px = self.token_pxs.get(fee_token).ok_or_else(|| {
+            Error::GenericParse(format!("no USDC price for fee token {fee_token}"))
+        })?;
+        Ok(fee * px)
+    }
+
+    pub fn on_fill(&mut self, fill: &TradeInfo) -> Result<FillPnl> {
+        let closed_pnl = parse_decimal(&fill.closed_pnl)?;
+        let fee = parse_decimal(&fill.fee)?;
+        let fee_token = fill.fee_token.clone().unwrap_or_else(|| USDC.to_string());
+        let fee_usdc = self.fee_in_usdc(fee, &fee_token)?;
+        let net_pnl = closed_pnl - fee_usdc;
+        self.cumulative_net_pnl += net_pnl;
+        Ok(FillPnl {
+            coin: fill.coin.clone(),
+            tid: fill.tid,
+            time: fill.time,
+            closed_pnl,
+            fee,
+            fee_token,
+            fee_usdc,
+            net_pnl,
+            cumulative_net_pnl: self.cumulative_net_pnl,
+        })
+    }
+
+    /// Subscribes to AllMids and `user`'s fills and returns the PnL of each new fill.
+    /// Fills replayed in the initial snapshot are skipped, while those a later snapshot
+    /// replays after a reconnect are processed unless they were already seen.
+    /// The stream ends when a subscription is dropped or the receiver is closed.
+    pub async fn start(
+        mut self,
+        info_client: &mut InfoClient,
+        user: Address,
+    ) -> Result<UnboundedReceiver<FillPnl>> {
+        let (sender, mut receiver) = unbounded_channel();
+        info_client
+            .subscribe(Subscription::AllMids, sender.clone())
+            .await?;
+        info_client
+            .subscribe(Subscription::UserFills { user }, sender)
+            .await?;
+
+        let (pnl_sender, pnl_receiver) = unbounded_channel();
+        spawn(async move {
+            let mut seen = SeenFills::default();
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    Message::AllMids(all_mids) => self.on_mids(&all_mids.data.mids),
+                    Message::UserFills(user_fills) => {
+                        let is_snapshot = user_fills.data.is_snapshot == Some(true);
+                        for fill in seen.new_fills(is_snapshot, &user_fills.data.fills) {
+                            match self.on_fill(fill) {
+                                Ok(pnl) => {
+                                    if pnl_sender.send(pnl).is_err() {
+                                        return;
+                                    }
+                                }
+                                Err(err) => {
+                                    error!("Could not compute PnL of fill {}: {err}", fill.tid)
+                                }
+                            }
+                        }
+                    }
+                    Message::NoData => warn!("Fill PnL stream lost its websocket feed"),
+                    _ => {}
+                }
+            }
+        });
+        Ok(pnl_receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws::trade_info;
+
+    fn fill(coin: &str, closed_pnl: &str, fee: &str, fee_token: Option<&str>) -> TradeInfo {
+        TradeInfo {
+            start_position: "1".to_string(),
+            closed_pnl: closed_pnl.to_string(),
+            oid: 1,
+            fee: fee.to_string(),
+            fee_token: fee_token.map(str::to_string),
+            tid: 1,
+            ..trade_info(coin, "A", "1", "1")
+        }
+    }
+
+    #[test]
+    fn test_fee_adjusted_pnl() {
+        let spot_meta: SpotMeta = serde_json::from_value(serde_json::json!({
+            "universe": [{"tokens": [1, 0], "name": "@1", "index": 1, "isCanonical": false}],
+            "tokens": [
+                {"name": "USDC", "szDecimals": 8, "weiDecimals": 8, "index": 0, "tokenId": "0x6d1e7cde53ba9467b783cb7c530ce054", "isCanonical": true},
+                {"name": "HYPE", "szDecimals": 2, "weiDecimals": 8, "index": 1, "tokenId": "0x0d01dc56dcaaca66ad901c959b4011ec", "isCanonical": false}
+            ]
+        }))
+        .unwrap();
+        let mut tracker = FillPnlTracker::new(&spot_meta);
+
+        let pnl = tracker.on_fill(&fill("ETH", "10", "0.5", None)).unwrap();
+        assert_eq!(pnl.net_pnl, Decimal::new(95, 1));
+        assert_eq!(pnl.fee_token, "USDC");
+
+        assert!(tracker
+            .on_fill(&fill("@1", "0", "0.1", Some("HYPE")))
+            .is_err());
+        tracker.on_mids(&HashMap::from([("@1".to_string(), "20".to_string())]));
+        let pnl = tracker
+            .on_fill(&fill("@1", "3", "0.1", Some("HYPE")))
+            .unwrap();
+        assert_eq!(pnl.fee_usdc, Decimal::new(2, 0));
+        assert_eq!(pnl.net_pnl, Decimal::new(1, 0));
+        assert_eq!(pnl.cumulative_net_pnl, Decimal::new(105, 1));
+
+        // rebates add to the net PnL
+        let pnl = tracker
+            .on_fill(&fill("ETH", "0", "-0.25", Some("USDC")))
+            .unwrap();
+        assert_eq!(pnl.net_pnl, Decimal::new(25, 2));
+    }
+
+    #[test]
+    fn test_reconnect_snapshot_yields_missed_fills() {
+        let fills = |tids: &[u64]| -> Vec<TradeInfo> {
+            tids.iter()
+                .map(|tid| TradeInfo {
+                    tid: *tid,
+                    ..fill("ETH", "0", "0", None)
+                })
+                .collect()
+        };
+        let tids = |fills: Vec<&TradeInfo>| fills.iter().map(|fill| fill.tid).collect::<Vec<_>>();
+        let mut seen = SeenFills::default();
+
+        assert!(seen.new_fills(true, &fills(&[1, 2])).is_empty());
+        assert_eq!(tids(seen.new_fills(false, &fills(&[3]))), vec![3]);
+        assert_eq!(tids(seen.new_fills(true, &fills(&[1, 2, 3, 4]))), vec![4]);
+    }
+}
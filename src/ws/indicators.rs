@@ -0,0 +1,330 @@
+use crate::{
+    prelude::*, CandleData, CandlesSnapshotResponse, Error, InfoClient, Message, Subscription,
+    Trade,
+};
+use log::{error, warn};
+use std::{collections::VecDeque, fmt};
+use tokio::{
+    spawn,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver},
+};
+
+fn parse_f64(value: &str) -> Result<f64> {
+    value.parse().map_err(|_| Error::FloatStringParse)
+}
+
+/// A candle with numeric fields, the shape technical analysis crates take as input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ohlcv {
+    /// Open and close times in milliseconds
+    pub time_open: u64,
+    pub time_close: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+impl TryFrom<&CandleData> for Ohlcv {
+    type Error = Error;
+
+    fn try_from(candle: &CandleData) -> Result<Ohlcv> {
+        Ok(Ohlcv {
+            time_open: candle.time_open,
+            time_close: candle.time_close,
+            open: parse_f64(&candle.open)?,
+            high: parse_f64(&candle.high)?,
+            low: parse_f64(&candle.low)?,
+            close: parse_f64(&candle.close)?,
+            volume: parse_f64(&candle.volume)?,
+        })
+    }
+}
+
+impl TryFrom<&CandlesSnapshotResponse> for Ohlcv {
+    type Error = Error;
+
+    fn try_from(candle: &CandlesSnapshotResponse) -> Result<Ohlcv> {
+        Ok(Ohlcv {
+            time_open: candle.time_open,
+            time_close: candle.time_close,
+            open: parse_f64(&candle.open)?,
+            high: parse_f64(&candle.high)?,
+            low: parse_f64(&candle.low)?,
+            close: parse_f64(&candle.close)?,
+            volume: parse_f64(&candle.vlm)?,
+        })
+    }
+}
+
+/// A public trade with numeric fields
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradeTick {
+    pub time: u64,
+    pub px: f64,
+    pub sz: f64,
+    /// Side of the aggressor
+    pub is_buy: bool,
+}
+
+impl TryFrom<&Trade> for TradeTick {
+    type Error = Error;
+
+    fn try_from(trade: &Trade) -> Result<TradeTick> {
+        Ok(TradeTick {
+            time: trade.time,
+            px: parse_f64(&trade.px)?,
+            sz: parse_f64(&trade.sz)?,
+            is_buy: trade.side == "B",
+        })
+    }
+}
+
+/// Updated with every closed candle, returning `None` until it has enough history.
+/// Implemented for closures, so indicators from other crates can be wrapped, e.g.
+/// `move |candle: &Ohlcv| Some(rsi.next(candle.close))`.
+pub trait Indicator: Send {
+    fn next(&mut self, candle: &Ohlcv) -> Option<f64>;
+}
+
+impl<F: FnMut(&Ohlcv) -> Option<f64> + Send> Indicator for F {
+    fn next(&mut self, candle: &Ohlcv) -> Option<f64> {
+        self(candle)
+    }
+}
+
+/// Simple moving average of closes
+#[derive(Debug, Clone)]
+pub struct Sma {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Sma {
+        Sma {
+            period: period.max(1),
+            window: VecDeque::new(),
+            sum: 0.0,
+        }
+    }
+}
+
+impl Indicator for Sma {
+    fn next(&mut self, candle: &Ohlcv) -> Option<f64> {
+        self.window.push_back(candle.close);
+        self.sum += candle.close;
+        if self.window.len() > self.period {
+            self.sum -= self.window.pop_front().unwrap_or_default();
+        }
+        (self.window.len() == self.period).then(|| self.sum / self.period as f64)
+    }
+}
+
+/// Exponential moving average of closes, seeded with the SMA of the first `period` closes
+#[derive(Debug, Clone)]
+pub struct Ema {
+    alpha: f64,
+    seed: Sma,
+    value: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Ema {
+        Ema {
+            alpha: 2.0 / (period.max(1) as f64 + 1.0),
+            seed: Sma::new(period),
+            value: None,
+        }
+    }
+}
+
+impl Indicator for Ema {
+    fn next(&mut self, candle: &Ohlcv) -> Option<f64> {
+        self.value = match self.value {
+            Some(value) => Some(value + self.alpha * (candle.close - value)),
+            None => self.seed.next(candle),
+        };
+        self.value
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndicatorUpdate {
+    /// The candle that just closed
+    pub candle: Ohlcv,
+    /// Value of each indicator in the order they were added
+    pub values: Vec<(String, Option<f64>)>,
+}
+
+/// Runs indicators over the closed candles of one coin and interval from the `candle` subscription.
+/// The subscription resends the current candle on every trade; a candle counts as closed once
+/// the next one starts.
+pub struct IndicatorPipeline {
+    pub coin: String,
+    pub interval: String,
+    indicators: Vec<(String, Box<dyn Indicator>)>,
+    current: Option<Ohlcv>,
+}
+
+impl fmt::Debug for IndicatorPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IndicatorPipeline")
+            .field("coin", &self.coin)
+            .field("interval", &self.interval)
+            .field(
+                "indicators",
+                &self
+                    .indicators
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .field("current", &self.current)
+            .finish()
+    }
+}
+
+impl IndicatorPipeline {
+    pub fn new(coin: String, interval: String) -> IndicatorPipeline {
+        IndicatorPipeline {
+            coin,
+            interval,
+            indicators: Vec::new(),
+            current: None,
+        }
+    }
+
+    pub fn with_indicator(mut self, name: &str, indicator: impl Indicator + 'static) -> Self {
+        self.indicators
+            .push((name.to_string(), Box::new(indicator)));
+        self
+    }
+
+    /// Feeds historical closed candles, e.g. from `candles_snapshot`, so indicators are warm
+    /// once the stream starts. Candles must be oldest first.
+    pub fn warm_up(&mut self, candles: &[Ohlcv]) {
+        for candle in candles {
+            self.apply(*candle);
+        }
+    }
+
+    fn apply(&mut self, candle: Ohlcv) -> IndicatorUpdate {
+        IndicatorUpdate {
+            candle,
+            values: self
+                .indicators
+                .iter_mut()
+                .map(|(name, indicator)| (name.clone(), indicator.next(&candle)))
+                .collect(),
+        }
+    }
+
+    /// Records an update of the current candle, returning the indicators for the previous
+    /// candle if this one replaces it
+    pub fn on_candle(&mut self, candle: Ohlcv) -> Option<IndicatorUpdate> {
+        match self.current.replace(candle) {
+            Some(previous) if candle.time_open > previous.time_open => Some(self.apply(previous)),
+            Some(previous) if candle.time_open < previous.time_open => {
+                // stale update for a candle already closed
+                self.current = Some(previous);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Subscribes to the candles on `info_client` and returns the stream of updates.
+    /// The task stops once the receiver is dropped.
+    pub async fn start(
+        mut self,
+        info_client: &mut InfoClient,
+    ) -> Result<UnboundedReceiver<IndicatorUpdate>> {
+        let (sender, mut receiver) = unbounded_channel();
+        info_client
+            .subscribe(
+                Subscription::Candle {
+                    coin: self.coin.clone(),
+                    interval: self.interval.clone(),
+                },
+                sender,
+            )
+            .await?;
+
+        let (update_sender, update_receiver) = unbounded_channel();
+        spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    Message::Candle(candle) => {
+                        let candle = match Ohlcv::try_from(&candle.data) {
+                            Ok(candle) => candle,
+                            Err(err) => {
+                                error!("Could not parse {} candle: {err}", self.coin);
+                                continue;
+                            }
+                        };
+                        if let Some(update) = self.on_candle(candle) {
+                            if update_sender.send(update).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    Message::NoData => {
+                        warn!("IndicatorPipeline for {} lost its candle feed", self.coin)
+                    }
+                    _ => {}
+                }
+            }
+            warn!("IndicatorPipeline for {} stopped", self.coin);
+        });
+        Ok(update_receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(time_open: u64, close: f64) -> Ohlcv {
+        Ohlcv {
+            time_open,
+            time_close: time_open + 59_999,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_indicator_pipeline_on_closed_candles() {
+        let mut pipeline = IndicatorPipeline::new("ETH".to_string(), "1m".to_string())
+            .with_indicator("sma", Sma::new(2))
+            .with_indicator("ema", Ema::new(2))
+            .with_indicator("close", |candle: &Ohlcv| Some(candle.close));
+        pipeline.warm_up(&[candle(0, 1.0)]);
+
+        assert!(pipeline.on_candle(candle(60_000, 2.0)).is_none());
+        // same candle updated by a trade
+        assert!(pipeline.on_candle(candle(60_000, 3.0)).is_none());
+        let update = pipeline.on_candle(candle(120_000, 5.0)).unwrap();
+        assert_eq!(update.candle.close, 3.0);
+        assert_eq!(
+            update.values,
+            vec![
+                ("sma".to_string(), Some(2.0)),
+                ("ema".to_string(), Some(2.0)),
+                ("close".to_string(), Some(3.0)),
+            ]
+        );
+        assert!(pipeline.on_candle(candle(60_000, 4.0)).is_none());
+
+        let update = pipeline.on_candle(candle(180_000, 5.0)).unwrap();
+        assert_eq!(update.values[0].1, Some(4.0));
+        // 2 + 2/3 * (5 - 2)
+        assert_eq!(update.values[1].1, Some(4.0));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::helpers::now_timestamp_ms;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::mpsc::UnboundedSender, time::Instant};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WsLatency {
+    /// Round trip time of the latest ping
+    pub rtt: Option<Duration>,
+    /// Local receive time minus the server `time` of the latest timestamped message, in milliseconds.
+    /// Includes the one-way network delay on top of the clock difference.
+    pub clock_offset_ms: Option<i64>,
+    /// Local time of the latest measurement in milliseconds
+    pub measured_at: u64,
+}
+
+#[derive(Debug, Default)]
+struct LatencyState {
+    ping_sent_at: Option<Instant>,
+    latency: WsLatency,
+    listeners: Vec<UnboundedSender<WsLatency>>,
+}
+
+/// Latency measurements shared between the ws reader and ping tasks
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LatencyTracker {
+    state: Arc<Mutex<LatencyState>>,
+}
+
+impl LatencyTracker {
+    fn lock(&self) -> std::sync::MutexGuard<'_, LatencyState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn latency(&self) -> WsLatency {
+        self.lock().latency
+    }
+
+    pub(crate) fn add_listener(&self, listener: UnboundedSender<WsLatency>) {
+        self.lock().listeners.push(listener);
+    }
+
+    pub(crate) fn on_ping_sent(&self) {
+        self.lock().ping_sent_at = Some(Instant::now());
+    }
+
+    /// Forgets the outstanding ping, which will never be answered after a reconnect
+    pub(crate) fn clear_ping(&self) {
+        self.lock().ping_sent_at = None;
+    }
+
+    /// Whether the latest ping is still unanswered
+    pub(crate) fn awaiting_pong(&self) -> bool {
+        self.lock().ping_sent_at.is_some()
+    }
+
+    /// Records the round trip of the outstanding ping and publishes the measurement to listeners
+    pub(crate) fn on_pong(&self) {
+        let mut state = self.lock();
+        let Some(ping_sent_at) = state.ping_sent_at.take() else {
+            return;
+        };
+        state.latency.rtt = Some(ping_sent_at.elapsed());
+        state.latency.measured_at = now_timestamp_ms();
+        let latency = state.latency;
+        state
+            .listeners
+            .retain(|listener| listener.send(latency).is_ok());
+    }
+
+    pub(crate) fn on_server_time(&self, server_time_ms: u64) {
+        let now = now_timestamp_ms();
+        let mut state = self.lock();
+        state.latency.clock_offset_ms = Some(now as i64 - server_time_ms as i64);
+        state.latency.measured_at = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[tokio::test]
+    async fn test_latency_tracker() {
+        let tracker = LatencyTracker::default();
+        let (sender, mut receiver) = unbounded_channel();
+        tracker.add_listener(sender);
+
+        // a pong without an outstanding ping is ignored
+        tracker.on_pong();
+        assert!(receiver.try_recv().is_err());
+
+        tracker.on_ping_sent();
+        tracker.on_pong();
+        let latency = receiver.try_recv().unwrap();
+        assert!(latency.rtt.is_some());
+        assert_eq!(latency, tracker.latency());
+
+        tracker.on_server_time(now_timestamp_ms() - 50);
+        assert!(tracker.latency().clock_offset_ms.unwrap() >= 50);
+    }
+}
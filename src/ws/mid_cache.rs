@@ -0,0 +1,120 @@
+use crate::{prelude::*, InfoClient, Message, Subscription};
+use arc_swap::ArcSwap;
+use log::{error, warn};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    spawn,
+    sync::{mpsc::unbounded_channel, watch},
+};
+
+type Watchers = Arc<Mutex<HashMap<String, watch::Sender<Option<f64>>>>>;
+
+/// Shared view of all mids backed by a single AllMids subscription.
+/// Cloning is cheap and every clone reads the same snapshot.
+#[derive(Debug, Clone)]
+pub struct MidCache {
+    mids: Arc<ArcSwap<HashMap<String, f64>>>,
+    watchers: Watchers,
+}
+
+impl MidCache {
+    pub async fn start(info_client: &mut InfoClient) -> Result<MidCache> {
+        let (sender, mut receiver) = unbounded_channel();
+        info_client.subscribe(Subscription::AllMids, sender).await?;
+
+        let cache = MidCache {
+            mids: Arc::new(ArcSwap::from_pointee(HashMap::new())),
+            watchers: Arc::new(Mutex::new(HashMap::new())),
+        };
+
+        let task_cache = cache.clone();
+        spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    Message::AllMids(all_mids) => task_cache.update(&all_mids.data.mids),
+                    Message::NoData => warn!("MidCache lost its AllMids feed"),
+                    _ => {}
+                }
+            }
+            warn!("MidCache update task stopped");
+        });
+
+        Ok(cache)
+    }
+
+    fn update(&self, raw_mids: &HashMap<String, String>) {
+        let mut mids = HashMap::with_capacity(raw_mids.len());
+        for (coin, mid) in raw_mids {
+            match mid.parse::<f64>() {
+                Ok(mid) => {
+                    mids.insert(coin.clone(), mid);
+                }
+                Err(err) => error!("Could not parse mid {mid} for {coin}: {err}"),
+            }
+        }
+
+        let previous = self.mids.swap(Arc::new(mids));
+        let current = self.mids.load();
+        let mut watchers = self.watchers.lock().unwrap_or_else(|e| e.into_inner());
+        watchers.retain(|coin, watcher| {
+            let mid = current.get(coin).copied();
+            if mid != previous.get(coin).copied() {
+                watcher.send_replace(mid);
+            }
+            !watcher.is_closed()
+        });
+    }
+
+    /// Lock-free snapshot of every known mid
+    pub fn snapshot(&self) -> Arc<HashMap<String, f64>> {
+        self.mids.load_full()
+    }
+
+    pub fn get(&self, coin: &str) -> Option<f64> {
+        self.mids.load().get(coin).copied()
+    }
+
+    /// Returns a receiver that is notified whenever the mid for `coin` changes
+    pub fn watch(&self, coin: &str) -> watch::Receiver<Option<f64>> {
+        let mut watchers = self.watchers.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(watcher) = watchers.get(coin) {
+            return watcher.subscribe();
+        }
+        let (watcher, receiver) = watch::channel(self.get(coin));
+        watchers.insert(coin.to_string(), watcher);
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mid_cache_update_and_watch() {
+        let cache = MidCache {
+            mids: Arc::new(ArcSwap::from_pointee(HashMap::new())),
+            watchers: Arc::new(Mutex::new(HashMap::new())),
+        };
+        let mut eth = cache.watch("ETH");
+        assert_eq!(*eth.borrow_and_update(), None);
+
+        cache.update(&HashMap::from([
+            ("ETH".to_string(), "2000.5".to_string()),
+            ("BTC".to_string(), "60000".to_string()),
+        ]));
+        assert!(eth.has_changed().unwrap());
+        assert_eq!(*eth.borrow_and_update(), Some(2000.5));
+        assert_eq!(cache.get("BTC"), Some(60000.0));
+
+        cache.update(&HashMap::from([
+            ("ETH".to_string(), "2000.5".to_string()),
+            ("BTC".to_string(), "60001".to_string()),
+        ]));
+        assert!(!eth.has_changed().unwrap());
+        assert_eq!(cache.snapshot().get("BTC"), Some(&60001.0));
+    }
+}
@@ -1,7 +1,39 @@
+mod coin_filter;
+mod config;
+mod fill_pnl;
+mod indicators;
+mod latency;
 mod message_types;
+mod mid_cache;
+mod multi_user;
+mod order_book;
+mod post;
+mod stats;
 mod sub_structs;
+mod ticker;
+mod timing;
+mod trade_aggregator;
+#[cfg(feature = "trade-tape")]
+mod trade_tape;
 mod ws_manager;
+pub use coin_filter::CoinFilter;
+pub use config::{WsConfig, WsConfigPreset};
+pub use fill_pnl::{FillPnl, FillPnlTracker};
+pub use indicators::{Ema, Indicator, IndicatorPipeline, IndicatorUpdate, Ohlcv, Sma, TradeTick};
+pub use latency::WsLatency;
 pub use message_types::*;
+pub use mid_cache::MidCache;
+pub use multi_user::{MonitorEvent, MultiUserMonitor, UserEvent};
+pub use order_book::{BookEvent, BookIntegrityError, LocalBook, OrderBook, OrderBookStream};
+pub use post::{PostResponseData, WsPostClient, WsPostRequest, WsPostResponse};
+pub use stats::SubscriptionStats;
+#[cfg(test)]
+pub(crate) use sub_structs::tests::trade_info;
 pub use sub_structs::*;
-pub(crate) use ws_manager::WsManager;
-pub use ws_manager::{Message, Subscription};
+pub use ticker::{Tick, Ticker};
+pub use timing::{TimingStats, TIMING_WINDOW};
+pub use trade_aggregator::{AggregatedTrade, TradeAggregator};
+#[cfg(feature = "trade-tape")]
+pub use trade_tape::TradeTape;
+pub use ws_manager::{DeliveryKind, ExportedSubscription, Message, Subscription};
+pub(crate) use ws_manager::{SubscriptionSender, WsManager};
@@ -0,0 +1,164 @@
+use crate::{
+    prelude::*, AccountSnapshot, Address, BaseUrl, InfoClient, Message, OrderUpdate, Subscription,
+    TradeInfo,
+};
+use log::warn;
+use tokio::{
+    spawn,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+};
+
+#[derive(Debug, Clone)]
+pub enum MonitorEvent {
+    /// Positions, balances and open orders captured after the subscriptions were opened,
+    /// so updates that follow may already be reflected in it but none are missed
+    Snapshot(Box<AccountSnapshot>),
+    Fills {
+        fills: Vec<TradeInfo>,
+        /// True for the recent fills replayed when the subscription opens
+        is_snapshot: bool,
+    },
+    OrderUpdates(Vec<OrderUpdate>),
+    /// The user's websocket dropped; updates may be missed until it reconnects
+    Disconnected,
+}
+
+#[derive(Debug, Clone)]
+pub struct UserEvent {
+    pub user: Address,
+    pub event: MonitorEvent,
+}
+
+/// Watches fills, order updates and positions of several users and merges them into one stream.
+/// `orderUpdates` messages don't name their user, so every user gets its own websocket connection.
+#[derive(Debug)]
+pub struct MultiUserMonitor {
+    info_clients: Vec<(Address, InfoClient)>,
+}
+
+fn monitor_event(message: Message) -> Option<MonitorEvent> {
+    match message {
+        Message::UserFills(user_fills) => Some(MonitorEvent::Fills {
+            fills: user_fills.data.fills,
+            is_snapshot: user_fills.data.is_snapshot == Some(true),
+        }),
+        Message::OrderUpdates(order_updates) => {
+            Some(MonitorEvent::OrderUpdates(order_updates.data))
+        }
+        Message::NoData => Some(MonitorEvent::Disconnected),
+        _ => None,
+    }
+}
+
+impl MultiUserMonitor {
+    /// Opens one connection per user and returns the merged event stream.
+    /// Each user's stream starts with a [`MonitorEvent::Snapshot`].
+    pub async fn start(
+        users: Vec<Address>,
+        base_url: Option<BaseUrl>,
+        reconnect: bool,
+    ) -> Result<(MultiUserMonitor, UnboundedReceiver<UserEvent>)> {
+        let info_client = InfoClient::new(None, base_url).await?;
+        Self::start_from(users, &info_client, reconnect).await
+    }
+
+    /// Like [`MultiUserMonitor::start`], on the network and with the request options of
+    /// `info_client`
+    pub(crate) async fn start_from(
+        users: Vec<Address>,
+        info_client: &InfoClient,
+        reconnect: bool,
+    ) -> Result<(MultiUserMonitor, UnboundedReceiver<UserEvent>)> {
+        let (sender, receiver) = unbounded_channel();
+        let mut info_clients = Vec::with_capacity(users.len());
+        for user in users {
+            let mut info_client = info_client.detached();
+            if reconnect {
+                info_client = info_client.reconnecting();
+            }
+            info_clients.push((user, Self::watch(info_client, user, sender.clone()).await?));
+        }
+        Ok((MultiUserMonitor { info_clients }, receiver))
+    }
+
+    async fn watch(
+        mut info_client: InfoClient,
+        user: Address,
+        sender: UnboundedSender<UserEvent>,
+    ) -> Result<InfoClient> {
+        let (user_sender, mut user_receiver) = unbounded_channel();
+        info_client
+            .subscribe(Subscription::UserFills { user }, user_sender.clone())
+            .await?;
+        info_client
+            .subscribe(Subscription::OrderUpdates { user }, user_sender)
+            .await?;
+
+        // messages received meanwhile wait in the channel until the snapshot is sent
+        let snapshot = AccountSnapshot::capture(&info_client, user).await?;
+        let _ = sender.send(UserEvent {
+            user,
+            event: MonitorEvent::Snapshot(Box::new(snapshot)),
+        });
+
+        spawn(async move {
+            while let Some(message) = user_receiver.recv().await {
+                let Some(event) = monitor_event(message) else {
+                    continue;
+                };
+                if sender.send(UserEvent { user, event }).is_err() {
+                    break;
+                }
+            }
+            warn!("MultiUserMonitor for {user} stopped");
+        });
+        Ok(info_client)
+    }
+
+    pub fn users(&self) -> Vec<Address> {
+        self.info_clients.iter().map(|(user, _)| *user).collect()
+    }
+
+    /// Connection used for `user`, e.g. to read its latency or subscription stats
+    pub fn info_client(&self, user: Address) -> Option<&InfoClient> {
+        self.info_clients
+            .iter()
+            .find(|(monitored, _)| *monitored == user)
+            .map(|(_, info_client)| info_client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monitor_events() {
+        let message: Message = serde_json::from_str(
+            r#"{"channel": "userFills", "data": {"isSnapshot": true, "user": "0x0000000000000000000000000000000000000000", "fills": []}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            monitor_event(message),
+            Some(MonitorEvent::Fills {
+                is_snapshot: true,
+                ..
+            })
+        ));
+
+        let message: Message = serde_json::from_str(
+            r#"{"channel": "orderUpdates", "data": [{"order": {"coin": "ETH", "side": "B", "limitPx": "1", "sz": "1", "oid": 1, "timestamp": 1, "origSz": "1"}, "status": "open", "statusTimestamp": 1}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            monitor_event(message),
+            Some(MonitorEvent::OrderUpdates(updates)) if updates.len() == 1
+        ));
+
+        assert!(matches!(
+            monitor_event(Message::NoData),
+            Some(MonitorEvent::Disconnected)
+        ));
+        assert!(monitor_event(Message::Pong).is_none());
+    }
+}
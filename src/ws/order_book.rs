@@ -0,0 +1,575 @@
+use crate::{
+    helpers::now_timestamp_ms, info::L2SnapshotResponse, prelude::*, BookLevel, InfoClient,
+    L2BookData, Message, Subscription,
+};
+use arc_swap::ArcSwapOption;
+use log::{error, warn};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+use tokio::{
+    spawn,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver},
+    task::JoinHandle,
+};
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BookIntegrityError {
+    #[error("Expected bid and ask sides, got {0} sides")]
+    MissingSide(usize),
+    #[error("Level has an invalid price or size: {px} {sz}")]
+    InvalidLevel { px: String, sz: String },
+    #[error("{side} levels are not strictly ordered at index {index}")]
+    NonMonotonic { side: &'static str, index: usize },
+    #[error("Book is crossed: best bid {best_bid} >= best ask {best_ask}")]
+    Crossed { best_bid: f64, best_ask: f64 },
+    #[error("Book time {time} is older than the previous book time {last_time}")]
+    OutOfOrder { time: u64, last_time: u64 },
+    #[error("Book time {time} is {age_ms}ms old")]
+    Stale { time: u64, age_ms: u64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct OrderBook {
+    pub coin: String,
+    pub time: u64,
+    /// Bids, best first
+    pub bids: Vec<BookLevel>,
+    /// Asks, best first
+    pub asks: Vec<BookLevel>,
+}
+
+fn parse_level(level: &BookLevel) -> std::result::Result<(f64, f64), BookIntegrityError> {
+    match (level.px.parse::<f64>(), level.sz.parse::<f64>()) {
+        (Ok(px), Ok(sz)) if px > 0.0 && sz > 0.0 => Ok((px, sz)),
+        _ => Err(BookIntegrityError::InvalidLevel {
+            px: level.px.clone(),
+            sz: level.sz.clone(),
+        }),
+    }
+}
+
+fn check_side(
+    levels: &[BookLevel],
+    side: &'static str,
+    is_bid: bool,
+) -> std::result::Result<Option<f64>, BookIntegrityError> {
+    let mut prev_px = None;
+    for (index, level) in levels.iter().enumerate() {
+        let (px, _) = parse_level(level)?;
+        if let Some(prev_px) = prev_px {
+            let ordered = if is_bid { px < prev_px } else { px > prev_px };
+            if !ordered {
+                return Err(BookIntegrityError::NonMonotonic { side, index });
+            }
+        }
+        prev_px = Some(px);
+    }
+    levels
+        .first()
+        .map(|level| parse_level(level).map(|(px, _)| px))
+        .transpose()
+}
+
+impl OrderBook {
+    fn from_levels(
+        coin: String,
+        time: u64,
+        levels: Vec<Vec<BookLevel>>,
+    ) -> std::result::Result<OrderBook, BookIntegrityError> {
+        let sides = levels.len();
+        let mut levels = levels.into_iter();
+        match (levels.next(), levels.next(), levels.next()) {
+            (Some(bids), Some(asks), None) => Ok(OrderBook {
+                coin,
+                time,
+                bids,
+                asks,
+            }),
+            _ => Err(BookIntegrityError::MissingSide(sides)),
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<f64> {
+        self.bids.first().and_then(|level| level.px.parse().ok())
+    }
+
+    pub fn best_ask(&self) -> Option<f64> {
+        self.asks.first().and_then(|level| level.px.parse().ok())
+    }
+
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.best_bid()? + self.best_ask()?) / 2.0)
+    }
+
+    /// Mid weighted by the size at the touch, leaning towards the side with less size
+    pub fn microprice(&self) -> Option<f64> {
+        let (bid, bid_sz) = parse_level(self.bids.first()?).ok()?;
+        let (ask, ask_sz) = parse_level(self.asks.first()?).ok()?;
+        Some((bid * ask_sz + ask * bid_sz) / (bid_sz + ask_sz))
+    }
+
+    /// The book with at most `levels` levels per side
+    pub fn depth(&self, levels: usize) -> OrderBook {
+        OrderBook {
+            coin: self.coin.clone(),
+            time: self.time,
+            bids: self.bids.iter().take(levels).cloned().collect(),
+            asks: self.asks.iter().take(levels).cloned().collect(),
+        }
+    }
+
+    /// Checks that the book is uncrossed, levels are strictly ordered away from the touch,
+    /// the book is not older than `last_time` and not older than `max_age` at `now` (ms).
+    pub fn validate(
+        &self,
+        last_time: Option<u64>,
+        now: u64,
+        max_age: Duration,
+    ) -> std::result::Result<(), BookIntegrityError> {
+        let best_bid = check_side(&self.bids, "Bid", true)?;
+        let best_ask = check_side(&self.asks, "Ask", false)?;
+        if let (Some(best_bid), Some(best_ask)) = (best_bid, best_ask) {
+            if best_bid >= best_ask {
+                return Err(BookIntegrityError::Crossed { best_bid, best_ask });
+            }
+        }
+        if let Some(last_time) = last_time {
+            if self.time < last_time {
+                return Err(BookIntegrityError::OutOfOrder {
+                    time: self.time,
+                    last_time,
+                });
+            }
+        }
+        let age_ms = now.saturating_sub(self.time);
+        if age_ms > max_age.as_millis() as u64 {
+            return Err(BookIntegrityError::Stale {
+                time: self.time,
+                age_ms,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<L2BookData> for OrderBook {
+    type Error = BookIntegrityError;
+
+    fn try_from(data: L2BookData) -> std::result::Result<Self, Self::Error> {
+        OrderBook::from_levels(data.coin, data.time, data.levels)
+    }
+}
+
+impl TryFrom<L2SnapshotResponse> for OrderBook {
+    type Error = BookIntegrityError;
+
+    fn try_from(snapshot: L2SnapshotResponse) -> std::result::Result<Self, Self::Error> {
+        let levels = snapshot
+            .levels
+            .into_iter()
+            .map(|side| {
+                side.into_iter()
+                    .map(|level| BookLevel {
+                        px: level.px,
+                        sz: level.sz,
+                        n: level.n,
+                    })
+                    .collect()
+            })
+            .collect();
+        OrderBook::from_levels(snapshot.coin, snapshot.time, levels)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum BookEvent {
+    /// A validated book from the ws stream
+    Update(OrderBook),
+    /// A validated book fetched over http after an integrity error
+    Snapshot(OrderBook),
+    /// The latest ws book failed validation and was dropped; a snapshot refresh follows unless
+    /// one was fetched too recently, see [`OrderBookStream::min_refresh_interval`]
+    IntegrityError {
+        coin: String,
+        error: BookIntegrityError,
+    },
+}
+
+/// Longest wait between snapshot refreshes while integrity errors keep coming
+const MAX_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Spaces out snapshot refreshes: at most one per interval, which doubles with every refresh
+/// until a valid ws book resets it to the minimum
+#[derive(Debug)]
+struct RefreshBackoff {
+    min_interval: Duration,
+    interval: Duration,
+    last_refresh: Option<Instant>,
+}
+
+impl RefreshBackoff {
+    fn new(min_interval: Duration) -> RefreshBackoff {
+        RefreshBackoff {
+            min_interval,
+            interval: min_interval,
+            last_refresh: None,
+        }
+    }
+
+    /// Whether to refresh after an integrity error at `now`
+    fn try_refresh(&mut self, now: Instant) -> bool {
+        if let Some(last_refresh) = self.last_refresh {
+            if now.saturating_duration_since(last_refresh) < self.interval {
+                return false;
+            }
+            self.interval = (self.interval * 2).min(MAX_REFRESH_INTERVAL.max(self.min_interval));
+        }
+        self.last_refresh = Some(now);
+        true
+    }
+
+    fn reset(&mut self) {
+        self.interval = self.min_interval;
+    }
+}
+
+/// L2 book stream for a coin that only passes on books that pass [`OrderBook::validate`].
+#[derive(Debug, Clone)]
+pub struct OrderBookStream {
+    pub coin: String,
+    pub max_age: Duration,
+    /// Minimum time between snapshot refreshes after integrity errors, 1 second by default.
+    /// Doubled while errors persist, up to a minute, so a flapping feed can't hammer the info
+    /// endpoint.
+    pub min_refresh_interval: Duration,
+}
+
+impl OrderBookStream {
+    pub fn new(coin: String, max_age: Duration) -> OrderBookStream {
+        OrderBookStream {
+            coin,
+            max_age,
+            min_refresh_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Subscribes to the coin's L2 book on `info_client` and returns the validated event stream.
+    /// The stream ends when the subscription is dropped or the receiver is closed.
+    pub async fn start(self, info_client: &mut InfoClient) -> Result<UnboundedReceiver<BookEvent>> {
+        let (sender, mut receiver) = unbounded_channel();
+        info_client
+            .subscribe(
+                Subscription::L2Book {
+                    coin: self.coin.clone(),
+                },
+                sender,
+            )
+            .await?;
+
+        let snapshot_client = info_client.detached();
+        let (event_sender, event_receiver) = unbounded_channel();
+        spawn(async move {
+            let mut last_time = None;
+            let mut backoff = RefreshBackoff::new(self.min_refresh_interval);
+            while let Some(message) = receiver.recv().await {
+                let data = match message {
+                    Message::L2Book(l2_book) => l2_book.data,
+                    Message::NoData => {
+                        warn!("Order book stream for {} lost its L2Book feed", self.coin);
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                let checked = OrderBook::try_from(data).and_then(|book| {
+                    book.validate(last_time, now_timestamp_ms(), self.max_age)
+                        .map(|_| book)
+                });
+                let event = match checked {
+                    Ok(book) => {
+                        last_time = Some(book.time);
+                        backoff.reset();
+                        BookEvent::Update(book)
+                    }
+                    Err(error) => {
+                        warn!("Order book for {} failed validation: {error}", self.coin);
+                        if event_sender
+                            .send(BookEvent::IntegrityError {
+                                coin: self.coin.clone(),
+                                error,
+                            })
+                            .is_err()
+                        {
+                            break;
+                        }
+                        if !backoff.try_refresh(Instant::now()) {
+                            continue;
+                        }
+                        match self.snapshot(&snapshot_client).await {
+                            Some(book) => {
+                                last_time = Some(book.time);
+                                BookEvent::Snapshot(book)
+                            }
+                            None => continue,
+                        }
+                    }
+                };
+                if event_sender.send(event).is_err() {
+                    break;
+                }
+            }
+            warn!("Order book stream for {} stopped", self.coin);
+        });
+
+        Ok(event_receiver)
+    }
+
+    async fn snapshot(&self, info_client: &InfoClient) -> Option<OrderBook> {
+        let snapshot = match info_client.l2_snapshot(self.coin.clone()).await {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                error!("Could not fetch L2 snapshot for {}: {err}", self.coin);
+                return None;
+            }
+        };
+        let checked = OrderBook::try_from(snapshot).and_then(|book| {
+            book.validate(None, now_timestamp_ms(), self.max_age)
+                .map(|_| book)
+        });
+        match checked {
+            Ok(book) => Some(book),
+            Err(err) => {
+                error!("L2 snapshot for {} failed validation: {err}", self.coin);
+                None
+            }
+        }
+    }
+}
+
+/// Latest validated L2 book of a coin, kept up to date by an [`OrderBookStream`] in the
+/// background. Cloning is cheap and every clone reads the same book. The background task stops
+/// when the last clone is dropped.
+#[derive(Debug, Clone)]
+pub struct LocalBook {
+    pub coin: String,
+    book: Arc<ArcSwapOption<OrderBook>>,
+    _task: Arc<BookTask>,
+}
+
+/// Task applying book events to a [`LocalBook`], aborted on drop
+#[derive(Debug)]
+struct BookTask(JoinHandle<()>);
+
+impl Drop for BookTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+fn apply(book: &ArcSwapOption<OrderBook>, event: BookEvent) {
+    match event {
+        BookEvent::Update(update) | BookEvent::Snapshot(update) => {
+            book.store(Some(Arc::new(update)))
+        }
+        BookEvent::IntegrityError { .. } => book.store(None),
+    }
+}
+
+impl LocalBook {
+    pub async fn start(
+        coin: String,
+        max_age: Duration,
+        info_client: &mut InfoClient,
+    ) -> Result<LocalBook> {
+        let mut events = OrderBookStream::new(coin.clone(), max_age)
+            .start(info_client)
+            .await?;
+        let book = Arc::new(ArcSwapOption::empty());
+
+        let (task_coin, task_book) = (coin.clone(), book.clone());
+        let task = spawn(async move {
+            while let Some(event) = events.recv().await {
+                apply(&task_book, event);
+            }
+            warn!("LocalBook for {task_coin} stopped");
+        });
+
+        Ok(LocalBook {
+            coin,
+            book,
+            _task: Arc::new(BookTask(task)),
+        })
+    }
+
+    /// Latest book, `None` until the first one arrives and after an integrity error until the
+    /// snapshot refresh
+    pub fn book(&self) -> Option<Arc<OrderBook>> {
+        self.book.load_full()
+    }
+
+    pub fn best_bid(&self) -> Option<f64> {
+        self.book()?.best_bid()
+    }
+
+    pub fn best_ask(&self) -> Option<f64> {
+        self.book()?.best_ask()
+    }
+
+    pub fn mid(&self) -> Option<f64> {
+        self.book()?.mid()
+    }
+
+    pub fn microprice(&self) -> Option<f64> {
+        self.book()?.microprice()
+    }
+
+    pub fn depth(&self, levels: usize) -> Option<OrderBook> {
+        Some(self.book()?.depth(levels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(px: &str) -> BookLevel {
+        BookLevel {
+            px: px.to_string(),
+            sz: "1.0".to_string(),
+            n: 1,
+        }
+    }
+
+    fn book(bids: &[&str], asks: &[&str]) -> OrderBook {
+        OrderBook {
+            coin: "ETH".to_string(),
+            time: 1000,
+            bids: bids.iter().map(|px| level(px)).collect(),
+            asks: asks.iter().map(|px| level(px)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_book_validation() {
+        let max_age = Duration::from_secs(1);
+        assert!(book(&["99", "98"], &["101", "102"])
+            .validate(Some(900), 1500, max_age)
+            .is_ok());
+        assert_eq!(
+            book(&["101"], &["100"]).validate(None, 1000, max_age),
+            Err(BookIntegrityError::Crossed {
+                best_bid: 101.0,
+                best_ask: 100.0
+            })
+        );
+        assert_eq!(
+            book(&["99", "99.5"], &["101"]).validate(None, 1000, max_age),
+            Err(BookIntegrityError::NonMonotonic {
+                side: "Bid",
+                index: 1
+            })
+        );
+        assert_eq!(
+            book(&["99"], &["101", "101"]).validate(None, 1000, max_age),
+            Err(BookIntegrityError::NonMonotonic {
+                side: "Ask",
+                index: 1
+            })
+        );
+        assert_eq!(
+            book(&["99"], &["101"]).validate(Some(1001), 1000, max_age),
+            Err(BookIntegrityError::OutOfOrder {
+                time: 1000,
+                last_time: 1001
+            })
+        );
+        assert_eq!(
+            book(&["99"], &["101"]).validate(None, 2500, max_age),
+            Err(BookIntegrityError::Stale {
+                time: 1000,
+                age_ms: 1500
+            })
+        );
+    }
+
+    #[test]
+    fn test_refresh_backoff() {
+        let start = Instant::now();
+        let second = Duration::from_secs(1);
+        let mut backoff = RefreshBackoff::new(second);
+        assert!(backoff.try_refresh(start));
+        // errors within the interval are coalesced into the first refresh
+        assert!(!backoff.try_refresh(start));
+        assert!(!backoff.try_refresh(start + second / 2));
+        assert!(backoff.try_refresh(start + second));
+        // the interval doubled while errors persist
+        assert!(!backoff.try_refresh(start + second * 2));
+        assert!(backoff.try_refresh(start + second * 3));
+        assert!(!backoff.try_refresh(start + second * 6));
+        assert!(backoff.try_refresh(start + second * 7));
+
+        // a valid book resets it
+        backoff.reset();
+        assert!(!backoff.try_refresh(start + second * 7 + second / 2));
+        assert!(backoff.try_refresh(start + second * 8));
+    }
+
+    fn local_book(task: JoinHandle<()>) -> LocalBook {
+        LocalBook {
+            coin: "ETH".to_string(),
+            book: Arc::new(ArcSwapOption::empty()),
+            _task: Arc::new(BookTask(task)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_book() {
+        let local_book = local_book(spawn(async {}));
+        assert_eq!(local_book.mid(), None);
+
+        let mut update = book(&["99", "98", "97"], &["101", "102"]);
+        update.asks[0].sz = "3.0".to_string();
+        apply(&local_book.book, BookEvent::Update(update));
+        assert_eq!(local_book.best_bid(), Some(99.0));
+        assert_eq!(local_book.best_ask(), Some(101.0));
+        assert_eq!(local_book.mid(), Some(100.0));
+        // three times the size on the ask leans the price towards the bid
+        assert_eq!(local_book.microprice(), Some(99.5));
+        let depth = local_book.depth(2).unwrap();
+        assert_eq!((depth.bids.len(), depth.asks.len()), (2, 2));
+
+        apply(
+            &local_book.book,
+            BookEvent::IntegrityError {
+                coin: "ETH".to_string(),
+                error: BookIntegrityError::MissingSide(1),
+            },
+        );
+        assert!(local_book.book().is_none());
+        apply(
+            &local_book.book,
+            BookEvent::Snapshot(book(&["98"], &["100"])),
+        );
+        assert_eq!(local_book.mid(), Some(99.0));
+    }
+
+    #[tokio::test]
+    async fn test_task_stops_with_the_last_clone() {
+        let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+        let local_book = local_book(spawn(async move {
+            let _sender = sender;
+            std::future::pending::<()>().await
+        }));
+        let clone = local_book.clone();
+        drop(local_book);
+        tokio::task::yield_now().await;
+        assert!(!clone._task.0.is_finished());
+
+        drop(clone);
+        // the aborted task drops the sender
+        assert!(receiver.await.is_err());
+    }
+}
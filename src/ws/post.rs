@@ -0,0 +1,260 @@
+use crate::{
+    prelude::*,
+    ws::{
+        timing::{TimingStats, TimingTracker},
+        WsConfig, WsManager,
+    },
+    BaseUrl, Error, InfoRequest, RequestOptions,
+};
+use futures_util::{stream::SplitSink, SinkExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    net::TcpStream,
+    sync::{oneshot, Mutex as AsyncMutex},
+};
+use tokio_tungstenite::{tungstenite::protocol, MaybeTlsStream, WebSocketStream};
+
+pub(crate) type WsWriter =
+    Arc<AsyncMutex<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, protocol::Message>>>;
+
+/// Request sent over the websocket `post` method
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", content = "payload", rename_all = "camelCase")]
+pub enum WsPostRequest {
+    /// Any `/info` request body
+    Info(serde_json::Value),
+    /// A signed `/exchange` request body with `action`, `nonce`, `signature` and `vaultAddress`
+    Action(serde_json::Value),
+}
+
+#[derive(Serialize)]
+struct PostSendData<'a> {
+    method: &'static str,
+    id: u64,
+    request: &'a WsPostRequest,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", content = "payload", rename_all = "camelCase")]
+pub enum WsPostResponse {
+    Info(serde_json::Value),
+    Action(serde_json::Value),
+    Error(String),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PostResponseData {
+    pub id: u64,
+    pub response: WsPostResponse,
+}
+
+/// Routes `post` responses read by the `WsManager` reader back to the waiting request
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PostRouter {
+    next_id: Arc<AtomicU64>,
+    pending: Arc<Mutex<HashMap<u64, oneshot::Sender<WsPostResponse>>>>,
+}
+
+impl PostRouter {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, oneshot::Sender<WsPostResponse>>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn register(&self) -> (u64, oneshot::Receiver<WsPostResponse>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        self.lock().insert(id, sender);
+        (id, receiver)
+    }
+
+    fn cancel(&self, id: u64) {
+        self.lock().remove(&id);
+    }
+
+    /// Delivers a response to its request, dropping responses nobody is waiting for
+    pub(crate) fn route(&self, data: PostResponseData) {
+        if let Some(sender) = self.lock().remove(&data.id) {
+            let _ = sender.send(data.response);
+        }
+    }
+}
+
+/// Sends info and signed exchange requests over a websocket instead of HTTP.
+///
+/// A client from [`WsPostClient::new`] opens its own connection. One from
+/// [`crate::InfoClient::ws_post_client`] shares the `InfoClient` subscription socket, with responses
+/// routed by request id, and stops working once that `InfoClient` is dropped.
+#[derive(Debug)]
+pub struct WsPostClient {
+    writer: WsWriter,
+    router: PostRouter,
+    timeout: Duration,
+    timing: TimingTracker,
+    _ws_manager: Option<WsManager>,
+}
+
+impl WsPostClient {
+    const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub async fn new(base_url: Option<BaseUrl>) -> Result<WsPostClient> {
+        Self::with_ws_config(base_url, WsConfig::default()).await
+    }
+
+    pub async fn with_ws_config(
+        base_url: Option<BaseUrl>,
+        ws_config: WsConfig,
+    ) -> Result<WsPostClient> {
+        let base_url = base_url.unwrap_or(BaseUrl::Mainnet).get_url();
+        let ws_manager =
+            WsManager::new(format!("ws{}/ws", &base_url[4..]), true, ws_config).await?;
+        let (writer, router) = ws_manager.post_handle();
+        Ok(WsPostClient {
+            writer,
+            router,
+            timeout: Self::DEFAULT_TIMEOUT,
+            timing: TimingTracker::default(),
+            _ws_manager: Some(ws_manager),
+        })
+    }
+
+    pub(crate) fn shared(writer: WsWriter, router: PostRouter) -> WsPostClient {
+        WsPostClient {
+            writer,
+            router,
+            timeout: Self::DEFAULT_TIMEOUT,
+            timing: TimingTracker::default(),
+            _ws_manager: None,
+        }
+    }
+
+    /// Sets how long to wait for each response, 10 seconds by default
+    pub fn with_timeout(mut self, timeout: Duration) -> WsPostClient {
+        self.timeout = timeout;
+        self
+    }
+
+    pub async fn post(&self, request: WsPostRequest) -> Result<WsPostResponse> {
+        self.post_with_options(request, &RequestOptions::default())
+            .await
+    }
+
+    /// Like [`WsPostClient::post`], with `options.timeout` replacing the client's timeout and
+    /// `options.cancel` abandoning the request when cancelled
+    pub async fn post_with_options(
+        &self,
+        request: WsPostRequest,
+        options: &RequestOptions,
+    ) -> Result<WsPostResponse> {
+        let options = RequestOptions {
+            timeout: Some(options.timeout.unwrap_or(self.timeout)),
+            cancel: options.cancel.clone(),
+            rate_limit: None,
+        };
+        let (id, receiver) = self.router.register();
+        let sent_at = Instant::now();
+        let result = options.run(self.send(id, request, receiver)).await;
+        match result {
+            Ok(_) => self.timing.record(sent_at.elapsed()),
+            Err(Error::Timeout(_) | Error::Cancelled) => self.router.cancel(id),
+            Err(_) => {}
+        }
+        result
+    }
+
+    /// Round trip times of the latest responses, `None` before the first one
+    pub fn timing_stats(&self) -> Option<TimingStats> {
+        self.timing.stats()
+    }
+
+    async fn send(
+        &self,
+        id: u64,
+        request: WsPostRequest,
+        receiver: oneshot::Receiver<WsPostResponse>,
+    ) -> Result<WsPostResponse> {
+        let payload = serde_json::to_string(&PostSendData {
+            method: "post",
+            id,
+            request: &request,
+        })
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        let sent = self
+            .writer
+            .lock()
+            .await
+            .send(protocol::Message::Text(payload))
+            .await;
+        if let Err(err) = sent {
+            self.router.cancel(id);
+            return Err(Error::Websocket(err.to_string()));
+        }
+
+        receiver
+            .await
+            .map_err(|_| Error::WsPost(format!("connection closed before response {id}")))
+    }
+
+    /// Sends an info request, deserializing the `data` of the response payload
+    pub async fn info<T: DeserializeOwned>(&self, request: &InfoRequest) -> Result<T> {
+        let request = serde_json::to_value(request).map_err(|e| Error::JsonParse(e.to_string()))?;
+        match self.post(WsPostRequest::Info(request)).await? {
+            WsPostResponse::Info(mut payload) => serde_json::from_value(payload["data"].take())
+                .map_err(|e| Error::JsonParse(e.to_string())),
+            WsPostResponse::Error(err) => Err(Error::WsPost(err)),
+            WsPostResponse::Action(payload) => Err(Error::WsPost(format!(
+                "expected an info response, got action response {payload}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_post_router() {
+        let router = PostRouter::default();
+        let (first, mut first_receiver) = router.register();
+        let (second, mut second_receiver) = router.register();
+        assert_ne!(first, second);
+
+        let data: PostResponseData = serde_json::from_str(&format!(
+            r#"{{"id": {second}, "response": {{"type": "error", "payload": "bad request"}}}}"#
+        ))
+        .unwrap();
+        router.route(data);
+        assert!(matches!(
+            second_receiver.try_recv(),
+            Ok(WsPostResponse::Error(err)) if err == "bad request"
+        ));
+        assert!(first_receiver.try_recv().is_err());
+
+        router.cancel(first);
+        assert!(router.lock().is_empty());
+
+        let request = serde_json::to_value(PostSendData {
+            method: "post",
+            id: 3,
+            request: &WsPostRequest::Info(serde_json::json!({"type": "allMids"})),
+        })
+        .unwrap();
+        assert_eq!(
+            request,
+            serde_json::json!({
+                "method": "post",
+                "id": 3,
+                "request": {"type": "info", "payload": {"type": "allMids"}}
+            })
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use crate::helpers::now_timestamp_ms;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Message counters for one websocket subscription
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubscriptionStats {
+    /// Subscription identifier, the serialized `Subscription` (or `userEvents` / `orderUpdates`)
+    pub subscription: String,
+    pub messages_received: u64,
+    /// Payloads for this subscription's channel that could not be parsed
+    pub parse_failures: u64,
+    /// Local time of the latest message in milliseconds
+    pub last_message_at: Option<u64>,
+    /// Time since the latest message when the stats were taken
+    pub last_message_age: Option<Duration>,
+}
+
+/// Whether `payload` could belong to the subscription `identifier`, matching on its channel
+/// and, when present, its coin. Used for payloads that failed to parse.
+pub(crate) fn payload_matches(identifier: &str, payload: &serde_json::Value) -> bool {
+    let Some(channel) = payload["channel"].as_str() else {
+        return false;
+    };
+    let data = &payload["data"];
+    let coin = data["coin"].as_str().or_else(|| data[0]["coin"].as_str());
+    match identifier {
+        "userEvents" => channel == "user",
+        "orderUpdates" => channel == "orderUpdates",
+        identifier => serde_json::from_str::<serde_json::Value>(identifier)
+            .map(|subscription| {
+                subscription["type"].as_str() == Some(channel)
+                    && match (subscription["coin"].as_str(), coin) {
+                        (Some(subscribed), Some(coin)) => subscribed == coin,
+                        _ => true,
+                    }
+            })
+            .unwrap_or(false),
+    }
+}
+
+/// Per-subscription counters shared between the ws reader task and `WsManager`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StatsTracker {
+    stats: Arc<Mutex<HashMap<String, SubscriptionStats>>>,
+}
+
+impl StatsTracker {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, SubscriptionStats>> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn track(&self, identifier: &str) {
+        self.lock()
+            .entry(identifier.to_string())
+            .or_insert_with(|| SubscriptionStats {
+                subscription: identifier.to_string(),
+                ..Default::default()
+            });
+    }
+
+    pub(crate) fn untrack(&self, identifier: &str) {
+        self.lock().remove(identifier);
+    }
+
+    pub(crate) fn on_message(&self, identifier: &str) {
+        if let Some(stats) = self.lock().get_mut(identifier) {
+            stats.messages_received += 1;
+            stats.last_message_at = Some(now_timestamp_ms());
+        }
+    }
+
+    /// Counts a payload that failed to parse against every subscription it could belong to
+    pub(crate) fn on_parse_failure(&self, payload: &serde_json::Value) {
+        for (identifier, stats) in self.lock().iter_mut() {
+            if payload_matches(identifier, payload) {
+                stats.parse_failures += 1;
+            }
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> Vec<SubscriptionStats> {
+        let now = now_timestamp_ms();
+        let mut stats: Vec<SubscriptionStats> = self
+            .lock()
+            .values()
+            .cloned()
+            .map(|mut stats| {
+                stats.last_message_age = stats
+                    .last_message_at
+                    .map(|at| Duration::from_millis(now.saturating_sub(at)));
+                stats
+            })
+            .collect();
+        stats.sort_by(|a, b| a.subscription.cmp(&b.subscription));
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_tracker() {
+        let tracker = StatsTracker::default();
+        let eth = r#"{"type":"l2Book","coin":"ETH"}"#;
+        let btc = r#"{"type":"l2Book","coin":"BTC"}"#;
+        tracker.track(eth);
+        tracker.track(btc);
+
+        tracker.on_message(eth);
+        tracker.on_message(eth);
+        tracker.on_message("not tracked");
+
+        let stats = tracker.snapshot();
+        assert_eq!(stats.len(), 2);
+        let (btc_stats, eth_stats) = (&stats[0], &stats[1]);
+        assert_eq!(btc_stats.subscription, btc);
+        assert_eq!(btc_stats.messages_received, 0);
+        assert!(btc_stats.last_message_age.is_none());
+        assert_eq!(eth_stats.messages_received, 2);
+        assert!(eth_stats.last_message_age.is_some());
+
+        tracker.untrack(eth);
+        assert_eq!(tracker.snapshot().len(), 1);
+    }
+}
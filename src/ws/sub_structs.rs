@@ -1,8 +1,8 @@
-use ethers::types::H160;
-use serde::Deserialize;
+use crate::Address;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Trade {
     pub coin: String,
     pub side: String,
@@ -13,26 +13,34 @@ pub struct Trade {
     pub tid: u64,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct BookLevel {
     pub px: String,
     pub sz: String,
     pub n: u64,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct L2BookData {
     pub coin: String,
     pub time: u64,
     pub levels: Vec<Vec<BookLevel>>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+/// Best bid and offer of `coin`, either side `None` while it is empty
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct BboData {
+    pub coin: String,
+    pub time: u64,
+    pub bbo: [Option<BookLevel>; 2],
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct AllMidsData {
     pub mids: HashMap<String, String>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TradeInfo {
     pub coin: String,
@@ -48,27 +56,33 @@ pub struct TradeInfo {
     pub cloid: Option<String>,
     pub crossed: bool,
     pub fee: String,
+    /// Token `fee` is charged in, e.g. the base token of spot buys. `None` from older servers.
+    #[serde(default)]
+    pub fee_token: Option<String>,
     pub tid: u64,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UserFillsData {
     pub is_snapshot: Option<bool>,
-    pub user: H160,
+    pub user: Address,
     pub fills: Vec<TradeInfo>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum UserData {
     Fills(Vec<TradeInfo>),
     Funding(UserFunding),
     Liquidation(Liquidation),
     NonUserCancel(Vec<NonUserCancel>),
+    /// Any user event this version of the SDK does not know about, kept as raw JSON
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Liquidation {
     pub lid: u64,
     pub liquidator: String,
@@ -77,13 +91,13 @@ pub struct Liquidation {
     pub liquidated_account_value: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct NonUserCancel {
     pub coin: String,
     pub oid: u64,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct CandleData {
     #[serde(rename = "T")]
     pub time_close: u64,
@@ -107,7 +121,7 @@ pub struct CandleData {
     pub volume: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderUpdate {
     pub order: BasicOrder,
@@ -115,7 +129,7 @@ pub struct OrderUpdate {
     pub status_timestamp: u64,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct BasicOrder {
     pub coin: String,
@@ -128,15 +142,15 @@ pub struct BasicOrder {
     pub cloid: Option<String>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UserFundingsData {
     pub is_snapshot: Option<bool>,
-    pub user: H160,
+    pub user: Address,
     pub fundings: Vec<UserFunding>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UserFunding {
     pub time: u64,
@@ -146,22 +160,22 @@ pub struct UserFunding {
     pub funding_rate: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UserNonFundingLedgerUpdatesData {
     pub is_snapshot: Option<bool>,
-    pub user: H160,
+    pub user: Address,
     pub non_funding_ledger_updates: Vec<LedgerUpdateData>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct LedgerUpdateData {
     pub time: u64,
     pub hash: String,
     pub delta: LedgerUpdate,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "type")]
 pub enum LedgerUpdate {
@@ -169,6 +183,7 @@ pub enum LedgerUpdate {
     Withdraw(Withdraw),
     InternalTransfer(InternalTransfer),
     SubAccountTransfer(SubAccountTransfer),
+    #[serde(alias = "liquidation")]
     LedgerLiquidation(LedgerLiquidation),
     VaultDeposit(VaultDelta),
     VaultCreate(VaultDelta),
@@ -178,36 +193,45 @@ pub enum LedgerUpdate {
     AccountClassTransfer(AccountClassTransfer),
     SpotTransfer(SpotTransfer),
     SpotGenesis(SpotGenesis),
+    RewardsClaim(RewardsClaim),
+    CStakingTransfer(CStakingTransfer),
+    CDeposit(CDeposit),
+    CWithdrawal(CWithdrawal),
+    Send(LedgerSend),
+    DeployGasAuction(DeployGasAuction),
+    /// Any ledger update type this version of the SDK does not know about, kept as raw JSON
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Deposit {
     pub usdc: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Withdraw {
     pub usdc: String,
     pub nonce: u64,
     pub fee: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct InternalTransfer {
     pub usdc: String,
-    pub user: H160,
-    pub destination: H160,
+    pub user: Address,
+    pub destination: Address,
     pub fee: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct SubAccountTransfer {
     pub usdc: String,
-    pub user: H160,
-    pub destination: H160,
+    pub user: Address,
+    pub destination: Address,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct LedgerLiquidation {
     pub account_value: u64,
@@ -215,23 +239,23 @@ pub struct LedgerLiquidation {
     pub liquidated_positions: Vec<LiquidatedPosition>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct LiquidatedPosition {
     pub coin: String,
     pub szi: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct VaultDelta {
-    pub vault: H160,
+    pub vault: Address,
     pub usdc: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct VaultWithdraw {
-    pub vault: H160,
-    pub user: H160,
+    pub vault: Address,
+    pub user: Address,
     pub requested_usd: String,
     pub commission: String,
     pub closing_cost: String,
@@ -239,43 +263,136 @@ pub struct VaultWithdraw {
     pub net_withdrawn_usd: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct VaultLeaderCommission {
-    pub user: H160,
+    pub user: Address,
     pub usdc: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountClassTransfer {
     pub usdc: String,
     pub to_perp: bool,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SpotTransfer {
     pub token: String,
     pub amount: String,
     pub usdc_value: String,
-    pub user: H160,
-    pub destination: H160,
+    pub user: Address,
+    pub destination: Address,
     pub fee: String,
+    pub native_token_fee: Option<String>,
+    pub nonce: Option<u64>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct SpotGenesis {
     pub token: String,
     pub amount: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RewardsClaim {
+    pub amount: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CStakingTransfer {
+    pub token: String,
+    pub amount: String,
+    pub is_deposit: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CDeposit {
+    pub amount: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CWithdrawal {
+    pub amount: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerSend {
+    pub user: Address,
+    pub destination: Address,
+    pub source_dex: String,
+    pub destination_dex: String,
+    pub token: String,
+    pub amount: String,
+    pub usdc_value: String,
+    pub fee: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct DeployGasAuction {
+    pub token: String,
+    pub amount: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct NotificationData {
     pub notification: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct WebData2Data {
-    pub user: H160,
+    pub user: Address,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Fill of `sz` at `px` with every other field zero or empty, to adjust with struct update
+    /// syntax
+    pub(crate) fn trade_info(coin: &str, side: &str, px: &str, sz: &str) -> TradeInfo {
+        TradeInfo {
+            coin: coin.to_string(),
+            side: side.to_string(),
+            px: px.to_string(),
+            sz: sz.to_string(),
+            time: 0,
+            hash: "0x".to_string(),
+            start_position: "0".to_string(),
+            dir: String::new(),
+            closed_pnl: "0".to_string(),
+            oid: 0,
+            cloid: None,
+            crossed: true,
+            fee: "0".to_string(),
+            fee_token: None,
+            tid: 0,
+        }
+    }
+
+    #[test]
+    fn test_ledger_update_variants() {
+        let update: LedgerUpdate =
+            serde_json::from_str(r#"{"type": "rewardsClaim", "amount": "1.5"}"#).unwrap();
+        assert!(
+            matches!(update, LedgerUpdate::RewardsClaim(RewardsClaim { amount }) if amount == "1.5")
+        );
+
+        let update: LedgerUpdate = serde_json::from_str(
+            r#"{"type": "cStakingTransfer", "token": "HYPE", "amount": "10.0", "isDeposit": true}"#,
+        )
+        .unwrap();
+        assert!(matches!(update, LedgerUpdate::CStakingTransfer(_)));
+
+        let update: LedgerUpdate =
+            serde_json::from_str(r#"{"type": "someFutureDelta", "amount": "1"}"#).unwrap();
+        match update {
+            LedgerUpdate::Unknown(value) => assert_eq!(value["type"], "someFutureDelta"),
+            _ => panic!("expected unknown ledger update"),
+        }
+    }
 }
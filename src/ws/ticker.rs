@@ -0,0 +1,138 @@
+use crate::{
+    helpers::now_timestamp_ms, info::poller::Poller, prelude::*, InfoClient, Message, Subscription,
+};
+use log::{error, warn};
+use std::time::Duration;
+use tokio::{
+    spawn,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver},
+    time::{self, Instant, MissedTickBehavior},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tick {
+    /// Local time of the tick in milliseconds
+    pub time: u64,
+    /// Latest known mid, `None` until the first AllMids update for the coin arrives
+    pub mid: Option<f64>,
+    /// Time since the mid was last updated
+    pub age: Option<Duration>,
+    /// True if the mid is missing or older than the configured staleness threshold
+    pub is_stale: bool,
+}
+
+#[derive(Debug)]
+struct TickerState {
+    mid: Option<f64>,
+    updated_at: Option<Instant>,
+    stale_after: Duration,
+}
+
+impl TickerState {
+    fn update(&mut self, mid: f64, now: Instant) {
+        self.mid = Some(mid);
+        self.updated_at = Some(now);
+    }
+
+    fn tick(&self, time: u64, now: Instant) -> Tick {
+        let age = self.updated_at.map(|updated_at| now - updated_at);
+        Tick {
+            time,
+            mid: self.mid,
+            age,
+            is_stale: age.is_none_or(|age| age > self.stale_after),
+        }
+    }
+}
+
+/// Emits the latest mid for a coin at a fixed cadence, regardless of how often the mid changes.
+#[derive(Debug, Clone)]
+pub struct Ticker {
+    pub coin: String,
+    pub interval: Duration,
+    pub stale_after: Duration,
+}
+
+impl Ticker {
+    pub fn new(coin: String, interval: Duration, stale_after: Duration) -> Ticker {
+        Ticker {
+            coin,
+            interval,
+            stale_after,
+        }
+    }
+
+    /// Subscribes to AllMids on `info_client` and returns the tick stream.
+    /// The stream ends when the subscription is dropped or the receiver is closed.
+    pub async fn start(self, info_client: &mut InfoClient) -> Result<UnboundedReceiver<Tick>> {
+        let poller = Poller::new("Ticker", self.interval)?;
+
+        let (sender, mut receiver) = unbounded_channel();
+        info_client.subscribe(Subscription::AllMids, sender).await?;
+
+        let (tick_sender, tick_receiver) = unbounded_channel();
+        spawn(async move {
+            let mut state = TickerState {
+                mid: None,
+                updated_at: None,
+                stale_after: self.stale_after,
+            };
+            let mut interval = time::interval(poller.interval());
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+            loop {
+                tokio::select! {
+                    message = receiver.recv() => match message {
+                        Some(Message::AllMids(all_mids)) => {
+                            if let Some(mid) = all_mids.data.mids.get(&self.coin) {
+                                match mid.parse::<f64>() {
+                                    Ok(mid) => state.update(mid, Instant::now()),
+                                    Err(err) => error!("Could not parse mid {mid} for {}: {err}", self.coin),
+                                }
+                            }
+                        }
+                        Some(Message::NoData) => warn!("Ticker for {} lost its AllMids feed", self.coin),
+                        Some(_) => {}
+                        None => break,
+                    },
+                    _ = interval.tick() => {
+                        if tick_sender.send(state.tick(now_timestamp_ms(), Instant::now())).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+            warn!("Ticker for {} stopped", self.coin);
+        });
+
+        Ok(tick_receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticker_staleness() {
+        let start = Instant::now();
+        let mut state = TickerState {
+            mid: None,
+            updated_at: None,
+            stale_after: Duration::from_secs(5),
+        };
+
+        let tick = state.tick(0, start);
+        assert_eq!(tick.mid, None);
+        assert!(tick.is_stale);
+
+        state.update(100.0, start);
+        let tick = state.tick(0, start + Duration::from_secs(1));
+        assert_eq!(tick.mid, Some(100.0));
+        assert!(!tick.is_stale);
+
+        let tick = state.tick(0, start + Duration::from_secs(6));
+        assert_eq!(tick.mid, Some(100.0));
+        assert!(tick.is_stale);
+    }
+}
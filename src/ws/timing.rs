@@ -0,0 +1,96 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Number of latest round trips [`TimingStats`] are computed over
+pub const TIMING_WINDOW: usize = 1024;
+
+/// Round trip times of the latest [`TIMING_WINDOW`] responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimingStats {
+    /// Responses received since the client was created
+    pub count: u64,
+    pub min: Duration,
+    pub avg: Duration,
+    pub max: Duration,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+#[derive(Debug, Default)]
+struct Samples {
+    latest: VecDeque<Duration>,
+    count: u64,
+}
+
+/// Round trips recorded by a [`crate::WsPostClient`]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TimingTracker {
+    samples: Arc<Mutex<Samples>>,
+}
+
+impl TimingTracker {
+    pub(crate) fn record(&self, round_trip: Duration) {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        if samples.latest.len() == TIMING_WINDOW {
+            samples.latest.pop_front();
+        }
+        samples.latest.push_back(round_trip);
+        samples.count += 1;
+    }
+
+    /// `None` until a round trip is recorded
+    pub(crate) fn stats(&self) -> Option<TimingStats> {
+        let (mut sorted, count) = {
+            let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+            (Vec::from(samples.latest.clone()), samples.count)
+        };
+        sorted.sort_unstable();
+        // nearest rank
+        let quantile = |q: f64| sorted[((q * sorted.len() as f64).ceil() as usize).max(1) - 1];
+        Some(TimingStats {
+            count,
+            min: *sorted.first()?,
+            avg: sorted.iter().sum::<Duration>() / sorted.len() as u32,
+            max: *sorted.last()?,
+            p50: quantile(0.5),
+            p95: quantile(0.95),
+            p99: quantile(0.99),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_stats() {
+        let tracker = TimingTracker::default();
+        assert_eq!(tracker.stats(), None);
+
+        for ms in (1..=100).rev() {
+            tracker.record(Duration::from_millis(ms));
+        }
+        let stats = tracker.stats().unwrap();
+        assert_eq!(stats.count, 100);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.max, Duration::from_millis(100));
+        assert_eq!(stats.avg, Duration::from_micros(50_500));
+        assert_eq!(stats.p50, Duration::from_millis(50));
+        assert_eq!(stats.p95, Duration::from_millis(95));
+        assert_eq!(stats.p99, Duration::from_millis(99));
+
+        // only the latest window counts towards the durations
+        for _ in 0..TIMING_WINDOW {
+            tracker.record(Duration::from_millis(200));
+        }
+        let stats = tracker.stats().unwrap();
+        assert_eq!(stats.count, 100 + TIMING_WINDOW as u64);
+        assert_eq!(stats.min, Duration::from_millis(200));
+        assert_eq!(stats.p99, Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{helpers::parse_decimal, prelude::*, TradeInfo};
+use rust_decimal::Decimal;
+use std::{collections::HashMap, time::Duration};
+
+/// A position from the fill that opened it to the fill that brought it back to flat
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregatedTrade {
+    pub coin: String,
+    pub is_long: bool,
+    /// Total size entered
+    pub sz: Decimal,
+    pub avg_entry_px: Decimal,
+    pub avg_exit_px: Decimal,
+    /// Sum of the closed PnL reported on the exit fills, before fees
+    pub realized_pnl: Decimal,
+    pub fees: Decimal,
+    /// Times of the first and last fill in milliseconds
+    pub opened_at: u64,
+    pub closed_at: u64,
+    pub holding_time: Duration,
+    pub fills: usize,
+}
+
+#[derive(Debug, Clone)]
+struct OpenTrade {
+    is_long: bool,
+    position: Decimal,
+    entry_sz: Decimal,
+    entry_ntl: Decimal,
+    exit_sz: Decimal,
+    exit_ntl: Decimal,
+    realized_pnl: Decimal,
+    fees: Decimal,
+    opened_at: u64,
+    fills: usize,
+}
+
+impl OpenTrade {
+    fn open(is_long: bool, sz: Decimal, px: Decimal, fee: Decimal, time: u64) -> OpenTrade {
+        OpenTrade {
+            is_long,
+            position: sz,
+            entry_sz: sz,
+            entry_ntl: sz * px,
+            exit_sz: Decimal::ZERO,
+            exit_ntl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            fees: fee,
+            opened_at: time,
+            fills: 1,
+        }
+    }
+
+    fn close(self, coin: &str, closed_at: u64) -> AggregatedTrade {
+        AggregatedTrade {
+            coin: coin.to_string(),
+            is_long: self.is_long,
+            sz: self.entry_sz,
+            avg_entry_px: self.entry_ntl / self.entry_sz,
+            avg_exit_px: self.exit_ntl / self.exit_sz,
+            realized_pnl: self.realized_pnl,
+            fees: self.fees,
+            opened_at: self.opened_at,
+            closed_at,
+            holding_time: Duration::from_millis(closed_at.saturating_sub(self.opened_at)),
+            fills: self.fills,
+        }
+    }
+}
+
+/// Groups partial fills into [`AggregatedTrade`]s per coin.
+/// Feed fills oldest first; a fill that flips the position closes the trade and opens a new one
+/// with the remaining size, splitting its fee by size. Fills of a position opened before the
+/// first fill seen are skipped until that position is flat.
+#[derive(Debug, Clone, Default)]
+pub struct TradeAggregator {
+    open: HashMap<String, OpenTrade>,
+}
+
+impl TradeAggregator {
+    pub fn new() -> TradeAggregator {
+        TradeAggregator::default()
+    }
+
+    /// Groups `fills` into the trades they close, leaving trades still open out
+    pub fn aggregate(fills: &[TradeInfo]) -> Result<Vec<AggregatedTrade>> {
+        let mut aggregator = TradeAggregator::new();
+        let mut trades = Vec::new();
+        for fill in fills {
+            trades.extend(aggregator.on_fill(fill)?);
+        }
+        Ok(trades)
+    }
+
+    /// Size of the open trade in `coin`, positive when long
+    pub fn open_position(&self, coin: &str) -> Option<Decimal> {
+        self.open.get(coin).map(|trade| {
+            if trade.is_long {
+                trade.position
+            } else {
+                -trade.position
+            }
+        })
+    }
+
+    /// Applies a fill, returning the trade it closes
+    pub fn on_fill(&mut self, fill: &TradeInfo) -> Result<Option<AggregatedTrade>> {
+        let sz = parse_decimal(&fill.sz)?;
+        let px = parse_decimal(&fill.px)?;
+        let fee = parse_decimal(&fill.fee)?;
+        let is_buy = fill.side == "B";
+        if sz.is_zero() {
+            return Ok(None);
+        }
+
+        let Some(mut trade) = self.open.remove(&fill.coin) else {
+            let start_position = parse_decimal(&fill.start_position)?;
+            let signed_sz = if is_buy { sz } else { -sz };
+            let end_position = start_position + signed_sz;
+            // only the part beyond flat belongs to a trade we have seen open
+            let opened_sz = if start_position.is_zero()
+                || start_position.is_sign_positive() != end_position.is_sign_positive()
+            {
+                end_position.abs()
+            } else {
+                Decimal::ZERO
+            };
+            if !opened_sz.is_zero() {
+                self.open.insert(
+                    fill.coin.clone(),
+                    OpenTrade::open(is_buy, opened_sz, px, fee * opened_sz / sz, fill.time),
+                );
+            }
+            return Ok(None);
+        };
+
+        trade.fills += 1;
+        if is_buy == trade.is_long {
+            trade.position += sz;
+            trade.entry_sz += sz;
+            trade.entry_ntl += sz * px;
+            trade.fees += fee;
+            self.open.insert(fill.coin.clone(), trade);
+            return Ok(None);
+        }
+
+        let exit_sz = sz.min(trade.position);
+        let exit_fee = fee * exit_sz / sz;
+        trade.position -= exit_sz;
+        trade.exit_sz += exit_sz;
+        trade.exit_ntl += exit_sz * px;
+        trade.realized_pnl += parse_decimal(&fill.closed_pnl)?;
+        trade.fees += exit_fee;
+        if !trade.position.is_zero() {
+            self.open.insert(fill.coin.clone(), trade);
+            return Ok(None);
+        }
+
+        let remaining_sz = sz - exit_sz;
+        if !remaining_sz.is_zero() {
+            self.open.insert(
+                fill.coin.clone(),
+                OpenTrade::open(is_buy, remaining_sz, px, fee - exit_fee, fill.time),
+            );
+        }
+        Ok(Some(trade.close(&fill.coin, fill.time)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws::trade_info;
+
+    fn fill(
+        side: &str,
+        px: &str,
+        sz: &str,
+        start_position: &str,
+        closed_pnl: &str,
+        time: u64,
+    ) -> TradeInfo {
+        TradeInfo {
+            time,
+            start_position: start_position.to_string(),
+            closed_pnl: closed_pnl.to_string(),
+            oid: time,
+            fee: "1".to_string(),
+            tid: time,
+            ..trade_info("ETH", side, px, sz)
+        }
+    }
+
+    #[test]
+    fn test_aggregate_fills_into_trades() -> Result<()> {
+        let trades = TradeAggregator::aggregate(&[
+            // tail of a short opened before these fills
+            fill("B", "1000", "1", "-1", "5", 1_000),
+            fill("B", "2000", "1", "0", "0", 2_000),
+            fill("B", "2100", "1", "1", "0", 3_000),
+            fill("A", "2200", "0.5", "2", "75", 4_000),
+            // closes the long and flips short by 0.5
+            fill("A", "2300", "2", "1.5", "375", 5_000),
+            fill("B", "2250", "0.5", "-0.5", "25", 6_000),
+        ])?;
+
+        assert_eq!(trades.len(), 2);
+        let long = &trades[0];
+        assert!(long.is_long);
+        assert_eq!(long.sz, Decimal::from(2));
+        assert_eq!(long.avg_entry_px, Decimal::from(2050));
+        assert_eq!(long.avg_exit_px, Decimal::new(22750, 1));
+        assert_eq!(long.realized_pnl, Decimal::from(450));
+        assert_eq!(long.fees, Decimal::new(375, 2));
+        assert_eq!(long.holding_time, Duration::from_secs(3));
+        assert_eq!(long.fills, 4);
+
+        let short = &trades[1];
+        assert!(!short.is_long);
+        assert_eq!(short.sz, Decimal::new(5, 1));
+        assert_eq!(short.avg_entry_px, Decimal::from(2300));
+        assert_eq!(short.avg_exit_px, Decimal::from(2250));
+        assert_eq!(short.fees, Decimal::new(125, 2));
+        assert_eq!(short.opened_at, 5_000);
+        Ok(())
+    }
+}
@@ -0,0 +1,263 @@
+use crate::{
+    helpers::now_timestamp_ms, prelude::*, Error, InfoClient, Message, Subscription, Trade,
+};
+use log::{error, warn};
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    spawn,
+    sync::mpsc::unbounded_channel,
+    task::{spawn_blocking, JoinHandle},
+    time::{interval, MissedTickBehavior},
+};
+
+/// Trades of selected coins stored in sqlite, e.g. to collect tick history for research.
+/// Trades are keyed by coin and trade id, so overlapping inserts are ignored.
+#[derive(Debug, Clone)]
+pub struct TradeTape {
+    connection: Arc<Mutex<rusqlite::Connection>>,
+    /// Trades are written once this many are buffered or `flush_interval` elapses
+    pub batch_size: usize,
+    pub flush_interval: Duration,
+    /// Trades older than this are deleted on every flush, `None` to keep everything
+    pub retention: Option<Duration>,
+}
+
+fn sqlite_error(err: rusqlite::Error) -> Error {
+    Error::TradeTape(err.to_string())
+}
+
+impl TradeTape {
+    pub fn open(path: impl AsRef<Path>) -> Result<TradeTape> {
+        TradeTape::from_connection(rusqlite::Connection::open(path).map_err(sqlite_error)?)
+    }
+
+    /// Tape that lives as long as the process, for tests and short sessions
+    pub fn open_in_memory() -> Result<TradeTape> {
+        TradeTape::from_connection(rusqlite::Connection::open_in_memory().map_err(sqlite_error)?)
+    }
+
+    fn from_connection(connection: rusqlite::Connection) -> Result<TradeTape> {
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS trades (
+                    coin TEXT NOT NULL,
+                    tid INTEGER NOT NULL,
+                    time INTEGER NOT NULL,
+                    side TEXT NOT NULL,
+                    px TEXT NOT NULL,
+                    sz TEXT NOT NULL,
+                    hash TEXT NOT NULL,
+                    PRIMARY KEY (coin, tid)
+                );
+                CREATE INDEX IF NOT EXISTS trades_coin_time ON trades (coin, time);",
+            )
+            .map_err(sqlite_error)?;
+        Ok(TradeTape {
+            connection: Arc::new(Mutex::new(connection)),
+            batch_size: 500,
+            flush_interval: Duration::from_secs(1),
+            retention: None,
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, rusqlite::Connection> {
+        self.connection.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Stores `trades` in one transaction and returns how many were new
+    pub fn insert(&self, trades: &[Trade]) -> Result<usize> {
+        let mut connection = self.lock();
+        let transaction = connection.transaction().map_err(sqlite_error)?;
+        let mut inserted = 0;
+        {
+            let mut statement = transaction
+                .prepare_cached(
+                    "INSERT OR IGNORE INTO trades (coin, tid, time, side, px, sz, hash)
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                )
+                .map_err(sqlite_error)?;
+            for trade in trades {
+                inserted += statement
+                    .execute(rusqlite::params![
+                        trade.coin,
+                        trade.tid as i64,
+                        trade.time as i64,
+                        trade.side,
+                        trade.px,
+                        trade.sz,
+                        trade.hash,
+                    ])
+                    .map_err(sqlite_error)?;
+            }
+        }
+        transaction.commit().map_err(sqlite_error)?;
+        Ok(inserted)
+    }
+
+    /// Deletes trades older than `retention` at `now` (ms) and returns how many were deleted
+    pub fn prune(&self, now: u64) -> Result<usize> {
+        let Some(retention) = self.retention else {
+            return Ok(0);
+        };
+        let cutoff = now.saturating_sub(retention.as_millis() as u64);
+        self.lock()
+            .execute("DELETE FROM trades WHERE time < ?1", [cutoff as i64])
+            .map_err(sqlite_error)
+    }
+
+    /// Trades of `coin` with `start_time <= time < end_time` (ms), oldest first
+    pub fn trades(&self, coin: &str, start_time: u64, end_time: u64) -> Result<Vec<Trade>> {
+        let connection = self.lock();
+        let mut statement = connection
+            .prepare_cached(
+                "SELECT coin, side, px, sz, time, hash, tid FROM trades
+                    WHERE coin = ?1 AND time >= ?2 AND time < ?3 ORDER BY time, tid",
+            )
+            .map_err(sqlite_error)?;
+        let rows = statement
+            .query_map(
+                rusqlite::params![coin, start_time as i64, end_time as i64],
+                |row| {
+                    Ok(Trade {
+                        coin: row.get(0)?,
+                        side: row.get(1)?,
+                        px: row.get(2)?,
+                        sz: row.get(3)?,
+                        time: row.get::<_, i64>(4)? as u64,
+                        hash: row.get(5)?,
+                        tid: row.get::<_, i64>(6)? as u64,
+                    })
+                },
+            )
+            .map_err(sqlite_error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(sqlite_error)
+    }
+
+    pub fn count(&self, coin: &str) -> Result<u64> {
+        self.lock()
+            .query_row(
+                "SELECT COUNT(*) FROM trades WHERE coin = ?1",
+                [coin],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(|count| count as u64)
+            .map_err(sqlite_error)
+    }
+
+    fn write(&self, trades: Vec<Trade>) {
+        if !trades.is_empty() {
+            if let Err(err) = self.insert(&trades) {
+                error!("Could not store {} trades: {err}", trades.len());
+            }
+        }
+        if let Err(err) = self.prune(now_timestamp_ms()) {
+            error!("Could not prune trade tape: {err}");
+        }
+    }
+
+    /// Writes the buffered trades and prunes on the blocking pool, so sqlite doesn't hold up a
+    /// runtime worker
+    async fn flush(&self, buffer: &mut Vec<Trade>) {
+        let trades = std::mem::replace(buffer, Vec::with_capacity(self.batch_size));
+        let tape = self.clone();
+        if let Err(err) = spawn_blocking(move || tape.write(trades)).await {
+            error!("Trade tape flush failed: {err}");
+        }
+    }
+
+    /// Subscribes to the trades of `coins` and stores them in the background.
+    /// The task flushes and stops when the subscriptions end. Aborting it stops recording right
+    /// away, dropping the trades buffered since the last flush.
+    pub async fn record(
+        &self,
+        info_client: &mut InfoClient,
+        coins: Vec<String>,
+    ) -> Result<JoinHandle<()>> {
+        let (sender, mut receiver) = unbounded_channel();
+        for coin in coins {
+            info_client
+                .subscribe(Subscription::Trades { coin }, sender.clone())
+                .await?;
+        }
+        drop(sender);
+
+        let tape = self.clone();
+        Ok(spawn(async move {
+            let mut buffer = Vec::with_capacity(tape.batch_size);
+            let mut ticker = interval(tape.flush_interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    message = receiver.recv() => match message {
+                        Some(Message::Trades(trades)) => {
+                            buffer.extend(trades.data);
+                            if buffer.len() >= tape.batch_size {
+                                tape.flush(&mut buffer).await;
+                            }
+                        }
+                        Some(Message::NoData) => warn!("Trade tape lost its websocket feed"),
+                        Some(_) => {}
+                        None => break,
+                    },
+                    _ = ticker.tick() => tape.flush(&mut buffer).await,
+                }
+            }
+            tape.flush(&mut buffer).await;
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(coin: &str, tid: u64, time: u64) -> Trade {
+        Trade {
+            coin: coin.to_string(),
+            side: "B".to_string(),
+            px: "1800.0".to_string(),
+            sz: "0.1".to_string(),
+            time,
+            hash: "0x01".to_string(),
+            tid,
+        }
+    }
+
+    #[test]
+    fn test_trade_tape() -> Result<()> {
+        let mut tape = TradeTape::open_in_memory()?;
+        let trades = [
+            trade("ETH", 1, 1_000),
+            trade("ETH", 2, 2_000),
+            trade("BTC", 1, 1_500),
+        ];
+        assert_eq!(tape.insert(&trades)?, 3);
+        assert_eq!(tape.insert(&trades[1..])?, 0);
+
+        let eth = tape.trades("ETH", 0, 2_000)?;
+        assert_eq!(eth.len(), 1);
+        assert_eq!(eth[0].tid, 1);
+        assert_eq!(tape.count("ETH")?, 2);
+
+        assert_eq!(tape.prune(10_000)?, 0);
+        tape.retention = Some(Duration::from_millis(8_400));
+        assert_eq!(tape.prune(10_000)?, 2);
+        assert_eq!(tape.count("ETH")?, 1);
+        assert_eq!(tape.count("BTC")?, 0);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_flush_writes_off_the_runtime() -> Result<()> {
+        let tape = TradeTape::open_in_memory()?;
+        let mut buffer = vec![trade("ETH", 1, 1_000), trade("ETH", 2, 2_000)];
+        tape.flush(&mut buffer).await;
+        assert!(buffer.is_empty());
+        assert_eq!(tape.count("ETH")?, 2);
+        Ok(())
+    }
+}
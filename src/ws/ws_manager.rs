@@ -1,10 +1,17 @@
 use crate::{
     prelude::*,
-    ws::message_types::{AllMids, Candle, L2Book, OrderUpdates, Trades, User},
-    Error, Notification, UserFills, UserFundings, UserNonFundingLedgerUpdates, WebData2,
+    ws::{
+        latency::LatencyTracker,
+        message_types::{AllMids, Bbo, Candle, L2Book, OrderUpdates, Post, Trades, User},
+        post::{PostRouter, WsWriter},
+        stats::{payload_matches, StatsTracker},
+        CoinFilter, WsConfig,
+    },
+    Error, Notification, SubscriptionStats, UserFills, UserFundings, UserNonFundingLedgerUpdates,
+    WebData2, WsLatency,
 };
 use futures_util::{stream::SplitSink, SinkExt, StreamExt};
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 use std::{
     borrow::BorrowMut,
@@ -19,22 +26,78 @@ use std::{
 use tokio::{
     net::TcpStream,
     spawn,
-    sync::{mpsc::UnboundedSender, Mutex},
+    sync::{mpsc::UnboundedSender, Mutex, Notify},
     time,
 };
 use tokio_tungstenite::{
-    connect_async,
+    connect_async_with_config,
     tungstenite::{self, protocol},
     MaybeTlsStream, WebSocketStream,
 };
 
-use ethers::types::H160;
+use crate::Address;
+
+/// Where a subscription's messages are delivered
+#[derive(Debug)]
+pub(crate) enum SubscriptionSender {
+    Typed(UnboundedSender<Message>),
+    /// Also delivers the raw payload each message was parsed from.
+    /// Messages generated locally, like `NoData` on disconnect, come with an empty payload.
+    /// Payloads on the subscription's channel that fail to parse are delivered too, as
+    /// `Message::Unknown`.
+    WithRaw(UnboundedSender<(Message, Arc<str>)>),
+    WithDelivery(UnboundedSender<(Message, DeliveryKind)>),
+}
+
+/// Whether a message is replayed state or a new event, so consumers fed by several
+/// subscriptions can tell them apart without inspecting every payload's `is_snapshot` flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryKind {
+    /// State replayed by the server on subscribing, e.g. recent fills
+    Snapshot,
+    /// A new event, or a message from a channel without snapshots
+    Live,
+    /// State replayed after a reconnect, which repeats events already delivered before the
+    /// disconnect and may contain events missed during it
+    Recovered,
+}
+
+impl SubscriptionSender {
+    fn send(&self, message: Message, raw: &Arc<str>, kind: DeliveryKind) -> Result<()> {
+        match self {
+            SubscriptionSender::Typed(sender) => sender
+                .send(message)
+                .map_err(|e| Error::WsSend(e.to_string())),
+            SubscriptionSender::WithRaw(sender) => sender
+                .send((message, raw.clone()))
+                .map_err(|e| Error::WsSend(e.to_string())),
+            SubscriptionSender::WithDelivery(sender) => sender
+                .send((message, kind))
+                .map_err(|e| Error::WsSend(e.to_string())),
+        }
+    }
+}
 
 #[derive(Debug)]
 struct SubscriptionData {
-    sending_channel: UnboundedSender<Message>,
+    sending_channel: SubscriptionSender,
     subscription_id: u32,
     id: String,
+    filter: CoinFilter,
+    /// Set on reconnect until the resubscription's snapshot arrives
+    reconnected: bool,
+}
+
+impl SubscriptionData {
+    fn delivery_kind(&mut self, message: &Message) -> DeliveryKind {
+        if !message.is_snapshot() {
+            DeliveryKind::Live
+        } else if std::mem::take(&mut self.reconnected) {
+            DeliveryKind::Recovered
+        } else {
+            DeliveryKind::Snapshot
+        }
+    }
 }
 #[derive(Debug)]
 pub(crate) struct WsManager {
@@ -43,34 +106,56 @@ pub(crate) struct WsManager {
     subscriptions: Arc<Mutex<HashMap<String, Vec<SubscriptionData>>>>,
     subscription_id: u32,
     subscription_identifiers: HashMap<u32, String>,
+    latency: LatencyTracker,
+    stats: StatsTracker,
+    posts: PostRouter,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(tag = "type")]
 #[serde(rename_all = "camelCase")]
 pub enum Subscription {
     AllMids,
     Trades { coin: String },
     L2Book { coin: String },
-    UserEvents { user: H160 },
-    UserFills { user: H160 },
+    Bbo { coin: String },
+    UserEvents { user: Address },
+    UserFills { user: Address },
     Candle { coin: String, interval: String },
-    OrderUpdates { user: H160 },
-    UserFundings { user: H160 },
-    UserNonFundingLedgerUpdates { user: H160 },
-    Notification { user: H160 },
-    WebData2 { user: H160 },
+    OrderUpdates { user: Address },
+    UserFundings { user: Address },
+    UserNonFundingLedgerUpdates { user: Address },
+    Notification { user: Address },
+    WebData2 { user: Address },
+}
+
+/// An active subscription as saved by [`crate::InfoClient::export_subscriptions`], to be
+/// re-established after a restart with [`crate::InfoClient::resume_subscriptions`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExportedSubscription {
+    pub subscription: Subscription,
+    #[serde(default)]
+    pub filter: CoinFilter,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+/// Serializes back to the server's JSON, so messages can be forwarded to other processes and
+/// deserialized there. `NoData` and `HyperliquidError`, which the SDK creates itself, become
+/// `{"channel": "noData"}` and `{"channel": "hyperliquidError", "data": "..."}`.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(remote = "Self")]
 #[serde(tag = "channel")]
 #[serde(rename_all = "camelCase")]
 pub enum Message {
     NoData,
+    #[serde(
+        serialize_with = "serialize_error",
+        deserialize_with = "deserialize_error"
+    )]
     HyperliquidError(String),
     AllMids(AllMids),
     Trades(Trades),
     L2Book(L2Book),
+    Bbo(Bbo),
     User(User),
     UserFills(UserFills),
     Candle(Candle),
@@ -80,7 +165,89 @@ pub enum Message {
     UserNonFundingLedgerUpdates(UserNonFundingLedgerUpdates),
     Notification(Notification),
     WebData2(WebData2),
+    Post(Post),
     Pong,
+    /// Any channel this version of the SDK does not know about, kept as raw JSON. Payloads on
+    /// known channels that don't parse are errors instead.
+    #[serde(untagged, skip_deserializing)]
+    Unknown(serde_json::Value),
+}
+
+/// Channels of every `Message` variant except `Unknown`, checked by `test_every_channel_is_listed`
+const CHANNELS: [&str; 17] = [
+    "noData",
+    "hyperliquidError",
+    "allMids",
+    "trades",
+    "l2Book",
+    "bbo",
+    "user",
+    "userFills",
+    "candle",
+    "subscriptionResponse",
+    "orderUpdates",
+    "userFundings",
+    "userNonFundingLedgerUpdates",
+    "notification",
+    "webData2",
+    "post",
+    "pong",
+];
+
+impl Serialize for Message {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        Message::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Message {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Message, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let is_known = value["channel"]
+            .as_str()
+            .is_some_and(|channel| CHANNELS.contains(&channel));
+        if !is_known {
+            return Ok(Message::Unknown(value));
+        }
+        Message::deserialize(value).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Message {
+    /// Whether the message replays state rather than reporting a new event
+    pub fn is_snapshot(&self) -> bool {
+        let is_snapshot = match self {
+            Message::UserFills(user_fills) => user_fills.data.is_snapshot,
+            Message::UserFundings(user_fundings) => user_fundings.data.is_snapshot,
+            Message::UserNonFundingLedgerUpdates(updates) => updates.data.is_snapshot,
+            _ => None,
+        };
+        is_snapshot == Some(true)
+    }
+}
+
+/// Internally tagged variants can't hold a bare string, so the error is wrapped in `data`
+#[derive(Serialize, Deserialize)]
+struct ErrorData<T> {
+    data: T,
+}
+
+fn serialize_error<S: serde::Serializer>(
+    error: &str,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    ErrorData { data: error }.serialize(serializer)
+}
+
+fn deserialize_error<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<String, D::Error> {
+    Ok(ErrorData::<String>::deserialize(deserializer)?.data)
 }
 
 #[derive(Serialize)]
@@ -95,26 +262,50 @@ pub(crate) struct Ping {
 }
 
 impl WsManager {
-    const SEND_PING_INTERVAL: u64 = 50;
-
-    pub(crate) async fn new(url: String, reconnect: bool) -> Result<WsManager> {
+    pub(crate) async fn new(url: String, reconnect: bool, config: WsConfig) -> Result<WsManager> {
         let stop_flag = Arc::new(AtomicBool::new(false));
 
-        let (writer, mut reader) = Self::connect(&url).await?.split();
+        let (writer, mut reader) = Self::connect(&url, &config).await?.split();
         let writer = Arc::new(Mutex::new(writer));
 
         let subscriptions_map: HashMap<String, Vec<SubscriptionData>> = HashMap::new();
         let subscriptions = Arc::new(Mutex::new(subscriptions_map));
         let subscriptions_copy = Arc::clone(&subscriptions);
+        let latency = LatencyTracker::default();
+        let stats = StatsTracker::default();
+        let posts = PostRouter::default();
+        // notified by the ping task when a pong is overdue
+        let stale = Arc::new(Notify::new());
 
         {
             let writer = writer.clone();
+            let stale = Arc::clone(&stale);
             let stop_flag = Arc::clone(&stop_flag);
+            let latency = latency.clone();
+            let stats = stats.clone();
+            let posts = posts.clone();
             let reader_fut = async move {
                 while !stop_flag.load(Ordering::Relaxed) {
-                    if let Some(data) = reader.next().await {
-                        if let Err(err) =
-                            WsManager::parse_and_send_data(data, &subscriptions_copy).await
+                    let data = tokio::select! {
+                        data = reader.next() => data,
+                        _ = stale.notified() => {
+                            // the permit may predate a reconnect
+                            if !latency.awaiting_pong() {
+                                continue;
+                            }
+                            warn!("WsManager pong timed out, treating the connection as stale");
+                            None
+                        }
+                    };
+                    if let Some(data) = data {
+                        if let Err(err) = WsManager::parse_and_send_data(
+                            data,
+                            &subscriptions_copy,
+                            &latency,
+                            &stats,
+                            &posts,
+                        )
+                        .await
                         {
                             error!("Error processing data received by WsManager reader: {err}");
                         }
@@ -132,23 +323,32 @@ impl WsManager {
                             // Always sleep for 1 second before attempting to reconnect so it does not spin during reconnecting. This could be enhanced with exponential backoff.
                             tokio::time::sleep(Duration::from_secs(1)).await;
                             info!("WsManager attempting to reconnect");
-                            match Self::connect(&url).await {
+                            match Self::connect(&url, &config).await {
                                 Ok(ws) => {
                                     let (new_writer, new_reader) = ws.split();
                                     reader = new_reader;
                                     let mut writer_guard = writer.lock().await;
                                     *writer_guard = new_writer;
-                                    for (identifier, v) in subscriptions_copy.lock().await.iter() {
+                                    latency.clear_ping();
+                                    for (identifier, v) in
+                                        subscriptions_copy.lock().await.iter_mut()
+                                    {
+                                        for subscription_data in v.iter_mut() {
+                                            subscription_data.reconnected = true;
+                                        }
                                         // TODO should these special keys be removed and instead use the simpler direct identifier mapping?
                                         if identifier.eq("userEvents")
                                             || identifier.eq("orderUpdates")
                                         {
-                                            for subscription_data in v {
-                                                if let Err(err) = Self::subscribe(
-                                                    writer_guard.deref_mut(),
-                                                    &subscription_data.id,
-                                                )
-                                                .await
+                                            let mut ids: Vec<&str> =
+                                                v.iter().map(|data| data.id.as_str()).collect();
+                                            // filtered userEvents subscriptions share one feed
+                                            ids.sort_unstable();
+                                            ids.dedup();
+                                            for id in ids {
+                                                if let Err(err) =
+                                                    Self::subscribe(writer_guard.deref_mut(), id)
+                                                        .await
                                                 {
                                                     error!(
                                                         "Could not resubscribe {identifier}: {err}"
@@ -180,6 +380,13 @@ impl WsManager {
         {
             let stop_flag = Arc::clone(&stop_flag);
             let writer = Arc::clone(&writer);
+            let latency = latency.clone();
+            let ping_interval = config.ping_interval;
+            // without reconnecting, a stale connection would end the reader for good
+            let pong_timeout = config
+                .pong_timeout
+                .filter(|_| reconnect)
+                .map(|timeout| timeout.min(ping_interval));
             let ping_fut = async move {
                 while !stop_flag.load(Ordering::Relaxed) {
                     match serde_json::to_string(&Ping { method: "ping" }) {
@@ -187,11 +394,22 @@ impl WsManager {
                             let mut writer = writer.lock().await;
                             if let Err(err) = writer.send(protocol::Message::Text(payload)).await {
                                 error!("Error pinging server: {err}")
+                            } else {
+                                latency.on_ping_sent();
                             }
                         }
                         Err(err) => error!("Error serializing ping message: {err}"),
                     }
-                    time::sleep(Duration::from_secs(Self::SEND_PING_INTERVAL)).await;
+                    match pong_timeout {
+                        Some(pong_timeout) => {
+                            time::sleep(pong_timeout).await;
+                            if latency.awaiting_pong() {
+                                stale.notify_one();
+                            }
+                            time::sleep(ping_interval - pong_timeout).await;
+                        }
+                        None => time::sleep(ping_interval).await,
+                    }
                 }
                 warn!("ws ping task stopped");
             };
@@ -204,14 +422,22 @@ impl WsManager {
             subscriptions,
             subscription_id: 0,
             subscription_identifiers: HashMap::new(),
+            latency,
+            stats,
+            posts,
         })
     }
 
-    async fn connect(url: &str) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
-        Ok(connect_async(url)
-            .await
-            .map_err(|e| Error::Websocket(e.to_string()))?
-            .0)
+    async fn connect(
+        url: &str,
+        config: &WsConfig,
+    ) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+        Ok(
+            connect_async_with_config(url, Some(config.websocket_config()), config.disable_nagle)
+                .await
+                .map_err(|e| Error::Websocket(e.to_string()))?
+                .0,
+        )
     }
 
     fn get_identifier(message: &Message) -> Result<String> {
@@ -237,6 +463,10 @@ impl WsManager {
                 coin: l2_book.data.coin.clone(),
             })
             .map_err(|e| Error::JsonParse(e.to_string())),
+            Message::Bbo(bbo) => serde_json::to_string(&Subscription::Bbo {
+                coin: bbo.data.coin.clone(),
+            })
+            .map_err(|e| Error::JsonParse(e.to_string())),
             Message::Candle(candle) => serde_json::to_string(&Subscription::Candle {
                 coin: candle.data.coin.clone(),
                 interval: candle.data.interval.clone(),
@@ -258,15 +488,24 @@ impl WsManager {
                 user: web_data2.data.user,
             })
             .map_err(|e| Error::JsonParse(e.to_string())),
-            Message::SubscriptionResponse | Message::Pong => Ok(String::default()),
+            Message::SubscriptionResponse | Message::Pong | Message::Post(_) => {
+                Ok(String::default())
+            }
             Message::NoData => Ok("".to_string()),
             Message::HyperliquidError(err) => Ok(format!("hyperliquid error: {err:?}")),
+            Message::Unknown(value) => {
+                debug!("Dropping message from unknown channel: {value}");
+                Ok(String::default())
+            }
         }
     }
 
     async fn parse_and_send_data(
         data: std::result::Result<protocol::Message, tungstenite::Error>,
         subscriptions: &Arc<Mutex<HashMap<String, Vec<SubscriptionData>>>>,
+        latency: &LatencyTracker,
+        stats: &StatsTracker,
+        posts: &PostRouter,
     ) -> Result<()> {
         match data {
             Ok(data) => match data.into_text() {
@@ -274,21 +513,51 @@ impl WsManager {
                     if !data.starts_with('{') {
                         return Ok(());
                     }
-                    let message = serde_json::from_str::<Message>(&data)
-                        .map_err(|e| Error::JsonParse(e.to_string()))?;
+                    let message = match serde_json::from_str::<Message>(&data) {
+                        Ok(message) => message,
+                        Err(err) => {
+                            // a known channel whose schema changed, not dropped silently
+                            warn!("Could not parse message: {err}, payload: {data}");
+                            let Ok(payload) = serde_json::from_str(&data) else {
+                                return Ok(());
+                            };
+                            stats.on_parse_failure(&payload);
+                            return WsManager::send_unparsed(subscriptions, payload, data).await;
+                        }
+                    };
+                    match &message {
+                        Message::Pong => latency.on_pong(),
+                        Message::Post(post) => {
+                            posts.route(post.data.clone());
+                            return Ok(());
+                        }
+                        Message::L2Book(l2_book) => latency.on_server_time(l2_book.data.time),
+                        Message::Bbo(bbo) => latency.on_server_time(bbo.data.time),
+                        Message::Trades(trades) => {
+                            if let Some(time) = trades.data.iter().map(|trade| trade.time).max() {
+                                latency.on_server_time(time);
+                            }
+                        }
+                        _ => {}
+                    }
                     let identifier = WsManager::get_identifier(&message)?;
                     if identifier.is_empty() {
                         return Ok(());
                     }
 
+                    stats.on_message(&identifier);
+
                     let mut subscriptions = subscriptions.lock().await;
                     let mut res = Ok(());
                     if let Some(subscription_datas) = subscriptions.get_mut(&identifier) {
+                        let raw: Arc<str> = Arc::from(data);
                         for subscription_data in subscription_datas {
-                            if let Err(e) = subscription_data
-                                .sending_channel
-                                .send(message.clone())
-                                .map_err(|e| Error::WsSend(e.to_string()))
+                            let Some(message) = subscription_data.filter.apply(&message) else {
+                                continue;
+                            };
+                            let kind = subscription_data.delivery_kind(&message);
+                            if let Err(e) =
+                                subscription_data.sending_channel.send(message, &raw, kind)
                             {
                                 res = Err(e);
                             }
@@ -316,19 +585,48 @@ impl WsManager {
         }
     }
 
+    /// Forwards a payload that failed to parse to the raw subscribers it could belong to
+    async fn send_unparsed(
+        subscriptions: &Arc<Mutex<HashMap<String, Vec<SubscriptionData>>>>,
+        payload: serde_json::Value,
+        data: String,
+    ) -> Result<()> {
+        let mut subscriptions = subscriptions.lock().await;
+        let mut res = Ok(());
+        let raw: Arc<str> = Arc::from(data);
+        for (identifier, subscription_datas) in subscriptions.iter_mut() {
+            if !payload_matches(identifier, &payload) {
+                continue;
+            }
+            for subscription_data in subscription_datas {
+                if let SubscriptionSender::WithRaw(_) = subscription_data.sending_channel {
+                    if let Err(e) = subscription_data.sending_channel.send(
+                        Message::Unknown(payload.clone()),
+                        &raw,
+                        DeliveryKind::Live,
+                    ) {
+                        res = Err(e);
+                    }
+                }
+            }
+        }
+        res
+    }
+
     async fn send_to_all_subscriptions(
         subscriptions: &Arc<Mutex<HashMap<String, Vec<SubscriptionData>>>>,
         message: Message,
     ) -> Result<()> {
         let mut subscriptions = subscriptions.lock().await;
         let mut res = Ok(());
+        let raw: Arc<str> = Arc::from("");
         for subscription_datas in subscriptions.values_mut() {
             for subscription_data in subscription_datas {
-                if let Err(e) = subscription_data
-                    .sending_channel
-                    .send(message.clone())
-                    .map_err(|e| Error::WsSend(e.to_string()))
-                {
+                if let Err(e) = subscription_data.sending_channel.send(
+                    message.clone(),
+                    &raw,
+                    DeliveryKind::Live,
+                ) {
                     res = Err(e);
                 }
             }
@@ -372,7 +670,8 @@ impl WsManager {
     pub(crate) async fn add_subscription(
         &mut self,
         identifier: String,
-        sending_channel: UnboundedSender<Message>,
+        sending_channel: SubscriptionSender,
+        filter: CoinFilter,
     ) -> Result<u32> {
         let mut subscriptions = self.subscriptions.lock().await;
 
@@ -393,12 +692,18 @@ impl WsManager {
             .entry(identifier_entry.clone())
             .or_insert(Vec::new());
 
-        if !subscriptions.is_empty() && identifier_entry.eq("userEvents") {
+        // userEvents messages don't name their user, so only one user can be subscribed
+        if identifier_entry.eq("userEvents")
+            && subscriptions
+                .iter()
+                .any(|subscription_data| subscription_data.id != identifier)
+        {
             return Err(Error::UserEvents);
         }
 
         if subscriptions.is_empty() {
             Self::subscribe(self.writer.lock().await.borrow_mut(), identifier.as_str()).await?;
+            self.stats.track(&identifier_entry);
         }
 
         let subscription_id = self.subscription_id;
@@ -408,12 +713,51 @@ impl WsManager {
             sending_channel,
             subscription_id,
             id: identifier,
+            filter,
+            reconnected: false,
         });
 
         self.subscription_id += 1;
         Ok(subscription_id)
     }
 
+    /// Active subscriptions in the order they were added, without duplicates
+    pub(crate) async fn export_subscriptions(&self) -> Result<Vec<ExportedSubscription>> {
+        let subscriptions = self.subscriptions.lock().await;
+        let mut datas: Vec<&SubscriptionData> = subscriptions.values().flatten().collect();
+        datas.sort_by_key(|data| data.subscription_id);
+
+        let mut exported: Vec<ExportedSubscription> = Vec::new();
+        for data in datas {
+            let subscription = ExportedSubscription {
+                subscription: serde_json::from_str(&data.id)
+                    .map_err(|e| Error::JsonParse(e.to_string()))?,
+                filter: data.filter.clone(),
+            };
+            if !exported.contains(&subscription) {
+                exported.push(subscription);
+            }
+        }
+        Ok(exported)
+    }
+
+    pub(crate) fn latency(&self) -> WsLatency {
+        self.latency.latency()
+    }
+
+    /// Writer and response router for sending `post` requests over this connection
+    pub(crate) fn post_handle(&self) -> (WsWriter, PostRouter) {
+        (self.writer.clone(), self.posts.clone())
+    }
+
+    pub(crate) fn subscription_stats(&self) -> Vec<SubscriptionStats> {
+        self.stats.snapshot()
+    }
+
+    pub(crate) fn add_latency_listener(&self, listener: UnboundedSender<WsLatency>) {
+        self.latency.add_listener(listener);
+    }
+
     pub(crate) async fn remove_subscription(&mut self, subscription_id: u32) -> Result<()> {
         let identifier = self
             .subscription_identifiers
@@ -449,6 +793,7 @@ impl WsManager {
         subscriptions.remove(index);
 
         if subscriptions.is_empty() {
+            self.stats.untrack(&identifier_entry);
             Self::unsubscribe(self.writer.lock().await.borrow_mut(), identifier.as_str()).await?;
         }
         Ok(())
@@ -460,3 +805,354 @@ impl Drop for WsManager {
         self.stop_flag.store(true, Ordering::Relaxed);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UserData;
+
+    /// Local websocket server that reads everything and never answers.
+    /// Returns its url and a channel notified on every connection.
+    async fn spawn_silent_server() -> (String, tokio::sync::mpsc::UnboundedReceiver<()>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (connected, connections) = tokio::sync::mpsc::unbounded_channel();
+        spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let connected = connected.clone();
+                spawn(async move {
+                    let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                    let _ = connected.send(());
+                    while let Some(Ok(_)) = ws.next().await {}
+                });
+            }
+        });
+        (url, connections)
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_when_pong_times_out() {
+        let (url, mut connections) = spawn_silent_server().await;
+        let config = WsConfig {
+            ping_interval: Duration::from_millis(100),
+            pong_timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        };
+        let _manager = WsManager::new(url, true, config).await.unwrap();
+        for _ in 0..2 {
+            time::timeout(Duration::from_secs(5), connections.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pong_timeout_needs_reconnect() {
+        let (url, _connections) = spawn_silent_server().await;
+        let config = WsConfig {
+            ping_interval: Duration::from_millis(100),
+            pong_timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        };
+        let mut manager = WsManager::new(url, false, config).await.unwrap();
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        manager
+            .add_subscription(
+                serde_json::to_string(&Subscription::AllMids).unwrap(),
+                SubscriptionSender::Typed(sender),
+                CoinFilter::All,
+            )
+            .await
+            .unwrap();
+        // missed pongs don't disconnect a manager that won't reconnect
+        time::sleep(Duration::from_millis(500)).await;
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_export_subscriptions() {
+        let (url, _connections) = spawn_silent_server().await;
+        let mut manager = WsManager::new(url, false, WsConfig::default())
+            .await
+            .unwrap();
+        let user = Address::repeat_byte(1);
+        let eth_only = CoinFilter::Only(vec!["ETH".to_string()]);
+        let subscriptions = [
+            (
+                Subscription::L2Book {
+                    coin: "ETH".to_string(),
+                },
+                CoinFilter::All,
+            ),
+            (Subscription::UserEvents { user }, eth_only.clone()),
+            (
+                Subscription::L2Book {
+                    coin: "ETH".to_string(),
+                },
+                CoinFilter::All,
+            ),
+            (Subscription::AllMids, CoinFilter::All),
+        ];
+        for (subscription, filter) in subscriptions {
+            let (sender, _receiver) = tokio::sync::mpsc::unbounded_channel();
+            manager
+                .add_subscription(
+                    serde_json::to_string(&subscription).unwrap(),
+                    SubscriptionSender::Typed(sender),
+                    filter,
+                )
+                .await
+                .unwrap();
+        }
+
+        let exported = manager.export_subscriptions().await.unwrap();
+        assert_eq!(
+            exported,
+            vec![
+                ExportedSubscription {
+                    subscription: Subscription::L2Book {
+                        coin: "ETH".to_string()
+                    },
+                    filter: CoinFilter::All,
+                },
+                ExportedSubscription {
+                    subscription: Subscription::UserEvents { user },
+                    filter: eth_only,
+                },
+                ExportedSubscription {
+                    subscription: Subscription::AllMids,
+                    filter: CoinFilter::All,
+                },
+            ]
+        );
+        let json = serde_json::to_string(&exported).unwrap();
+        let restored: Vec<ExportedSubscription> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, exported);
+    }
+
+    #[test]
+    fn test_unknown_variants_deserialize() {
+        let message: Message =
+            serde_json::from_str(r#"{"channel": "someNewChannel", "data": {"x": 1}}"#).unwrap();
+        assert!(matches!(message, Message::Unknown(_)));
+        assert_eq!(WsManager::get_identifier(&message).unwrap(), "");
+
+        let message: Message = serde_json::from_str(r#"{"channel": "pong"}"#).unwrap();
+        assert!(matches!(message, Message::Pong));
+
+        // malformed payloads on known channels are errors, not unknown messages
+        assert!(serde_json::from_str::<Message>(
+            r#"{"channel": "l2Book", "data": {"coin": "BTC", "levels": 1}}"#
+        )
+        .is_err());
+
+        let user_data: UserData = serde_json::from_str(r#"{"someNewEvent": []}"#).unwrap();
+        assert!(matches!(user_data, UserData::Unknown(_)));
+    }
+
+    #[test]
+    fn test_delivery_kind() {
+        let (sender, _receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut subscription_data = SubscriptionData {
+            sending_channel: SubscriptionSender::WithDelivery(sender),
+            subscription_id: 0,
+            id: String::new(),
+            filter: CoinFilter::All,
+            reconnected: false,
+        };
+        let fills = |is_snapshot: bool| -> Message {
+            serde_json::from_value(serde_json::json!({
+                "channel": "userFills",
+                "data": {
+                    "isSnapshot": is_snapshot,
+                    "user": "0x0000000000000000000000000000000000000001",
+                    "fills": []
+                }
+            }))
+            .unwrap()
+        };
+
+        assert_eq!(
+            subscription_data.delivery_kind(&fills(true)),
+            DeliveryKind::Snapshot
+        );
+        assert_eq!(
+            subscription_data.delivery_kind(&fills(false)),
+            DeliveryKind::Live
+        );
+        assert_eq!(
+            subscription_data.delivery_kind(&Message::Pong),
+            DeliveryKind::Live
+        );
+
+        subscription_data.reconnected = true;
+        assert_eq!(
+            subscription_data.delivery_kind(&fills(false)),
+            DeliveryKind::Live
+        );
+        assert_eq!(
+            subscription_data.delivery_kind(&fills(true)),
+            DeliveryKind::Recovered
+        );
+        assert_eq!(
+            subscription_data.delivery_kind(&fills(true)),
+            DeliveryKind::Snapshot
+        );
+    }
+
+    /// A message of every variant, and one of a channel the SDK doesn't know
+    const MESSAGES: [&str; 19] = [
+        r#"{"channel":"noData"}"#,
+        r#"{"channel":"hyperliquidError","data":"Connection reset"}"#,
+        r#"{"channel":"allMids","data":{"mids":{"BTC":"60000.5"}}}"#,
+        r#"{"channel":"trades","data":[{"coin":"ETH","side":"B","px":"1800.0","sz":"0.1","time":1,"hash":"0x01","tid":2}]}"#,
+        r#"{"channel":"l2Book","data":{"coin":"ETH","time":1,"levels":[[{"px":"1799.9","sz":"1.0","n":1}],[{"px":"1800.1","sz":"2.0","n":2}]]}}"#,
+        r#"{"channel":"bbo","data":{"coin":"ETH","time":1,"bbo":[{"px":"1799.9","sz":"1.0","n":1},null]}}"#,
+        r#"{"channel":"user","data":{"nonUserCancel":[{"coin":"ETH","oid":3}]}}"#,
+        r#"{"channel":"user","data":{"someNewEvent":[]}}"#,
+        r#"{"channel":"userFills","data":{"isSnapshot":true,"user":"0x0000000000000000000000000000000000000001","fills":[{"coin":"ETH","side":"A","px":"1800.0","sz":"0.1","time":1,"hash":"0x01","startPosition":"0.1","dir":"Close Long","closedPnl":"1.0","oid":3,"cloid":null,"crossed":true,"fee":"0.01","feeToken":"USDC","tid":2}]}}"#,
+        r#"{"channel":"candle","data":{"T":2,"c":"1.0","h":"1.1","i":"1m","l":"0.9","n":5,"o":"1.0","s":"ETH","t":1,"v":"10.0"}}"#,
+        r#"{"channel":"subscriptionResponse"}"#,
+        r#"{"channel":"orderUpdates","data":[{"order":{"coin":"ETH","side":"B","limitPx":"1800.0","sz":"0.1","oid":3,"timestamp":1,"origSz":"0.1","cloid":null},"status":"open","statusTimestamp":1}]}"#,
+        r#"{"channel":"userFundings","data":{"isSnapshot":null,"user":"0x0000000000000000000000000000000000000001","fundings":[{"time":1,"coin":"ETH","usdc":"-0.1","szi":"1.0","fundingRate":"0.0001"}]}}"#,
+        r#"{"channel":"userNonFundingLedgerUpdates","data":{"isSnapshot":false,"user":"0x0000000000000000000000000000000000000001","nonFundingLedgerUpdates":[{"time":1,"hash":"0x01","delta":{"type":"deposit","usdc":"10.0"}},{"time":2,"hash":"0x02","delta":{"type":"someFutureDelta","amount":"1"}}]}}"#,
+        r#"{"channel":"notification","data":{"notification":"Order filled"}}"#,
+        r#"{"channel":"webData2","data":{"user":"0x0000000000000000000000000000000000000001"}}"#,
+        r#"{"channel":"post","data":{"id":1,"response":{"type":"info","payload":{"type":"allMids","data":{}}}}}"#,
+        r#"{"channel":"pong"}"#,
+        r#"{"channel":"someNewChannel","data":{"x":1}}"#,
+    ];
+
+    /// Fails to compile when a variant is added, until it gets an index here
+    fn variant_index(message: &Message) -> usize {
+        match message {
+            Message::NoData => 0,
+            Message::HyperliquidError(_) => 1,
+            Message::AllMids(_) => 2,
+            Message::Trades(_) => 3,
+            Message::L2Book(_) => 4,
+            Message::Bbo(_) => 5,
+            Message::User(_) => 6,
+            Message::UserFills(_) => 7,
+            Message::Candle(_) => 8,
+            Message::SubscriptionResponse => 9,
+            Message::OrderUpdates(_) => 10,
+            Message::UserFundings(_) => 11,
+            Message::UserNonFundingLedgerUpdates(_) => 12,
+            Message::Notification(_) => 13,
+            Message::WebData2(_) => 14,
+            Message::Post(_) => 15,
+            Message::Pong => 16,
+            Message::Unknown(_) => 17,
+        }
+    }
+
+    #[test]
+    fn test_every_channel_is_listed() {
+        // a variant whose channel is missing from CHANNELS deserializes as Unknown
+        let variants: std::collections::HashSet<_> = MESSAGES
+            .iter()
+            .map(|json| variant_index(&serde_json::from_str(json).unwrap()))
+            .collect();
+        let unknown = variant_index(&Message::Unknown(serde_json::Value::Null));
+        assert_eq!(variants, (0..=unknown).collect());
+    }
+
+    #[test]
+    fn test_message_serialize_round_trip() {
+        for json in MESSAGES {
+            let message: Message = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                matches!(message, Message::Unknown(_)),
+                json.contains("someNewChannel")
+            );
+            let serialized = serde_json::to_string(&message).unwrap();
+            assert_eq!(serialized, json);
+            let message: Message = serde_json::from_str(&serialized).unwrap();
+            assert_eq!(serde_json::to_string(&message).unwrap(), json);
+        }
+
+        let bbo: Message = serde_json::from_str(MESSAGES[5]).unwrap();
+        assert_eq!(
+            WsManager::get_identifier(&bbo).unwrap(),
+            r#"{"type":"bbo","coin":"ETH"}"#
+        );
+    }
+
+    #[cfg(feature = "exchange")]
+    #[test]
+    fn test_unknown_exchange_status_deserialize() {
+        use crate::ExchangeDataStatus;
+
+        let status: ExchangeDataStatus = serde_json::from_str(r#""waitingForSomething""#).unwrap();
+        assert!(matches!(status, ExchangeDataStatus::Unknown(_)));
+        let status: ExchangeDataStatus =
+            serde_json::from_str(r#"{"resting": {"oid": 1}}"#).unwrap();
+        assert!(matches!(status, ExchangeDataStatus::Resting(_)));
+    }
+
+    #[tokio::test]
+    async fn test_reader_counts_parse_failures() {
+        let stats = StatsTracker::default();
+        let eth = r#"{"type":"l2Book","coin":"ETH"}"#;
+        let btc = r#"{"type":"l2Book","coin":"BTC"}"#;
+        stats.track(eth);
+        stats.track(btc);
+        let (sender, mut raw_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let subscriptions = Arc::new(Mutex::new(HashMap::from([(
+            btc.to_string(),
+            vec![SubscriptionData {
+                sending_channel: SubscriptionSender::WithRaw(sender),
+                subscription_id: 0,
+                id: btc.to_string(),
+                filter: CoinFilter::All,
+                reconnected: false,
+            }],
+        )])));
+
+        let payloads = [
+            r#"{"channel": "l2Book", "data": {"coin": "BTC", "levels": 1}}"#,
+            r#"{"channel": "l2Book", "data": {"coin": "ETH", "time": 1, "levels": [[], []]}}"#,
+            r#"{"channel": "someNewChannel", "data": {"coin": "BTC"}}"#,
+            "not json",
+        ];
+        for payload in payloads {
+            WsManager::parse_and_send_data(
+                Ok(protocol::Message::Text(payload.to_string())),
+                &subscriptions,
+                &LatencyTracker::default(),
+                &stats,
+                &PostRouter::default(),
+            )
+            .await
+            .unwrap();
+        }
+
+        // the raw subscriber gets the payload that failed to parse
+        let (message, raw) = raw_receiver.try_recv().unwrap();
+        assert!(matches!(message, Message::Unknown(_)));
+        assert_eq!(&*raw, payloads[0]);
+        assert!(raw_receiver.try_recv().is_err());
+
+        let stats = stats.snapshot();
+        let (btc_stats, eth_stats) = (&stats[0], &stats[1]);
+        assert_eq!(btc_stats.subscription, btc);
+        assert_eq!(btc_stats.parse_failures, 1);
+        assert_eq!(btc_stats.messages_received, 0);
+        assert_eq!(eth_stats.parse_failures, 0);
+        assert_eq!(eth_stats.messages_received, 1);
+    }
+
+    #[test]
+    fn test_subscription_sender_with_raw() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let raw: Arc<str> = Arc::from(r#"{"channel": "pong"}"#);
+        SubscriptionSender::WithRaw(sender)
+            .send(Message::Pong, &raw, DeliveryKind::Live)
+            .unwrap();
+        let (message, received_raw) = receiver.try_recv().unwrap();
+        assert!(matches!(message, Message::Pong));
+        assert_eq!(received_raw, raw);
+    }
+}
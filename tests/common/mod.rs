@@ -0,0 +1,162 @@
+use alloy::signers::local::PrivateKeySigner;
+use hyperliquid_rust_sdk::{
+    Address, BaseUrl, ExchangeClient, ExchangeResponseStatus, InfoClient, OpenOrdersResponse,
+    UserStateResponse,
+};
+use std::{
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::time::{sleep, Instant};
+
+/// Key with a USDC balance on the local node, used to fund fresh test accounts.
+/// Override with `HL_LOCAL_FUNDED_KEY` when the node was started with a different genesis.
+const DEFAULT_FUNDED_KEY: &str = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e";
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct LocalNode {
+    pub info_client: InfoClient,
+    pub funder: ExchangeClient,
+}
+
+impl LocalNode {
+    /// Connects to the local node, or returns `None` so the calling test is skipped if it is
+    /// not running. Set `HL_LOCAL_NODE_REQUIRED` to fail instead, e.g. in CI that starts one.
+    pub async fn connect() -> Option<LocalNode> {
+        let info_client = InfoClient::new(None, Some(BaseUrl::Localhost))
+            .await
+            .expect("could not create local info client");
+        if let Err(err) = info_client.exchange_status().await {
+            assert!(
+                std::env::var_os("HL_LOCAL_NODE_REQUIRED").is_none(),
+                "local node unreachable: {err}"
+            );
+            eprintln!("skipping, local node unreachable: {err}");
+            return None;
+        }
+
+        let key = std::env::var("HL_LOCAL_FUNDED_KEY").unwrap_or(DEFAULT_FUNDED_KEY.to_string());
+        let wallet: PrivateKeySigner = key.parse().expect("invalid HL_LOCAL_FUNDED_KEY");
+        Some(LocalNode {
+            info_client,
+            funder: exchange_client(wallet).await,
+        })
+    }
+
+    /// Node time in milliseconds
+    pub async fn time(&self) -> u64 {
+        self.info_client
+            .exchange_status()
+            .await
+            .expect("could not fetch exchange status")
+            .time
+    }
+
+    /// Moves the node at least `duration` ahead and returns its time then. The node can't skip
+    /// time, so this keeps it producing blocks with noop actions from the funded account until
+    /// its clock has passed `duration`, e.g. for the next funding or a trigger to be processed.
+    pub async fn fast_forward(&self, duration: Duration) -> u64 {
+        let target = self.time().await + duration.as_millis() as u64;
+        wait_until(DEFAULT_TIMEOUT + duration, || async {
+            let _ = self.funder.noop(unique_nonce(), None).await;
+            let time = self.time().await;
+            (time >= target).then_some(time)
+        })
+        .await
+    }
+
+    /// Creates a fresh account and transfers `usdc` to it from the funded account
+    pub async fn funded_account(&self, usdc: &str) -> ExchangeClient {
+        let wallet = PrivateKeySigner::random();
+        let address = wallet.address();
+        assert_ok(
+            self.funder
+                .usdc_transfer(usdc, &format!("{address:?}"), None)
+                .await
+                .expect("usdc transfer failed"),
+        );
+        let expected: f64 = usdc.parse().expect("invalid usdc amount");
+        self.wait_for_user_state(address, |state| {
+            state.withdrawable.parse::<f64>().unwrap_or_default() >= expected
+        })
+        .await;
+        exchange_client(wallet).await
+    }
+
+    pub async fn wait_for_user_state(
+        &self,
+        user: Address,
+        predicate: impl Fn(&UserStateResponse) -> bool,
+    ) -> UserStateResponse {
+        wait_until(DEFAULT_TIMEOUT, || async {
+            self.info_client
+                .user_state(user)
+                .await
+                .ok()
+                .filter(|state| predicate(state))
+        })
+        .await
+    }
+
+    pub async fn wait_for_open_orders(
+        &self,
+        user: Address,
+        predicate: impl Fn(&[OpenOrdersResponse]) -> bool,
+    ) -> Vec<OpenOrdersResponse> {
+        wait_until(DEFAULT_TIMEOUT, || async {
+            self.info_client
+                .open_orders(user)
+                .await
+                .ok()
+                .filter(|orders| predicate(orders))
+        })
+        .await
+    }
+}
+
+pub async fn exchange_client(wallet: PrivateKeySigner) -> ExchangeClient {
+    ExchangeClient::new(None, wallet, Some(BaseUrl::Localhost), None, None)
+        .await
+        .expect("could not create local exchange client")
+}
+
+/// Polls `check` every `POLL_INTERVAL` until it returns `Some`, panicking after `timeout`
+pub async fn wait_until<T, F, Fut>(timeout: Duration, mut check: F) -> T
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Option<T>>,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(value) = check().await {
+            return value;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "condition not met within {timeout:?}"
+        );
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Millisecond nonce above every one handed out before, for actions sent outside the clients
+fn unique_nonce() -> u64 {
+    static LAST: AtomicU64 = AtomicU64::new(0);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock before unix epoch")
+        .as_millis() as u64;
+    let next = |last: u64| last.max(now - 1) + 1;
+    next(
+        LAST.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| Some(next(last)))
+            .unwrap(),
+    )
+}
+
+pub fn assert_ok(response: ExchangeResponseStatus) {
+    if let ExchangeResponseStatus::Err(err) = response {
+        panic!("exchange returned an error: {err}");
+    }
+}
@@ -0,0 +1,86 @@
+//! Integration tests against a Hyperliquid node listening on `LOCAL_API_URL`.
+//! Run with `cargo test --features local-node --test local_node`. Tests are skipped when no
+//! node is running, unless `HL_LOCAL_NODE_REQUIRED` is set.
+#![cfg(feature = "local-node")]
+
+mod common;
+
+use common::{assert_ok, LocalNode};
+use hyperliquid_rust_sdk::{
+    ClientCancelRequest, DecimalOrder, ExchangeDataStatus, ExchangeResponseStatus, Tif,
+};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_usdc_transfer_funds_new_account() {
+    let Some(node) = LocalNode::connect().await else {
+        return;
+    };
+    let account = node.funded_account("10").await;
+
+    let state = node
+        .info_client
+        .user_state(account.wallet.address())
+        .await
+        .unwrap();
+    assert!(state.withdrawable.parse::<f64>().unwrap() >= 10.0);
+}
+
+#[tokio::test]
+async fn test_order_rests_and_cancels() {
+    let Some(node) = LocalNode::connect().await else {
+        return;
+    };
+    let account = node.funded_account("100").await;
+    let user = account.wallet.address();
+
+    let response = account
+        .order(
+            DecimalOrder::new(
+                "ETH",
+                true,
+                "100".parse().unwrap(),
+                "0.01".parse().unwrap(),
+                Tif::Gtc,
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    let ExchangeResponseStatus::Ok(response) = response else {
+        panic!("order rejected: {response:?}");
+    };
+    let oid = match &response.data.unwrap().statuses[0] {
+        ExchangeDataStatus::Resting(resting) => resting.oid,
+        status => panic!("expected resting order, got {status:?}"),
+    };
+
+    node.wait_for_open_orders(user, |orders| orders.iter().any(|order| order.oid == oid))
+        .await;
+
+    assert_ok(
+        account
+            .cancel(
+                ClientCancelRequest {
+                    asset: "ETH".to_string(),
+                    oid,
+                },
+                None,
+            )
+            .await
+            .unwrap(),
+    );
+    node.wait_for_open_orders(user, |orders| orders.iter().all(|order| order.oid != oid))
+        .await;
+}
+
+#[tokio::test]
+async fn test_fast_forward_moves_node_time() {
+    let Some(node) = LocalNode::connect().await else {
+        return;
+    };
+    let start = node.time().await;
+
+    let time = node.fast_forward(Duration::from_secs(2)).await;
+    assert!(time >= start + 2_000);
+}